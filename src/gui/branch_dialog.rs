@@ -1,12 +1,17 @@
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 
 use gtk::prelude::*;
 
 use super::{comando_gui::ComandoGui, conflicts_modal, dibujar_dialog, info_dialog, log_list};
 use crate::{
     tipos_de_dato::{
-        comandos::{branch::Branch, merge::Merge, rebase::Rebase},
+        comandos::{
+            branch::Branch,
+            merge::Merge,
+            rebase::{Rebase, DIR_REBASE},
+        },
         logger::Logger,
+        repositorio::Repositorio,
     },
     utils::ramas,
 };
@@ -61,7 +66,7 @@ fn comfirmar(builder: &gtk::Builder, accion: AccionBranchDialog, logger: Arc<Log
         match accion {
             AccionBranchDialog::Merge => Merge::from(&mut args, logger.clone()).ejecutar_gui(),
             AccionBranchDialog::Rebase => {
-                if PathBuf::from(".gir/rebase-merge").exists() {
+                if Repositorio::actual().ruta_gir(DIR_REBASE).exists() {
                     args = vec!["--continue".to_string()];
                 }
                 Rebase::from(args, logger.clone()).ejecutar_gui()
@@ -1,8 +1,8 @@
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 
 use gtk::prelude::*;
 
-use crate::tipos_de_dato::{comandos::clone::Clone, logger::Logger};
+use crate::tipos_de_dato::{comandos::clone::Clone, logger::Logger, repositorio::Repositorio};
 
 use super::comando_gui::ComandoGui;
 
@@ -42,7 +42,7 @@ pub fn render(builder: &gtk::Builder, logger: Arc<Logger>) -> bool {
     clonar_dialog(builder, logger);
     run_dialog(builder);
 
-    if !PathBuf::from(".gir").is_dir() {
+    if !Repositorio::actual().existe() {
         error_no_repo_dialog(builder);
         return false;
     }
@@ -1,6 +1,5 @@
 use std::{
     collections::{HashMap, HashSet},
-    path::Path,
     sync::Arc,
 };
 
@@ -11,6 +10,7 @@ use crate::{
         comandos::{branch::Branch, log::Log},
         logger::Logger,
         objetos::commit::CommitObj,
+        repositorio::Repositorio,
     },
     utils::{
         compresion::descomprimir_objeto_gir,
@@ -46,8 +46,9 @@ fn obtener_commits_con_branches(
 
     let mut commits_por_ramas = Vec::new();
 
+    let dir_heads = Repositorio::actual().ruta_gir("refs/heads");
     for rama in ramas {
-        let commit_hash_rama = leer_a_string(".gir/refs/heads/".to_string() + rama)?;
+        let commit_hash_rama = leer_a_string(dir_heads.join(rama))?;
         if commit_hash_rama.is_empty() {
             continue;
         }
@@ -100,8 +101,8 @@ fn generar_clases_por_rama() -> HashMap<String, String> {
 }
 
 fn obtener_listas_de_commits(branch: &str, logger: Arc<Logger>) -> Result<Vec<CommitObj>, String> {
-    let ruta = format!(".gir/refs/heads/{}", branch);
-    let ultimo_commit = io::leer_a_string(Path::new(&ruta))?;
+    let ruta = Repositorio::actual().ruta_gir(format!("refs/heads/{}", branch));
+    let ultimo_commit = io::leer_a_string(ruta)?;
 
     if ultimo_commit.is_empty() {
         return Ok(Vec::new());
@@ -26,10 +26,10 @@ mod tag_button;
 mod tag_list;
 mod upstream_dialog;
 
-use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::tipos_de_dato::logger::Logger;
+use crate::tipos_de_dato::repositorio::Repositorio;
 use crate::utils::ramas;
 use gtk::{self, StyleContext};
 use gtk::{gdk, prelude::*};
@@ -104,7 +104,7 @@ pub fn ejecutar(logger: Arc<Logger>) {
     window.set_position(gtk::WindowPosition::Center);
     window.set_default_size(800, 600);
 
-    if !PathBuf::from(".gir").is_dir() && !clone_dialog::render(&builder, logger.clone()) {
+    if !Repositorio::actual().existe() && !clone_dialog::render(&builder, logger.clone()) {
         return;
     }
 
@@ -36,12 +36,7 @@ fn boton_confimar_dialog(builder: &gtk::Builder, logger: Arc<Logger>) {
     let input: gtk::Entry = builder.object("commit-input").unwrap();
     let builder_clone = builder.clone();
     confirm.connect_clicked(move |_| {
-        let mut args = match input.text().as_str() {
-            "" => vec![],
-            input_str => vec!["-m".to_string(), input_str.to_string()],
-        };
-
-        let commit = Commit::from(&mut args, logger.clone()).ejecutar_gui();
+        let commit = Commit::from_gui(input.text().as_str(), logger.clone()).ejecutar_gui();
 
         if commit.is_none() {
             return;
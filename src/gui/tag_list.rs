@@ -3,7 +3,9 @@ use std::sync::Arc;
 use gtk::{prelude::*, Orientation};
 
 use crate::{
-    tipos_de_dato::{comandos::tag::Tag, logger::Logger, objetos::commit::CommitObj},
+    tipos_de_dato::{
+        comandos::tag::Tag, logger::Logger, objetos::commit::CommitObj, repositorio::Repositorio,
+    },
     utils::io::{self},
 };
 
@@ -65,14 +67,15 @@ pub fn render(builder: &gtk::Builder, logger: Arc<Logger>) {
         return;
     }
 
+    let dir_tags = Repositorio::actual().ruta_gir("refs/tags");
     tags.sort_by_key(|tag| {
-        let hash = io::leer_a_string(".gir/refs/tags/".to_string() + tag).unwrap();
+        let hash = io::leer_a_string(dir_tags.join(tag)).unwrap();
         let commit = CommitObj::from_hash(hash, logger.clone()).unwrap();
         commit.date.tiempo.clone()
     });
 
     for tag in tags {
-        let hash = io::leer_a_string(".gir/refs/tags/".to_string() + &tag).unwrap();
+        let hash = io::leer_a_string(dir_tags.join(&tag)).unwrap();
         let event_box = crear_label(&tag, "blue", &hash);
         let builder_clone = builder.clone();
         event_box.connect_button_press_event(move |_, _| {
@@ -1,20 +1,64 @@
 pub mod err_comunicacion;
 pub mod servidor {
+    pub mod archivo_cambiado;
     pub mod gir_server;
     pub mod http_server;
+    pub mod autenticacion;
+    pub mod comentario;
+    pub mod comparacion;
+    pub mod contador;
+    pub mod estado_commit;
+    pub mod etiqueta;
+    pub mod explorador;
+    pub mod issue;
+    pub mod mantenimiento;
+    pub mod pool_threads;
+    pub mod proteccion_ramas;
     pub mod pull_request;
     pub mod receive_pack;
     pub mod repos_almacen;
+    pub mod repositorio_servidor;
+    pub mod revision;
     pub mod upload_pack;
-    pub mod vector_threads;
+    pub mod webhook;
     pub mod rutas {
+        pub mod actualizar_comentario;
+        pub mod actualizar_issue;
         pub mod actualizar_pull_request;
+        pub mod comparar_referencias;
+        pub mod crear_comentario;
+        pub mod crear_estado_commit;
+        pub mod crear_etiqueta;
+        pub mod crear_fork;
+        pub mod crear_issue;
         pub mod crear_pull_request;
+        pub mod crear_repositorio;
+        pub mod crear_revision;
+        pub mod crear_webhook;
+        pub mod eliminar_comentario;
+        pub mod eliminar_repositorio;
+        pub mod listar_comentarios;
+        pub mod listar_commits;
+        pub mod listar_etiquetas;
+        pub mod listar_issues;
         pub mod listar_pull_request;
+        pub mod listar_ramas;
+        pub mod listar_repositorios;
+        pub mod listar_revisiones;
+        pub mod listar_tags;
+        pub mod listar_webhooks;
         pub mod mensaje_servidor;
         pub mod mergear_pull_request;
+        pub mod obtener_arbol;
+        pub mod obtener_archivos_pull_request;
+        pub mod obtener_blob;
         pub mod obtener_commits_pull_request;
+        pub mod obtener_contenido;
+        pub mod obtener_estado_commit;
+        pub mod obtener_estado_merge;
+        pub mod obtener_issue;
         pub mod obtener_pull_request;
+        pub mod respuesta;
     }
 }
 pub mod tipos_de_dato {
@@ -24,6 +68,7 @@ pub mod tipos_de_dato {
     pub mod conflicto;
     pub mod date;
     pub mod diffgrid;
+    pub mod indice_pack;
     pub mod info_ramas;
     pub mod lado_conflicto;
     pub mod logger;
@@ -33,9 +78,11 @@ pub mod tipos_de_dato {
     pub mod referencia;
     pub mod referencia_commit;
     pub mod region;
+    pub mod repositorio;
     pub mod respuesta_pedido;
     pub mod tipo_de_rama;
     pub mod tipo_diff;
+    pub mod transaccion_refs;
     pub mod variante_comando_remote;
     pub mod visualizaciones;
     pub mod objetos {
@@ -50,6 +97,8 @@ pub mod tipos_de_dato {
         pub mod error;
         pub mod estado;
         pub mod metodos;
+        pub mod paginacion;
+        pub mod permiso;
         pub mod request;
         pub mod response;
         pub mod tipo_contenido;
@@ -63,16 +112,20 @@ pub mod tipos_de_dato {
         pub mod clone;
         pub mod commit;
         pub mod fetch;
+        pub mod fsck;
+        pub mod gc;
         pub mod hash_object;
         pub mod init;
         pub mod log;
         pub mod ls_files;
         pub mod ls_tree;
         pub mod merge;
+        pub mod prune;
         pub mod pull;
         pub mod push;
         pub mod rebase;
         pub mod remote;
+        pub mod repack;
         pub mod rm;
         pub mod set_upstream;
         pub mod show_ref;
@@ -85,16 +138,23 @@ pub mod tipos_de_dato {
 pub mod gui;
 
 pub mod utils {
+    pub mod alcanzabilidad;
+    pub mod archivo_lock;
+    pub mod base64;
     pub mod compresion;
     pub mod fase_descubrimiento;
     pub mod gir_config;
+    pub mod girignore;
+    pub mod hooks;
     pub mod index;
     pub mod io;
     pub mod objects;
+    pub mod packs;
     pub mod path_buf;
     pub mod ramas;
     pub mod referencia;
     pub mod strings;
     pub mod tags;
     pub mod testing;
+    pub mod ubicacion_repositorio;
 }
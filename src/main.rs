@@ -3,15 +3,24 @@ use std::sync::Arc;
 
 use gir::{
     tipos_de_dato::{comando::Comando, logger::Logger},
-    utils::gir_config::conseguir_ubicacion_log_config,
+    utils::{gir_config::conseguir_ubicacion_log_config, ubicacion_repositorio},
 };
 
 fn main() -> Result<(), String> {
-    let logger = Arc::new(Logger::new(conseguir_ubicacion_log_config()?)?);
-
     let mut argv = args().collect::<Vec<String>>();
     argv.remove(0);
 
+    ubicacion_repositorio::aplicar_opciones_globales(&mut argv)?;
+    let repositorio = match ubicacion_repositorio::entrar_al_repositorio(&mut argv) {
+        Ok(repositorio) => repositorio,
+        Err(err) => {
+            println!("ERROR: {}\n", err);
+            return Ok(());
+        }
+    };
+
+    let logger = Arc::new(Logger::new(conseguir_ubicacion_log_config()?)?);
+
     if argv.is_empty() {
         return Err("Ningun comando ingresado".to_string());
     }
@@ -27,7 +36,7 @@ fn main() -> Result<(), String> {
         return Ok(());
     }
 
-    let mut comando = match Comando::new(argv, logger.clone()) {
+    let mut comando = match Comando::new(argv, repositorio, logger.clone()) {
         Ok(comando) => comando,
         Err(err) => {
             println!("ERROR: {}\n", err);
@@ -0,0 +1,291 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::tipos_de_dato::{
+    comandos::{cat_file, merge::Merge, write_tree},
+    logger::Logger,
+    objeto::Objeto,
+    objetos::{commit::CommitObj, tree::Tree},
+    tipo_diff::TipoDiff,
+};
+
+/// Lineas sin cambios que se muestran antes y despues de cada cambio en el patch
+const LINEAS_DE_CONTEXTO: usize = 3;
+
+/// Archivo que cambia entre dos commits, con su diff en formato unificado
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ArchivoCambiado {
+    pub ruta: String,
+    /// `added`, `removed` o `modified`
+    pub estado: String,
+    /// Cantidad de lineas agregadas
+    pub adiciones: usize,
+    /// Cantidad de lineas eliminadas
+    pub eliminaciones: usize,
+    /// Los hunks del diff unificado (`@@ -a,b +c,d @@` y sus lineas), sin los encabezados
+    pub patch: String,
+}
+
+impl ArchivoCambiado {
+    /// Devuelve los archivos que cambian del commit `hash_commit_viejo` al commit
+    /// `hash_commit_nuevo`, ordenados por ruta. Se tiene que llamar dentro del repositorio.
+    pub fn comparar_commits(
+        hash_commit_viejo: &str,
+        hash_commit_nuevo: &str,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<ArchivoCambiado>, String> {
+        let archivos_viejos = obtener_archivos_commit(hash_commit_viejo, logger.clone())?;
+        let archivos_nuevos = obtener_archivos_commit(hash_commit_nuevo, logger)?;
+        Self::comparar_archivos(&archivos_viejos, &archivos_nuevos)
+    }
+
+    /// Devuelve los archivos que cambia el commit respecto de su primer padre. En el primer
+    /// commit todos sus archivos son nuevos. Se tiene que llamar dentro del repositorio.
+    pub fn comparar_con_padre(
+        commit: &CommitObj,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<ArchivoCambiado>, String> {
+        let archivos_viejos = match commit.padres.first() {
+            Some(padre) => obtener_archivos_commit(padre, logger.clone())?,
+            None => BTreeMap::new(),
+        };
+        let archivos_nuevos = obtener_archivos_commit(&commit.hash, logger)?;
+        Self::comparar_archivos(&archivos_viejos, &archivos_nuevos)
+    }
+
+    fn comparar_archivos(
+        archivos_viejos: &BTreeMap<String, String>,
+        archivos_nuevos: &BTreeMap<String, String>,
+    ) -> Result<Vec<ArchivoCambiado>, String> {
+        let rutas: BTreeSet<&String> = archivos_viejos
+            .keys()
+            .chain(archivos_nuevos.keys())
+            .collect();
+
+        let mut archivos_cambiados = Vec::new();
+        for ruta in rutas {
+            let hash_viejo = archivos_viejos.get(ruta);
+            let hash_nuevo = archivos_nuevos.get(ruta);
+            let estado = match (hash_viejo, hash_nuevo) {
+                (Some(viejo), Some(nuevo)) if viejo == nuevo => continue,
+                (None, _) => "added",
+                (_, None) => "removed",
+                _ => "modified",
+            };
+
+            let contenido_viejo = obtener_contenido(hash_viejo)?;
+            let contenido_nuevo = obtener_contenido(hash_nuevo)?;
+            let (patch, adiciones, eliminaciones) =
+                generar_patch(&contenido_viejo, &contenido_nuevo);
+            archivos_cambiados.push(ArchivoCambiado {
+                ruta: ruta.to_string(),
+                estado: estado.to_string(),
+                adiciones,
+                eliminaciones,
+                patch,
+            });
+        }
+        Ok(archivos_cambiados)
+    }
+
+    /// Devuelve el diff del archivo con sus encabezados, como lo muestra `git diff`
+    pub fn obtener_diff(&self) -> String {
+        let mut diff = format!("diff --git a/{ruta} b/{ruta}\n", ruta = self.ruta);
+        let (origen, destino) = match self.estado.as_str() {
+            "added" => {
+                diff.push_str("new file mode 100644\n");
+                ("/dev/null".to_string(), format!("b/{}", self.ruta))
+            }
+            "removed" => {
+                diff.push_str("deleted file mode 100644\n");
+                (format!("a/{}", self.ruta), "/dev/null".to_string())
+            }
+            _ => (format!("a/{}", self.ruta), format!("b/{}", self.ruta)),
+        };
+        diff.push_str(&format!("--- {origen}\n+++ {destino}\n"));
+        diff.push_str(&self.patch);
+        diff
+    }
+}
+
+/// Devuelve las rutas de los archivos del commit junto al hash de su blob
+fn obtener_archivos_commit(
+    hash_commit: &str,
+    logger: Arc<Logger>,
+) -> Result<BTreeMap<String, String>, String> {
+    let hash_arbol = write_tree::conseguir_arbol(hash_commit)?;
+    let arbol = Tree::from_hash(&hash_arbol, PathBuf::from("."), logger)?;
+
+    let mut archivos = BTreeMap::new();
+    for objeto in arbol.obtener_objetos_hoja() {
+        if let Objeto::Blob(blob) = objeto {
+            archivos.insert(blob.ubicacion.to_string_lossy().to_string(), blob.hash);
+        }
+    }
+    Ok(archivos)
+}
+
+fn obtener_contenido(hash: Option<&String>) -> Result<String, String> {
+    match hash {
+        Some(hash) => Ok(cat_file::obtener_contenido_objeto(hash)?.1),
+        None => Ok(String::new()),
+    }
+}
+
+/// Genera los hunks del diff unificado entre dos versiones de un archivo.
+/// Devuelve el patch junto a la cantidad de lineas agregadas y eliminadas.
+pub fn generar_patch(contenido_viejo: &str, contenido_nuevo: &str) -> (String, usize, usize) {
+    let lineas = obtener_lineas_diff(contenido_viejo, contenido_nuevo);
+    let adiciones = lineas
+        .iter()
+        .filter(|linea| matches!(linea, TipoDiff::Added(_)))
+        .count();
+    let eliminaciones = lineas
+        .iter()
+        .filter(|linea| matches!(linea, TipoDiff::Removed(_)))
+        .count();
+
+    let mut patch = String::new();
+    for (inicio, fin) in agrupar_en_hunks(&lineas) {
+        let lineas_viejas_previas = lineas[..inicio]
+            .iter()
+            .filter(|linea| !matches!(linea, TipoDiff::Added(_)))
+            .count();
+        let lineas_nuevas_previas = lineas[..inicio]
+            .iter()
+            .filter(|linea| !matches!(linea, TipoDiff::Removed(_)))
+            .count();
+        let hunk = &lineas[inicio..fin];
+        let cantidad_viejas = hunk
+            .iter()
+            .filter(|linea| !matches!(linea, TipoDiff::Added(_)))
+            .count();
+        let cantidad_nuevas = hunk
+            .iter()
+            .filter(|linea| !matches!(linea, TipoDiff::Removed(_)))
+            .count();
+
+        patch.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            obtener_inicio_rango(lineas_viejas_previas, cantidad_viejas),
+            cantidad_viejas,
+            obtener_inicio_rango(lineas_nuevas_previas, cantidad_nuevas),
+            cantidad_nuevas
+        ));
+        for linea in hunk {
+            let (prefijo, texto) = match linea {
+                TipoDiff::Added(texto) => ('+', texto),
+                TipoDiff::Removed(texto) => ('-', texto),
+                TipoDiff::Unchanged(texto) => (' ', texto),
+            };
+            patch.push_str(&format!("{prefijo}{texto}\n"));
+        }
+    }
+    (patch, adiciones, eliminaciones)
+}
+
+/// Calcula el diff linea por linea. `Merge::obtener_diff` recorta los espacios de las lineas,
+/// asi que se usa solo para saber el orden de los cambios y se recuperan las lineas originales.
+fn obtener_lineas_diff(contenido_viejo: &str, contenido_nuevo: &str) -> Vec<TipoDiff> {
+    let lineas_viejas: Vec<&str> = contenido_viejo.lines().collect();
+    let lineas_nuevas: Vec<&str> = contenido_nuevo.lines().collect();
+    let (mut vieja, mut nueva) = (0, 0);
+
+    Merge::obtener_diff(lineas_viejas.clone(), lineas_nuevas.clone())
+        .into_iter()
+        .map(|(_, diff)| match diff {
+            TipoDiff::Added(_) => {
+                nueva += 1;
+                TipoDiff::Added(lineas_nuevas[nueva - 1].to_string())
+            }
+            TipoDiff::Removed(_) => {
+                vieja += 1;
+                TipoDiff::Removed(lineas_viejas[vieja - 1].to_string())
+            }
+            TipoDiff::Unchanged(_) => {
+                vieja += 1;
+                nueva += 1;
+                TipoDiff::Unchanged(lineas_viejas[vieja - 1].to_string())
+            }
+        })
+        .collect()
+}
+
+/// Devuelve los rangos `[inicio, fin)` de lineas de cada hunk: los cambios con sus lineas de
+/// contexto, juntando los cambios cuyo contexto se superpone
+fn agrupar_en_hunks(lineas: &[TipoDiff]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, linea) in lineas.iter().enumerate() {
+        if let TipoDiff::Unchanged(_) = linea {
+            continue;
+        }
+        let inicio = i.saturating_sub(LINEAS_DE_CONTEXTO);
+        let fin = (i + 1 + LINEAS_DE_CONTEXTO).min(lineas.len());
+        match hunks.last_mut() {
+            Some((_, fin_anterior)) if inicio <= *fin_anterior => *fin_anterior = fin,
+            _ => hunks.push((inicio, fin)),
+        }
+    }
+    hunks
+}
+
+// como en git, un rango vacio indica la linea anterior a la posicion del cambio
+fn obtener_inicio_rango(lineas_previas: usize, cantidad: usize) -> usize {
+    if cantidad == 0 {
+        lineas_previas
+    } else {
+        lineas_previas + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test01_el_patch_muestra_los_cambios_con_su_contexto() {
+        let viejo = "uno\ndos\ntres\ncuatro\ncinco\nseis\nsiete\nocho\nnueve\n";
+        let nuevo = "uno\ndos\ntres\ncuatro\n    cinco!\nseis\nsiete\nocho\nnueve\ndiez\n";
+
+        let (patch, adiciones, eliminaciones) = generar_patch(viejo, nuevo);
+
+        assert_eq!(
+            patch,
+            "@@ -2,8 +2,9 @@\n dos\n tres\n cuatro\n-cinco\n+    cinco!\n seis\n siete\n ocho\n nueve\n+diez\n"
+        );
+        assert_eq!((adiciones, eliminaciones), (2, 1));
+    }
+
+    #[test]
+    fn test02_los_cambios_lejanos_van_en_hunks_separados() {
+        let viejo = (1..=20).map(|n| format!("{n}\n")).collect::<String>();
+        let nuevo = (1..=20)
+            .filter(|n| *n != 19)
+            .map(|n| {
+                if n == 2 {
+                    "dos\n".to_string()
+                } else {
+                    format!("{n}\n")
+                }
+            })
+            .collect::<String>();
+
+        let (patch, _, _) = generar_patch(&viejo, &nuevo);
+
+        assert!(patch.starts_with("@@ -1,5 +1,5 @@\n 1\n-2\n+dos\n 3\n"));
+        assert!(patch.contains("@@ -16,5 +16,4 @@\n 16\n 17\n 18\n-19\n 20\n"));
+    }
+
+    #[test]
+    fn test03_un_archivo_nuevo_tiene_un_rango_vacio_en_la_version_vieja() {
+        let (patch, adiciones, eliminaciones) = generar_patch("", "hola\n");
+
+        assert_eq!(patch, "@@ -0,0 +1,1 @@\n+hola\n");
+        assert_eq!((adiciones, eliminaciones), (1, 0));
+    }
+}
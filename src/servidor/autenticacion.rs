@@ -0,0 +1,403 @@
+use std::{collections::HashMap, fs::File, io::Read, path::Path};
+
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::{
+    tipos_de_dato::http::{error::ErrorHttp, permiso::Permiso, request::Request},
+    utils::{base64, gir_config, io},
+};
+
+/// Archivo del servidor con los usuarios, sus contraseñas y sus tokens
+pub const ARCHIVO_USUARIOS: &str = "srv/usuarios.json";
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con los permisos de
+/// los usuarios sobre el repositorio (Ej: `srv/repo/.gir/permisos.json`)
+pub const ARCHIVO_PERMISOS: &str = "permisos.json";
+
+/// Cantidad de veces que se aplica sha1 al hashear un secreto, para que probar contraseñas
+/// por fuerza bruta sea caro
+const ITERACIONES_HASH: usize = 1000;
+/// Cantidad de bytes aleatorios de la sal de cada usuario
+const LARGO_SAL: usize = 16;
+/// Cantidad de bytes aleatorios de los tokens que genera el servidor
+const LARGO_TOKEN: usize = 20;
+
+/// Usuario del servidor. La contraseña y los tokens se guardan hasheados con la sal del
+/// usuario (ver `hashear_secreto`), nunca en texto plano.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Usuario {
+    pub nombre: String,
+    pub sal: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contrasenia: Option<String>,
+    #[serde(default)]
+    pub tokens: Vec<String>,
+}
+
+impl Usuario {
+    /// Crea un usuario con una sal nueva y su contraseña y sus tokens hasheados con ella
+    pub fn new(
+        nombre: &str,
+        contrasenia: Option<&str>,
+        tokens: &[&str],
+    ) -> Result<Usuario, String> {
+        let sal = generar_sal()?;
+        Ok(Usuario {
+            nombre: nombre.to_string(),
+            contrasenia: contrasenia.map(|contrasenia| hashear_secreto(contrasenia, &sal)),
+            tokens: tokens
+                .iter()
+                .map(|token| hashear_secreto(token, &sal))
+                .collect(),
+            sal,
+        })
+    }
+
+    fn verificar_contrasenia(&self, contrasenia: &str) -> bool {
+        let hash = self.hashear(contrasenia);
+        self.contrasenia
+            .as_ref()
+            .is_some_and(|guardado| son_iguales(guardado.as_bytes(), hash.as_bytes()))
+    }
+
+    fn verificar_token(&self, token: &str) -> bool {
+        let hash = self.hashear(token);
+        // se comparan todos los tokens para que el tiempo no dependa de cual coincide
+        self.tokens.iter().fold(false, |coincide, guardado| {
+            son_iguales(guardado.as_bytes(), hash.as_bytes()) | coincide
+        })
+    }
+
+    fn hashear(&self, secreto: &str) -> String {
+        hashear_secreto(secreto, &self.sal)
+    }
+}
+
+/// Permisos sobre un repositorio. `publico` es el permiso de cualquiera, incluso sin
+/// autenticarse, y `usuarios` el de cada usuario (si es mayor que el publico).
+///
+/// Por ejemplo: `{"publico": "read", "usuarios": {"juani": "admin", "messi": "write"}}`
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct PermisosRepositorio {
+    #[serde(default)]
+    pub publico: Permiso,
+    #[serde(default)]
+    pub usuarios: HashMap<String, Permiso>,
+}
+
+/// Devuelve el hash con el que se guarda una contraseña o un token en el archivo de usuarios:
+/// sha1 de la sal y el secreto, aplicado `ITERACIONES_HASH` veces
+pub fn hashear_secreto(secreto: &str, sal: &str) -> String {
+    let mut hash = sha1_hexa(format!("{sal}{secreto}").as_bytes());
+    for _ in 1..ITERACIONES_HASH {
+        hash = sha1_hexa(format!("{sal}{hash}{secreto}").as_bytes());
+    }
+    hash
+}
+
+/// Devuelve una sal aleatoria nueva, en hexadecimal
+pub fn generar_sal() -> Result<String, String> {
+    generar_hexa_aleatorio(LARGO_SAL).map_err(|e| format!("No se pudo generar la sal: {e}"))
+}
+
+/// Devuelve un token aleatorio nuevo, en hexadecimal
+pub fn generar_token() -> Result<String, String> {
+    generar_hexa_aleatorio(LARGO_TOKEN).map_err(|e| format!("No se pudo generar el token: {e}"))
+}
+
+fn generar_hexa_aleatorio(largo: usize) -> std::io::Result<String> {
+    let mut bytes = vec![0; largo];
+    File::open("/dev/urandom").and_then(|mut aleatorio| aleatorio.read_exact(&mut bytes))?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Da de alta a `nombre` en el archivo de usuarios, o lo actualiza si ya existe: le genera un
+/// token nuevo y, si se da, le cambia la contraseña. Devuelve el token en texto plano, que es
+/// la unica vez que se puede ver: en el archivo solo se guarda su hash.
+///
+/// ## Errores
+/// - Si el nombre es vacio o tiene `:` (no se podria autenticar con `Basic`)
+/// - Si no se puede leer o escribir el archivo de usuarios
+pub fn provisionar_usuario(
+    archivo_usuarios: &Path,
+    nombre: &str,
+    contrasenia: Option<&str>,
+) -> Result<String, String> {
+    if nombre.is_empty() || nombre.contains(':') {
+        return Err(format!("Nombre de usuario invalido: {nombre}"));
+    }
+    let mut usuarios = cargar_usuarios(archivo_usuarios).map_err(|e| e.to_string())?;
+    let token = generar_token()?;
+
+    match usuarios.iter_mut().find(|usuario| usuario.nombre == nombre) {
+        Some(usuario) => {
+            if let Some(contrasenia) = contrasenia {
+                usuario.contrasenia = Some(usuario.hashear(contrasenia));
+            }
+            let hash_token = usuario.hashear(&token);
+            usuario.tokens.push(hash_token);
+        }
+        None => usuarios.push(Usuario::new(nombre, contrasenia, &[&token])?),
+    }
+
+    let contenido = serde_json::to_string(&usuarios)
+        .map_err(|e| format!("No se pudieron serializar los usuarios: {e}"))?;
+    io::escribir_bytes(archivo_usuarios, contenido)?;
+    Ok(token)
+}
+
+fn sha1_hexa(contenido: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(contenido);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Compara en tiempo constante, para no revelar cuantos bytes coinciden
+fn son_iguales(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .fold(0, |diferencia, (x, y)| diferencia | (x ^ y))
+            == 0
+}
+
+/// Devuelve el usuario que hizo el pedido segun su header `Authorization`, que puede ser
+/// `Basic <base64 de usuario:contraseña>` o `Bearer <token>` (tambien `token <token>`, como en github).
+/// Si el pedido no tiene el header, devuelve None: el pedido es anonimo.
+///
+/// ## Errores
+/// - Si las credenciales no son validas o el esquema no es conocido
+pub fn autenticar(request: &Request, archivo_usuarios: &Path) -> Result<Option<String>, ErrorHttp> {
+    let autorizacion = match request.obtener_header("Authorization") {
+        Some(autorizacion) => autorizacion,
+        None => return Ok(None),
+    };
+    let (esquema, credenciales) = autorizacion
+        .split_once(' ')
+        .ok_or_else(|| ErrorHttp::Unauthorized("Header Authorization invalido".to_string()))?;
+
+    let usuarios = cargar_usuarios(archivo_usuarios)?;
+    let credenciales = credenciales.trim();
+    let usuario = match esquema.to_ascii_lowercase().as_str() {
+        "basic" => {
+            let (nombre, contrasenia) = decodificar_basic(credenciales)?;
+            usuarios.into_iter().find(|usuario| {
+                usuario.nombre == nombre && usuario.verificar_contrasenia(&contrasenia)
+            })
+        }
+        "bearer" | "token" => usuarios
+            .into_iter()
+            .find(|usuario| usuario.verificar_token(credenciales)),
+        _ => {
+            return Err(ErrorHttp::Unauthorized(format!(
+                "Esquema de autenticacion {} no soportado",
+                esquema
+            )))
+        }
+    };
+
+    match usuario {
+        Some(usuario) => Ok(Some(usuario.nombre)),
+        None => Err(ErrorHttp::Unauthorized(
+            "Credenciales invalidas".to_string(),
+        )),
+    }
+}
+
+/// Devuelve el permiso de `usuario` (o de un pedido anonimo si es None) sobre el repositorio
+/// cuyo directorio gir es `dir_gir`. En los repositorios sin archivo de permisos (Ej: los
+/// creados con un push) todos tienen el permiso por defecto (ver `obtener_permiso_por_defecto`).
+pub fn obtener_permiso(dir_gir: &Path, usuario: Option<&str>) -> Result<Permiso, ErrorHttp> {
+    let archivo = dir_gir.join(ARCHIVO_PERMISOS);
+    if !io::existe(&archivo) {
+        return Ok(obtener_permiso_por_defecto());
+    }
+
+    let contenido = io::leer_a_string(&archivo).map_err(ErrorHttp::InternalServerError)?;
+    let permisos: PermisosRepositorio = serde_json::from_str(&contenido).map_err(|e| {
+        ErrorHttp::InternalServerError(format!(
+            "El archivo de permisos {} no es valido: {}",
+            archivo.display(),
+            e
+        ))
+    })?;
+
+    let permiso_usuario = usuario
+        .and_then(|usuario| permisos.usuarios.get(usuario))
+        .copied()
+        .unwrap_or_default();
+    Ok(permisos.publico.max(permiso_usuario))
+}
+
+/// Devuelve el permiso sobre los repositorios sin archivo de permisos, configurado con
+/// `permiso_por_defecto` (por defecto `read`). Nunca es mas que `read`: para escribir en un
+/// repositorio (o administrarlo) hay que darle el permiso a un usuario, o al publico, en su
+/// archivo de permisos.
+fn obtener_permiso_por_defecto() -> Permiso {
+    gir_config::conseguir_permiso_por_defecto()
+        .and_then(|permiso| serde_json::from_value(serde_json::Value::String(permiso)).ok())
+        .unwrap_or(Permiso::Lectura)
+        .min(Permiso::Lectura)
+}
+
+/// Verifica que `usuario` tenga al menos el permiso `requerido` sobre el repositorio.
+///
+/// ## Errores
+/// - `Unauthorized` si el pedido es anonimo y autenticandose podria tener permiso
+/// - `Forbidden` si el usuario autenticado no tiene permiso
+pub fn verificar_permiso(
+    dir_gir: &Path,
+    usuario: Option<&str>,
+    requerido: Permiso,
+) -> Result<(), ErrorHttp> {
+    if obtener_permiso(dir_gir, usuario)? >= requerido {
+        return Ok(());
+    }
+    match usuario {
+        Some(usuario) => Err(ErrorHttp::Forbidden(format!(
+            "El usuario {} no tiene permiso para esta operacion",
+            usuario
+        ))),
+        None => Err(ErrorHttp::Unauthorized(
+            "Se requiere autenticacion para esta operacion".to_string(),
+        )),
+    }
+}
+
+fn cargar_usuarios(archivo_usuarios: &Path) -> Result<Vec<Usuario>, ErrorHttp> {
+    if !io::existe(archivo_usuarios) {
+        return Ok(Vec::new());
+    }
+    let contenido = io::leer_a_string(archivo_usuarios).map_err(ErrorHttp::InternalServerError)?;
+    serde_json::from_str(&contenido).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("El archivo de usuarios no es valido: {}", e))
+    })
+}
+
+/// Decodifica las credenciales de `Basic`: `usuario:contraseña` en base64
+fn decodificar_basic(credenciales: &str) -> Result<(String, String), ErrorHttp> {
+    let invalidas = || ErrorHttp::Unauthorized("Credenciales Basic invalidas".to_string());
+    let decodificadas = base64::decodificar(credenciales).ok_or_else(invalidas)?;
+    let decodificadas = String::from_utf8(decodificadas).map_err(|_| invalidas())?;
+    let (usuario, contrasenia) = decodificadas.split_once(':').ok_or_else(invalidas)?;
+    Ok((usuario.to_string(), contrasenia.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::BufReader, path::PathBuf, sync::Arc};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{tipos_de_dato::logger::Logger, utils::testing::MockTcpStream};
+
+    fn request_con_autorizacion(autorizacion: &str) -> Request {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/autenticacion_test")).unwrap());
+        let contenido = format!(
+            "GET /repos/repo/pulls HTTP/1.1\r\nAuthorization: {}\r\n\r\n",
+            autorizacion
+        );
+        let mut mock = MockTcpStream {
+            lectura_data: contenido.into_bytes(),
+            escritura_data: vec![],
+        };
+        Request::from(&mut BufReader::new(&mut mock), logger).unwrap()
+    }
+
+    fn escribir_usuarios(archivo: &Path) {
+        let usuarios = vec![Usuario::new("juani", Some("secreto"), &["token123"]).unwrap()];
+        io::escribir_bytes(archivo, serde_json::to_string(&usuarios).unwrap()).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test01_se_autentica_con_basic_y_con_token() {
+        let archivo = PathBuf::from("tmp/autenticacion_test01/usuarios.json");
+        escribir_usuarios(&archivo);
+
+        // "juani:secreto" en base64
+        let basic = request_con_autorizacion("Basic anVhbmk6c2VjcmV0bw==");
+        let bearer = request_con_autorizacion("Bearer token123");
+        let incorrecta = request_con_autorizacion("Basic anVhbmk6b3Rybw==");
+
+        assert_eq!(
+            autenticar(&basic, &archivo).unwrap(),
+            Some("juani".to_string())
+        );
+        assert_eq!(
+            autenticar(&bearer, &archivo).unwrap(),
+            Some("juani".to_string())
+        );
+        assert!(matches!(
+            autenticar(&incorrecta, &archivo),
+            Err(ErrorHttp::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test02_los_permisos_se_verifican_por_repositorio() {
+        let dir_gir = PathBuf::from("tmp/autenticacion_test02/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+
+        assert!(verificar_permiso(&dir_gir, None, Permiso::Lectura).is_ok());
+        assert!(matches!(
+            verificar_permiso(&dir_gir, None, Permiso::Escritura),
+            Err(ErrorHttp::Unauthorized(_))
+        ));
+
+        io::escribir_bytes(
+            dir_gir.join(ARCHIVO_PERMISOS),
+            r#"{"publico": "read", "usuarios": {"juani": "write"}}"#,
+        )
+        .unwrap();
+
+        assert!(verificar_permiso(&dir_gir, None, Permiso::Lectura).is_ok());
+        assert!(matches!(
+            verificar_permiso(&dir_gir, None, Permiso::Escritura),
+            Err(ErrorHttp::Unauthorized(_))
+        ));
+        assert!(verificar_permiso(&dir_gir, Some("juani"), Permiso::Escritura).is_ok());
+        assert!(matches!(
+            verificar_permiso(&dir_gir, Some("juani"), Permiso::Admin),
+            Err(ErrorHttp::Forbidden(_))
+        ));
+        assert!(matches!(
+            verificar_permiso(&dir_gir, Some("otro"), Permiso::Escritura),
+            Err(ErrorHttp::Forbidden(_))
+        ));
+    }
+
+    #[test]
+    fn test03_los_secretos_se_guardan_con_la_sal_de_cada_usuario() {
+        let juani = Usuario::new("juani", Some("secreto"), &["token123"]).unwrap();
+        let otro = Usuario::new("otro", Some("secreto"), &[]).unwrap();
+
+        assert_ne!(juani.sal, otro.sal);
+        assert_ne!(juani.contrasenia, otro.contrasenia);
+        assert!(!juani.contrasenia.as_ref().unwrap().contains("secreto"));
+        assert!(juani.verificar_contrasenia("secreto"));
+        assert!(!juani.verificar_contrasenia("secret"));
+        assert!(juani.verificar_token("token123") && !juani.verificar_token("token12"));
+    }
+
+    #[test]
+    #[serial]
+    fn test04_se_provisionan_usuarios_con_tokens_nuevos() {
+        let archivo = PathBuf::from("tmp/autenticacion_test04/usuarios.json");
+        let _ = std::fs::remove_file(&archivo);
+
+        let primer_token = provisionar_usuario(&archivo, "juani", Some("secreto")).unwrap();
+        let segundo_token = provisionar_usuario(&archivo, "juani", None).unwrap();
+
+        let usuarios = cargar_usuarios(&archivo).unwrap();
+        assert_eq!(usuarios.len(), 1);
+        assert_ne!(primer_token, segundo_token);
+        assert!(!io::leer_a_string(&archivo).unwrap().contains(&primer_token));
+        assert!(usuarios[0].verificar_token(&primer_token));
+        assert!(usuarios[0].verificar_token(&segundo_token));
+        assert!(usuarios[0].verificar_contrasenia("secreto"));
+        assert!(provisionar_usuario(&archivo, "juani:otro", None).is_err());
+    }
+}
@@ -0,0 +1,290 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{tipos_de_dato::http::error::ErrorHttp, utils::io};
+
+/// Comentario sobre un pull request. Puede ser un comentario general de la conversacion o un
+/// comentario de revision, anclado a una linea de un archivo en un commit.
+///
+/// Cada comentario se guarda en `./srv/{repositorio}/comentarios/{id}`, al lado de los pull
+/// requests de `./srv/{repositorio}/pulls`. El id es unico en el repositorio.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Comentario {
+    pub id: u64,
+    /// Numero del pull request comentado
+    pub numero: u64,
+    pub autor: String,
+    pub cuerpo: String,
+    /// Archivo comentado. Solo lo tienen los comentarios de revision
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub ruta: Option<String>,
+    /// Commit en el que se hizo el comentario de revision
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub commit: Option<String>,
+    /// Linea del archivo comentada
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub linea: Option<usize>,
+    pub fecha_creacion: String,
+    pub fecha_modificacion: String,
+}
+
+impl Comentario {
+    /// Crea un comentario general en el pull request `numero` a partir del body de la request.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener el campo `body` con el texto del comentario
+    ///
+    /// ## Errores
+    /// - Si falta el campo `body`
+    pub fn crear(
+        repositorio: &str,
+        numero: u64,
+        autor: &str,
+        body: &HashMap<String, String>,
+    ) -> Result<Comentario, ErrorHttp> {
+        let cuerpo = obtener_campo(body, "body")?;
+        let fecha_actual = Utc::now().to_rfc3339();
+
+        Ok(Comentario {
+            id: Self::obtener_id_siguiente(repositorio)?,
+            numero,
+            autor: autor.to_string(),
+            cuerpo,
+            ruta: None,
+            commit: None,
+            linea: None,
+            fecha_creacion: fecha_actual.clone(),
+            fecha_modificacion: fecha_actual,
+        })
+    }
+
+    /// Crea un comentario de revision en el pull request `numero` a partir del body de la request.
+    ///
+    /// ## Argumentos
+    /// - body: ademas de `body`, tiene que tener `path` (el archivo), `commit_id` (el commit
+    ///   revisado) y `line` (la linea del archivo en ese commit)
+    ///
+    /// ## Errores
+    /// - Si falta algun campo o `line` no es un numero
+    pub fn crear_de_revision(
+        repositorio: &str,
+        numero: u64,
+        autor: &str,
+        body: &HashMap<String, String>,
+    ) -> Result<Comentario, ErrorHttp> {
+        let ruta = obtener_campo(body, "path")?;
+        let commit = obtener_campo(body, "commit_id")?;
+        let linea = obtener_campo(body, "line")?.parse::<usize>().map_err(|_| {
+            ErrorHttp::ValidationFailed("El parametro 'line' tiene que ser un numero".to_string())
+        })?;
+
+        let mut comentario = Self::crear(repositorio, numero, autor, body)?;
+        comentario.ruta = Some(ruta);
+        comentario.commit = Some(commit);
+        comentario.linea = Some(linea);
+        Ok(comentario)
+    }
+
+    /// Devuelve si es un comentario de revision (anclado a una linea) o uno general
+    pub fn es_de_revision(&self) -> bool {
+        self.ruta.is_some()
+    }
+
+    /// Actualiza el texto del comentario con el campo `body` de la request.
+    /// Devuelve si se cambio el comentario.
+    pub fn actualizar(&mut self, body: &HashMap<String, String>) -> bool {
+        match body.get("body") {
+            Some(cuerpo) if *cuerpo != self.cuerpo => {
+                self.cuerpo = cuerpo.to_owned();
+                self.fecha_modificacion = Utc::now().to_rfc3339();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Guarda el comentario en el directorio de comentarios del repositorio
+    pub fn guardar(&self, repositorio: &str) -> Result<(), ErrorHttp> {
+        let contenido = serde_json::to_string(&self).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se ha podido serializar el comentario: {e}"))
+        })?;
+        io::escribir_bytes(Self::obtener_dir(repositorio, self.id), contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se ha podido guardar el comentario: {e}"))
+        })
+    }
+
+    /// Carga el comentario `id` del repositorio
+    ///
+    /// ## Errores
+    /// - `NotFound` si no existe el comentario
+    pub fn cargar(repositorio: &str, id: u64) -> Result<Comentario, ErrorHttp> {
+        let direccion = Self::obtener_dir(repositorio, id);
+        if !io::existe(&direccion) {
+            return Err(ErrorHttp::NotFound(format!(
+                "No existe el comentario {id} en el repositorio {repositorio}"
+            )));
+        }
+        let contenido = io::leer_a_string(&direccion).map_err(ErrorHttp::InternalServerError)?;
+        serde_json::from_str(&contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("Fallo al leer el comentario {id}: {e}"))
+        })
+    }
+
+    /// Borra el comentario `id` del repositorio
+    pub fn eliminar(repositorio: &str, id: u64) -> Result<(), ErrorHttp> {
+        Self::cargar(repositorio, id)?;
+        io::rm_directorio(Self::obtener_dir(repositorio, id)).map_err(|e| {
+            ErrorHttp::InternalServerError(format!(
+                "No se ha podido borrar el comentario {id}: {e}"
+            ))
+        })
+    }
+
+    /// Devuelve los comentarios del pull request `numero` ordenados por id, es decir, por
+    /// fecha de creacion. Si `de_revision` es verdadero devuelve los comentarios de revision,
+    /// si no los generales.
+    pub fn listar(
+        repositorio: &str,
+        numero: u64,
+        de_revision: bool,
+    ) -> Result<Vec<Comentario>, ErrorHttp> {
+        let mut comentarios: Vec<Comentario> = Self::cargar_todos(repositorio)?
+            .into_iter()
+            .filter(|comentario| {
+                comentario.numero == numero && comentario.es_de_revision() == de_revision
+            })
+            .collect();
+        comentarios.sort_by_key(|comentario| comentario.id);
+        Ok(comentarios)
+    }
+
+    fn cargar_todos(repositorio: &str) -> Result<Vec<Comentario>, ErrorHttp> {
+        let dir_comentarios = PathBuf::from(format!("./srv/{repositorio}/comentarios"));
+        if !io::existe(&dir_comentarios) {
+            return Ok(Vec::new());
+        }
+
+        let mut comentarios = Vec::new();
+        for entrada in
+            io::leer_directorio(&dir_comentarios).map_err(ErrorHttp::InternalServerError)?
+        {
+            let entrada = entrada.map_err(|e| {
+                ErrorHttp::InternalServerError(format!("Fallo al leer los comentarios: {e}"))
+            })?;
+            if let Some(id) = entrada.file_name().to_str().and_then(|id| id.parse().ok()) {
+                comentarios.push(Self::cargar(repositorio, id)?);
+            }
+        }
+        Ok(comentarios)
+    }
+
+    // el id nuevo es el mayor de los existentes mas uno y no la cantidad de comentarios,
+    // asi borrar un comentario no hace que se repitan ids
+    fn obtener_id_siguiente(repositorio: &str) -> Result<u64, ErrorHttp> {
+        let mayor = Self::cargar_todos(repositorio)?
+            .iter()
+            .map(|comentario| comentario.id)
+            .max()
+            .unwrap_or(0);
+        Ok(mayor + 1)
+    }
+
+    fn obtener_dir(repositorio: &str, id: u64) -> PathBuf {
+        PathBuf::from(format!("./srv/{repositorio}/comentarios/{id}"))
+    }
+}
+
+fn obtener_campo(body: &HashMap<String, String>, campo: &str) -> Result<String, ErrorHttp> {
+    body.get(campo)
+        .map(|valor| valor.to_owned())
+        .ok_or_else(|| {
+            ErrorHttp::ValidationFailed(format!(
+                "Falta el parametro '{campo}' en el body de la request"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    const REPOSITORIO: &str = "comentario_test";
+
+    fn body(campos: &[(&str, &str)]) -> HashMap<String, String> {
+        campos
+            .iter()
+            .map(|(clave, valor)| (clave.to_string(), valor.to_string()))
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn test01_se_listan_los_comentarios_generales_y_de_revision_por_separado() {
+        let _ = io::rm_directorio(format!("./srv/{REPOSITORIO}"));
+        let general =
+            Comentario::crear(REPOSITORIO, 1, "juani", &body(&[("body", "hola")])).unwrap();
+        general.guardar(REPOSITORIO).unwrap();
+        let revision = Comentario::crear_de_revision(
+            REPOSITORIO,
+            1,
+            "messi",
+            &body(&[
+                ("body", "esto no compila"),
+                ("path", "src/main.rs"),
+                ("commit_id", &"a".repeat(40)),
+                ("line", "3"),
+            ]),
+        )
+        .unwrap();
+        revision.guardar(REPOSITORIO).unwrap();
+        let de_otro_pr =
+            Comentario::crear(REPOSITORIO, 2, "juani", &body(&[("body", "chau")])).unwrap();
+        de_otro_pr.guardar(REPOSITORIO).unwrap();
+
+        let generales = Comentario::listar(REPOSITORIO, 1, false).unwrap();
+        let de_revision = Comentario::listar(REPOSITORIO, 1, true).unwrap();
+        io::rm_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+
+        assert_eq!(generales, vec![general]);
+        assert_eq!(de_revision, vec![revision]);
+        assert_eq!(de_revision[0].linea, Some(3));
+        assert_eq!(de_otro_pr.id, 3);
+    }
+
+    #[test]
+    #[serial]
+    fn test02_se_puede_editar_y_borrar_un_comentario() {
+        let _ = io::rm_directorio(format!("./srv/{REPOSITORIO}"));
+        let comentario =
+            Comentario::crear(REPOSITORIO, 1, "juani", &body(&[("body", "hola")])).unwrap();
+        comentario.guardar(REPOSITORIO).unwrap();
+
+        let mut cargado = Comentario::cargar(REPOSITORIO, comentario.id).unwrap();
+        assert!(cargado.actualizar(&body(&[("body", "hola, edite")])));
+        cargado.guardar(REPOSITORIO).unwrap();
+        let editado = Comentario::cargar(REPOSITORIO, comentario.id).unwrap();
+        Comentario::eliminar(REPOSITORIO, comentario.id).unwrap();
+        let borrado = Comentario::cargar(REPOSITORIO, comentario.id);
+        io::rm_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+
+        assert_eq!(editado.cuerpo, "hola, edite");
+        assert!(matches!(borrado, Err(ErrorHttp::NotFound(_))));
+    }
+
+    #[test]
+    #[serial]
+    fn test03_un_comentario_de_revision_necesita_archivo_commit_y_linea() {
+        let sin_linea = Comentario::crear_de_revision(
+            REPOSITORIO,
+            1,
+            "juani",
+            &body(&[("body", "hola"), ("path", "a"), ("commit_id", "b")]),
+        );
+
+        assert!(matches!(sin_linea, Err(ErrorHttp::ValidationFailed(_))));
+    }
+}
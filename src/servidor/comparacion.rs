@@ -0,0 +1,138 @@
+use std::{collections::HashSet, sync::Arc};
+
+use serde::Serialize;
+
+use crate::tipos_de_dato::{
+    comandos::log::Log, http::error::ErrorHttp, logger::Logger, objetos::commit::CommitObj,
+};
+
+use super::{archivo_cambiado::ArchivoCambiado, explorador};
+
+/// Comparacion entre dos referencias del repositorio, como la que muestra un pull request
+/// entre su rama base y su rama head
+#[derive(Serialize, Debug)]
+pub struct Comparacion {
+    /// `identical`, `ahead`, `behind` o `diverged`, segun la posicion de head respecto de base
+    pub estado: String,
+    /// Commit base mas cercano entre las dos referencias
+    pub commit_base: CommitObj,
+    /// Cantidad de commits de head que no estan en base
+    pub commits_adelante: usize,
+    /// Cantidad de commits de base que no estan en head
+    pub commits_atras: usize,
+    /// Commits de head que no estan en base, del mas nuevo al mas viejo
+    pub commits: Vec<CommitObj>,
+    /// Archivos que cambian del commit base a head
+    pub archivos: Vec<ArchivoCambiado>,
+}
+
+impl Comparacion {
+    /// Compara las referencias `base` y `head`, que pueden ser ramas, tags o hashes de
+    /// commits. Se tiene que llamar dentro del repositorio.
+    ///
+    /// ## Errores
+    /// - `NotFound` si alguna referencia no existe o no tienen historia en comun
+    pub fn new(base: &str, head: &str, logger: Arc<Logger>) -> Result<Comparacion, ErrorHttp> {
+        let hash_base = explorador::resolver_referencia(Some(base))?;
+        let hash_head = explorador::resolver_referencia(Some(head))?;
+        let commits_base = Self::obtener_historia(&hash_base, logger.clone())
+            .map_err(ErrorHttp::InternalServerError)?;
+        let commits_head = Self::obtener_historia(&hash_head, logger.clone())
+            .map_err(ErrorHttp::InternalServerError)?;
+
+        let hashes_base: HashSet<&str> = commits_base.iter().map(|c| c.hash.as_str()).collect();
+        let hashes_head: HashSet<&str> = commits_head.iter().map(|c| c.hash.as_str()).collect();
+        let commit_base = commits_base
+            .iter()
+            .find(|commit| hashes_head.contains(commit.hash.as_str()))
+            .cloned()
+            .ok_or_else(|| {
+                ErrorHttp::NotFound(format!("{base} y {head} no tienen un commit en comun"))
+            })?;
+
+        let commits: Vec<CommitObj> = commits_head
+            .iter()
+            .filter(|commit| !hashes_base.contains(commit.hash.as_str()))
+            .cloned()
+            .collect();
+        let commits_atras = commits_base
+            .iter()
+            .filter(|commit| !hashes_head.contains(commit.hash.as_str()))
+            .count();
+        let archivos = ArchivoCambiado::comparar_commits(&commit_base.hash, &hash_head, logger)
+            .map_err(ErrorHttp::InternalServerError)?;
+
+        Ok(Comparacion {
+            estado: Self::obtener_estado(commits.len(), commits_atras).to_string(),
+            commit_base,
+            commits_adelante: commits.len(),
+            commits_atras,
+            commits,
+            archivos,
+        })
+    }
+
+    fn obtener_historia(hash_commit: &str, logger: Arc<Logger>) -> Result<Vec<CommitObj>, String> {
+        let commit = CommitObj::from_hash(hash_commit.to_string(), logger.clone())?;
+        Log::obtener_listas_de_commits(commit, logger)
+    }
+
+    fn obtener_estado(commits_adelante: usize, commits_atras: usize) -> &'static str {
+        match (commits_adelante, commits_atras) {
+            (0, 0) => "identical",
+            (_, 0) => "ahead",
+            (0, _) => "behind",
+            _ => "diverged",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use crate::utils::{
+        io,
+        testing::{addear_archivos_y_comittear, escribir_rama_local, limpiar_archivo_gir},
+    };
+
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn test01_se_comparan_dos_ramas_con_historia_en_comun() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/comparacion_test01")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let archivo = "tmp/comparacion_test01_archivo".to_string();
+        io::escribir_bytes(&archivo, "uno\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+        let hash_primer_commit = explorador::resolver_referencia(None).unwrap();
+        escribir_rama_local("rama", logger.clone());
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        io::escribir_bytes(&archivo, "uno\ndos\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+
+        let adelante = Comparacion::new("rama", "master", logger.clone()).unwrap();
+        let atras = Comparacion::new("master", "rama", logger.clone()).unwrap();
+        let identica = Comparacion::new("rama", &hash_primer_commit, logger.clone()).unwrap();
+
+        assert_eq!(adelante.estado, "ahead");
+        assert_eq!(adelante.commit_base.hash, hash_primer_commit);
+        assert_eq!((adelante.commits_adelante, adelante.commits_atras), (1, 0));
+        assert_eq!(adelante.archivos.len(), 1);
+        assert_eq!(adelante.archivos[0].adiciones, 1);
+        assert_eq!(atras.estado, "behind");
+        assert!(atras.commits.is_empty() && atras.archivos.is_empty());
+        assert_eq!(identica.estado, "identical");
+    }
+
+    #[test]
+    fn test02_el_estado_depende_de_los_commits_de_cada_lado() {
+        assert_eq!(Comparacion::obtener_estado(0, 0), "identical");
+        assert_eq!(Comparacion::obtener_estado(2, 0), "ahead");
+        assert_eq!(Comparacion::obtener_estado(0, 1), "behind");
+        assert_eq!(Comparacion::obtener_estado(1, 3), "diverged");
+    }
+}
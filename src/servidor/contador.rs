@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use crate::{tipos_de_dato::http::error::ErrorHttp, utils::io};
+
+/// Archivo de cada repositorio del servidor con el ultimo valor usado de cada contador
+/// (Ej: `./srv/repo/contadores.json`)
+pub const ARCHIVO_CONTADORES: &str = "contadores.json";
+
+/// Devuelve el siguiente valor del contador `nombre` del repositorio y lo guarda como usado.
+/// Los valores nunca se repiten, aunque se borre lo que los usaba.
+///
+/// Si el contador todavia no existe (Ej: en un repositorio de antes de los contadores) arranca
+/// despues de `ultimo_en_uso`, que tiene que devolver el mayor valor que ya se usa.
+/// Se tiene que llamar con el lock del repositorio tomado, para que dos pedidos no obtengan
+/// el mismo valor.
+pub fn siguiente<F>(repositorio: &str, nombre: &str, ultimo_en_uso: F) -> Result<u64, ErrorHttp>
+where
+    F: FnOnce() -> Result<u64, ErrorHttp>,
+{
+    let direccion = format!("./srv/{repositorio}/{ARCHIVO_CONTADORES}");
+    let mut contadores: HashMap<String, u64> = if io::existe(&direccion) {
+        let contenido = io::leer_a_string(&direccion).map_err(ErrorHttp::InternalServerError)?;
+        serde_json::from_str(&contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("Fallo al leer los contadores: {e}"))
+        })?
+    } else {
+        HashMap::new()
+    };
+
+    let ultimo = match contadores.get(nombre) {
+        Some(ultimo) => *ultimo,
+        None => ultimo_en_uso()?,
+    };
+    let siguiente = ultimo + 1;
+    contadores.insert(nombre.to_string(), siguiente);
+
+    let contenido = serde_json::to_string(&contadores).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se han podido serializar los contadores: {e}"))
+    })?;
+    io::escribir_bytes(&direccion, contenido).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se han podido guardar los contadores: {e}"))
+    })?;
+    Ok(siguiente)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    const REPOSITORIO: &str = "repo_test_contador";
+
+    #[test]
+    #[serial]
+    fn test01_los_valores_no_se_repiten_y_arrancan_despues_del_ultimo_en_uso() {
+        let _ = io::rm_directorio(format!("./srv/{REPOSITORIO}"));
+
+        let primero = siguiente(REPOSITORIO, "numeros", || Ok(4)).unwrap();
+        let segundo = siguiente(REPOSITORIO, "numeros", || Ok(0)).unwrap();
+        let otro = siguiente(REPOSITORIO, "estados", || Ok(0)).unwrap();
+        io::rm_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+
+        assert_eq!((primero, segundo, otro), (5, 6, 1));
+    }
+}
@@ -0,0 +1,326 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{tipos_de_dato::http::error::ErrorHttp, utils::io};
+
+use super::{contador, explorador, repositorio_servidor};
+
+const CONTEXTO_POR_DEFECTO: &str = "default";
+/// Contador del repositorio con los ids de los estados de todos sus commits
+const CONTADOR_ESTADOS: &str = "estados";
+
+/// Resultado de un chequeo (Ej: un script de integracion continua) sobre un commit
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstadoChequeo {
+    #[serde(rename = "pending")]
+    Pendiente,
+    #[serde(rename = "success")]
+    Exitoso,
+    #[serde(rename = "failure")]
+    Fallido,
+    #[serde(rename = "error")]
+    Error,
+}
+
+impl EstadoChequeo {
+    /// Convierte el `state` de la request (`pending`, `success`, `failure` o `error`) en el
+    /// estado del chequeo
+    pub fn from_nombre(nombre: &str) -> Result<EstadoChequeo, ErrorHttp> {
+        match nombre {
+            "pending" => Ok(EstadoChequeo::Pendiente),
+            "success" => Ok(EstadoChequeo::Exitoso),
+            "failure" => Ok(EstadoChequeo::Fallido),
+            "error" => Ok(EstadoChequeo::Error),
+            _ => Err(ErrorHttp::ValidationFailed(format!(
+                "Estado invalido: {nombre}. Tiene que ser pending, success, failure o error"
+            ))),
+        }
+    }
+}
+
+/// Estado que informa un chequeo sobre un commit. Cada contexto (Ej: `ci/tests`) puede informar
+/// varios estados sobre el mismo commit y vale el ultimo.
+///
+/// Los estados de cada commit se guardan juntos en `./srv/{repositorio}/estados/{sha}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct EstadoCommit {
+    pub id: u64,
+    pub estado: EstadoChequeo,
+    pub contexto: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub descripcion: Option<String>,
+    /// Url con el detalle del chequeo (Ej: el log de la corrida)
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub url_destino: Option<String>,
+    pub creador: String,
+    pub fecha: String,
+}
+
+impl EstadoCommit {
+    /// Crea un estado del commit `sha` a partir del body de la request y lo agrega a sus estados.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `state`. Puede tener `context` (por defecto `default`),
+    ///   `description` y `target_url`
+    ///
+    /// ## Errores
+    /// - Si el estado no es valido
+    /// - `NotFound` si `sha` no es un commit del repositorio
+    pub fn crear(
+        repositorio: &str,
+        sha: &str,
+        creador: &str,
+        body: &HashMap<String, String>,
+    ) -> Result<EstadoCommit, ErrorHttp> {
+        let estado = body.get("state").ok_or_else(|| {
+            ErrorHttp::ValidationFailed(
+                "Falta el parametro 'state' en el body de la request".to_string(),
+            )
+        })?;
+        let estado = EstadoChequeo::from_nombre(estado)?;
+        {
+            let _repositorio = repositorio_servidor::obtener_repositorio(repositorio).entrar();
+            explorador::verificar_tipo_objeto(sha, "commit")?;
+        }
+
+        let mut estados = Self::listar(repositorio, sha)?;
+        let estado_commit = EstadoCommit {
+            id: Self::siguiente_id(repositorio)?,
+            estado,
+            contexto: body
+                .get("context")
+                .cloned()
+                .unwrap_or(CONTEXTO_POR_DEFECTO.to_string()),
+            descripcion: body.get("description").cloned(),
+            url_destino: body.get("target_url").cloned(),
+            creador: creador.to_string(),
+            fecha: Utc::now().to_rfc3339(),
+        };
+        estados.push(estado_commit.clone());
+        Self::guardar_todos(repositorio, sha, &estados)?;
+        Ok(estado_commit)
+    }
+
+    /// Devuelve los estados del commit `sha`, en el orden en que se informaron
+    pub fn listar(repositorio: &str, sha: &str) -> Result<Vec<EstadoCommit>, ErrorHttp> {
+        let direccion = Self::obtener_dir(repositorio, sha);
+        if !io::existe(&direccion) {
+            return Ok(Vec::new());
+        }
+        let contenido = io::leer_a_string(&direccion).map_err(ErrorHttp::InternalServerError)?;
+        serde_json::from_str(&contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!(
+                "Fallo al leer los estados del commit {sha}: {e}"
+            ))
+        })
+    }
+
+    /// Devuelve el id del proximo estado. Los ids son unicos en todo el repositorio, no solo
+    /// entre los estados de un commit
+    fn siguiente_id(repositorio: &str) -> Result<u64, ErrorHttp> {
+        contador::siguiente(repositorio, CONTADOR_ESTADOS, || {
+            Self::obtener_mayor_id(repositorio)
+        })
+    }
+
+    /// Devuelve el mayor id entre los estados guardados de todos los commits del repositorio
+    fn obtener_mayor_id(repositorio: &str) -> Result<u64, ErrorHttp> {
+        let direccion = PathBuf::from(format!("./srv/{repositorio}/estados"));
+        if !io::existe(&direccion) {
+            return Ok(0);
+        }
+
+        let entradas = io::leer_directorio(&direccion).map_err(|_| {
+            ErrorHttp::InternalServerError("Fallo al obtener el id del estado".to_string())
+        })?;
+        let mut mayor = 0;
+        for entrada in entradas.flatten() {
+            if let Some(sha) = entrada.file_name().to_str() {
+                for estado in Self::listar(repositorio, sha)? {
+                    mayor = mayor.max(estado.id);
+                }
+            }
+        }
+        Ok(mayor)
+    }
+
+    fn guardar_todos(
+        repositorio: &str,
+        sha: &str,
+        estados: &[EstadoCommit],
+    ) -> Result<(), ErrorHttp> {
+        let contenido = serde_json::to_string(estados).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se han podido serializar los estados: {e}"))
+        })?;
+        io::escribir_bytes(Self::obtener_dir(repositorio, sha), contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se han podido guardar los estados: {e}"))
+        })
+    }
+
+    fn obtener_dir(repositorio: &str, sha: &str) -> PathBuf {
+        PathBuf::from(format!("./srv/{repositorio}/estados/{sha}"))
+    }
+}
+
+/// Estado combinado de un commit: el ultimo estado de cada contexto y el resumen de todos
+#[derive(Serialize, Debug)]
+pub struct EstadoCombinado {
+    /// `failure` si algun contexto fallo o dio error, `pending` si no hay estados o alguno
+    /// esta pendiente, o `success` si pasaron todos
+    pub estado: String,
+    pub sha: String,
+    pub total: usize,
+    pub estados: Vec<EstadoCommit>,
+}
+
+impl EstadoCombinado {
+    /// Combina los estados del commit `sha` del repositorio
+    pub fn from_commit(repositorio: &str, sha: &str) -> Result<EstadoCombinado, ErrorHttp> {
+        let estados = EstadoCommit::listar(repositorio, sha)?;
+        Ok(Self::from_estados(sha, estados))
+    }
+
+    fn from_estados(sha: &str, estados: Vec<EstadoCommit>) -> EstadoCombinado {
+        let mut ultimos: Vec<EstadoCommit> = Vec::new();
+        for estado in estados {
+            ultimos.retain(|ultimo| ultimo.contexto != estado.contexto);
+            ultimos.push(estado);
+        }
+
+        let estado = if ultimos
+            .iter()
+            .any(|ultimo| matches!(ultimo.estado, EstadoChequeo::Fallido | EstadoChequeo::Error))
+        {
+            "failure"
+        } else if ultimos.is_empty()
+            || ultimos
+                .iter()
+                .any(|ultimo| ultimo.estado == EstadoChequeo::Pendiente)
+        {
+            "pending"
+        } else {
+            "success"
+        };
+        EstadoCombinado {
+            estado: estado.to_string(),
+            sha: sha.to_string(),
+            total: ultimos.len(),
+            estados: ultimos,
+        }
+    }
+
+    /// Devuelve los contextos de `requeridos` cuyo ultimo estado no es exitoso, incluidos los
+    /// que todavia no informaron ningun estado
+    pub fn obtener_contextos_sin_exito(&self, requeridos: &[String]) -> Vec<String> {
+        requeridos
+            .iter()
+            .filter(|requerido| {
+                !self.estados.iter().any(|estado| {
+                    estado.contexto == **requerido && estado.estado == EstadoChequeo::Exitoso
+                })
+            })
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    fn estado(id: u64, contexto: &str, estado: EstadoChequeo) -> EstadoCommit {
+        EstadoCommit {
+            id,
+            estado,
+            contexto: contexto.to_string(),
+            descripcion: None,
+            url_destino: None,
+            creador: "ci".to_string(),
+            fecha: String::new(),
+        }
+    }
+
+    #[test]
+    fn test01_el_estado_combinado_depende_del_ultimo_estado_de_cada_contexto() {
+        let mut estados = Vec::new();
+        assert_eq!(
+            EstadoCombinado::from_estados("a", estados.clone()).estado,
+            "pending"
+        );
+
+        estados.push(estado(1, "ci/tests", EstadoChequeo::Fallido));
+        estados.push(estado(2, "ci/lint", EstadoChequeo::Exitoso));
+        assert_eq!(
+            EstadoCombinado::from_estados("a", estados.clone()).estado,
+            "failure"
+        );
+
+        estados.push(estado(3, "ci/tests", EstadoChequeo::Pendiente));
+        assert_eq!(
+            EstadoCombinado::from_estados("a", estados.clone()).estado,
+            "pending"
+        );
+
+        estados.push(estado(4, "ci/tests", EstadoChequeo::Exitoso));
+        let combinado = EstadoCombinado::from_estados("a", estados);
+        assert_eq!(combinado.estado, "success");
+        assert_eq!(combinado.total, 2);
+    }
+
+    #[test]
+    fn test02_los_contextos_requeridos_tienen_que_ser_exitosos() {
+        let combinado = EstadoCombinado::from_estados(
+            "a",
+            vec![
+                estado(1, "ci/tests", EstadoChequeo::Exitoso),
+                estado(2, "ci/lint", EstadoChequeo::Error),
+            ],
+        );
+        let requeridos = vec![
+            "ci/tests".to_string(),
+            "ci/lint".to_string(),
+            "ci/build".to_string(),
+        ];
+
+        assert_eq!(
+            combinado.obtener_contextos_sin_exito(&requeridos),
+            vec!["ci/lint".to_string(), "ci/build".to_string()]
+        );
+        assert!(matches!(
+            EstadoChequeo::from_nombre("ok"),
+            Err(ErrorHttp::ValidationFailed(_))
+        ));
+    }
+
+    #[test]
+    #[serial]
+    fn test03_los_ids_no_se_repiten_entre_commits() {
+        let repositorio = "repo_test_ids_estados";
+        let _ = io::rm_directorio(format!("srv/{repositorio}"));
+        EstadoCommit::guardar_todos(
+            repositorio,
+            "sha_a",
+            &[
+                estado(1, "ci/tests", EstadoChequeo::Exitoso),
+                estado(2, "ci/lint", EstadoChequeo::Exitoso),
+            ],
+        )
+        .unwrap();
+        EstadoCommit::guardar_todos(
+            repositorio,
+            "sha_b",
+            &[estado(3, "ci/tests", EstadoChequeo::Fallido)],
+        )
+        .unwrap();
+
+        let primero = EstadoCommit::siguiente_id(repositorio).unwrap();
+        let segundo = EstadoCommit::siguiente_id(repositorio).unwrap();
+        io::rm_directorio(format!("srv/{repositorio}")).unwrap();
+
+        assert_eq!(primero, 4);
+        assert_eq!(segundo, 5);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{collections::HashMap, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{tipos_de_dato::http::error::ErrorHttp, utils::io};
+
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con su catalogo de
+/// etiquetas
+pub const ARCHIVO_ETIQUETAS: &str = "etiquetas.json";
+
+const COLOR_POR_DEFECTO: &str = "ededed";
+
+/// Etiqueta del catalogo de un repositorio. Los pull requests solo pueden usar etiquetas del
+/// catalogo de su repositorio.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Etiqueta {
+    pub nombre: String,
+    /// Color en hexadecimal, sin el `#` (Ej: `d73a4a`)
+    pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descripcion: Option<String>,
+}
+
+impl Etiqueta {
+    /// Crea una etiqueta a partir del body de la request y la agrega al catalogo del
+    /// repositorio cuyo directorio gir es `dir_gir`.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `name`. Puede tener `color` (6 digitos hexadecimales, con o
+    ///   sin `#`) y `description`
+    ///
+    /// ## Errores
+    /// - Si falta el nombre, el color no es valido o ya existe una etiqueta con ese nombre
+    pub fn crear(dir_gir: &Path, body: &HashMap<String, String>) -> Result<Etiqueta, ErrorHttp> {
+        let nombre = body
+            .get("name")
+            .map(|nombre| nombre.trim())
+            .ok_or_else(|| {
+                ErrorHttp::ValidationFailed(
+                    "Falta el parametro 'name' en el body de la request".to_string(),
+                )
+            })?;
+        if nombre.is_empty() || nombre.contains(',') {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "El nombre de etiqueta '{nombre}' no es valido"
+            )));
+        }
+        let color = body
+            .get("color")
+            .map(|color| color.trim_start_matches('#').to_lowercase())
+            .unwrap_or(COLOR_POR_DEFECTO.to_string());
+        if color.len() != 6 || !color.chars().all(|caracter| caracter.is_ascii_hexdigit()) {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "El color {color} no es un color hexadecimal valido"
+            )));
+        }
+
+        let mut etiquetas = Self::listar(dir_gir).map_err(ErrorHttp::InternalServerError)?;
+        if etiquetas.iter().any(|etiqueta| etiqueta.nombre == nombre) {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "Ya existe la etiqueta {nombre}"
+            )));
+        }
+        let etiqueta = Etiqueta {
+            nombre: nombre.to_string(),
+            color,
+            descripcion: body.get("description").cloned(),
+        };
+        etiquetas.push(etiqueta.clone());
+
+        let contenido = serde_json::to_string(&etiquetas).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se pudieron serializar las etiquetas: {e}"))
+        })?;
+        io::escribir_bytes(dir_gir.join(ARCHIVO_ETIQUETAS), contenido)
+            .map_err(ErrorHttp::InternalServerError)?;
+        Ok(etiqueta)
+    }
+
+    /// Devuelve el catalogo de etiquetas del repositorio cuyo directorio gir es `dir_gir`
+    pub fn listar(dir_gir: &Path) -> Result<Vec<Etiqueta>, String> {
+        let archivo = dir_gir.join(ARCHIVO_ETIQUETAS);
+        if !io::existe(&archivo) {
+            return Ok(Vec::new());
+        }
+        let contenido = io::leer_a_string(&archivo)?;
+        serde_json::from_str(&contenido)
+            .map_err(|e| format!("El archivo {} no es valido: {}", archivo.display(), e))
+    }
+}
+
+/// Verifica que todas las etiquetas esten en el catalogo del repositorio
+///
+/// ## Errores
+/// - `ValidationFailed` con la primera etiqueta que no existe
+pub fn verificar_etiquetas(dir_gir: &Path, etiquetas: &[String]) -> Result<(), ErrorHttp> {
+    if etiquetas.is_empty() {
+        return Ok(());
+    }
+    let catalogo = Etiqueta::listar(dir_gir).map_err(ErrorHttp::InternalServerError)?;
+    match etiquetas.iter().find(|etiqueta| {
+        !catalogo
+            .iter()
+            .any(|existente| existente.nombre == **etiqueta)
+    }) {
+        Some(etiqueta) => Err(ErrorHttp::ValidationFailed(format!(
+            "La etiqueta {etiqueta} no existe en el repositorio"
+        ))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn body(campos: &[(&str, &str)]) -> HashMap<String, String> {
+        campos
+            .iter()
+            .map(|(clave, valor)| (clave.to_string(), valor.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test01_se_crea_una_etiqueta_y_no_se_puede_repetir() {
+        let dir_gir = PathBuf::from("tmp/etiqueta_test01/.gir");
+        let _ = io::rm_directorio("tmp/etiqueta_test01");
+
+        let etiqueta =
+            Etiqueta::crear(&dir_gir, &body(&[("name", "bug"), ("color", "#D73A4A")])).unwrap();
+        let repetida = Etiqueta::crear(&dir_gir, &body(&[("name", "bug")]));
+        let sin_color = Etiqueta::crear(&dir_gir, &body(&[("name", "docs")])).unwrap();
+        let catalogo = Etiqueta::listar(&dir_gir).unwrap();
+        io::rm_directorio("tmp/etiqueta_test01").unwrap();
+
+        assert_eq!(etiqueta.color, "d73a4a");
+        assert!(matches!(repetida, Err(ErrorHttp::ValidationFailed(_))));
+        assert_eq!(sin_color.color, COLOR_POR_DEFECTO);
+        assert_eq!(catalogo, vec![etiqueta, sin_color]);
+    }
+
+    #[test]
+    fn test02_solo_se_aceptan_etiquetas_del_catalogo() {
+        let dir_gir = PathBuf::from("tmp/etiqueta_test02/.gir");
+        let _ = io::rm_directorio("tmp/etiqueta_test02");
+        Etiqueta::crear(&dir_gir, &body(&[("name", "bug")])).unwrap();
+
+        let existentes = verificar_etiquetas(&dir_gir, &["bug".to_string()]);
+        let inexistentes = verificar_etiquetas(&dir_gir, &["bug".to_string(), "wip".to_string()]);
+        let color_invalido = Etiqueta::crear(&dir_gir, &body(&[("name", "x"), ("color", "rojo")]));
+        io::rm_directorio("tmp/etiqueta_test02").unwrap();
+
+        assert!(existentes.is_ok());
+        assert!(matches!(inexistentes, Err(ErrorHttp::ValidationFailed(_))));
+        assert!(matches!(
+            color_invalido,
+            Err(ErrorHttp::ValidationFailed(_))
+        ));
+    }
+}
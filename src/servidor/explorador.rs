@@ -0,0 +1,369 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::Serialize;
+
+use crate::{
+    tipos_de_dato::{
+        comandos::{branch::Branch, cat_file, log::Log, write_tree},
+        http::error::ErrorHttp,
+        logger::Logger,
+        objeto::Objeto,
+        objetos::{commit::CommitObj, tree::Tree},
+        repositorio::Repositorio,
+    },
+    utils::{base64, compresion, io, ramas, tags},
+};
+
+const MODO_BLOB: &str = "100644";
+const MODO_TREE: &str = "040000";
+
+// Todas las funciones de este modulo se tienen que llamar dentro del repositorio a explorar
+
+/// Rama o tag del repositorio junto al commit al que apunta
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct Referencia {
+    pub nombre: String,
+    pub commit: String,
+}
+
+/// Entrada de un arbol: un archivo (`blob`) o un directorio (`tree`)
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct EntradaArbol {
+    /// Ruta desde la raiz del repositorio
+    pub ruta: String,
+    pub modo: String,
+    /// `blob` o `tree`
+    pub tipo: String,
+    pub hash: String,
+    /// Tamanio en bytes. Solo lo tienen los blobs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tamanio: Option<usize>,
+}
+
+/// Arbol de un commit, con sus entradas directas o, si es recursivo, todas las de sus subarboles
+#[derive(Serialize, Debug)]
+pub struct Arbol {
+    pub hash: String,
+    pub entradas: Vec<EntradaArbol>,
+}
+
+/// Contenido de un blob codificado en base64, como lo muestra github
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct ContenidoBlob {
+    pub hash: String,
+    pub tamanio: usize,
+    pub contenido: String,
+    pub codificacion: String,
+}
+
+/// Contenido de una ruta del repositorio: un archivo con su contenido o las entradas de un
+/// directorio
+#[derive(Serialize, Debug)]
+#[serde(untagged)]
+pub enum Contenido {
+    Archivo {
+        ruta: String,
+        #[serde(flatten)]
+        blob: ContenidoBlob,
+    },
+    Directorio(Vec<EntradaArbol>),
+}
+
+impl Arbol {
+    /// Arma el arbol a partir de un `Tree` leido de la base de objetos
+    pub fn from_tree(tree: &Tree, recursivo: bool) -> Result<Arbol, String> {
+        let objetos = if recursivo {
+            tree.obtener_objetos()
+        } else {
+            tree.objetos.clone()
+        };
+        let entradas = objetos
+            .iter()
+            .map(EntradaArbol::from_objeto)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Arbol {
+            hash: tree.obtener_hash()?,
+            entradas,
+        })
+    }
+}
+
+impl EntradaArbol {
+    fn from_objeto(objeto: &Objeto) -> Result<EntradaArbol, String> {
+        let (modo, tipo, tamanio) = match objeto {
+            Objeto::Blob(blob) => (MODO_BLOB, "blob", Some(blob.obtener_tamanio()?)),
+            Objeto::Tree(_) => (MODO_TREE, "tree", None),
+        };
+        Ok(EntradaArbol {
+            ruta: objeto.obtener_path().to_string_lossy().to_string(),
+            modo: modo.to_string(),
+            tipo: tipo.to_string(),
+            hash: objeto.obtener_hash(),
+            tamanio,
+        })
+    }
+}
+
+impl ContenidoBlob {
+    /// Lee el blob `hash` de la base de objetos
+    ///
+    /// ## Errores
+    /// - `NotFound` si no existe el objeto o no es un blob
+    pub fn from_hash(hash: &str) -> Result<ContenidoBlob, ErrorHttp> {
+        verificar_tipo_objeto(hash, "blob")?;
+        let objeto =
+            compresion::leer_objeto_descomprimido(hash, &Repositorio::actual().dir_objetos())
+                .map_err(ErrorHttp::InternalServerError)?;
+        let contenido = match objeto.iter().position(|byte| *byte == 0) {
+            Some(fin_header) => &objeto[fin_header + 1..],
+            None => {
+                return Err(ErrorHttp::InternalServerError(format!(
+                    "El objeto {hash} no es valido"
+                )))
+            }
+        };
+
+        Ok(ContenidoBlob {
+            hash: hash.to_string(),
+            tamanio: contenido.len(),
+            contenido: base64::codificar(contenido),
+            codificacion: "base64".to_string(),
+        })
+    }
+}
+
+/// Devuelve las ramas del repositorio ordenadas por nombre
+pub fn listar_ramas() -> Result<Vec<Referencia>, String> {
+    Branch::obtener_ramas()?
+        .into_iter()
+        .map(|rama| {
+            let commit = ramas::obtener_hash_commit_asociado_rama(&rama)?;
+            Ok(Referencia {
+                nombre: rama,
+                commit: commit.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Devuelve los tags del repositorio ordenados por nombre. Los tags anotados se resuelven al
+/// commit que etiquetan
+pub fn listar_tags() -> Result<Vec<Referencia>, String> {
+    let mut nombres = tags::obtener_tags()?;
+    nombres.sort();
+    nombres
+        .into_iter()
+        .map(|tag| {
+            let commit = obtener_commit_de_tag(&tag)?;
+            Ok(Referencia {
+                nombre: tag,
+                commit,
+            })
+        })
+        .collect()
+}
+
+fn obtener_commit_de_tag(tag: &str) -> Result<String, String> {
+    let hash = io::leer_a_string(Repositorio::actual().ruta_gir(format!("refs/tags/{tag}")))?
+        .trim()
+        .to_string();
+    if verificar_tipo_objeto(&hash, "tag").is_err() {
+        return Ok(hash);
+    }
+    // un tag anotado empieza con `object <hash>`
+    let (_, contenido) = cat_file::obtener_contenido_objeto(&hash)?;
+    contenido
+        .lines()
+        .next()
+        .and_then(|linea| linea.strip_prefix("object "))
+        .map(|hash_commit| hash_commit.to_string())
+        .ok_or_else(|| format!("El tag {tag} no es valido"))
+}
+
+/// Resuelve una referencia al hash de un commit. La referencia puede ser una rama, un tag o el
+/// hash de un commit. Si no hay referencia se usa la rama a la que apunta el HEAD.
+///
+/// ## Errores
+/// - `NotFound` si la referencia no existe o no apunta a un commit
+pub fn resolver_referencia(referencia: Option<&str>) -> Result<String, ErrorHttp> {
+    let referencia = match referencia {
+        Some(referencia) => referencia.to_string(),
+        None => ramas::obtener_rama_actual().map_err(|e| {
+            ErrorHttp::NotFound(format!("El repositorio no tiene una rama por defecto: {e}"))
+        })?,
+    };
+
+    let hash = if ramas::existe_la_rama(&referencia) {
+        ramas::obtener_hash_commit_asociado_rama(&referencia)
+            .map_err(ErrorHttp::InternalServerError)?
+    } else if tags::existe_tag(&referencia) {
+        obtener_commit_de_tag(&referencia).map_err(ErrorHttp::InternalServerError)?
+    } else {
+        referencia
+    };
+    let hash = hash.trim().to_string();
+    if hash.is_empty() {
+        return Err(ErrorHttp::NotFound(
+            "La referencia no tiene commits".to_string(),
+        ));
+    }
+    verificar_tipo_objeto(&hash, "commit")?;
+    Ok(hash)
+}
+
+/// Verifica que exista el objeto `hash` y sea del tipo esperado. El header se lee como bytes
+/// porque el contenido de un blob puede no ser texto
+///
+/// ## Errores
+/// - `NotFound` si el hash no es valido, no existe el objeto o es de otro tipo
+pub fn verificar_tipo_objeto(hash: &str, tipo_esperado: &str) -> Result<(), ErrorHttp> {
+    let error = || ErrorHttp::NotFound(format!("No existe el {tipo_esperado} {hash}"));
+    if hash.len() != 40 || !hash.chars().all(|caracter| caracter.is_ascii_hexdigit()) {
+        return Err(error());
+    }
+    let objeto = compresion::leer_objeto_descomprimido(hash, &Repositorio::actual().dir_objetos())
+        .map_err(|_| error())?;
+    if objeto.starts_with(format!("{tipo_esperado} ").as_bytes()) {
+        Ok(())
+    } else {
+        Err(error())
+    }
+}
+
+/// Devuelve el arbol raiz del commit
+pub fn obtener_arbol_de_commit(hash_commit: &str, logger: Arc<Logger>) -> Result<Tree, String> {
+    let hash_arbol = write_tree::conseguir_arbol(hash_commit)?;
+    Tree::from_hash(&hash_arbol, PathBuf::from("."), logger)
+}
+
+/// Devuelve los commits alcanzables desde `hash_commit`, del mas nuevo al mas viejo. Si hay
+/// `ruta`, solo los que cambian ese archivo o directorio respecto de su primer padre
+pub fn listar_commits(
+    hash_commit: &str,
+    ruta: Option<&str>,
+    logger: Arc<Logger>,
+) -> Result<Vec<CommitObj>, String> {
+    let commit = CommitObj::from_hash(hash_commit.to_string(), logger.clone())?;
+    let commits = Log::obtener_listas_de_commits(commit, logger.clone())?;
+    let ruta = match ruta {
+        Some(ruta) => ruta.trim_matches('/'),
+        None => return Ok(commits),
+    };
+
+    let mut commits_que_cambian_la_ruta = Vec::new();
+    for commit in commits {
+        let hash_en_commit = obtener_hash_en_commit(&commit.hash, ruta, logger.clone())?;
+        let hash_en_padre = match commit.padres.first() {
+            Some(padre) => obtener_hash_en_commit(padre, ruta, logger.clone())?,
+            None => None,
+        };
+        if hash_en_commit != hash_en_padre {
+            commits_que_cambian_la_ruta.push(commit);
+        }
+    }
+    Ok(commits_que_cambian_la_ruta)
+}
+
+fn obtener_hash_en_commit(
+    hash_commit: &str,
+    ruta: &str,
+    logger: Arc<Logger>,
+) -> Result<Option<String>, String> {
+    let arbol = obtener_arbol_de_commit(hash_commit, logger)?;
+    Ok(buscar_objeto(&arbol, ruta).map(|objeto| objeto.obtener_hash()))
+}
+
+fn buscar_objeto(arbol: &Tree, ruta: &str) -> Option<Objeto> {
+    arbol
+        .obtener_objetos()
+        .into_iter()
+        .find(|objeto| objeto.obtener_path() == Path::new(ruta))
+}
+
+/// Devuelve el contenido de `ruta` en el arbol: el archivo o las entradas del directorio. Una
+/// ruta vacia es la raiz del arbol
+///
+/// ## Errores
+/// - `NotFound` si la ruta no existe
+pub fn obtener_contenido(arbol: &Tree, ruta: &str) -> Result<Contenido, ErrorHttp> {
+    let ruta = ruta.trim_matches('/');
+    let directorio = if ruta.is_empty() {
+        arbol.clone()
+    } else {
+        match buscar_objeto(arbol, ruta) {
+            Some(Objeto::Tree(directorio)) => directorio,
+            Some(Objeto::Blob(blob)) => {
+                return Ok(Contenido::Archivo {
+                    ruta: ruta.to_string(),
+                    blob: ContenidoBlob::from_hash(&blob.hash)?,
+                })
+            }
+            None => return Err(ErrorHttp::NotFound(format!("No existe la ruta {ruta}"))),
+        }
+    };
+
+    let arbol = Arbol::from_tree(&directorio, false).map_err(ErrorHttp::InternalServerError)?;
+    Ok(Contenido::Directorio(arbol.entradas))
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use crate::utils::testing::{addear_archivos_y_comittear, limpiar_archivo_gir};
+
+    use super::*;
+
+    #[test]
+    #[serial]
+    fn test01_se_explora_el_contenido_y_los_commits_de_una_rama() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/explorador_test02")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let readme = "tmp/explorador_test02_readme".to_string();
+        let main = "tmp/explorador_test02_dir/main.rs".to_string();
+        io::escribir_bytes(&readme, "hola\n").unwrap();
+        addear_archivos_y_comittear(vec![readme.clone()], logger.clone());
+        let hash_primer_commit = resolver_referencia(None).unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        io::escribir_bytes(&main, "fn main() {}\n").unwrap();
+        addear_archivos_y_comittear(vec![main.clone()], logger.clone());
+
+        let hash_master = resolver_referencia(Some("master")).unwrap();
+        let arbol = obtener_arbol_de_commit(&hash_master, logger.clone()).unwrap();
+        let raiz = Arbol::from_tree(&arbol, false).unwrap();
+        let recursivo = Arbol::from_tree(&arbol, true).unwrap();
+        let contenido_readme = obtener_contenido(&arbol, &readme).unwrap();
+        let contenido_tmp = obtener_contenido(&arbol, "tmp/").unwrap();
+        let inexistente = obtener_contenido(&arbol, "no/existe");
+        let commits = listar_commits(&hash_master, None, logger.clone()).unwrap();
+        let commits_readme = listar_commits(&hash_master, Some(&readme), logger).unwrap();
+
+        assert_eq!(raiz.entradas.len(), 1);
+        assert_eq!(recursivo.entradas.len(), 4);
+        assert!(matches!(
+            contenido_readme,
+            Contenido::Archivo { ref blob, .. } if blob.contenido == "aG9sYQo=" && blob.tamanio == 5
+        ));
+        assert!(
+            matches!(contenido_tmp, Contenido::Directorio(ref entradas) if entradas.len() == 2)
+        );
+        assert!(matches!(inexistente, Err(ErrorHttp::NotFound(_))));
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits_readme.len(), 1);
+        assert_eq!(commits_readme[0].hash, hash_primer_commit);
+        assert_eq!(
+            listar_ramas().unwrap(),
+            vec![Referencia {
+                nombre: "master".to_string(),
+                commit: hash_master
+            }]
+        );
+        assert!(matches!(
+            resolver_referencia(Some("no_existe")),
+            Err(ErrorHttp::NotFound(_))
+        ));
+    }
+}
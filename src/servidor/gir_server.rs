@@ -1,5 +1,6 @@
 use crate::err_comunicacion::ErrorDeComunicacion;
 use crate::servidor::{receive_pack::receive_pack, upload_pack::upload_pack};
+use crate::tipos_de_dato::repositorio::Repositorio;
 use crate::tipos_de_dato::respuesta_pedido::RespuestaDePedido;
 use crate::tipos_de_dato::{comunicacion::Comunicacion, logger::Logger};
 use crate::utils::{self, io as gir_io};
@@ -15,13 +16,16 @@ use std::{
     thread,
 };
 
+use super::pool_threads::{ConfiguracionPool, PoolThreads};
 use super::repos_almacen::ReposAlmacen;
 use super::rutas::mensaje_servidor::MensajeServidor;
-use super::vector_threads::VectorThreads;
 
 const VERSION: &str = "version 1\n";
 const CAPABILITIES: &str = "ofs-delta symref=HEAD:refs/heads/master agent=git/2.17.1";
-const DIR: &str = "/srv"; // direccion relativa
+// receive-pack ademas puede enviar el reporte de estado y la salida de los hooks por bandas
+const CAPABILITIES_RECEIVE_PACK: &str =
+    "report-status side-band-64k ofs-delta symref=HEAD:refs/heads/master agent=git/2.17.1";
+pub const DIR: &str = "/srv"; // direccion relativa
 static SERVER_ARGS: usize = 2;
 
 ///
@@ -29,8 +33,8 @@ pub struct ServidorGir {
     /// Canal para escuchar las conexiones de clientes
     pub listener: TcpListener,
 
-    /// Threads que se spawnean para atender a los clientes
-    pub threads: VectorThreads,
+    /// Tamanio del pool de threads que atienden a los clientes
+    pub pool: ConfiguracionPool,
 
     /// Logger para registrar los eventos del servidor
     pub logger: Arc<Logger>,
@@ -48,7 +52,6 @@ impl ServidorGir {
     /// * `logger` - Logger para registrar los eventos del servidor
     pub fn new(
         logger: Arc<Logger>,
-        threads: VectorThreads,
         tx: Sender<MensajeServidor>,
         repos_almacen: ReposAlmacen,
     ) -> Result<ServidorGir, String> {
@@ -68,7 +71,7 @@ impl ServidorGir {
 
         Ok(ServidorGir {
             listener,
-            threads,
+            pool: ConfiguracionPool::desde_config("gir"),
             logger,
             main: None,
             tx,
@@ -84,43 +87,19 @@ impl ServidorGir {
 
     fn aceptar_conexiones(
         listener: Arc<TcpListener>,
-        threads: VectorThreads,
+        pool: PoolThreads<TcpStream>,
         logger: Arc<Logger>,
         tx: Sender<MensajeServidor>,
-        repos_almacen: ReposAlmacen,
     ) {
         while let Ok((stream, socket)) = listener.accept() {
             logger.log(&format!("Se conecto un cliente a gir desde {}", socket));
-            let logger_clone = logger.clone();
-            let tx = tx.clone();
-            let repos_almacen = repos_almacen.clone();
-            let handle = thread::spawn(move || -> Result<(), String> {
-                let stream_clonado = match stream.try_clone() {
-                    Ok(stream) => stream,
-                    Err(e) => {
-                        tx.send(MensajeServidor::HttpErrorFatal)
-                            .expect("Error al enviar mensaje de error fatal al servidor");
-                        logger_clone.log(&format!("Error al clonar el stream en http server: {e}"));
-                        return Err(format!("Error al clonar el stream en http server: {e}"));
-                    }
-                };
-                let mut comunicacion = Comunicacion::<TcpStream>::new_para_server(
-                    stream_clonado,
-                    logger_clone.clone(),
-                );
-                Self::manejar_cliente(
-                    &mut comunicacion,
-                    &(env!("CARGO_MANIFEST_DIR").to_string() + DIR),
-                    logger_clone.clone(),
-                    repos_almacen,
-                )?;
-                Ok(())
-            });
-
-            if let Ok(mut threads) = threads.lock() {
-                threads.push(handle);
-            } else {
-                logger.log("Error al obtener el lock de threads");
+            if let Err(stream) = pool.encolar(stream) {
+                logger.log(&format!("Servidor gir ocupado, se rechaza a {}", socket));
+                let mut comunicacion =
+                    Comunicacion::<TcpStream>::new_para_server(stream, logger.clone());
+                let _ = comunicacion.enviar(&utils::strings::obtener_linea_con_largo_hex(
+                    "ERR El servidor esta ocupado, intente mas tarde\n",
+                ));
             }
         }
 
@@ -129,16 +108,33 @@ impl ServidorGir {
         logger.log("Se cerro el servidor");
     }
 
-    /// Pone en funcionamiento el servidor, spawneando un thread por cada cliente que se conecte al mismo.
+    /// Atiende a un cliente desde un thread del pool
+    fn atender_conexion(stream: TcpStream, logger: Arc<Logger>, repos_almacen: ReposAlmacen) {
+        let mut comunicacion = Comunicacion::<TcpStream>::new_para_server(stream, logger.clone());
+        if let Err(e) = Self::manejar_cliente(
+            &mut comunicacion,
+            &(env!("CARGO_MANIFEST_DIR").to_string() + DIR),
+            logger.clone(),
+            repos_almacen,
+        ) {
+            logger.log(&format!("Error atendiendo cliente gir: {e}"));
+        }
+    }
+
+    /// Pone en funcionamiento el servidor. Las conexiones de los clientes las atiende un pool
+    /// de threads, y si estan todos ocupados y la cola esta llena se rechazan con un `ERR`.
     /// Procesa el pedido del cliente y responde en consecuencia.
     pub fn iniciar_servidor(&mut self) -> Result<(), String> {
         let listener = Arc::new(self.listener.try_clone().map_err(|e| e.to_string())?);
-        let threads = self.threads.clone();
         let logger = self.logger.clone();
         let tx = self.tx.clone();
         let repos_almacen = self.repos_almacen.clone();
+        let logger_pool = logger.clone();
+        let pool = PoolThreads::new("gir", self.pool, logger.clone(), move |stream| {
+            Self::atender_conexion(stream, logger_pool.clone(), repos_almacen.clone())
+        })?;
         let handle = thread::spawn(move || {
-            Self::aceptar_conexiones(listener, threads, logger, tx, repos_almacen);
+            Self::aceptar_conexiones(listener, pool, logger, tx);
         });
         self.main = Some(handle);
         Ok(())
@@ -171,7 +167,12 @@ impl ServidorGir {
 
         let args: Vec<String> = pedido[1].split('\0').map(|s| s.to_string()).collect();
         let repositorio = args[0].clone();
-        let dir_repositorio = dir.to_string() + &args[0] + "/.gir/";
+        let dir_repositorio = format!(
+            "{}/",
+            Repositorio::new(dir.to_string() + &args[0])
+                .dir_gir()
+                .display()
+        );
         let pedido = &pedido[0];
         Ok((pedido.to_owned(), repositorio, dir_repositorio))
     }
@@ -194,7 +195,7 @@ impl ServidorGir {
         let refs: Vec<String>;
         let resultado_ejecucion = match pedido.as_str() {
             "git-upload-pack" => {
-                if !PathBuf::from(&dir_repo).exists() {
+                if !gir_io::existe(&dir_repo) {
                     let error = ErrorDeComunicacion::ErrorRepositorioNoExiste(repo).to_string();
                     comunicacion.enviar(&utils::strings::obtener_linea_con_largo_hex(&error))?;
                     logger.log(&error);
@@ -202,7 +203,7 @@ impl ServidorGir {
                 }
                 comunicacion.enviar(&utils::strings::obtener_linea_con_largo_hex(VERSION))?;
                 println!("upload-pack recibido, ejecutando");
-                refs = server_utils::obtener_refs_de(PathBuf::from(&dir_repo))?;
+                refs = server_utils::obtener_refs_de(PathBuf::from(&dir_repo), CAPABILITIES)?;
                 comunicacion.responder(&refs)?;
                 upload_pack(dir_repo, comunicacion, &refs, logger.clone())
             }
@@ -211,7 +212,7 @@ impl ServidorGir {
 
                 let path = PathBuf::from(&dir_repo);
 
-                if !path.exists() {
+                if !gir_io::existe(&path) {
                     gir_io::crear_directorio(path.join("refs/"))?;
                     gir_io::crear_directorio(path.join("refs/heads/"))?;
                     gir_io::crear_directorio(path.join("refs/tags/"))?;
@@ -219,7 +220,7 @@ impl ServidorGir {
                 }
 
                 comunicacion.enviar(&utils::strings::obtener_linea_con_largo_hex(VERSION))?;
-                refs = server_utils::obtener_refs_de(path)?;
+                refs = server_utils::obtener_refs_de(path, CAPABILITIES_RECEIVE_PACK)?;
                 comunicacion.responder(&refs)?;
                 receive_pack(dir_repo.to_string(), comunicacion, logger.clone())
             }
@@ -244,8 +245,9 @@ impl ServidorGir {
 mod server_utils {
     use super::*;
 
-    /// Funcion que busca y devuelve las referencias de una direccion dada en formato pkt de un directorio con el formato de git
-    pub fn obtener_refs_de(dir: PathBuf) -> Result<Vec<String>, String> {
+    /// Funcion que busca y devuelve las referencias de una direccion dada en formato pkt de un directorio con el formato de git.
+    /// A la primera referencia se le agregan las capacidades dadas
+    pub fn obtener_refs_de(dir: PathBuf, capacidades: &str) -> Result<Vec<String>, String> {
         let mut refs: Vec<String> = Vec::new();
         let head_ref = utils::referencia::obtener_ref_head(dir.join("HEAD"));
         if let Ok(head) = head_ref {
@@ -258,24 +260,24 @@ mod server_utils {
         utils::referencia::obtener_refs_con_largo_hex(&mut refs, dir.join("refs/heads/"), dir_str)?;
         utils::referencia::obtener_refs_con_largo_hex(&mut refs, dir.join("refs/tags/"), dir_str)?;
         if !refs.is_empty() {
-            let ref_con_cap = agregar_capacidades(refs[0].clone());
+            let ref_con_cap = agregar_capacidades(refs[0].clone(), capacidades);
             refs.remove(0);
             refs.insert(0, ref_con_cap);
         } else {
-            refs.push(agregar_capacidades("0".repeat(40)));
+            refs.push(agregar_capacidades("0".repeat(40), capacidades));
         }
         Ok(refs)
     }
 
     /// Funcion que agrega las capacidades del servidor a una referencia dada en formato pkt
-    pub fn agregar_capacidades(referencia: String) -> String {
+    pub fn agregar_capacidades(referencia: String, capacidades: &str) -> String {
         let mut referencia_con_capacidades: String;
         if referencia.len() > 40 {
             referencia_con_capacidades = referencia.split_at(4).1.to_string() + "\0";
         } else {
             referencia_con_capacidades = referencia + "\0";
         }
-        let capacidades: Vec<&str> = CAPABILITIES.split_whitespace().collect();
+        let capacidades: Vec<&str> = capacidades.split_whitespace().collect();
         for cap in capacidades.iter() {
             referencia_con_capacidades.push_str(&format!("{} ", cap));
         }
@@ -294,7 +296,8 @@ mod tests {
     #[serial]
     fn test01_agregar_capacidades() {
         let referencia = "0".repeat(40);
-        let referencia_con_capacidades = server_utils::agregar_capacidades(referencia);
+        let referencia_con_capacidades =
+            server_utils::agregar_capacidades(referencia, CAPABILITIES);
         println!("{}", referencia_con_capacidades);
         assert_eq!(
             referencia_con_capacidades,
@@ -309,7 +312,7 @@ mod tests {
     fn test02_obtener_refs_con_ref_vacia_devuelve_ref_nula() {
         let dir =
             PathBuf::from(env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test02/.gir/");
-        let refs = server_utils::obtener_refs_de(dir).unwrap();
+        let refs = server_utils::obtener_refs_de(dir, CAPABILITIES).unwrap();
         println!("{:?}", refs);
         assert_eq!(
             refs[0],
@@ -324,7 +327,7 @@ mod tests {
     fn test03_obtener_refs_con_ref_head_devuelve_ref_head() {
         let dir =
             PathBuf::from(env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test03/.gir/");
-        let refs = server_utils::obtener_refs_de(dir).unwrap();
+        let refs = server_utils::obtener_refs_de(dir, CAPABILITIES).unwrap();
         println!("{:?}", refs);
         assert_eq!(
             refs[0],
@@ -336,4 +339,15 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    #[serial]
+    fn test04_receive_pack_anuncia_report_status_y_side_band() {
+        let dir =
+            PathBuf::from(env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test02/.gir/");
+        let refs = server_utils::obtener_refs_de(dir, CAPABILITIES_RECEIVE_PACK).unwrap();
+        let capacidades = refs[0].split_once('\0').unwrap().1;
+        assert!(capacidades.contains("report-status"));
+        assert!(capacidades.contains("side-band-64k"));
+    }
 }
@@ -1,15 +1,17 @@
 use std::{
-    io::{BufReader, Read, Write},
-    net::TcpListener,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::Path,
     sync::{mpsc::Sender, Arc},
     thread,
+    time::Duration,
 };
 
 use crate::{
     tipos_de_dato::{
         http::{
-            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, request::Request,
-            response::Response,
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, permiso::Permiso,
+            request::Request, response::Response,
         },
         logger::Logger,
     },
@@ -17,15 +19,30 @@ use crate::{
 };
 
 use super::{
+    autenticacion,
+    pool_threads::{ConfiguracionPool, EstadoCola, PoolThreads},
     repos_almacen::ReposAlmacen,
+    repositorio_servidor,
     rutas::{
-        actualizar_pull_request, crear_pull_request, listar_pull_request,
-        mensaje_servidor::MensajeServidor, mergear_pull_request, obtener_commits_pull_request,
+        actualizar_comentario, actualizar_issue, actualizar_pull_request, comparar_referencias,
+        crear_comentario, crear_estado_commit, crear_etiqueta, crear_fork, crear_issue,
+        crear_pull_request, crear_repositorio, crear_revision, crear_webhook, eliminar_comentario,
+        eliminar_repositorio, listar_comentarios, listar_commits, listar_etiquetas, listar_issues,
+        listar_pull_request, listar_ramas, listar_repositorios, listar_revisiones, listar_tags,
+        listar_webhooks, mensaje_servidor::MensajeServidor, mergear_pull_request, obtener_arbol,
+        obtener_archivos_pull_request, obtener_blob, obtener_commits_pull_request,
+        obtener_contenido, obtener_estado_commit, obtener_estado_merge, obtener_issue,
         obtener_pull_request,
     },
-    vector_threads::VectorThreads,
 };
 
+/// Tiempo que se espera una nueva request en una conexion abierta antes de cerrarla. Es corto
+/// porque mientras espera la conexion ocupa un thread del pool
+const TIEMPO_INACTIVIDAD: Duration = Duration::from_secs(1);
+
+/// Cantidad maxima de requests que se atienden por una misma conexion
+const MAXIMO_REQUESTS_POR_CONEXION: usize = 100;
+
 pub struct ServidorHttp {
     /// Canal para escuchar las conexiones de clientes
     listener: TcpListener,
@@ -35,7 +52,8 @@ pub struct ServidorHttp {
 
     main: Option<thread::JoinHandle<()>>,
 
-    threads: VectorThreads,
+    /// Tamanio del pool de threads que atienden las conexiones
+    pool: ConfiguracionPool,
 
     tx: Sender<MensajeServidor>,
 
@@ -48,7 +66,6 @@ impl ServidorHttp {
     /// * `logger` - Logger para registrar los eventos del servidor
     pub fn new(
         logger: Arc<Logger>,
-        threads: VectorThreads,
         tx: Sender<MensajeServidor>,
         repos_almacen: ReposAlmacen,
     ) -> Result<Self, String> {
@@ -64,7 +81,7 @@ impl ServidorHttp {
         Ok(Self {
             listener,
             logger,
-            threads,
+            pool: ConfiguracionPool::desde_config("http"),
             main: None,
             tx,
             repos_almacen,
@@ -76,50 +93,60 @@ impl ServidorHttp {
         listar_pull_request::agregar_a_router(endpoints);
         obtener_pull_request::agregar_a_router(endpoints);
         obtener_commits_pull_request::agregar_a_router(endpoints);
+        obtener_archivos_pull_request::agregar_a_router(endpoints);
+        obtener_estado_merge::agregar_a_router(endpoints);
         actualizar_pull_request::agregar_a_router(endpoints);
         mergear_pull_request::agregar_a_router(endpoints);
+        crear_comentario::agregar_a_router(endpoints);
+        listar_comentarios::agregar_a_router(endpoints);
+        actualizar_comentario::agregar_a_router(endpoints);
+        eliminar_comentario::agregar_a_router(endpoints);
+        crear_revision::agregar_a_router(endpoints);
+        listar_revisiones::agregar_a_router(endpoints);
+        crear_webhook::agregar_a_router(endpoints);
+        listar_webhooks::agregar_a_router(endpoints);
+        crear_issue::agregar_a_router(endpoints);
+        listar_issues::agregar_a_router(endpoints);
+        obtener_issue::agregar_a_router(endpoints);
+        actualizar_issue::agregar_a_router(endpoints);
+        crear_etiqueta::agregar_a_router(endpoints);
+        listar_etiquetas::agregar_a_router(endpoints);
+        listar_ramas::agregar_a_router(endpoints);
+        listar_tags::agregar_a_router(endpoints);
+        listar_commits::agregar_a_router(endpoints);
+        obtener_arbol::agregar_a_router(endpoints);
+        obtener_blob::agregar_a_router(endpoints);
+        obtener_contenido::agregar_a_router(endpoints);
+        comparar_referencias::agregar_a_router(endpoints);
+        crear_repositorio::agregar_a_router(endpoints);
+        listar_repositorios::agregar_a_router(endpoints);
+        eliminar_repositorio::agregar_a_router(endpoints);
+        crear_fork::agregar_a_router(endpoints);
+        crear_estado_commit::agregar_a_router(endpoints);
+        obtener_estado_commit::agregar_a_router(endpoints);
     }
 
     fn aceptar_conexiones(
-        endpoints: Arc<Vec<Endpoint>>,
         listener: TcpListener,
-        threads: VectorThreads,
+        pool: PoolThreads<TcpStream>,
         logger: Arc<Logger>,
         tx: Sender<MensajeServidor>,
-        repos_almacen: ReposAlmacen,
     ) {
-        while let Ok((mut stream, socket)) = listener.accept() {
+        while let Ok((stream, socket)) = listener.accept() {
             logger.log(&format!("Se conecto un cliente por http desde {}", socket));
-
-            let logger_clone = logger.clone();
-            let endpoints = endpoints.clone();
-            let repos_almacen = repos_almacen.clone();
-            let handle = thread::spawn(move || -> Result<(), String> {
-                let response = Self::manejar_cliente(
-                    logger_clone.clone(),
-                    &mut stream,
-                    &endpoints,
-                    repos_almacen,
+            if let Err(mut stream) = pool.encolar(stream) {
+                logger.log(&format!("Servidor http ocupado, se rechaza a {}", socket));
+                let mut response = Response::from_error(
+                    logger.clone(),
+                    ErrorHttp::ServiceUnavailable(
+                        "El servidor esta ocupado, intente mas tarde".to_string(),
+                    ),
                 );
-
-                match response {
-                    Ok(response) => response.enviar(&mut stream).map_err(|e| e.to_string()),
-                    Err(error_http) => {
-                        logger_clone.log(&format!("Error procesando request: {:?}", error_http));
-                        let response = Response::from_error(logger_clone.clone(), error_http);
-                        response.enviar(&mut stream).map_err(|e| e.to_string())
-                    }
-                }?;
-
-                Ok(())
-            });
-
-            let threads = threads.lock();
-
-            if let Ok(mut threads) = threads {
-                threads.push(handle);
-            } else {
-                logger.log("Error al obtener el lock de threads");
+                response.preparar_para_conexion("HTTP/1.1", false);
+                response
+                    .headers
+                    .insert("Retry-After".to_string(), "1".to_string());
+                let _ = response.enviar(&mut stream);
             }
         }
 
@@ -127,39 +154,162 @@ impl ServidorHttp {
             .expect("Error al enviar mensaje de error fatal al servidor");
     }
 
+    /// Atiende una conexion desde un thread del pool
+    fn atender_conexion_tcp(
+        mut stream: TcpStream,
+        logger: Arc<Logger>,
+        endpoints: &Vec<Endpoint>,
+        repos_almacen: ReposAlmacen,
+        estado_cola: &EstadoCola,
+    ) {
+        let resultado = stream
+            .set_read_timeout(Some(TIEMPO_INACTIVIDAD))
+            .map_err(|e| e.to_string())
+            .and_then(|_| {
+                Self::atender_conexion(
+                    logger.clone(),
+                    &mut stream,
+                    endpoints,
+                    repos_almacen,
+                    estado_cola,
+                )
+            });
+        if let Err(e) = resultado {
+            logger.log(&format!("Error atendiendo conexion http: {e}"));
+        }
+    }
+
     pub fn reiniciar_servidor(&mut self) -> Result<(), String> {
         self.logger.log("Reiniciando servidor http");
         self.main.take();
         self.iniciar_servidor()
     }
 
-    /// Pone en funcionamiento el servidor, spawneando un thread por cada cliente que se conecte al mismo.
-    /// Procesa el pedido del cliente y responde en consecuencia.
+    /// Pone en funcionamiento el servidor. Las conexiones de los clientes las atiende un pool
+    /// de threads, y si estan todos ocupados y la cola esta llena se responden con
+    /// `503 Service Unavailable`. Procesa el pedido del cliente y responde en consecuencia.
     pub fn iniciar_servidor(&mut self) -> Result<(), String> {
         let logger = self.logger.clone();
         let listener = self.listener.try_clone().map_err(|e| e.to_string())?;
-        let threads = self.threads.clone();
         let tx = self.tx.clone();
         let repos_almacen = self.repos_almacen.clone();
+        let mut endpoints = Vec::new();
+        Self::agregar_endpoints(&mut endpoints);
+        let logger_pool = logger.clone();
+        let estado_cola = EstadoCola::default();
+        let estado_cola_pool = estado_cola.clone();
+        let pool = PoolThreads::con_estado_cola(
+            "http",
+            self.pool,
+            estado_cola,
+            logger.clone(),
+            move |stream| {
+                Self::atender_conexion_tcp(
+                    stream,
+                    logger_pool.clone(),
+                    &endpoints,
+                    repos_almacen.clone(),
+                    &estado_cola_pool,
+                )
+            },
+        )?;
         let main = thread::spawn(|| {
-            let mut endpoints = Vec::new();
-            Self::agregar_endpoints(&mut endpoints);
-            let endpoints = Arc::new(endpoints);
-            Self::aceptar_conexiones(endpoints, listener, threads, logger, tx, repos_almacen);
+            Self::aceptar_conexiones(listener, pool, logger, tx);
         });
 
         self.main.replace(main);
         Ok(())
     }
 
-    fn manejar_cliente<R: Read + Write>(
+    /// Atiende las requests que llegan por la conexion, una despues de la otra, hasta que el
+    /// cliente la cierre o pida cerrarla, pase `TIEMPO_INACTIVIDAD` sin que llegue nada o se
+    /// atiendan `MAXIMO_REQUESTS_POR_CONEXION` requests. Si hay otras conexiones esperando un
+    /// thread del pool, se cierra despues de responder la request actual
+    fn atender_conexion<R: Read + Write>(
         logger: Arc<Logger>,
         stream: &mut R,
         endpoints: &Vec<Endpoint>,
         repos_almacen: ReposAlmacen,
-    ) -> Result<Response, ErrorHttp> {
+        estado_cola: &EstadoCola,
+    ) -> Result<(), String> {
         let mut reader = BufReader::new(stream);
-        let request = Request::from(&mut reader, logger.clone())?;
+        for numero_request in 1..=MAXIMO_REQUESTS_POR_CONEXION {
+            if !Self::hay_request_pendiente(&mut reader) {
+                break;
+            }
+
+            let mut response = match Self::manejar_cliente(
+                logger.clone(),
+                &mut reader,
+                endpoints,
+                repos_almacen.clone(),
+            ) {
+                Ok(response) => response,
+                Err(error_http) => {
+                    // si no se pudo leer la request no se sabe donde empieza la siguiente
+                    logger.log(&format!("Error leyendo request: {:?}", error_http));
+                    let mut response = Response::from_error(logger.clone(), error_http);
+                    response.preparar_para_conexion("HTTP/1.1", false);
+                    response
+                }
+            };
+            if numero_request == MAXIMO_REQUESTS_POR_CONEXION
+                || estado_cola.hay_trabajos_esperando()
+            {
+                response
+                    .headers
+                    .insert("Connection".to_string(), "close".to_string());
+            }
+
+            response
+                .enviar(reader.get_mut())
+                .map_err(|e| e.to_string())?;
+            if !response.mantiene_conexion() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Espera a que llegue algo por la conexion. Devuelve false si el cliente la cerro o se
+    /// llego al tiempo de inactividad
+    fn hay_request_pendiente<R: Read + Write>(reader: &mut BufReader<&mut R>) -> bool {
+        match reader.fill_buf() {
+            Ok(pendiente) => !pendiente.is_empty(),
+            Err(_) => false,
+        }
+    }
+
+    /// Lee una request de la conexion y la responde. Los errores de la request se responden
+    /// con su estado, y solo se devuelve error si no se pudo leer la request
+    fn manejar_cliente<R: Read + Write>(
+        logger: Arc<Logger>,
+        reader: &mut BufReader<&mut R>,
+        endpoints: &Vec<Endpoint>,
+        repos_almacen: ReposAlmacen,
+    ) -> Result<Response, ErrorHttp> {
+        let request = Request::from(reader, logger.clone())?;
+        let version = request.version.clone();
+        let mantener_conexion = request.mantener_conexion();
+
+        let mut response = match Self::responder(request, endpoints, repos_almacen, logger.clone())
+        {
+            Ok(response) => response,
+            Err(error_http) => {
+                logger.log(&format!("Error procesando request: {:?}", error_http));
+                Response::from_error(logger, error_http)
+            }
+        };
+        response.preparar_para_conexion(&version, mantener_conexion);
+        Ok(response)
+    }
+
+    fn responder(
+        mut request: Request,
+        endpoints: &Vec<Endpoint>,
+        repos_almacen: ReposAlmacen,
+        logger: Arc<Logger>,
+    ) -> Result<Response, ErrorHttp> {
         for endpoint in endpoints {
             if endpoint.metodo != request.metodo {
                 continue;
@@ -169,11 +319,31 @@ impl ServidorHttp {
                 None => continue,
             };
 
-            let repo = params.get("repo").ok_or_else(|| {
-                ErrorHttp::InternalServerError(
-                    "No se ha encontrado el nombre del repositorio".to_string(),
-                )
-            })?;
+            let usuario =
+                autenticacion::autenticar(&request, Path::new(autenticacion::ARCHIVO_USUARIOS))?;
+            // los endpoints que no son de un repositorio (Ej: `POST /repos`) no requieren
+            // permisos sobre ninguno, pero los que modifican algo requieren autenticarse
+            let repo = match params.get("repo") {
+                Some(repo) => repo,
+                None => {
+                    if endpoint.permiso > Permiso::Lectura && usuario.is_none() {
+                        return Err(ErrorHttp::Unauthorized(
+                            "Se requiere autenticacion para esta operacion".to_string(),
+                        ));
+                    }
+                    request.usuario = usuario;
+                    return (endpoint.handler)(request, params, logger.clone());
+                }
+            };
+            // el nombre del repositorio se usa como ruta, asi que no puede salir de `srv`
+            repositorio_servidor::validar_nombre(repo, "repositorio")?;
+            autenticacion::verificar_permiso(
+                repositorio_servidor::obtener_repositorio(repo).dir_gir(),
+                usuario.as_deref(),
+                endpoint.permiso,
+            )?;
+            request.usuario = usuario;
+            request.repos_almacen = Some(repos_almacen.clone());
 
             let mutex = repos_almacen
                 .obtener_mutex_del_repo(repo)
@@ -195,11 +365,12 @@ impl ServidorHttp {
 
 #[cfg(test)]
 mod test {
-    use std::{path::PathBuf, sync::Mutex};
+    use std::path::PathBuf;
 
     use super::*;
     use crate::{
         servidor::gir_server::ServidorGir,
+        tipos_de_dato::repositorio::Repositorio,
         utils::{
             io,
             testing::{self, crear_repo_para_pr},
@@ -209,6 +380,16 @@ mod test {
     const NOMBRE_REPOSITORIO: &str = "repo";
     const RUTA_REPOSITORIO: &str = "/srv/repo/";
 
+    /// Los repositorios creados con un push son de solo lectura para los anonimos, asi que
+    /// se les da permiso de escritura para poder crear y modificar pull requests sin autenticarse
+    fn permitir_escritura_publica(ruta_repositorio: &str) {
+        io::escribir_bytes(
+            RUTA_RAIZ.to_string() + ruta_repositorio + ".gir/" + autenticacion::ARCHIVO_PERMISOS,
+            r#"{"publico": "write"}"#,
+        )
+        .unwrap();
+    }
+
     fn iniciar_servidor_pushear_pr_y_obtener_respuesta_final(
         logger: Arc<Logger>,
         ruta_especifica: &str,
@@ -219,12 +400,11 @@ mod test {
         let repos_almacen = ReposAlmacen::new();
         let repos_almacen_clone = repos_almacen.clone();
         let handle = std::thread::spawn(move || {
-            let threads: VectorThreads = Arc::new(Mutex::new(Vec::new()));
             let listener = TcpListener::bind("127.0.0.1:9933").unwrap();
 
             let mut servidor_gir = ServidorGir {
                 listener,
-                threads,
+                pool: ConfiguracionPool::default(),
                 logger: logger_clone,
                 main: None,
                 tx,
@@ -241,10 +421,13 @@ mod test {
         let _ = io::rm_directorio(RUTA_RAIZ.to_string() + ruta_especifica);
         let _ = io::rm_directorio(RUTA_RAIZ.to_string() + RUTA_REPOSITORIO);
         io::crear_directorio(RUTA_RAIZ.to_string() + ruta_especifica).unwrap();
-        io::cambiar_directorio(RUTA_RAIZ.to_string() + ruta_especifica).unwrap();
 
-        crear_repo_para_pr(logger.clone());
+        crear_repo_para_pr(
+            &Repositorio::new(RUTA_RAIZ.to_string() + ruta_especifica),
+            logger.clone(),
+        );
         std::thread::sleep(std::time::Duration::from_secs(1));
+        permitir_escritura_publica(RUTA_REPOSITORIO);
 
         let repo = "repo";
         let body = r#"{
@@ -270,12 +453,11 @@ mod test {
             lectura_data: request_string.as_bytes().to_vec(),
             escritura_data: vec![],
         };
-        io::cambiar_directorio(RUTA_RAIZ).unwrap();
         let mut endpoints = Vec::new();
         ServidorHttp::agregar_endpoints(&mut endpoints);
         let _ = ServidorHttp::manejar_cliente(
             logger.clone(),
-            &mut mock,
+            &mut BufReader::new(&mut mock),
             &endpoints,
             repos_almacen.clone(),
         )
@@ -284,7 +466,7 @@ mod test {
 
         let respuesta = ServidorHttp::manejar_cliente(
             logger.clone(),
-            &mut mock,
+            &mut BufReader::new(&mut mock),
             &endpoints,
             repos_almacen.clone(),
         )
@@ -306,9 +488,13 @@ mod test {
             lectura_data: contenido_mock.as_bytes().to_vec(),
             escritura_data: vec![],
         };
-        let respuesta =
-            ServidorHttp::manejar_cliente(logger.clone(), &mut mock, &vec![], repos_almacen)
-                .unwrap();
+        let respuesta = ServidorHttp::manejar_cliente(
+            logger.clone(),
+            &mut BufReader::new(&mut mock),
+            &vec![],
+            repos_almacen,
+        )
+        .unwrap();
 
         assert_eq!(404, respuesta.estado);
         assert_eq!("Not Found", respuesta.mensaje_estado);
@@ -329,12 +515,11 @@ mod test {
         let repos_almacen_clone = repos_almacen.clone();
 
         let handle = std::thread::spawn(move || {
-            let threads: VectorThreads = Arc::new(Mutex::new(Vec::new()));
             let listener = TcpListener::bind("127.0.0.1:9933").unwrap();
 
             let mut servidor_gir = ServidorGir {
                 listener,
-                threads,
+                pool: ConfiguracionPool::default(),
                 logger: logger_clone,
                 main: None,
                 tx,
@@ -351,10 +536,13 @@ mod test {
         let _ = io::rm_directorio(RUTA_RAIZ.to_string() + "/tmp/servidor_http_test02_dir");
         let _ = io::rm_directorio(RUTA_RAIZ.to_string() + RUTA_REPOSITORIO);
         io::crear_directorio(RUTA_RAIZ.to_string() + "/tmp/servidor_http_test02_dir").unwrap();
-        io::cambiar_directorio(RUTA_RAIZ.to_string() + "/tmp/servidor_http_test02_dir").unwrap();
 
-        crear_repo_para_pr(logger.clone());
+        crear_repo_para_pr(
+            &Repositorio::new(RUTA_RAIZ.to_string() + "/tmp/servidor_http_test02_dir"),
+            logger.clone(),
+        );
         std::thread::sleep(std::time::Duration::from_secs(1));
+        permitir_escritura_publica(RUTA_REPOSITORIO);
 
         let repo = "repo";
         let body = r#"{
@@ -380,13 +568,12 @@ mod test {
             lectura_data: request_string.as_bytes().to_vec(),
             escritura_data: vec![],
         };
-        io::cambiar_directorio(RUTA_RAIZ).unwrap();
         let mut endpoints = Vec::new();
         ServidorHttp::agregar_endpoints(&mut endpoints);
 
         let respuesta = ServidorHttp::manejar_cliente(
             logger.clone(),
-            &mut mock,
+            &mut BufReader::new(&mut mock),
             &endpoints,
             repos_almacen.clone(),
         )
@@ -526,4 +713,278 @@ mod test {
         assert_eq!(200, respuesta.estado);
         assert_eq!("OK", respuesta.mensaje_estado);
     }
+
+    #[test]
+    fn test08_los_repos_con_permisos_requieren_autenticacion() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test08",
+            ))
+            .unwrap(),
+        );
+        let dir_repo = RUTA_RAIZ.to_string() + "/srv/repo_test08";
+        let _ = io::rm_directorio(&dir_repo);
+        io::escribir_bytes(
+            dir_repo.clone() + "/.gir/" + autenticacion::ARCHIVO_PERMISOS,
+            r#"{"publico": "none"}"#,
+        )
+        .unwrap();
+        let mut endpoints = Vec::new();
+        ServidorHttp::agregar_endpoints(&mut endpoints);
+        let mut mock = testing::MockTcpStream {
+            lectura_data: b"GET /repos/repo_test08/pulls HTTP/1.1\r\n\r\n".to_vec(),
+            escritura_data: vec![],
+        };
+
+        let respuesta = ServidorHttp::manejar_cliente(
+            logger.clone(),
+            &mut BufReader::new(&mut mock),
+            &endpoints,
+            ReposAlmacen::new(),
+        )
+        .unwrap();
+        io::rm_directorio(&dir_repo).unwrap();
+
+        assert_eq!(401, respuesta.estado);
+        assert!(respuesta.headers.contains_key("WWW-Authenticate"));
+    }
+
+    #[test]
+    fn test09_se_atienden_varias_requests_por_la_misma_conexion() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test09",
+            ))
+            .unwrap(),
+        );
+        let mut mock = testing::MockTcpStream {
+            lectura_data: b"GET /uno HTTP/1.1\r\n\r\n\
+                GET /dos HTTP/1.1\r\nConnection: close\r\n\r\n\
+                GET /tres HTTP/1.1\r\n\r\n"
+                .to_vec(),
+            escritura_data: vec![],
+        };
+
+        ServidorHttp::atender_conexion(
+            logger,
+            &mut mock,
+            &vec![],
+            ReposAlmacen::new(),
+            &EstadoCola::default(),
+        )
+        .unwrap();
+
+        let enviado = String::from_utf8_lossy(&mock.escritura_data).to_string();
+        assert_eq!(enviado.matches("HTTP/1.1 404 Not Found\r\n").count(), 2);
+        assert!(enviado.contains("Connection: keep-alive\r\n"));
+        assert!(enviado.contains("Connection: close\r\n"));
+    }
+
+    #[test]
+    fn test10_no_se_puede_usar_un_repositorio_fuera_del_directorio_del_servidor() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test10",
+            ))
+            .unwrap(),
+        );
+        let mut endpoints = Vec::new();
+        ServidorHttp::agregar_endpoints(&mut endpoints);
+
+        for request in [
+            "DELETE /repos/.. HTTP/1.1\r\n\r\n",
+            "DELETE /repos/.gir HTTP/1.1\r\n\r\n",
+            "POST /repos/../forks HTTP/1.1\r\n\r\n",
+            "POST /repos/.gir/forks HTTP/1.1\r\n\r\n",
+        ] {
+            let mut mock = testing::MockTcpStream {
+                lectura_data: request.as_bytes().to_vec(),
+                escritura_data: vec![],
+            };
+            let respuesta = ServidorHttp::manejar_cliente(
+                logger.clone(),
+                &mut BufReader::new(&mut mock),
+                &endpoints,
+                ReposAlmacen::new(),
+            )
+            .unwrap();
+
+            assert_eq!(422, respuesta.estado);
+        }
+    }
+
+    #[test]
+    fn test11_crear_un_repositorio_requiere_autenticacion() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test11",
+            ))
+            .unwrap(),
+        );
+        let mut endpoints = Vec::new();
+        ServidorHttp::agregar_endpoints(&mut endpoints);
+        let body = r#"{"name": "repo_test11"}"#;
+        let mut mock = testing::MockTcpStream {
+            lectura_data: format!(
+                "POST /repos HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .into_bytes(),
+            escritura_data: vec![],
+        };
+
+        let respuesta = ServidorHttp::manejar_cliente(
+            logger.clone(),
+            &mut BufReader::new(&mut mock),
+            &endpoints,
+            ReposAlmacen::new(),
+        )
+        .unwrap();
+
+        assert_eq!(401, respuesta.estado);
+        assert!(!io::existe(RUTA_RAIZ.to_string() + "/srv/repo_test11"));
+    }
+
+    #[test]
+    fn test12_la_conexion_se_cierra_si_hay_otras_esperando_un_thread() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test12",
+            ))
+            .unwrap(),
+        );
+        let (tx_empezo, rx_empezo) = std::sync::mpsc::channel();
+        let (tx_seguir, rx_seguir) = std::sync::mpsc::channel::<()>();
+        let (tx_empezo, rx_seguir) = (
+            std::sync::Mutex::new(tx_empezo),
+            std::sync::Mutex::new(rx_seguir),
+        );
+        let estado_cola = EstadoCola::default();
+        let configuracion = ConfiguracionPool {
+            cantidad_workers: 1,
+            tamanio_cola: 1,
+        };
+        let pool = PoolThreads::con_estado_cola(
+            "test12",
+            configuracion,
+            estado_cola.clone(),
+            logger.clone(),
+            move |_: usize| {
+                tx_empezo.lock().unwrap().send(()).unwrap();
+                let _ = rx_seguir
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_secs(5));
+            },
+        )
+        .unwrap();
+        pool.encolar(1).unwrap();
+        rx_empezo.recv().unwrap();
+        pool.encolar(2).unwrap();
+        let mut mock = testing::MockTcpStream {
+            lectura_data: b"GET /uno HTTP/1.1\r\n\r\nGET /dos HTTP/1.1\r\n\r\n".to_vec(),
+            escritura_data: vec![],
+        };
+
+        ServidorHttp::atender_conexion(
+            logger,
+            &mut mock,
+            &vec![],
+            ReposAlmacen::new(),
+            &estado_cola,
+        )
+        .unwrap();
+        tx_seguir.send(()).unwrap();
+        tx_seguir.send(()).unwrap();
+        drop(pool);
+
+        let enviado = String::from_utf8_lossy(&mock.escritura_data).to_string();
+        assert_eq!(enviado.matches("HTTP/1.1 404 Not Found\r\n").count(), 1);
+        assert!(enviado.contains("Connection: close\r\n"));
+    }
+
+    #[test]
+    fn test13_los_comentarios_no_se_pueden_modificar_sin_autenticarse() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test13",
+            ))
+            .unwrap(),
+        );
+        let dir_repo = RUTA_RAIZ.to_string() + "/srv/repo_test13";
+        let _ = io::rm_directorio(&dir_repo);
+        io::escribir_bytes(
+            dir_repo.clone() + "/.gir/" + autenticacion::ARCHIVO_PERMISOS,
+            r#"{"publico": "write"}"#,
+        )
+        .unwrap();
+        let mut endpoints = Vec::new();
+        ServidorHttp::agregar_endpoints(&mut endpoints);
+        let body = r#"{"body": "editado"}"#;
+
+        let mut estados = Vec::new();
+        for request in [
+            format!(
+                "PATCH /repos/repo_test13/issues/comments/1 HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            ),
+            "DELETE /repos/repo_test13/pulls/comments/1 HTTP/1.1\r\n\r\n".to_string(),
+        ] {
+            let mut mock = testing::MockTcpStream {
+                lectura_data: request.into_bytes(),
+                escritura_data: vec![],
+            };
+            let respuesta = ServidorHttp::manejar_cliente(
+                logger.clone(),
+                &mut BufReader::new(&mut mock),
+                &endpoints,
+                ReposAlmacen::new(),
+            )
+            .unwrap();
+            estados.push(respuesta.estado);
+        }
+        io::rm_directorio(&dir_repo).unwrap();
+
+        assert_eq!(estados, vec![401, 401]);
+    }
+
+    #[test]
+    fn test14_crear_un_fork_requiere_autenticacion() {
+        let logger = Arc::new(
+            Logger::new(PathBuf::from(
+                RUTA_RAIZ.to_string() + "/tmp/servidor_http_test14",
+            ))
+            .unwrap(),
+        );
+        let dir_repo = RUTA_RAIZ.to_string() + "/srv/repo_test14";
+        let _ = io::rm_directorio(&dir_repo);
+        let _ = io::rm_directorio(RUTA_RAIZ.to_string() + "/srv/fork_test14");
+        io::crear_directorio(dir_repo.clone() + "/.gir").unwrap();
+        let mut endpoints = Vec::new();
+        ServidorHttp::agregar_endpoints(&mut endpoints);
+        let body = r#"{"name": "fork_test14"}"#;
+        let mut mock = testing::MockTcpStream {
+            lectura_data: format!(
+                "POST /repos/repo_test14/forks HTTP/1.1\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .into_bytes(),
+            escritura_data: vec![],
+        };
+
+        let respuesta = ServidorHttp::manejar_cliente(
+            logger.clone(),
+            &mut BufReader::new(&mut mock),
+            &endpoints,
+            ReposAlmacen::new(),
+        )
+        .unwrap();
+        io::rm_directorio(&dir_repo).unwrap();
+
+        assert_eq!(401, respuesta.estado);
+        assert!(!io::existe(RUTA_RAIZ.to_string() + "/srv/fork_test14"));
+    }
 }
@@ -0,0 +1,337 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    tipos_de_dato::http::error::ErrorHttp,
+    utils::{io, strings::separar_lista},
+};
+
+use super::pull_request::PullRequest;
+
+const OPEN: &str = "open";
+const CLOSED: &str = "closed";
+
+/// Palabras que, seguidas de `#N` en la descripcion o en un commit de un pull request, cierran
+/// el issue `N` cuando se mergea el pull request
+const PALABRAS_QUE_CIERRAN: [&str; 9] = [
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// Issue de un repositorio. Se guarda en `./srv/{repositorio}/issues/{numero}` y comparte la
+/// numeracion con los pull requests de `./srv/{repositorio}/pulls`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub numero: u64,
+    pub titulo: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub descripcion: Option<String>,
+    /// `open` o `closed`
+    pub estado: String,
+    pub autor: String,
+    #[serde(default)]
+    pub etiquetas: Vec<String>,
+    #[serde(default)]
+    pub asignados: Vec<String>,
+    /// Numero del pull request que cerro el issue al mergearse
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cerrado_por: Option<u64>,
+    pub fecha_creacion: String,
+    pub fecha_modificacion: String,
+    pub repositorio: String,
+}
+
+impl Issue {
+    /// Crea un issue a partir del body de la request.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `title`. Puede tener `body`, `labels` y `assignees`, estos
+    ///   ultimos separados por comas
+    ///
+    /// ## Errores
+    /// - Si no existe el repositorio o falta el titulo
+    pub fn crear(
+        repositorio: &str,
+        autor: &str,
+        body: &HashMap<String, String>,
+    ) -> Result<Issue, ErrorHttp> {
+        if !io::existe(format!("./srv/{repositorio}")) {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "No existe en el server el repositorio {repositorio}"
+            )));
+        }
+        let titulo = body.get("title").ok_or_else(|| {
+            ErrorHttp::ValidationFailed(
+                "Falta el parametro 'title' en el body de la request".to_string(),
+            )
+        })?;
+        let fecha_actual = Utc::now().to_rfc3339();
+
+        Ok(Issue {
+            numero: PullRequest::reservar_numero(repositorio)?,
+            titulo: titulo.to_owned(),
+            descripcion: body.get("body").cloned(),
+            estado: OPEN.to_string(),
+            autor: autor.to_string(),
+            etiquetas: body
+                .get("labels")
+                .map(|etiquetas| separar_lista(etiquetas))
+                .unwrap_or_default(),
+            asignados: body
+                .get("assignees")
+                .map(|asignados| separar_lista(asignados))
+                .unwrap_or_default(),
+            cerrado_por: None,
+            fecha_creacion: fecha_actual.clone(),
+            fecha_modificacion: fecha_actual,
+            repositorio: repositorio.to_string(),
+        })
+    }
+
+    /// Actualiza los campos del issue que esten en el body de la request: `title`, `body`,
+    /// `state`, `labels` y `assignees`. A diferencia de un pull request, un issue cerrado se
+    /// puede reabrir. Devuelve si se cambio algun campo.
+    ///
+    /// ## Errores
+    /// - Si `state` no es `open` o `closed`
+    pub fn actualizar(&mut self, body: &HashMap<String, String>) -> Result<bool, ErrorHttp> {
+        let estado_previo = self.clone();
+
+        if let Some(estado) = body.get("state") {
+            if estado != OPEN && estado != CLOSED {
+                return Err(ErrorHttp::ValidationFailed(format!(
+                    "El status {estado} no coincide con ninguno de los posibles: `open` o `closed`"
+                )));
+            }
+            self.estado = estado.to_owned();
+            if estado == OPEN {
+                self.cerrado_por = None;
+            }
+        }
+        if let Some(titulo) = body.get("title") {
+            self.titulo = titulo.to_owned();
+        }
+        if let Some(descripcion) = body.get("body") {
+            self.descripcion = Some(descripcion.to_owned());
+        }
+        if let Some(etiquetas) = body.get("labels") {
+            self.etiquetas = separar_lista(etiquetas);
+        }
+        if let Some(asignados) = body.get("assignees") {
+            self.asignados = separar_lista(asignados);
+        }
+
+        let se_actualizo = *self != estado_previo;
+        if se_actualizo {
+            self.fecha_modificacion = Utc::now().to_rfc3339();
+        }
+        Ok(se_actualizo)
+    }
+
+    /// Valida que el issue cumpla los filtros del body: `state`, `creator`, `assignee` y
+    /// `labels` (el issue tiene que tener todas las etiquetas, separadas por comas)
+    pub fn filtrar(&self, body: &HashMap<String, String>) -> bool {
+        let mut pasa_el_filtro_del_estado = true;
+        let mut pasa_el_filtro_del_autor = true;
+        let mut pasa_el_filtro_del_asignado = true;
+        let mut pasa_el_filtro_de_etiquetas = true;
+
+        if let Some(estado) = body.get("state") {
+            pasa_el_filtro_del_estado = estado == "all" || self.estado == *estado;
+        }
+        if let Some(autor) = body.get("creator") {
+            pasa_el_filtro_del_autor = self.autor == *autor;
+        }
+        if let Some(asignado) = body.get("assignee") {
+            pasa_el_filtro_del_asignado = self.asignados.contains(asignado);
+        }
+        if let Some(etiquetas) = body.get("labels") {
+            pasa_el_filtro_de_etiquetas = separar_lista(etiquetas)
+                .iter()
+                .all(|etiqueta| self.etiquetas.contains(etiqueta));
+        }
+
+        pasa_el_filtro_del_estado
+            && pasa_el_filtro_del_autor
+            && pasa_el_filtro_del_asignado
+            && pasa_el_filtro_de_etiquetas
+    }
+
+    /// Guarda el issue en el directorio de issues de su repositorio
+    pub fn guardar(&self) -> Result<(), ErrorHttp> {
+        let contenido = serde_json::to_string(&self).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se ha podido serializar el issue: {e}"))
+        })?;
+        io::escribir_bytes(Self::obtener_dir(&self.repositorio, self.numero), contenido).map_err(
+            |e| ErrorHttp::InternalServerError(format!("No se ha podido guardar el issue: {e}")),
+        )
+    }
+
+    /// Carga el issue `numero` del repositorio
+    ///
+    /// ## Errores
+    /// - `NotFound` si no existe el issue
+    pub fn cargar(repositorio: &str, numero: u64) -> Result<Issue, ErrorHttp> {
+        let direccion = Self::obtener_dir(repositorio, numero);
+        if !io::existe(&direccion) {
+            return Err(ErrorHttp::NotFound(format!(
+                "No existe el issue {numero} en el repositorio {repositorio}"
+            )));
+        }
+        let contenido = io::leer_a_string(&direccion).map_err(ErrorHttp::InternalServerError)?;
+        serde_json::from_str(&contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("Fallo al leer el issue {numero}: {e}"))
+        })
+    }
+
+    /// Devuelve los issues del repositorio ordenados por numero
+    pub fn listar(repositorio: &str) -> Result<Vec<Issue>, ErrorHttp> {
+        let dir_issues = PathBuf::from(format!("./srv/{repositorio}/issues"));
+        if !io::existe(&dir_issues) {
+            return Ok(Vec::new());
+        }
+
+        let mut issues = Vec::new();
+        for entrada in io::leer_directorio(&dir_issues).map_err(ErrorHttp::InternalServerError)? {
+            let entrada = entrada.map_err(|e| {
+                ErrorHttp::InternalServerError(format!("Fallo al leer los issues: {e}"))
+            })?;
+            if let Some(numero) = entrada.file_name().to_str().and_then(|n| n.parse().ok()) {
+                issues.push(Self::cargar(repositorio, numero)?);
+            }
+        }
+        issues.sort_by_key(|issue| issue.numero);
+        Ok(issues)
+    }
+
+    fn obtener_dir(repositorio: &str, numero: u64) -> PathBuf {
+        PathBuf::from(format!("./srv/{repositorio}/issues/{numero}"))
+    }
+}
+
+/// Cierra los issues abiertos del repositorio referenciados con `closes #N` (o `fixes`,
+/// `resolves`, etc.) en alguno de los textos, que son la descripcion y los mensajes de los
+/// commits del pull request `numero_pull_request`. Los numeros que no son issues se ignoran.
+/// Devuelve los numeros de los issues cerrados.
+pub fn cerrar_issues_referenciados(
+    repositorio: &str,
+    numero_pull_request: u64,
+    textos: &[String],
+) -> Result<Vec<u64>, ErrorHttp> {
+    let mut numeros: Vec<u64> = textos
+        .iter()
+        .flat_map(|texto| obtener_issues_referenciados(texto))
+        .collect();
+    numeros.sort();
+    numeros.dedup();
+
+    let mut cerrados = Vec::new();
+    for numero in numeros {
+        let mut issue = match Issue::cargar(repositorio, numero) {
+            Ok(issue) => issue,
+            Err(ErrorHttp::NotFound(_)) => continue,
+            Err(error) => return Err(error),
+        };
+        if issue.estado == CLOSED {
+            continue;
+        }
+        issue.estado = CLOSED.to_string();
+        issue.cerrado_por = Some(numero_pull_request);
+        issue.fecha_modificacion = Utc::now().to_rfc3339();
+        issue.guardar()?;
+        cerrados.push(numero);
+    }
+    Ok(cerrados)
+}
+
+/// Devuelve los numeros `N` que aparecen en el texto como `closes #N` (sin importar
+/// mayusculas, y tambien con `fixes`, `resolves`, etc.)
+pub fn obtener_issues_referenciados(texto: &str) -> Vec<u64> {
+    let palabras: Vec<String> = texto
+        .split_whitespace()
+        .map(|palabra| palabra.to_lowercase())
+        .collect();
+
+    palabras
+        .windows(2)
+        .filter(|par| PALABRAS_QUE_CIERRAN.contains(&par[0].trim_end_matches(':')))
+        .filter_map(|par| {
+            let numero: String = par[1]
+                .strip_prefix('#')?
+                .chars()
+                .take_while(|caracter| caracter.is_ascii_digit())
+                .collect();
+            numero.parse().ok()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    const REPOSITORIO: &str = "issue_test";
+
+    fn body(campos: &[(&str, &str)]) -> HashMap<String, String> {
+        campos
+            .iter()
+            .map(|(clave, valor)| (clave.to_string(), valor.to_string()))
+            .collect()
+    }
+
+    #[test]
+    #[serial]
+    fn test01_los_issues_y_los_pull_requests_comparten_la_numeracion() {
+        let _ = io::rm_directorio(format!("./srv/{REPOSITORIO}"));
+        io::escribir_bytes(format!("./srv/{REPOSITORIO}/pulls/1"), "{}").unwrap();
+
+        let issue = Issue::crear(
+            REPOSITORIO,
+            "juani",
+            &body(&[("title", "No anda"), ("labels", "bug, urgente")]),
+        )
+        .unwrap();
+        issue.guardar().unwrap();
+        // aunque se borre el ultimo issue su numero no se vuelve a usar
+        io::rm_directorio(format!("./srv/{REPOSITORIO}/issues")).unwrap();
+        let numero_siguiente = PullRequest::reservar_numero(REPOSITORIO).unwrap();
+        io::rm_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+
+        assert_eq!(issue.numero, 2);
+        assert_eq!(issue.etiquetas, vec!["bug", "urgente"]);
+        assert_eq!(numero_siguiente, 3);
+    }
+
+    #[test]
+    fn test02_se_encuentran_los_issues_referenciados() {
+        let texto = "Arregla el login.\n\nCloses #3, fixes: #12 y resolved #7. Ver #9, close#4";
+
+        assert_eq!(obtener_issues_referenciados(texto), vec![3, 12, 7]);
+    }
+
+    #[test]
+    #[serial]
+    fn test03_al_mergear_se_cierran_los_issues_referenciados_y_se_pueden_reabrir() {
+        let _ = io::rm_directorio(format!("./srv/{REPOSITORIO}"));
+        io::crear_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+        let issue = Issue::crear(REPOSITORIO, "juani", &body(&[("title", "No anda")])).unwrap();
+        issue.guardar().unwrap();
+
+        let cerrados =
+            cerrar_issues_referenciados(REPOSITORIO, 5, &["fixes #1, closes #2".to_string()])
+                .unwrap();
+        let mut cerrado = Issue::cargar(REPOSITORIO, 1).unwrap();
+        let reabierto = cerrado.actualizar(&body(&[("state", "open")])).unwrap();
+        io::rm_directorio(format!("./srv/{REPOSITORIO}")).unwrap();
+
+        assert_eq!(cerrados, vec![1]);
+        assert!(reabierto);
+        assert_eq!(cerrado.estado, "open");
+        assert_eq!(cerrado.cerrado_por, None);
+        assert!(cerrado.filtrar(&body(&[("state", "open")])));
+        assert!(!cerrado.filtrar(&body(&[("labels", "bug")])));
+    }
+}
@@ -0,0 +1,114 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    tipos_de_dato::{
+        comandos::{gc::Gc, prune::Prune},
+        logger::Logger,
+        repositorio::Repositorio,
+    },
+    utils::io,
+};
+
+use super::repos_almacen::ReposAlmacen;
+
+/// Cada cuanto el servidor corre el mantenimiento sobre sus repositorios
+const INTERVALO_MANTENIMIENTO: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Corre gc (repack y prune) sobre cada repositorio dentro de `dir_repos`
+/// (Ej: `<dir>/srv/repo/.gir/`), tomando el mutex del repo para que no se
+/// mezcle con un push, un fetch o un pedido http sobre el mismo repositorio.
+/// Si falla el mantenimiento de un repositorio se registra y se sigue con el resto.
+/// Devuelve los repositorios que se mantuvieron con exito.
+pub fn mantener_repositorios(
+    dir_repos: &Path,
+    repos_almacen: &ReposAlmacen,
+    logger: Arc<Logger>,
+) -> Result<Vec<String>, String> {
+    let mut mantenidos = Vec::new();
+    if !io::existe(dir_repos) {
+        return Ok(mantenidos);
+    }
+
+    for entrada in io::leer_directorio(&dir_repos)? {
+        let ruta = entrada
+            .map_err(|e| format!("Error leyendo directorio: {}", e))?
+            .path();
+        let repositorio = Repositorio::new(&ruta);
+        if !repositorio.existe() {
+            continue;
+        }
+        let repo = ruta
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let mutex = repos_almacen.obtener_mutex_del_repo(&repo)?;
+        let _lock = mutex.lock().map_err(|e| e.to_string())?;
+
+        match Gc::recolectar(
+            repositorio.dir_gir(),
+            Prune::expiracion_default(),
+            logger.clone(),
+        ) {
+            Ok(_) => mantenidos.push(repo),
+            Err(e) => logger.log(&format!("Error en el mantenimiento de {}: {}", repo, e)),
+        }
+    }
+    Ok(mantenidos)
+}
+
+/// Lanza un thread que corre `mantener_repositorios` periodicamente sobre `dir_repos`
+pub fn iniciar_mantenimiento_periodico(
+    dir_repos: PathBuf,
+    repos_almacen: ReposAlmacen,
+    logger: Arc<Logger>,
+) -> JoinHandle<()> {
+    thread::spawn(move || {
+        Repositorio::marcar_thread_de_servidor();
+        loop {
+            thread::sleep(INTERVALO_MANTENIMIENTO);
+            logger.log("Iniciando mantenimiento de repositorios");
+            if let Err(e) = mantener_repositorios(&dir_repos, &repos_almacen, logger.clone()) {
+                logger.log(&format!("Error en el mantenimiento de repositorios: {}", e));
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::utils::{compresion, packs};
+
+    #[test]
+    #[serial]
+    fn test01_se_empaquetan_los_repos_del_servidor() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/mantenimiento_test01")).unwrap());
+        let dir_repos = PathBuf::from("tmp/mantenimiento_test01_srv");
+        let _ = io::rm_directorio(&dir_repos);
+        let dir_gir = dir_repos.join("repo/.gir");
+        let hash = "a".repeat(40);
+        let objeto = compresion::comprimir_contenido_u8(b"blob 4\0hola").unwrap();
+        io::escribir_bytes(dir_gir.join("objects/aa").join(&hash[2..]), objeto).unwrap();
+        io::escribir_bytes(dir_gir.join("refs/heads/master"), &hash).unwrap();
+        io::crear_directorio(dir_repos.join("no_es_repo")).unwrap();
+
+        let mantenidos =
+            mantener_repositorios(&dir_repos, &ReposAlmacen::new(), logger.clone()).unwrap();
+
+        assert_eq!(mantenidos, vec!["repo".to_string()]);
+        let dir_objetos = dir_gir.join("objects/").to_string_lossy().to_string();
+        assert_eq!(packs::obtener_packs(&dir_objetos).unwrap().len(), 1);
+        assert!(!dir_gir.join("objects/aa").exists());
+    }
+}
@@ -1,16 +1,20 @@
 use std::{
-    path::PathBuf,
+    io::stdin,
+    path::{Path, PathBuf},
     sync::{
         mpsc::{channel, Receiver, Sender},
-        Arc, Mutex,
+        Arc,
     },
     time::{Duration, Instant},
 };
 
-use gir::servidor::vector_threads::VectorThreads;
 use gir::{
     servidor::{
-        gir_server::ServidorGir, http_server::ServidorHttp, repos_almacen::ReposAlmacen,
+        autenticacion,
+        gir_server::{self, ServidorGir},
+        http_server::ServidorHttp,
+        mantenimiento,
+        repos_almacen::ReposAlmacen,
         rutas::mensaje_servidor::MensajeServidor,
     },
     tipos_de_dato::logger::Logger,
@@ -18,11 +22,12 @@ use gir::{
 
 const MAX_INTENTOS_REINICIO: u8 = 5;
 static MINIMO_TIEMPO_DE_FUNCIONAMIENTO: u64 = 60;
+const COMANDO_USUARIO: &str = "usuario";
+const FLAG_CONTRASENIA: &str = "--contrasenia";
 
 fn correr_servidor(
     logger: Arc<Logger>,
     channel: (Sender<MensajeServidor>, Receiver<MensajeServidor>),
-    threads: VectorThreads,
 ) -> Result<(), String> {
     let (tx, rx) = channel;
     let repos_almacen = ReposAlmacen::new();
@@ -30,22 +35,18 @@ fn correr_servidor(
     let mut intentos_gir = 0;
     let mut intentos_http = 0;
 
-    let mut servidor_http = ServidorHttp::new(
-        logger.clone(),
-        threads.clone(),
-        tx.clone(),
-        repos_almacen.clone(),
-    )?;
+    let mut servidor_http = ServidorHttp::new(logger.clone(), tx.clone(), repos_almacen.clone())?;
     servidor_http.iniciar_servidor()?;
 
-    let mut servidor_gir = ServidorGir::new(
-        logger.clone(),
-        threads.clone(),
-        tx.clone(),
-        repos_almacen.clone(),
-    )?;
+    let mut servidor_gir = ServidorGir::new(logger.clone(), tx.clone(), repos_almacen.clone())?;
     servidor_gir.iniciar_servidor()?;
 
+    mantenimiento::iniciar_mantenimiento_periodico(
+        PathBuf::from(env!("CARGO_MANIFEST_DIR").to_string() + gir_server::DIR),
+        repos_almacen.clone(),
+        logger.clone(),
+    );
+
     let mut ultimo_gir = Instant::now();
     let mut ultimo_http = Instant::now();
 
@@ -75,21 +76,51 @@ fn correr_servidor(
     Ok(())
 }
 
+/// `server usuario <nombre> [--contrasenia]`: da de alta al usuario en el archivo de usuarios
+/// del servidor (o lo actualiza si ya existe) e imprime un token nuevo para el. Con
+/// `--contrasenia` la contraseña se lee de la entrada estandar, para que no quede en el historial.
+fn provisionar_usuario(args: &[String]) -> Result<(), String> {
+    let (nombre, pedir_contrasenia) = match args {
+        [nombre] => (nombre, false),
+        [nombre, flag] if flag == FLAG_CONTRASENIA => (nombre, true),
+        _ => {
+            return Err(format!(
+                "Uso: server {COMANDO_USUARIO} <nombre> [{FLAG_CONTRASENIA}]"
+            ))
+        }
+    };
+
+    let contrasenia = if pedir_contrasenia {
+        println!("Contraseña de {nombre}:");
+        let mut contrasenia = String::new();
+        stdin()
+            .read_line(&mut contrasenia)
+            .map_err(|e| format!("No se pudo leer la contraseña: {e}"))?;
+        Some(contrasenia.trim_end_matches(['\n', '\r']).to_string())
+    } else {
+        None
+    };
+
+    let token = autenticacion::provisionar_usuario(
+        Path::new(autenticacion::ARCHIVO_USUARIOS),
+        nombre,
+        contrasenia.as_deref(),
+    )?;
+    println!("Token de {nombre} (no se puede volver a ver): {token}");
+    Ok(())
+}
+
 fn main() -> Result<(), String> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some(COMANDO_USUARIO) {
+        return provisionar_usuario(&args[1..]);
+    }
+
     let logger = Arc::new(Logger::new(PathBuf::from("server_logger.txt"))?);
 
     let channel = channel::<MensajeServidor>();
-    let threads = Arc::new(Mutex::new(Vec::new()));
-
-    correr_servidor(logger.clone(), channel, threads.clone())?;
 
-    if let Ok(mut threads) = threads.lock() {
-        for handle in threads.drain(..) {
-            let _ = handle.join();
-        }
-    } else {
-        logger.log("Error al obtener el lock de threads desde main");
-    }
+    correr_servidor(logger.clone(), channel)?;
 
     Ok(())
 }
@@ -104,9 +135,8 @@ mod test {
         let logger = Arc::new(Logger::new(PathBuf::from("server_logger.txt")).unwrap());
 
         let channel = channel::<MensajeServidor>();
-        let threads = Arc::new(Mutex::new(Vec::new()));
 
-        let result = correr_servidor(logger.clone(), channel, threads.clone());
+        let result = correr_servidor(logger.clone(), channel);
 
         assert!(result.is_err());
     }
@@ -0,0 +1,314 @@
+use std::{
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    tipos_de_dato::{logger::Logger, repositorio::Repositorio},
+    utils::gir_config,
+};
+
+const CANTIDAD_WORKERS_POR_DEFECTO: usize = 8;
+const TAMANIO_COLA_POR_DEFECTO: usize = 32;
+
+/// Tamanio del pool de threads de un servidor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfiguracionPool {
+    /// Cantidad de threads que atienden conexiones al mismo tiempo
+    pub cantidad_workers: usize,
+    /// Cantidad de conexiones que pueden esperar a que se libere un thread. Si la cola esta
+    /// llena las conexiones nuevas se rechazan
+    pub tamanio_cola: usize,
+}
+
+impl ConfiguracionPool {
+    /// Lee la configuracion del servidor (`http` o `gir`) del archivo config, con las entradas
+    /// `workers_{servidor}` y `cola_{servidor}`. Las que no estan toman el valor por defecto
+    pub fn desde_config(servidor: &str) -> ConfiguracionPool {
+        ConfiguracionPool {
+            cantidad_workers: gir_config::conseguir_cantidad_workers(servidor)
+                .unwrap_or(CANTIDAD_WORKERS_POR_DEFECTO),
+            tamanio_cola: gir_config::conseguir_tamanio_cola(servidor)
+                .unwrap_or(TAMANIO_COLA_POR_DEFECTO),
+        }
+    }
+}
+
+impl Default for ConfiguracionPool {
+    fn default() -> Self {
+        ConfiguracionPool {
+            cantidad_workers: CANTIDAD_WORKERS_POR_DEFECTO,
+            tamanio_cola: TAMANIO_COLA_POR_DEFECTO,
+        }
+    }
+}
+
+/// Cantidad de trabajos encolados en un pool que todavia no tomo ningun thread. Se comparte con
+/// los threads para que un trabajo largo (Ej: una conexion que se mantiene abierta) pueda
+/// terminar antes si hay otros esperando
+#[derive(Debug, Clone, Default)]
+pub struct EstadoCola {
+    esperando: Arc<AtomicUsize>,
+}
+
+impl EstadoCola {
+    /// Devuelve si hay trabajos encolados esperando que se libere un thread
+    pub fn hay_trabajos_esperando(&self) -> bool {
+        self.esperando.load(Ordering::SeqCst) > 0
+    }
+}
+
+/// Pool con una cantidad fija de threads que atienden los trabajos (Ej: las conexiones de los
+/// clientes) que se le encolan. Al dropearse espera a que los threads terminen los trabajos
+/// encolados.
+pub struct PoolThreads<T: Send + 'static> {
+    emisor: Option<SyncSender<T>>,
+    workers: Vec<JoinHandle<()>>,
+    estado_cola: EstadoCola,
+}
+
+impl<T: Send + 'static> PoolThreads<T> {
+    /// Crea el pool y lanza sus threads.
+    ///
+    /// ## Argumentos
+    /// - nombre: nombre de los threads, para identificarlos en los logs
+    /// - configuracion: cantidad de threads y tamanio de la cola de trabajos
+    /// - atender: funcion que ejecuta un thread por cada trabajo
+    pub fn new<F>(
+        nombre: &str,
+        configuracion: ConfiguracionPool,
+        logger: Arc<Logger>,
+        atender: F,
+    ) -> Result<PoolThreads<T>, String>
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        Self::con_estado_cola(
+            nombre,
+            configuracion,
+            EstadoCola::default(),
+            logger,
+            atender,
+        )
+    }
+
+    /// Igual que `new`, pero el pool lleva la cuenta de los trabajos que esperan en
+    /// `estado_cola`, que se puede consultar desde `atender`
+    pub fn con_estado_cola<F>(
+        nombre: &str,
+        configuracion: ConfiguracionPool,
+        estado_cola: EstadoCola,
+        logger: Arc<Logger>,
+        atender: F,
+    ) -> Result<PoolThreads<T>, String>
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        if configuracion.cantidad_workers == 0 {
+            return Err(format!("El pool {nombre} necesita al menos un thread"));
+        }
+
+        let (emisor, receptor) = mpsc::sync_channel(configuracion.tamanio_cola);
+        let receptor = Arc::new(Mutex::new(receptor));
+        let atender = Arc::new(atender);
+        let mut workers = Vec::new();
+        for numero in 0..configuracion.cantidad_workers {
+            let receptor = receptor.clone();
+            let atender = atender.clone();
+            let logger = logger.clone();
+            let estado_cola = estado_cola.clone();
+            let nombre_worker = format!("{nombre}-{numero}");
+            let worker = thread::Builder::new()
+                .name(nombre_worker.clone())
+                .spawn(move || {
+                    Self::trabajar(
+                        &nombre_worker,
+                        receptor,
+                        &estado_cola,
+                        atender.as_ref(),
+                        logger,
+                    )
+                })
+                .map_err(|e| format!("No se pudo crear el thread {nombre}-{numero}: {e}"))?;
+            workers.push(worker);
+        }
+
+        Ok(PoolThreads {
+            emisor: Some(emisor),
+            workers,
+            estado_cola,
+        })
+    }
+
+    /// Encola el trabajo para que lo atienda el primer thread que se libere. Si la cola esta
+    /// llena devuelve el trabajo, para que se pueda rechazar
+    pub fn encolar(&self, trabajo: T) -> Result<(), T> {
+        let emisor = match &self.emisor {
+            Some(emisor) => emisor,
+            None => return Err(trabajo),
+        };
+        // se cuenta antes de encolarlo, para que el thread que lo tome no lo descuente antes
+        self.estado_cola.esperando.fetch_add(1, Ordering::SeqCst);
+        emisor.try_send(trabajo).map_err(|error| {
+            self.estado_cola.esperando.fetch_sub(1, Ordering::SeqCst);
+            match error {
+                TrySendError::Full(trabajo) | TrySendError::Disconnected(trabajo) => trabajo,
+            }
+        })
+    }
+
+    /// Atiende trabajos hasta que se cierre la cola. Si atender un trabajo panickea, el thread
+    /// lo registra y sigue con el proximo. Cada trabajo tiene que entrar al repositorio sobre el
+    /// que opera (ver `Repositorio::marcar_thread_de_servidor`)
+    fn trabajar<F>(
+        nombre: &str,
+        receptor: Arc<Mutex<Receiver<T>>>,
+        estado_cola: &EstadoCola,
+        atender: &F,
+        logger: Arc<Logger>,
+    ) where
+        F: Fn(T),
+    {
+        Repositorio::marcar_thread_de_servidor();
+        loop {
+            let trabajo = match receptor.lock() {
+                Ok(receptor) => receptor.recv(),
+                Err(_) => break,
+            };
+            let trabajo = match trabajo {
+                Ok(trabajo) => trabajo,
+                Err(_) => break,
+            };
+            estado_cola.esperando.fetch_sub(1, Ordering::SeqCst);
+            if panic::catch_unwind(AssertUnwindSafe(|| atender(trabajo))).is_err() {
+                logger.log(&format!(
+                    "El thread {nombre} panickeo atendiendo un trabajo"
+                ));
+            }
+        }
+    }
+}
+
+impl<T: Send + 'static> Drop for PoolThreads<T> {
+    fn drop(&mut self) {
+        // al cerrar la cola los threads terminan cuando no quedan trabajos
+        self.emisor.take();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::mpsc::channel, time::Duration};
+
+    use super::*;
+
+    fn configuracion(cantidad_workers: usize, tamanio_cola: usize) -> ConfiguracionPool {
+        ConfiguracionPool {
+            cantidad_workers,
+            tamanio_cola,
+        }
+    }
+
+    #[test]
+    fn test01_el_pool_atiende_todos_los_trabajos_encolados() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/pool_threads_test01")).unwrap());
+        let (tx, rx) = channel();
+        let tx = Mutex::new(tx);
+
+        let pool = PoolThreads::new("test01", configuracion(3, 10), logger, move |numero| {
+            tx.lock().unwrap().send(numero).unwrap();
+        })
+        .unwrap();
+        for numero in 0..10 {
+            pool.encolar(numero).unwrap();
+        }
+        drop(pool);
+
+        let mut atendidos: Vec<usize> = rx.try_iter().collect();
+        atendidos.sort();
+        assert_eq!(atendidos, (0..10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test02_si_la_cola_esta_llena_se_devuelve_el_trabajo() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/pool_threads_test02")).unwrap());
+        let (tx_empezo, rx_empezo) = channel();
+        let (tx_seguir, rx_seguir) = channel::<()>();
+        let (tx_empezo, rx_seguir) = (Mutex::new(tx_empezo), Mutex::new(rx_seguir));
+
+        let pool = PoolThreads::new("test02", configuracion(1, 1), logger, move |numero| {
+            tx_empezo.lock().unwrap().send(numero).unwrap();
+            let _ = rx_seguir
+                .lock()
+                .unwrap()
+                .recv_timeout(Duration::from_secs(5));
+        })
+        .unwrap();
+        pool.encolar(1).unwrap();
+        assert_eq!(rx_empezo.recv().unwrap(), 1);
+
+        pool.encolar(2).unwrap();
+        assert_eq!(pool.encolar(3), Err(3));
+
+        tx_seguir.send(()).unwrap();
+        tx_seguir.send(()).unwrap();
+        drop(pool);
+        assert_eq!(rx_empezo.try_iter().collect::<Vec<usize>>(), vec![2]);
+    }
+
+    #[test]
+    fn test03_un_pool_sin_threads_es_invalido() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/pool_threads_test03")).unwrap());
+
+        let pool = PoolThreads::new("test03", configuracion(0, 1), logger, |_: usize| {});
+
+        assert!(pool.is_err());
+    }
+
+    #[test]
+    fn test04_el_estado_de_la_cola_indica_si_hay_trabajos_esperando() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/pool_threads_test04")).unwrap());
+        let (tx_empezo, rx_empezo) = channel();
+        let (tx_seguir, rx_seguir) = channel::<()>();
+        let (tx_empezo, rx_seguir) = (Mutex::new(tx_empezo), Mutex::new(rx_seguir));
+        let estado_cola = EstadoCola::default();
+
+        let pool = PoolThreads::con_estado_cola(
+            "test04",
+            configuracion(1, 1),
+            estado_cola.clone(),
+            logger,
+            move |numero: usize| {
+                tx_empezo.lock().unwrap().send(numero).unwrap();
+                let _ = rx_seguir
+                    .lock()
+                    .unwrap()
+                    .recv_timeout(Duration::from_secs(5));
+            },
+        )
+        .unwrap();
+        pool.encolar(1).unwrap();
+        rx_empezo.recv().unwrap();
+        let esperando_sin_encolados = estado_cola.hay_trabajos_esperando();
+        pool.encolar(2).unwrap();
+        let esperando_con_encolados = estado_cola.hay_trabajos_esperando();
+        assert_eq!(pool.encolar(3), Err(3));
+        tx_seguir.send(()).unwrap();
+        rx_empezo.recv().unwrap();
+        let esperando_al_tomarlo = estado_cola.hay_trabajos_esperando();
+        tx_seguir.send(()).unwrap();
+        drop(pool);
+
+        assert!(!esperando_sin_encolados);
+        assert!(esperando_con_encolados);
+        assert!(!esperando_al_tomarlo);
+    }
+}
@@ -0,0 +1,188 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{girignore, io};
+
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con las reglas de
+/// proteccion de ramas (Ej: `srv/repo/.gir/proteccion_ramas.json`)
+pub const ARCHIVO_PROTECCION: &str = "proteccion_ramas.json";
+
+/// Regla de proteccion para las ramas cuyo nombre coincide con `patron`.
+///
+/// En el archivo de proteccion se escribe como json, por ejemplo:
+/// `{"patron": "release/*", "requerir_pull_request": true}`.
+/// Los campos que no se escriben toman su valor por defecto.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReglaProteccion {
+    /// Patron de las ramas protegidas, con la sintaxis de `.girignore` (Ej: `master` o `release/*`)
+    pub patron: String,
+    /// No se aceptan pushes que no sean fast-forward. Por defecto es verdadero
+    #[serde(default = "verdadero")]
+    pub bloquear_force_push: bool,
+    /// No se aceptan pushes que borren la rama. Por defecto es verdadero
+    #[serde(default = "verdadero")]
+    pub bloquear_borrado: bool,
+    /// Los cambios solo pueden llegar mergeando un pull request, no por push directo
+    #[serde(default)]
+    pub requerir_pull_request: bool,
+    /// Para mergear un pull request, la rama head tiene que contener el ultimo commit de la base
+    #[serde(default)]
+    pub requerir_rama_actualizada: bool,
+    /// Para mergear un pull request, cuantos revisores tienen que haber aprobado el ultimo
+    /// commit de la rama head. Con 0 no se requieren aprobaciones
+    #[serde(default)]
+    pub aprobaciones_requeridas: usize,
+    /// Para mergear un pull request, los contextos de chequeos (Ej: `ci/tests`) cuyo ultimo
+    /// estado sobre el ultimo commit de la rama head tiene que ser exitoso
+    #[serde(default)]
+    pub contextos_requeridos: Vec<String>,
+}
+
+fn verdadero() -> bool {
+    true
+}
+
+/// Reglas de proteccion de ramas de un repositorio del servidor.
+/// Si el repositorio no tiene archivo de proteccion, ninguna rama esta protegida.
+#[derive(Debug, Default)]
+pub struct ProteccionRamas {
+    reglas: Vec<ReglaProteccion>,
+}
+
+impl ProteccionRamas {
+    /// Carga las reglas del archivo de proteccion del repositorio cuyo directorio gir es `dir_gir`.
+    ///
+    /// ## Errores
+    /// - Si el archivo existe pero no es una lista de reglas valida
+    pub fn cargar(dir_gir: &Path) -> Result<ProteccionRamas, String> {
+        let archivo = dir_gir.join(ARCHIVO_PROTECCION);
+        if !io::existe(&archivo) {
+            return Ok(ProteccionRamas::default());
+        }
+
+        let contenido = io::leer_a_string(&archivo)?;
+        let reglas = serde_json::from_str(&contenido).map_err(|e| {
+            format!(
+                "El archivo de proteccion de ramas {} no es valido: {}",
+                archivo.display(),
+                e
+            )
+        })?;
+        Ok(ProteccionRamas { reglas })
+    }
+
+    /// Devuelve la regla que protege a `rama`: la primera cuyo patron coincide con su nombre
+    pub fn obtener_regla(&self, rama: &str) -> Option<&ReglaProteccion> {
+        self.reglas
+            .iter()
+            .find(|regla| girignore::coincide_patron(&regla.patron, rama))
+    }
+
+    /// Verifica que un push directo pueda llevar `referencia` de `viejo` a `nuevo`.
+    /// Solo se protegen las ramas (`refs/heads/...`). `es_fast_forward` se llama solo si hace
+    /// falta saber si `viejo` es ancestro de `nuevo`.
+    ///
+    /// ## Errores
+    /// - Si la actualizacion borra la rama, no pasa por un pull request o no es fast-forward
+    ///   y la regla de la rama no lo permite
+    pub fn verificar_push<F>(
+        &self,
+        referencia: &str,
+        viejo: &str,
+        nuevo: &str,
+        es_fast_forward: F,
+    ) -> Result<(), String>
+    where
+        F: FnOnce() -> Result<bool, String>,
+    {
+        let (rama, regla) = match referencia
+            .strip_prefix("refs/heads/")
+            .and_then(|rama| Some((rama, self.obtener_regla(rama)?)))
+        {
+            Some(rama_y_regla) => rama_y_regla,
+            None => return Ok(()),
+        };
+
+        if es_hash_nulo(nuevo) {
+            if regla.bloquear_borrado {
+                return Err(format!("rama protegida: no se puede borrar {}", rama));
+            }
+            return Ok(());
+        }
+        if regla.requerir_pull_request {
+            return Err(format!(
+                "rama protegida: los cambios a {} tienen que llegar por un pull request",
+                rama
+            ));
+        }
+        if regla.bloquear_force_push && !es_hash_nulo(viejo) && !es_fast_forward()? {
+            return Err(format!(
+                "rama protegida: no se permite hacer force push a {}",
+                rama
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// El hash de ceros indica que la referencia no existe (antes de crearla o despues de borrarla)
+fn es_hash_nulo(hash: &str) -> bool {
+    hash.chars().all(|caracter| caracter == '0')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use super::*;
+
+    fn proteccion(contenido: &str) -> ProteccionRamas {
+        let dir_gir = PathBuf::from("tmp/proteccion_ramas_test/.gir");
+        io::escribir_bytes(dir_gir.join(ARCHIVO_PROTECCION), contenido).unwrap();
+        ProteccionRamas::cargar(&dir_gir).unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test01_las_reglas_se_eligen_por_patron_y_completan_los_valores_por_defecto() {
+        let proteccion = proteccion(
+            r#"[{"patron": "master", "requerir_pull_request": true}, {"patron": "release/*", "bloquear_borrado": false}]"#,
+        );
+
+        let master = proteccion.obtener_regla("master").unwrap();
+        let release = proteccion.obtener_regla("release/1.0").unwrap();
+
+        assert!(master.requerir_pull_request && master.bloquear_borrado);
+        assert!(release.bloquear_force_push && !release.bloquear_borrado);
+        assert!(proteccion.obtener_regla("release/1.0/fix").is_none());
+        assert!(proteccion.obtener_regla("feature").is_none());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_se_rechazan_los_borrados_y_los_force_push_a_ramas_protegidas() {
+        let proteccion = proteccion(r#"[{"patron": "master"}]"#);
+        let viejo = "1".repeat(40);
+        let nuevo = "2".repeat(40);
+        let nulo = "0".repeat(40);
+
+        assert!(proteccion
+            .verificar_push("refs/heads/master", &viejo, &nuevo, || Ok(true))
+            .is_ok());
+        assert!(proteccion
+            .verificar_push("refs/heads/master", &viejo, &nuevo, || Ok(false))
+            .is_err());
+        assert!(proteccion
+            .verificar_push("refs/heads/master", &viejo, &nulo, || Ok(true))
+            .is_err());
+        assert!(proteccion
+            .verificar_push("refs/heads/otra", &viejo, &nulo, || Ok(false))
+            .is_ok());
+        assert!(proteccion
+            .verificar_push("refs/tags/master", &viejo, &nuevo, || Ok(false))
+            .is_ok());
+    }
+}
@@ -1,11 +1,18 @@
 use crate::{
+    servidor::{
+        archivo_cambiado::ArchivoCambiado,
+        contador, etiqueta,
+        repositorio_servidor::{self, RepositorioServidor},
+    },
     tipos_de_dato::{
         comandos::{log::Log, merge::Merge},
         http::error::ErrorHttp,
         logger::Logger,
         objetos::commit::CommitObj,
+        repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
     },
-    utils::{self, io},
+    utils::{self, io, strings::separar_lista},
 };
 
 use chrono::{DateTime, Utc};
@@ -14,6 +21,8 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 const OPEN: &str = "open";
 const CLOSED: &str = "closed";
+/// Contador del repositorio con los numeros de los pull requests e issues
+const CONTADOR_NUMEROS: &str = "numeros";
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PullRequest {
@@ -32,16 +41,58 @@ pub struct PullRequest {
     pub estado: String,
     pub autor: String,
     pub rama_head: String,
+    /// Fork del repositorio en el que esta la rama head, si el pull request es entre forks
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default = "default_valor_opcional"
+    )]
+    pub repositorio_head: Option<String>,
     pub rama_base: String,
     pub fecha_creacion: String,
     pub fecha_modificacion: String,
     pub repositorio: String,
+    /// Usuario autenticado que mergeo el pull request
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default = "default_valor_opcional"
+    )]
+    pub mergeado_por: Option<String>,
+    /// Etiquetas del catalogo del repositorio
+    #[serde(default)]
+    pub etiquetas: Vec<String>,
+    #[serde(default)]
+    pub asignados: Vec<String>,
+    /// Usuarios a los que se les pidio que revisen el pull request
+    #[serde(default)]
+    pub revisores_solicitados: Vec<String>,
+    /// Titulo del hito al que pertenece el pull request
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        default = "default_valor_opcional"
+    )]
+    pub hito: Option<String>,
+    /// Un borrador no se puede mergear hasta que se lo marque como listo
+    #[serde(default)]
+    pub borrador: bool,
 }
 
 fn default_valor_opcional() -> Option<String> {
     None
 }
 
+/// Resultado de simular el merge de un pull request sin hacerlo
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct EstadoMerge {
+    /// Si el pull request se puede mergear sin conflictos
+    pub mergeable: bool,
+    /// `clean` si se puede mergear, `conflicting` si hay conflictos, `draft` si no hay
+    /// conflictos pero es un borrador o `behind` si se puede mergear pero la rama head no
+    /// tiene el ultimo commit de la base
+    pub mergeable_state: String,
+    /// Archivos que tendrian conflictos al mergear
+    pub conflictos: Vec<String>,
+}
+
 impl PullRequest {
     ///Crea un pull request apartir del body de la request.
     ///
@@ -50,43 +101,55 @@ impl PullRequest {
     ///                 Tiene que existir `./srv/{repositorio}`
     /// -body: el cuerpo de la request recibida. El body tiene que contener
     ///         los campos: `head` y `base`. Puede tener como opcionales:
-    ///         `title` y `body`.
+    ///         `title`, `body`, `labels`, `assignees`, `reviewers`, `milestone` y `draft`.
     ///
     /// ## Resultado
     /// - El pr creados con los campos obligatorios y opcionales. El numero del
-    ///     pr depende de la cantidad de prs que ya tenga el repostiorio. El estado
+    ///     pr es el siguiente del contador del repositorio (ver `reservar_numero`). El estado
     ///     inicial siempre es `open`     
     ///
     /// ## Errores
     /// - Si falta algun campo obligatorio
     /// - Si no exite repositorio
     /// - Si no existe `rama_head`, `rama_base` en el repositorio
+    /// - Si alguna etiqueta no esta en el catalogo del repositorio o el autor es revisor
     pub fn crear_pr(
         repositorio: &str,
         body: HashMap<String, String>,
     ) -> Result<PullRequest, ErrorHttp> {
         Self::verificar_repositorio(repositorio)?;
 
-        let numero = Self::obtener_numero(repositorio)?;
         let titulo = Self::obtener_titulo(&body);
         let descripcion = Self::obtener_descripcion(&body);
         let estado = OPEN.to_string();
-        let (autor, rama_head) = Self::obtener_autor_y_rama_head(repositorio, &body)?;
+        let (autor, rama_head, repositorio_head) =
+            Self::obtener_autor_y_rama_head(repositorio, &body)?;
         let rama_base = Self::obtener_rama_base(repositorio, &body)?;
         let fecha_actual = Self::obtener_fecha_actual();
 
-        Ok(PullRequest {
-            numero,
+        let mut pull_request = PullRequest {
+            numero: 0,
             titulo,
             descripcion,
             estado,
             autor,
             rama_head,
+            repositorio_head,
             rama_base,
             fecha_creacion: fecha_actual.clone(),
             fecha_modificacion: fecha_actual,
             repositorio: repositorio.to_string(),
-        })
+            mergeado_por: None,
+            etiquetas: Vec::new(),
+            asignados: Vec::new(),
+            revisores_solicitados: Vec::new(),
+            hito: None,
+            borrador: false,
+        };
+        pull_request.actualizar_metadatos(&body)?;
+        // el numero se reserva cuando ya se valido todo, para no gastarlo en un pedido invalido
+        pull_request.numero = Self::reservar_numero(repositorio)?;
+        Ok(pull_request)
     }
 
     ///Valida que el pr cumple todo los filtros recibidos en el body. Si no pasa alguno de
@@ -95,7 +158,7 @@ impl PullRequest {
     /// ## Argumetos
     /// - body: el body de la request, desde donde se sacan los filtros
     ///         a aplicar al pr. Los filtros que se aceptan son `state`,
-    ///         `head` y `base`
+    ///         `head`, `base`, `labels`, `assignee`, `milestone` y `draft`
     /// ## Resultado
     /// - si pasa o no todo los filtros recibidos en el body
     pub fn filtrar(&self, body: &HashMap<String, String>) -> bool {
@@ -123,6 +186,37 @@ impl PullRequest {
         pasa_el_filtro_de_rama_base
             && pasa_el_filtro_del_estado
             && pasa_el_filtro_de_autor_y_rama_head
+            && self.filtrar_por_metadatos(body)
+    }
+
+    fn filtrar_por_metadatos(&self, body: &HashMap<String, String>) -> bool {
+        let mut pasa_el_filtro_de_etiquetas = true;
+        let mut pasa_el_filtro_del_asignado = true;
+        let mut pasa_el_filtro_del_hito = true;
+        let mut pasa_el_filtro_de_borrador = true;
+
+        if let Some(etiquetas) = body.get("labels") {
+            pasa_el_filtro_de_etiquetas = separar_lista(etiquetas)
+                .iter()
+                .all(|etiqueta| self.etiquetas.contains(etiqueta));
+        }
+
+        if let Some(asignado) = body.get("assignee") {
+            pasa_el_filtro_del_asignado = self.asignados.contains(asignado);
+        }
+
+        if let Some(hito) = body.get("milestone") {
+            pasa_el_filtro_del_hito = self.hito.as_ref() == Some(hito);
+        }
+
+        if let Some(borrador) = body.get("draft") {
+            pasa_el_filtro_de_borrador = self.borrador.to_string() == *borrador;
+        }
+
+        pasa_el_filtro_de_etiquetas
+            && pasa_el_filtro_del_asignado
+            && pasa_el_filtro_del_hito
+            && pasa_el_filtro_de_borrador
     }
 
     ///Actualiza los campos de un pull request con los parametros del body recibido
@@ -131,8 +225,8 @@ impl PullRequest {
     ///
     /// ## Argumentos
     /// - body: el cuerpo del request recivido. Los campos a actualizar pueden ser:
-    ///     `state`, `title`, `body` o `base`. En caso de ser `base`, tiene que existir la
-    ///     rama base. En caso de ser `state`, tiene ser `"open"` o `"closed"`.
+    ///     `state`, `title`, `body`, `base` o los metadatos (ver `actualizar_metadatos`). Si es
+    ///     `base`, tiene que existir la rama base. Si es `state`, tiene ser `"open"` o `"closed"`.
     /// - repositorio: el repositorio al cual pertenece el pr. Tiene que existir
     ///
     /// ## Resultado
@@ -150,8 +244,10 @@ impl PullRequest {
         let se_actualizo_descripcion = self.actualizar_descripcion(&body);
         let se_actulizo_estado = self.actualizar_estado(&body)?;
         let se_actualiza_rama_base = self.actualizar_rama_base(&body)?;
+        let se_actualizaron_metadatos = self.actualizar_metadatos(&body)?;
 
         let se_actualizo_el_pull_request = se_actualiza_rama_base
+            || se_actualizaron_metadatos
             || se_actualizo_descripcion
             || se_actulizo_estado
             || se_actualizo_titulo;
@@ -163,6 +259,57 @@ impl PullRequest {
         Ok(se_actualizo_el_pull_request)
     }
 
+    /// Actualiza las etiquetas (`labels`), asignados (`assignees`), revisores solicitados
+    /// (`reviewers`), hito (`milestone`) y si es borrador (`draft`, `true` o `false`) con los
+    /// campos que esten en el body. Las listas van separadas por comas y un `milestone` vacio
+    /// quita el hito. Devuelve si cambio alguno
+    fn actualizar_metadatos(&mut self, body: &HashMap<String, String>) -> Result<bool, ErrorHttp> {
+        let estado_previo = (
+            self.etiquetas.clone(),
+            self.asignados.clone(),
+            self.revisores_solicitados.clone(),
+            self.hito.clone(),
+            self.borrador,
+        );
+
+        if let Some(etiquetas) = body.get("labels") {
+            let etiquetas = separar_lista(etiquetas);
+            etiqueta::verificar_etiquetas(self.obtener_repositorio().dir_gir(), &etiquetas)?;
+            self.etiquetas = etiquetas;
+        }
+        if let Some(asignados) = body.get("assignees") {
+            self.asignados = separar_lista(asignados);
+        }
+        if let Some(revisores) = body.get("reviewers") {
+            let revisores = separar_lista(revisores);
+            if revisores.contains(&self.autor) {
+                return Err(ErrorHttp::ValidationFailed(
+                    "No se le puede pedir una revision al autor del pull request".to_string(),
+                ));
+            }
+            self.revisores_solicitados = revisores;
+        }
+        if let Some(hito) = body.get("milestone") {
+            self.hito = Some(hito.trim().to_string()).filter(|hito| !hito.is_empty());
+        }
+        if let Some(borrador) = body.get("draft") {
+            self.borrador = borrador.parse().map_err(|_| {
+                ErrorHttp::ValidationFailed(format!(
+                    "El valor de draft ({borrador}) tiene que ser `true` o `false`"
+                ))
+            })?;
+        }
+
+        let estado_actual = (
+            self.etiquetas.clone(),
+            self.asignados.clone(),
+            self.revisores_solicitados.clone(),
+            self.hito.clone(),
+            self.borrador,
+        );
+        Ok(estado_actual != estado_previo)
+    }
+
     fn actualizar_rama_base(&mut self, body: &HashMap<String, String>) -> Result<bool, ErrorHttp> {
         if let Some(nueva_rama_base) = body.get("base") {
             Self::validar_rama(nueva_rama_base, &self.repositorio)?;
@@ -230,9 +377,7 @@ impl PullRequest {
     }
 
     fn verificar_repositorio(repositorio: &str) -> Result<(), ErrorHttp> {
-        let dir_repositorio = PathBuf::from(format!("./srv/{repositorio}"));
-
-        if dir_repositorio.exists() {
+        if repositorio_servidor::obtener_repositorio(repositorio).existe() {
             Ok(())
         } else {
             Err(ErrorHttp::ValidationFailed(format!(
@@ -247,18 +392,17 @@ impl PullRequest {
     }
 
     fn _obtener_commits(&self, logger: Arc<Logger>) -> Result<Vec<CommitObj>, String> {
-        self.entrar_a_repositorio()
-            .map_err(|e| e.obtener_mensaje())?;
-        let hash_ultimo_commit = Merge::obtener_commit_de_branch(&self.rama_head)?;
-        let ultimo_commit = CommitObj::from_hash(hash_ultimo_commit, logger.clone())?;
+        let hash_ultimo_commit = self.leer_hash_head()?;
+        let repositorio = self.obtener_repositorio_de_lectura().entrar();
+        let hash_rama_base = Merge::obtener_commit_de_branch(&self.rama_base)?;
+        let ultimo_commit = CommitObj::from_hash(hash_ultimo_commit.clone(), logger.clone())?;
         let commits = Log::obtener_listas_de_commits(ultimo_commit, logger.clone())?;
-        let hash_commit_base = Merge::obtener_commit_base_entre_dos_branches(
-            &self.rama_base,
-            &self.rama_head,
+        let hash_commit_base = Merge::obtener_commit_base_entre_commits(
+            &hash_rama_base,
+            &hash_ultimo_commit,
             logger.clone(),
         )?;
-        self.salir_del_repositorio()
-            .map_err(|e| e.obtener_mensaje())?;
+        drop(repositorio);
 
         let commits_spliteados: Vec<&[CommitObj]> = commits
             .split(|commit| commit.hash == hash_commit_base)
@@ -270,17 +414,91 @@ impl PullRequest {
             .map(|commits| commits.to_vec())
     }
 
-    fn obtener_numero(repositorio: &str) -> Result<u64, ErrorHttp> {
-        let direccion = PathBuf::from(format!("./srv/{repositorio}/pulls"));
-        if !direccion.exists() {
-            return Ok(1);
-        }
+    /// Devuelve los archivos que cambian en la rama head desde su commit base con la rama base
+    pub fn obtener_archivos_cambiados(
+        &self,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<ArchivoCambiado>, ErrorHttp> {
+        self._obtener_archivos_cambiados(logger)
+            .map_err(ErrorHttp::InternalServerError)
+    }
+
+    fn _obtener_archivos_cambiados(
+        &self,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<ArchivoCambiado>, String> {
+        let hash_ultimo_commit = self.leer_hash_head()?;
+        let _repositorio = self.obtener_repositorio_de_lectura().entrar();
+        let hash_rama_base = Merge::obtener_commit_de_branch(&self.rama_base)?;
+        let hash_commit_base = Merge::obtener_commit_base_entre_commits(
+            &hash_rama_base,
+            &hash_ultimo_commit,
+            logger.clone(),
+        )?;
+        ArchivoCambiado::comparar_commits(&hash_commit_base, &hash_ultimo_commit, logger)
+    }
 
-        let numero = utils::io::cantidad_entradas_dir(&direccion).map_err(|_| {
-            ErrorHttp::InternalServerError("Fallo al obtener el numero del pr".to_string())
-        })? + 1;
+    /// Simula el merge de la rama head en la rama base sin tocar el index ni el directorio de
+    /// trabajo del repositorio, para saber si el pull request se puede mergear
+    pub fn obtener_estado_merge(&self, logger: Arc<Logger>) -> Result<EstadoMerge, ErrorHttp> {
+        self._obtener_estado_merge(logger)
+            .map_err(ErrorHttp::InternalServerError)
+    }
 
-        Ok(numero)
+    fn _obtener_estado_merge(&self, logger: Arc<Logger>) -> Result<EstadoMerge, String> {
+        let hash_head = self.leer_hash_head()?;
+        let _repositorio = self.obtener_repositorio_de_lectura().entrar();
+        let hash_rama_base = Merge::obtener_commit_de_branch(&self.rama_base)?;
+        let conflictos =
+            Merge::obtener_conflictos_entre_commits(&hash_rama_base, &hash_head, logger.clone())?;
+        let hash_commit_base =
+            Merge::obtener_commit_base_entre_commits(&hash_rama_base, &hash_head, logger)?;
+
+        let mergeable_state = if !conflictos.is_empty() {
+            "conflicting"
+        } else if self.borrador {
+            "draft"
+        } else if hash_commit_base != hash_rama_base {
+            "behind"
+        } else {
+            "clean"
+        };
+        Ok(EstadoMerge {
+            mergeable: conflictos.is_empty(),
+            mergeable_state: mergeable_state.to_string(),
+            conflictos,
+        })
+    }
+
+    /// Reserva el numero del proximo pull request o issue del repositorio. Los pull requests
+    /// y los issues comparten la numeracion, como en github, y los numeros nunca se repiten
+    pub fn reservar_numero(repositorio: &str) -> Result<u64, ErrorHttp> {
+        contador::siguiente(repositorio, CONTADOR_NUMEROS, || {
+            Self::obtener_mayor_numero(repositorio)
+        })
+    }
+
+    // el mayor numero de los pull requests e issues guardados, para los repositorios que
+    // todavia no tienen contador
+    fn obtener_mayor_numero(repositorio: &str) -> Result<u64, ErrorHttp> {
+        let mut mayor = 0;
+        for directorio in ["pulls", "issues"] {
+            let direccion = PathBuf::from(format!("./srv/{repositorio}/{directorio}"));
+            if !io::existe(&direccion) {
+                continue;
+            }
+
+            let entradas = utils::io::leer_directorio(&direccion).map_err(|_| {
+                ErrorHttp::InternalServerError("Fallo al obtener el numero del pr".to_string())
+            })?;
+            for entrada in entradas.flatten() {
+                if let Some(numero) = entrada.file_name().to_str().and_then(|n| n.parse().ok()) {
+                    mayor = mayor.max(numero);
+                }
+            }
+        }
+
+        Ok(mayor)
     }
 
     fn obtener_rama_base(
@@ -297,14 +515,22 @@ impl PullRequest {
         }
     }
 
+    /// Devuelve el autor, la rama head y, si `autor` tiene un fork del repositorio con esa
+    /// rama, el fork. Si no, la rama tiene que estar en el repositorio
     fn obtener_autor_y_rama_head(
         repositorio: &str,
         body: &HashMap<String, String>,
-    ) -> Result<(String, String), ErrorHttp> {
+    ) -> Result<(String, String, Option<String>), ErrorHttp> {
         if let Some(autor_y_rama_head) = body.get("head") {
             let (autor, rama_head) = Self::separara_autor_y_rama_head(autor_y_rama_head)?;
-            Self::validar_rama(&rama_head, repositorio)?;
-            Ok((autor, rama_head))
+            let repositorio_head = match RepositorioServidor::buscar_fork(repositorio, &autor)? {
+                Some(fork) if fork.existe_rama(&rama_head) => Some(fork.nombre),
+                _ => None,
+            };
+            if repositorio_head.is_none() {
+                Self::validar_rama(&rama_head, repositorio)?;
+            }
+            Ok((autor, rama_head, repositorio_head))
         } else {
             Err(ErrorHttp::ValidationFailed(
                 "Falta el parametro 'head' en el body de la request".to_string(),
@@ -314,9 +540,9 @@ impl PullRequest {
 
     //Comprueba si existe en
     fn validar_rama(rama: &str, repositorio: &str) -> Result<(), ErrorHttp> {
-        let direccion = PathBuf::from(format!("./srv/{repositorio}/.gir/refs/heads/{rama}"));
-        println!("direccion: {:?}", direccion);
-        if !direccion.exists() {
+        let direccion = repositorio_servidor::obtener_repositorio(repositorio)
+            .ruta_gir(format!("refs/heads/{rama}"));
+        if !io::existe(&direccion) {
             Err(ErrorHttp::ValidationFailed(format!(
                 "No existe la rama {rama} en el repositorio {repositorio}"
             )))
@@ -343,15 +569,91 @@ impl PullRequest {
         body.get("body").map(|descripcion| descripcion.to_owned())
     }
 
-    pub fn entrar_a_repositorio(&self) -> Result<(), ErrorHttp> {
-        let direccion = format!("srv/{}", self.repositorio);
-        utils::io::cambiar_directorio(direccion).map_err(ErrorHttp::InternalServerError)?;
-        Ok(())
+    /// Devuelve el repositorio del servidor sobre el que esta abierto el pull request
+    pub fn obtener_repositorio(&self) -> Repositorio {
+        Repositorio::new(format!("srv/{}", self.repositorio))
     }
 
-    pub fn salir_del_repositorio(&self) -> Result<(), ErrorHttp> {
-        utils::io::cambiar_directorio("../../").map_err(ErrorHttp::InternalServerError)?;
-        Ok(())
+    /// Devuelve el nombre con el que se encuentra la rama head dentro del repositorio del pull
+    /// request. La rama de un fork se trae como la rama remota `<fork>/<rama>`
+    pub fn obtener_rama_head(&self) -> String {
+        match &self.repositorio_head {
+            Some(fork) => format!("{fork}/{}", self.rama_head),
+            None => self.rama_head.clone(),
+        }
+    }
+
+    /// Devuelve el repositorio del pull request que, si la rama head esta en un fork, lee
+    /// tambien los objetos del fork. Asi se puede comparar la rama head con la base sin
+    /// escribir nada en ninguno de los dos repositorios
+    fn obtener_repositorio_de_lectura(&self) -> Repositorio {
+        let repositorio = self.obtener_repositorio();
+        match &self.repositorio_head {
+            Some(fork) => {
+                repositorio.con_objetos_de(&repositorio_servidor::obtener_repositorio(fork))
+            }
+            None => repositorio,
+        }
+    }
+
+    /// Lee el hash del ultimo commit de la rama head sin modificar ningun repositorio. La rama
+    /// de un fork se lee del propio fork y, si ya no existe ahi, se usa el ultimo commit que se
+    /// trajo al mergear
+    fn leer_hash_head(&self) -> Result<String, String> {
+        let repositorio = self.obtener_repositorio();
+        let ref_head = match &self.repositorio_head {
+            Some(fork) => {
+                let ref_fork = repositorio_servidor::obtener_repositorio(fork)
+                    .ruta_gir(format!("refs/heads/{}", self.rama_head));
+                if io::existe(&ref_fork) {
+                    ref_fork
+                } else {
+                    repositorio.ruta_gir(format!("refs/remotes/{}", self.obtener_rama_head()))
+                }
+            }
+            None => repositorio.ruta_gir(format!("refs/heads/{}", self.rama_head)),
+        };
+        Ok(io::leer_a_string(ref_head)?.trim().to_string())
+    }
+
+    /// Si la rama head esta en un fork, trae al repositorio del pull request los objetos del
+    /// fork y el ultimo commit de la rama, para poder mergearla con la base. Si el fork o la
+    /// rama ya no existen, se sigue usando el ultimo commit que se trajo.
+    ///
+    /// Escribe en el repositorio del pull request leyendo el fork, asi que solo se tiene que
+    /// llamar con los locks de los dos repositorios tomados
+    pub fn sincronizar_rama_head(&self) -> Result<(), String> {
+        let fork = match &self.repositorio_head {
+            Some(fork) => fork,
+            None => return Ok(()),
+        };
+        let dir_gir_fork = repositorio_servidor::obtener_repositorio(fork)
+            .dir_gir()
+            .to_path_buf();
+        let ref_fork = dir_gir_fork.join("refs/heads").join(&self.rama_head);
+        if !io::existe(&ref_fork) {
+            return Ok(());
+        }
+
+        let dir_gir = self.obtener_repositorio().dir_gir().to_path_buf();
+        let hash_head = io::leer_a_string(&ref_fork)?;
+        io::copiar_directorio(dir_gir_fork.join("objects"), dir_gir.join("objects"))?;
+        TransaccionRefs::new(dir_gir)
+            .actualizar(
+                &format!("refs/remotes/{}", self.obtener_rama_head()),
+                hash_head.trim(),
+            )
+            .confirmar()
+    }
+
+    /// Devuelve el hash del ultimo commit de la rama head
+    pub fn obtener_hash_head(&self) -> Result<String, ErrorHttp> {
+        self.leer_hash_head().map_err(|error| {
+            ErrorHttp::InternalServerError(format!(
+                "No se ha podido obtener el hash del commit de la rama {}: {}",
+                self.rama_head, error
+            ))
+        })
     }
 
     pub fn guardar_pr(&self, direccion: &PathBuf) -> Result<(), ErrorHttp> {
@@ -392,7 +694,8 @@ mod test {
 
     use crate::{
         servidor::{
-            gir_server::ServidorGir, repos_almacen::ReposAlmacen, vector_threads::VectorThreads,
+            etiqueta::Etiqueta, gir_server::ServidorGir, pool_threads::ConfiguracionPool,
+            repos_almacen::ReposAlmacen,
         },
         tipos_de_dato::{
             comando::Ejecutar,
@@ -400,9 +703,10 @@ mod test {
         },
         utils::testing::crear_repo_para_pr,
     };
-    use std::{fs::remove_file, net::TcpListener, sync::Mutex};
+    use std::{fs::remove_file, net::TcpListener};
 
-    fn agregar_commit_a_repo(logger: Arc<Logger>) {
+    fn agregar_commit_a_repo(repositorio: &Repositorio, logger: Arc<Logger>) {
+        let _activo = repositorio.entrar();
         io::escribir_bytes("archivo", "contenido3").unwrap();
         let mut add = Add::from(vec!["archivo".to_string()], logger.clone()).unwrap();
         add.ejecutar().unwrap();
@@ -447,6 +751,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test01_guardar_pr".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
         let direccion = PathBuf::from("tmp/test01.json");
@@ -485,6 +796,13 @@ mod test {
                 repositorio:
                     "test02_se_puede_guardar_y_cargar_un_pr_con_un_campo_que_no_se_seriliza"
                         .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
         let direccion = PathBuf::from("tmp/test02.json");
@@ -521,6 +839,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test03_se_puede_actualizar_el_titulo".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -555,6 +880,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test04_se_puede_actualizar_la_descripcion".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -589,6 +921,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test05_se_puede_actualizar_el_estado".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -625,6 +964,13 @@ mod test {
                 autor,
                 repositorio: "test06_se_el_estado_no_puede_cambiar_a_algo_que_no_se_open_o_closed"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -657,6 +1003,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "repo_test_07_pull_request".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -700,6 +1053,13 @@ mod test {
                 autor,
                 repositorio: "test08_no_se_puede_actualizar_la_rama_base_con_una_rama_inexistente"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -740,6 +1100,13 @@ mod test {
                 autor,
                 repositorio: "test09_no_se_puede_actualizar_la_rama_base_a_la_rama_head"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -778,6 +1145,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_10_se_puede_filtrar_el_pr_acorde_a_su_estado".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -802,6 +1176,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_10_se_puede_filtrar_el_pr_acorde_a_su_estado".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -840,6 +1221,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_11_se_puede_filtrar_el_pr_acorde_a_su_rama_base".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -864,6 +1252,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_11_se_puede_filtrar_el_pr_acorde_a_su_rama_base".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -898,6 +1293,13 @@ mod test {
                 autor,
                 repositorio: "test_12_se_puede_filtrar_el_pr_acorde_a_su_autor_y_rama_head"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -923,6 +1325,13 @@ mod test {
                 autor,
                 repositorio: "test_12_se_puede_filtrar_el_pr_acorde_a_su_autor_y_rama_head"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -947,6 +1356,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "repo".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -972,6 +1388,13 @@ mod test {
                 autor,
                 repositorio: "test_12_se_puede_filtrar_el_pr_acorde_a_su_autor_y_rama_head"
                     .to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -1007,6 +1430,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_13_se_puede_filtrar_el_pr_con_varios_filtros".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -1031,6 +1461,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_13_se_puede_filtrar_el_pr_con_varios_filtros".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -1055,6 +1492,13 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "test_13_se_puede_filtrar_el_pr_con_varios_filtros".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
@@ -1077,12 +1521,11 @@ mod test {
         let repos_almacen = ReposAlmacen::new();
 
         let handle = std::thread::spawn(move || {
-            let threads: VectorThreads = Arc::new(Mutex::new(Vec::new()));
             let listener = TcpListener::bind("127.0.0.1:9933").unwrap();
 
             let mut servidor_gir = ServidorGir {
                 listener,
-                threads,
+                pool: ConfiguracionPool::default(),
                 logger: logger_clone,
                 main: None,
                 tx,
@@ -1100,9 +1543,9 @@ mod test {
         let _ = io::rm_directorio("tmp/pr_test_14_dir");
         let _ = io::rm_directorio("srv/repo/");
         io::crear_directorio("tmp/pr_test_14_dir").unwrap();
-        io::cambiar_directorio("tmp/pr_test_14_dir").unwrap();
+        let repositorio = Repositorio::new("tmp/pr_test_14_dir");
 
-        crear_repo_para_pr(logger.clone());
+        crear_repo_para_pr(&repositorio, logger.clone());
         std::thread::sleep(std::time::Duration::from_secs(1));
 
         let pr = {
@@ -1126,11 +1569,16 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "repo".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
-        io::cambiar_directorio("../../").unwrap();
-
         let commits = pr.obtener_commits(logger.clone()).unwrap();
         assert!(commits.len() == 1);
         io::rm_directorio("tmp/pr_test_14_dir").unwrap();
@@ -1145,12 +1593,11 @@ mod test {
         let (tx, _) = std::sync::mpsc::channel();
 
         let handle = std::thread::spawn(move || {
-            let threads: VectorThreads = Arc::new(Mutex::new(Vec::new()));
             let listener = TcpListener::bind("127.0.0.1:9933").unwrap();
 
             let mut servidor_gir = ServidorGir {
                 listener,
-                threads,
+                pool: ConfiguracionPool::default(),
                 logger: logger_clone,
                 main: None,
                 tx,
@@ -1168,9 +1615,9 @@ mod test {
         let _ = io::rm_directorio("tmp/pr_test_15_dir");
         let _ = io::rm_directorio("srv/repo/");
         io::crear_directorio("tmp/pr_test_15_dir").unwrap();
-        io::cambiar_directorio("tmp/pr_test_15_dir").unwrap();
+        let repositorio = Repositorio::new("tmp/pr_test_15_dir");
 
-        crear_repo_para_pr(logger.clone());
+        crear_repo_para_pr(&repositorio, logger.clone());
         std::thread::sleep(std::time::Duration::from_secs(1));
 
         let pr = {
@@ -1194,22 +1641,139 @@ mod test {
                 fecha_modificacion,
                 autor,
                 repositorio: "repo".to_string(),
+                mergeado_por: None,
+                etiquetas: Vec::new(),
+                asignados: Vec::new(),
+                revisores_solicitados: Vec::new(),
+                hito: None,
+                borrador: false,
+                repositorio_head: None,
             }
         };
 
-        io::cambiar_directorio("../../").unwrap();
-
         let commits = pr.obtener_commits(logger.clone()).unwrap();
         assert!(commits.len() == 1);
 
-        io::cambiar_directorio("tmp/pr_test_15_dir").unwrap();
-        agregar_commit_a_repo(logger.clone());
+        agregar_commit_a_repo(&repositorio, logger.clone());
         std::thread::sleep(std::time::Duration::from_secs(1));
 
-        io::cambiar_directorio("../../").unwrap();
         let commits = pr.obtener_commits(logger.clone()).unwrap();
         assert!(commits.len() == 2);
 
         io::rm_directorio("tmp/pr_test_15_dir").unwrap();
     }
+
+    #[test]
+    #[serial]
+    fn test_16_se_pueden_actualizar_y_filtrar_los_metadatos_del_pr() {
+        let repositorio = "test_16_metadatos_pr";
+        let mut pr = PullRequest {
+            numero: 1,
+            titulo: None,
+            descripcion: None,
+            estado: String::from("open"),
+            autor: String::from("siro"),
+            rama_head: String::from("trabajo"),
+            repositorio_head: None,
+            rama_base: String::from("master"),
+            fecha_creacion: String::from("Fecha creacion"),
+            fecha_modificacion: String::from("Fecha modificacion"),
+            repositorio: repositorio.to_string(),
+            mergeado_por: None,
+            etiquetas: Vec::new(),
+            asignados: Vec::new(),
+            revisores_solicitados: Vec::new(),
+            hito: None,
+            borrador: true,
+        };
+        let mut etiqueta = HashMap::new();
+        etiqueta.insert("name".to_string(), "bug".to_string());
+        Etiqueta::crear(pr.obtener_repositorio().dir_gir(), &etiqueta).unwrap();
+
+        let mut body = HashMap::new();
+        body.insert("labels".to_string(), "wip".to_string());
+        let etiqueta_inexistente = pr.actualizar(body);
+
+        let mut body = HashMap::new();
+        body.insert("reviewers".to_string(), "siro, juani".to_string());
+        let autor_como_revisor = pr.actualizar(body);
+
+        let mut body = HashMap::new();
+        body.insert("labels".to_string(), "bug".to_string());
+        body.insert("assignees".to_string(), "juani, mateo".to_string());
+        body.insert("milestone".to_string(), "v1.0".to_string());
+        body.insert("draft".to_string(), "false".to_string());
+        let se_actualizo = pr.actualizar(body).unwrap();
+        io::rm_directorio(format!("srv/{repositorio}")).unwrap();
+
+        let mut filtros = HashMap::new();
+        filtros.insert("labels".to_string(), "bug".to_string());
+        filtros.insert("assignee".to_string(), "mateo".to_string());
+        filtros.insert("milestone".to_string(), "v1.0".to_string());
+        filtros.insert("draft".to_string(), "false".to_string());
+        let mut filtro_borrador = HashMap::new();
+        filtro_borrador.insert("draft".to_string(), "true".to_string());
+
+        assert!(matches!(
+            etiqueta_inexistente,
+            Err(ErrorHttp::ValidationFailed(_))
+        ));
+        assert!(matches!(
+            autor_como_revisor,
+            Err(ErrorHttp::ValidationFailed(_))
+        ));
+        assert!(se_actualizo);
+        assert!(!pr.borrador);
+        assert!(pr.filtrar(&filtros));
+        assert!(!pr.filtrar(&filtro_borrador));
+    }
+
+    #[test]
+    #[serial]
+    fn test_17_se_puede_crear_un_pr_desde_la_rama_de_un_fork() {
+        let repositorio = "test_17_pr_entre_forks";
+        let fork = format!("messi-{repositorio}");
+        let _ = io::rm_directorio(format!("srv/{repositorio}"));
+        let _ = io::rm_directorio(format!("srv/{fork}"));
+        let mut body = HashMap::new();
+        body.insert("name".to_string(), repositorio.to_string());
+        RepositorioServidor::crear(&body, None).unwrap();
+        let dir_gir = repositorio_servidor::obtener_repositorio(repositorio)
+            .dir_gir()
+            .to_path_buf();
+        io::escribir_bytes(dir_gir.join("refs/heads/master"), "hash_master").unwrap();
+        RepositorioServidor::crear_fork(repositorio, &HashMap::new(), "messi").unwrap();
+        let dir_gir_fork = repositorio_servidor::obtener_repositorio(&fork)
+            .dir_gir()
+            .to_path_buf();
+        io::escribir_bytes(dir_gir_fork.join("refs/heads/feature"), "hash_feature").unwrap();
+        io::escribir_bytes(dir_gir_fork.join("objects/ab/cdef"), "objeto").unwrap();
+
+        let mut body = HashMap::new();
+        body.insert("head".to_string(), "messi:feature".to_string());
+        body.insert("base".to_string(), "master".to_string());
+        let pr = PullRequest::crear_pr(repositorio, body.clone()).unwrap();
+        let hash_head = pr.obtener_hash_head().unwrap();
+        let leer_escribio_en_la_base = io::existe(dir_gir.join("objects/ab/cdef"))
+            || io::existe(dir_gir.join(format!("refs/remotes/{fork}/feature")));
+        pr.sincronizar_rama_head().unwrap();
+        let se_trajo_el_objeto = io::existe(dir_gir.join("objects/ab/cdef"));
+        let hash_remoto =
+            io::leer_a_string(dir_gir.join(format!("refs/remotes/{fork}/feature"))).unwrap();
+        body.insert("head".to_string(), "messi:no_existe".to_string());
+        let rama_inexistente = PullRequest::crear_pr(repositorio, body);
+        io::rm_directorio(format!("srv/{repositorio}")).unwrap();
+        io::rm_directorio(format!("srv/{fork}")).unwrap();
+
+        assert_eq!(pr.repositorio_head, Some(fork.clone()));
+        assert_eq!(pr.obtener_rama_head(), format!("{fork}/feature"));
+        assert_eq!(hash_head, "hash_feature");
+        assert!(!leer_escribio_en_la_base);
+        assert!(se_trajo_el_objeto);
+        assert_eq!(hash_remoto, "hash_feature");
+        assert!(matches!(
+            rama_inexistente,
+            Err(ErrorHttp::ValidationFailed(_))
+        ));
+    }
 }
@@ -1,16 +1,53 @@
-use crate::tipos_de_dato::comunicacion::Comunicacion;
+use crate::servidor::proteccion_ramas::ProteccionRamas;
+use crate::servidor::webhook;
+use crate::tipos_de_dato::comandos::log::Log;
+use crate::tipos_de_dato::comunicacion::{
+    Comunicacion, BANDA_DATOS, BANDA_PROGRESO, REPORT_STATUS, SIDE_BAND_64K,
+};
 use crate::tipos_de_dato::logger::Logger;
+use crate::tipos_de_dato::objetos::commit::CommitObj;
 use crate::tipos_de_dato::packfile::Packfile;
-use crate::utils::io;
+use crate::tipos_de_dato::repositorio::Repositorio;
+use crate::tipos_de_dato::transaccion_refs::TransaccionRefs;
+use crate::utils::{hooks, io, strings::obtener_linea_con_largo_hex};
 use std::io::{Read, Write};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-/// Funcion que se encarga de recibir un packfile y actualizar las referencias siguiendo el git transfer protocol
+/// Prefijo del directorio del repositorio en el que se escriben los objetos de un push mientras
+/// se decide si se acepta (Ej: `.gir/cuarentena-<id>/`)
+const PREFIJO_CUARENTENA: &str = "cuarentena-";
+
+/// Actualizacion de una referencia pedida por el cliente: hash viejo, hash nuevo y referencia
+type Actualizacion = (String, String, String);
+
+/// Capacidades que el cliente pidio en la primera actualizacion
+#[derive(Default, Clone, Copy)]
+struct CapacidadesPedidas {
+    report_status: bool,
+    side_band: bool,
+}
+
+/// Funcion que se encarga de recibir un packfile y actualizar las referencias siguiendo el git transfer protocol.
+/// Todas las referencias se actualizan en una misma transaccion: o se actualizan todas o ninguna.
+///
+/// Los objetos del packfile se escriben en un directorio de cuarentena, y recien pasan al
+/// directorio de objetos del repositorio si el push se acepta: uno rechazado no deja objetos.
+/// Antes de actualizar las referencias se verifican las reglas de proteccion de ramas del
+/// repositorio y se ejecutan los hooks `pre-receive` y `update` del repositorio,
+/// que pueden rechazar el push, y si se actualizo alguna referencia, al terminar `post-receive`. Si el cliente pidio `side-band-64k`,
+/// lo que imprimen los hooks se le envia por la banda de progreso a medida que se imprime.
+/// Si pidio `report-status`, al final se le envia el reporte de estado: `unpack ok` y una linea
+/// `ok <ref>` o `ng <ref> <motivo>` por referencia (dentro de la banda de datos si pidio `side-band-64k`).
+/// Si el push se hizo, cada referencia actualizada dispara el evento `push` de los webhooks.
 /// # Argumentos
 /// * `dir` - Direccion del repositorio
 /// * `comunicacion` - Comunicacion con el cliente
 /// # Errores
-/// Devuelve un error si no se puede leer el packfile o si no se puede escribir en el repositorio
+/// Devuelve un error si no se puede leer el packfile o si no se puede escribir en el repositorio.
+/// Tambien si alguna referencia no tiene el valor viejo que informo el cliente (otro push la modifico)
+/// o si alguna regla de proteccion o algun hook rechazo el push.
 pub fn receive_pack<T>(
     dir: String,
     comunicacion: &mut Comunicacion<T>,
@@ -20,28 +57,274 @@ where
     T: Read + Write,
 {
     logger.log("Iniciando receive pack");
-    let actualizaciones = comunicacion.obtener_lineas()?;
+    let lineas = comunicacion.obtener_lineas()?;
     let packfile = comunicacion.obtener_packfile()?;
 
-    Packfile::leer_packfile_y_escribir(&packfile, dir.clone() + "objects/")?;
+    let cuarentena = obtener_dir_cuarentena(&dir);
+    let resultado = io::crear_directorio(&cuarentena)
+        .and_then(|_| Packfile::leer_packfile_y_escribir(&packfile, cuarentena.clone()));
+    if let Err(error) = resultado {
+        let _ = io::rm_directorio(&cuarentena);
+        return Err(error);
+    }
+
+    let (actualizaciones, capacidades) = parsear_actualizaciones(&lineas);
+    let resultado = verificar_proteccion(&dir, &cuarentena, &actualizaciones, logger.clone())
+        .and_then(|_| {
+            ejecutar_hooks_previos(
+                &dir,
+                &actualizaciones,
+                comunicacion,
+                capacidades,
+                logger.clone(),
+            )
+        })
+        .and_then(|_| io::copiar_directorio(&cuarentena, dir.clone() + "objects/"))
+        .and_then(|_| actualizar_referencias(&dir, &actualizaciones));
+    let _ = io::rm_directorio(&cuarentena);
+
+    if resultado.is_ok() && !actualizaciones.is_empty() {
+        let entrada = armar_entrada_hook(&actualizaciones);
+        // el resultado de post-receive no cambia nada, el push ya se hizo
+        let _ = ejecutar_hook_remoto(
+            &dir,
+            hooks::POST_RECEIVE,
+            &[],
+            Some(&entrada),
+            comunicacion,
+            capacidades,
+            logger.clone(),
+        );
+        for (viejo, nuevo, referencia) in &actualizaciones {
+            webhook::disparar_evento_push(
+                Path::new(&dir),
+                referencia,
+                viejo,
+                nuevo,
+                logger.clone(),
+            );
+        }
+    }
 
-    for actualizacion in &actualizaciones {
-        let mut partes = actualizacion.split(' ');
+    if capacidades.report_status {
+        enviar_reporte_de_estado(comunicacion, &actualizaciones, &resultado, capacidades)?;
+    }
+    if capacidades.side_band {
+        comunicacion.enviar_flush_pkt()?;
+    }
+    resultado?;
+    logger.log("Receive pack ejecutado con exito");
+    Ok(())
+}
+
+/// Devuelve un directorio de cuarentena nuevo dentro del directorio gir `dir`, terminado en `/`
+fn obtener_dir_cuarentena(dir: &str) -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duracion| duracion.as_nanos())
+        .unwrap_or_default();
+    format!("{dir}{PREFIJO_CUARENTENA}{}-{nanos}/", std::process::id())
+}
+
+/// Parsea las lineas `<viejo> <nuevo> <ref>` enviadas por el cliente, salteando las que no cambian la referencia.
+/// La primera linea puede traer despues de un `\0` las capacidades que pide el cliente.
+fn parsear_actualizaciones(lineas: &[String]) -> (Vec<Actualizacion>, CapacidadesPedidas) {
+    let mut actualizaciones = Vec::new();
+    let mut capacidades = CapacidadesPedidas::default();
+    for (i, linea) in lineas.iter().enumerate() {
+        let linea = match linea.split_once('\0') {
+            Some((actualizacion, pedidas)) if i == 0 => {
+                let pedidas: Vec<&str> = pedidas.split_whitespace().collect();
+                capacidades.report_status = pedidas.contains(&REPORT_STATUS);
+                capacidades.side_band = pedidas.contains(&SIDE_BAND_64K);
+                actualizacion
+            }
+            _ => linea,
+        };
+        let mut partes = linea.split(' ');
         let viejo_hash_ref = partes.next().unwrap_or("");
         let nuevo_hash_ref = partes.next().unwrap_or("");
         let referencia = partes.next().unwrap_or("").trim_end_matches('\n');
         if nuevo_hash_ref != viejo_hash_ref {
-            io::escribir_bytes(dir.clone() + referencia, nuevo_hash_ref)?;
+            actualizaciones.push((
+                viejo_hash_ref.to_string(),
+                nuevo_hash_ref.to_string(),
+                referencia.to_string(),
+            ));
+        }
+    }
+    (actualizaciones, capacidades)
+}
+
+/// Verifica que las actualizaciones cumplan las reglas de proteccion de ramas del repositorio
+fn verificar_proteccion(
+    dir: &str,
+    cuarentena: &str,
+    actualizaciones: &[Actualizacion],
+    logger: Arc<Logger>,
+) -> Result<(), String> {
+    let proteccion = ProteccionRamas::cargar(Path::new(dir))?;
+    for (viejo, nuevo, referencia) in actualizaciones {
+        proteccion.verificar_push(referencia, viejo, nuevo, || {
+            es_ancestro(dir, cuarentena, viejo, nuevo, logger.clone())
+        })?;
+    }
+    Ok(())
+}
+
+/// Devuelve si el commit `viejo` es ancestro del commit `nuevo` en el repositorio `dir`,
+/// es decir, si pasar de uno al otro es un fast-forward. Los commits del push se leen de la
+/// cuarentena
+fn es_ancestro(
+    dir: &str,
+    cuarentena: &str,
+    viejo: &str,
+    nuevo: &str,
+    logger: Arc<Logger>,
+) -> Result<bool, String> {
+    let dir_gir = Path::new(dir);
+    let _repositorio = Repositorio::new(dir_gir.parent().unwrap_or(dir_gir))
+        .con_objetos_en(cuarentena)
+        .entrar();
+    let commit = CommitObj::from_hash(nuevo.to_string(), logger.clone())?;
+    let ancestros = Log::obtener_listas_de_commits(commit, logger)?;
+    Ok(ancestros.iter().any(|ancestro| ancestro.hash == viejo))
+}
+
+/// Arma la entrada de `pre-receive` y `post-receive`: una linea `<viejo> <nuevo> <ref>` por actualizacion
+fn armar_entrada_hook(actualizaciones: &[Actualizacion]) -> String {
+    actualizaciones
+        .iter()
+        .map(|(viejo, nuevo, referencia)| format!("{} {} {}\n", viejo, nuevo, referencia))
+        .collect()
+}
+
+/// Ejecuta `pre-receive` con todas las actualizaciones y `update` por cada referencia.
+/// Devuelve error si alguno rechaza el push.
+fn ejecutar_hooks_previos<T>(
+    dir: &str,
+    actualizaciones: &[Actualizacion],
+    comunicacion: &mut Comunicacion<T>,
+    capacidades: CapacidadesPedidas,
+    logger: Arc<Logger>,
+) -> Result<(), String>
+where
+    T: Read + Write,
+{
+    if actualizaciones.is_empty() {
+        return Ok(());
+    }
+    let entrada = armar_entrada_hook(actualizaciones);
+    if !ejecutar_hook_remoto(
+        dir,
+        hooks::PRE_RECEIVE,
+        &[],
+        Some(&entrada),
+        comunicacion,
+        capacidades,
+        logger.clone(),
+    )? {
+        return Err("pre-receive hook declined".to_string());
+    }
+
+    for (viejo, nuevo, referencia) in actualizaciones {
+        let args = [referencia.clone(), viejo.clone(), nuevo.clone()];
+        if !ejecutar_hook_remoto(
+            dir,
+            hooks::UPDATE,
+            &args,
+            None,
+            comunicacion,
+            capacidades,
+            logger.clone(),
+        )? {
+            return Err(format!("hook declined {}", referencia));
         }
     }
-    logger.log("Receive pack ejecutado con exito");
     Ok(())
 }
+
+/// Ejecuta un hook del repositorio del servidor. Si el cliente pidio `side-band-64k`, le envia
+/// cada linea que imprime por la banda de progreso; si no, solo se registran en el log.
+/// Devuelve si el hook termino bien.
+fn ejecutar_hook_remoto<T>(
+    dir: &str,
+    nombre: &str,
+    args: &[String],
+    entrada: Option<&str>,
+    comunicacion: &mut Comunicacion<T>,
+    capacidades: CapacidadesPedidas,
+    logger: Arc<Logger>,
+) -> Result<bool, String>
+where
+    T: Read + Write,
+{
+    let dir = Path::new(dir);
+    let mut error_envio = None;
+    let logger_hook = logger.clone();
+    let exito = hooks::ejecutar_hook_en(dir, dir, nombre, args, entrada, logger, |linea| {
+        if !capacidades.side_band {
+            logger_hook.log(&format!("{}: {}", nombre, linea));
+        } else if error_envio.is_none() {
+            let linea = format!("{}\n", linea);
+            error_envio = comunicacion
+                .enviar_por_banda(BANDA_PROGRESO, linea.as_bytes())
+                .err();
+        }
+    })?;
+    match error_envio {
+        Some(error) => Err(error),
+        None => Ok(exito),
+    }
+}
+
+/// Actualiza todas las referencias en una misma transaccion
+fn actualizar_referencias(dir: &str, actualizaciones: &[Actualizacion]) -> Result<(), String> {
+    let mut transaccion = TransaccionRefs::new(dir);
+    for (viejo, nuevo, referencia) in actualizaciones {
+        transaccion = transaccion.actualizar_verificando(referencia, viejo, nuevo);
+    }
+    transaccion.confirmar()
+}
+
+/// Le envia al cliente el reporte de estado del push, terminado en un flush pkt. Si el cliente
+/// pidio `side-band-64k`, el reporte entero viaja dentro de la banda de datos.
+/// Como las referencias se actualizan todas juntas, si hubo un error se rechazan todas con ese motivo.
+fn enviar_reporte_de_estado<T>(
+    comunicacion: &mut Comunicacion<T>,
+    actualizaciones: &[Actualizacion],
+    resultado: &Result<(), String>,
+    capacidades: CapacidadesPedidas,
+) -> Result<(), String>
+where
+    T: Read + Write,
+{
+    let mut reporte = obtener_linea_con_largo_hex("unpack ok\n");
+    for (_, _, referencia) in actualizaciones {
+        let linea = match resultado {
+            Ok(()) => format!("ok {}\n", referencia),
+            Err(motivo) => format!(
+                "ng {} {}\n",
+                referencia,
+                motivo.lines().next().unwrap_or_default()
+            ),
+        };
+        reporte.push_str(&obtener_linea_con_largo_hex(&linea));
+    }
+    reporte.push_str("0000");
+
+    if capacidades.side_band {
+        comunicacion.enviar_por_banda(BANDA_DATOS, reporte.as_bytes())
+    } else {
+        comunicacion.enviar(&reporte)
+    }
+}
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tipos_de_dato::comandos::hash_object::HashObject;
     use crate::tipos_de_dato::{comunicacion::Comunicacion, logger::Logger, packfile};
-    use crate::utils;
+    use crate::utils::{self, compresion, io};
     use serial_test::serial;
     use std::io::{Read, Write};
     use std::path::PathBuf;
@@ -70,29 +353,217 @@ mod tests {
             self.lectura_data.flush()
         }
     }
+    fn enviar_actualizacion_y_pack_vacio<T: Read + Write>(
+        comunicacion: &mut Comunicacion<T>,
+        viejo: &str,
+        nuevo: &str,
+        capacidades: &str,
+    ) {
+        let test_dir = env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test03/.gir/";
+        let actualizaciones = utils::strings::obtener_linea_con_largo_hex(
+            &(viejo.to_string() + " " + nuevo + " refs/heads/master" + capacidades + "\n"),
+        );
+        comunicacion.enviar(&actualizaciones).unwrap();
+        comunicacion.enviar("0000").unwrap();
+        let packfile =
+            packfile::Packfile::obtener_pack_con_archivos(vec![], &(test_dir.clone() + "objects/"))
+                .unwrap();
+        comunicacion.enviar_pack_file(packfile).unwrap();
+    }
+
     #[test]
     #[serial]
     fn test01_refs_se_actualizan_correctamente() {
-        let test_dir = env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test03/.gir/";
         let mock: MockTcpStream = MockTcpStream {
             lectura_data: Vec::new(),
         };
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/fetch_02.txt")).unwrap());
         let mut comunicacion = Comunicacion::new_para_testing(mock, logger.clone());
+        enviar_actualizacion_y_pack_vacio(&mut comunicacion, &"0".repeat(40), &"1".repeat(40), "");
 
-        let actualizaciones = utils::strings::obtener_linea_con_largo_hex(
-            &("0".repeat(40) + " " + &"1".repeat(40) + " refs/heads/master\n"),
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test01/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).unwrap();
+        let nueva_ref = io::leer_a_string(nuevo_repo + "refs/heads/master").unwrap();
+        assert_eq!(nueva_ref, "1".repeat(40));
+    }
+
+    #[test]
+    #[serial]
+    fn test02_no_se_actualiza_la_ref_si_el_valor_viejo_no_coincide() {
+        let mock: MockTcpStream = MockTcpStream {
+            lectura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/receive_pack_02.txt")).unwrap());
+        let mut comunicacion = Comunicacion::new_para_testing(mock, logger.clone());
+        enviar_actualizacion_y_pack_vacio(&mut comunicacion, &"2".repeat(40), &"3".repeat(40), "");
+
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test02/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        io::escribir_bytes(nuevo_repo.clone() + "refs/heads/master", "1".repeat(40)).unwrap();
+
+        assert!(receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).is_err());
+        let ref_sin_cambios = io::leer_a_string(nuevo_repo + "refs/heads/master").unwrap();
+        assert_eq!(ref_sin_cambios, "1".repeat(40));
+    }
+
+    #[test]
+    #[serial]
+    fn test03_un_hook_puede_rechazar_el_push_y_su_salida_llega_al_cliente() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mock: MockTcpStream = MockTcpStream {
+            lectura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/receive_pack_03.txt")).unwrap());
+        let mut comunicacion = Comunicacion::new_para_testing(mock, logger.clone());
+        enviar_actualizacion_y_pack_vacio(
+            &mut comunicacion,
+            &"0".repeat(40),
+            &"1".repeat(40),
+            "\0report-status side-band-64k",
         );
-        comunicacion.enviar(&actualizaciones).unwrap();
+
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test03/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        let hook = nuevo_repo.clone() + "hooks/update";
+        io::escribir_bytes(
+            &hook,
+            "#!/bin/sh\necho \"no se puede pushear a $1\"\nexit 1\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        assert!(receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).is_err());
+        let (datos, progreso) = comunicacion.obtener_bandas_hasta_flush().unwrap();
+        let reporte =
+            utils::strings::separar_lineas_pkt(&String::from_utf8(datos).unwrap()).unwrap();
+
+        assert!(io::leer_a_string(nuevo_repo + "refs/heads/master").is_err());
+        assert_eq!(
+            progreso,
+            vec!["no se puede pushear a refs/heads/master".to_string()]
+        );
+        assert_eq!(
+            reporte,
+            vec![
+                "unpack ok\n".to_string(),
+                "ng refs/heads/master hook declined refs/heads/master\n".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test04_no_se_puede_pushear_directo_a_una_rama_protegida() {
+        let mock: MockTcpStream = MockTcpStream {
+            lectura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/receive_pack_04.txt")).unwrap());
+        let mut comunicacion = Comunicacion::new_para_testing(mock, logger.clone());
+        enviar_actualizacion_y_pack_vacio(
+            &mut comunicacion,
+            &"0".repeat(40),
+            &"1".repeat(40),
+            "\0report-status",
+        );
+
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test04/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        io::escribir_bytes(
+            nuevo_repo.clone() + "proteccion_ramas.json",
+            r#"[{"patron": "master", "requerir_pull_request": true}]"#,
+        )
+        .unwrap();
+
+        assert!(receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).is_err());
+        let reporte = comunicacion.obtener_lineas_hasta_flush().unwrap();
+
+        assert!(io::leer_a_string(nuevo_repo + "refs/heads/master").is_err());
+        assert!(reporte[1].starts_with("ng refs/heads/master rama protegida"));
+    }
+
+    #[test]
+    #[serial]
+    fn test05_sin_report_status_no_se_envia_nada_al_cliente() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mut mock: MockTcpStream = MockTcpStream {
+            lectura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/receive_pack_05.txt")).unwrap());
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test05/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        let hook = nuevo_repo.clone() + "hooks/post-receive";
+        io::escribir_bytes(&hook, "#!/bin/sh\necho \"push recibido\"\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        {
+            let mut comunicacion = Comunicacion::new_para_testing(&mut mock, logger.clone());
+            enviar_actualizacion_y_pack_vacio(
+                &mut comunicacion,
+                &"0".repeat(40),
+                &"1".repeat(40),
+                "",
+            );
+            receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).unwrap();
+        }
+
+        assert_eq!(
+            io::leer_a_string(nuevo_repo + "refs/heads/master").unwrap(),
+            "1".repeat(40)
+        );
+        assert!(mock.lectura_data.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test06_un_push_rechazado_no_deja_objetos_en_el_repositorio() {
+        let mock: MockTcpStream = MockTcpStream {
+            lectura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/receive_pack_06.txt")).unwrap());
+        let mut comunicacion = Comunicacion::new_para_testing(mock, logger.clone());
+
+        let dir_objetos_cliente =
+            env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test06_cliente/";
+        let _ = io::rm_directorio(&dir_objetos_cliente);
+        let blob = b"blob 4\0hola".to_vec();
+        let hash = HashObject::hashear_contenido_objeto(&blob);
+        io::escribir_bytes(
+            format!("{}{}/{}", dir_objetos_cliente, &hash[..2], &hash[2..]),
+            compresion::comprimir_contenido_u8(&blob).unwrap(),
+        )
+        .unwrap();
+
+        let actualizacion = utils::strings::obtener_linea_con_largo_hex(&format!(
+            "{} {} refs/heads/master\0report-status\n",
+            "0".repeat(40),
+            "1".repeat(40)
+        ));
+        comunicacion.enviar(&actualizacion).unwrap();
         comunicacion.enviar("0000").unwrap();
         let packfile =
-            packfile::Packfile::obtener_pack_con_archivos(vec![], &(test_dir.clone() + "objects/"))
+            packfile::Packfile::obtener_pack_con_archivos(vec![hash.clone()], &dir_objetos_cliente)
                 .unwrap();
         comunicacion.enviar_pack_file(packfile).unwrap();
-        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/server_test_dir/test04/";
-        receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).unwrap();
-        // let ref_nuevo_repo = io::leer_bytes(nuevo_repo + "refs/heads/master").unwrap();
-        let nueva_ref = io::leer_a_string(nuevo_repo + "refs/heads/master").unwrap();
-        assert_eq!(nueva_ref, "1".repeat(40));
+
+        let nuevo_repo = env!("CARGO_MANIFEST_DIR").to_string() + "/tmp/receive_pack_test06/";
+        let _ = io::rm_directorio(&nuevo_repo);
+        io::escribir_bytes(
+            nuevo_repo.clone() + "proteccion_ramas.json",
+            r#"[{"patron": "master", "requerir_pull_request": true}]"#,
+        )
+        .unwrap();
+
+        assert!(receive_pack(nuevo_repo.clone(), &mut comunicacion, logger.clone()).is_err());
+
+        let objeto = format!("{}objects/{}/{}", nuevo_repo, &hash[..2], &hash[2..]);
+        assert!(!io::existe(objeto));
+        let entradas = std::fs::read_dir(&nuevo_repo).unwrap();
+        assert!(entradas.flatten().all(|entrada| !entrada
+            .file_name()
+            .to_string_lossy()
+            .starts_with("cuarentena-")));
     }
 }
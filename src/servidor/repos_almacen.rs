@@ -22,13 +22,14 @@ impl ReposAlmacen {
 
     ///Obtien el mutex asosiado a cierto repositorio. En caso de no existir
     /// todavia el repositorio, lo crea en la estructura y lo coloca con su
-    /// determinado mutex valor  
+    /// determinado mutex valor. El nombre se normaliza sin barras, ya que el
+    /// servidor gir recibe `/repo` y el http `repo`
     pub fn obtener_mutex_del_repo(&self, repo: &str) -> Result<Arc<Mutex<()>>, String> {
         Ok(self
             .repo_mutexes
             .lock()
             .map_err(|e| e.to_string())?
-            .entry(repo.to_string())
+            .entry(repo.trim_matches('/').to_string())
             .or_insert_with(|| Arc::new(Mutex::new(())))
             .clone())
     }
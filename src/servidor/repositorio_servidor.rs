@@ -0,0 +1,407 @@
+use std::{collections::HashMap, fs, io::ErrorKind};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    servidor::autenticacion::{self, PermisosRepositorio, ARCHIVO_PERMISOS},
+    tipos_de_dato::{
+        http::{error::ErrorHttp, permiso::Permiso},
+        repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
+    },
+    utils::io,
+};
+
+/// Directorio del servidor con sus repositorios (Ej: `srv/repo/.gir`)
+pub const DIR_REPOSITORIOS: &str = "srv";
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con su dueño y el
+/// repositorio del que es fork
+pub const ARCHIVO_REPOSITORIO: &str = "repositorio.json";
+
+const RAMA_POR_DEFECTO: &str = "master";
+const PREFIJO_HEAD: &str = "ref: refs/heads/";
+
+/// Repositorio alojado en el servidor. Los repositorios creados por un push no tienen
+/// dueño ni fecha de creacion.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RepositorioServidor {
+    pub nombre: String,
+    /// Usuario que creo el repositorio, que tiene permiso de administrador sobre el
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duenio: Option<String>,
+    /// Repositorio del servidor del que es fork
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fork_de: Option<String>,
+    /// Rama a la que apunta el HEAD del repositorio. Se toma del HEAD al cargar el repositorio
+    #[serde(default, skip_deserializing)]
+    pub rama_por_defecto: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fecha_creacion: Option<String>,
+}
+
+impl RepositorioServidor {
+    /// Crea un repositorio vacio sin directorio de trabajo, como el que crea un push.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `name`. Puede tener `default_branch` (por defecto `master`)
+    /// - duenio: usuario que crea el repositorio. Si hay, solo el puede escribirlo y el resto
+    ///   puede leerlo. Si no, el repositorio es publico
+    ///
+    /// ## Errores
+    /// - Si falta el nombre, el nombre o la rama no son validos o ya existe el repositorio
+    pub fn crear(
+        body: &HashMap<String, String>,
+        duenio: Option<String>,
+    ) -> Result<RepositorioServidor, ErrorHttp> {
+        let nombre = body.get("name").ok_or_else(|| {
+            ErrorHttp::ValidationFailed(
+                "Falta el parametro 'name' en el body de la request".to_string(),
+            )
+        })?;
+        let rama_por_defecto = body
+            .get("default_branch")
+            .map(|rama| rama.as_str())
+            .unwrap_or(RAMA_POR_DEFECTO);
+        validar_nombre(rama_por_defecto, "rama")?;
+
+        let repositorio = Self::crear_directorio(nombre)?;
+        let dir_gir = repositorio.dir_gir();
+        for directorio in ["objects", "refs/heads", "refs/tags"] {
+            io::crear_directorio(dir_gir.join(directorio))
+                .map_err(ErrorHttp::InternalServerError)?;
+        }
+        TransaccionRefs::new(dir_gir)
+            .apuntar_simbolicamente("HEAD", &format!("refs/heads/{rama_por_defecto}"))
+            .confirmar()
+            .map_err(ErrorHttp::InternalServerError)?;
+
+        let repositorio_servidor = RepositorioServidor {
+            nombre: nombre.to_string(),
+            duenio,
+            fork_de: None,
+            rama_por_defecto: rama_por_defecto.to_string(),
+            fecha_creacion: Some(Utc::now().to_rfc3339()),
+        };
+        repositorio_servidor.guardar()?;
+        Ok(repositorio_servidor)
+    }
+
+    /// Crea un fork del repositorio `origen` copiando sus objetos, sus ramas, sus tags y su
+    /// HEAD. Los pull requests, issues y configuracion del origen no se copian.
+    ///
+    /// ## Argumentos
+    /// - body: puede tener `name`. Por defecto el fork se llama `<duenio>-<origen>`
+    /// - duenio: usuario que hace el fork, que queda como su administrador
+    ///
+    /// ## Errores
+    /// - Si no existe el origen o ya existe un repositorio con el nombre del fork
+    pub fn crear_fork(
+        origen: &str,
+        body: &HashMap<String, String>,
+        duenio: &str,
+    ) -> Result<RepositorioServidor, ErrorHttp> {
+        let repositorio_origen = Self::cargar(origen)?;
+        let nombre = match body.get("name") {
+            Some(nombre) => nombre.to_string(),
+            None => format!("{duenio}-{origen}"),
+        };
+
+        let dir_gir_origen = obtener_repositorio(origen).dir_gir().to_path_buf();
+        let repositorio = Self::crear_directorio(&nombre)?;
+        let dir_gir = repositorio.dir_gir();
+        let copia = io::copiar_directorio(dir_gir_origen.join("objects"), dir_gir.join("objects"))
+            .and_then(|_| io::copiar_directorio(dir_gir_origen.join("refs"), dir_gir.join("refs")))
+            .and_then(|_| {
+                TransaccionRefs::new(dir_gir)
+                    .apuntar_simbolicamente(
+                        "HEAD",
+                        &format!("refs/heads/{}", repositorio_origen.rama_por_defecto),
+                    )
+                    .confirmar()
+            });
+        if let Err(error) = copia {
+            let _ = io::rm_directorio(repositorio.dir_trabajo());
+            return Err(ErrorHttp::InternalServerError(format!(
+                "No se pudo copiar el repositorio {origen}: {error}"
+            )));
+        }
+
+        let fork = RepositorioServidor {
+            nombre,
+            duenio: Some(duenio.to_string()),
+            fork_de: Some(origen.to_string()),
+            rama_por_defecto: repositorio_origen.rama_por_defecto,
+            fecha_creacion: Some(Utc::now().to_rfc3339()),
+        };
+        fork.guardar()?;
+        Ok(fork)
+    }
+
+    /// Carga el repositorio `nombre` del servidor
+    ///
+    /// ## Errores
+    /// - Si el nombre no es valido
+    /// - `NotFound` si no existe el repositorio
+    pub fn cargar(nombre: &str) -> Result<RepositorioServidor, ErrorHttp> {
+        validar_nombre(nombre, "repositorio")?;
+        let repositorio = obtener_repositorio(nombre);
+        if !repositorio.existe() {
+            return Err(ErrorHttp::NotFound(format!(
+                "No existe el repositorio {nombre}"
+            )));
+        }
+
+        let archivo = repositorio.dir_gir().join(ARCHIVO_REPOSITORIO);
+        let mut repositorio_servidor = if io::existe(&archivo) {
+            let contenido = io::leer_a_string(&archivo).map_err(ErrorHttp::InternalServerError)?;
+            serde_json::from_str(&contenido).map_err(|e| {
+                ErrorHttp::InternalServerError(format!(
+                    "El archivo {} no es valido: {}",
+                    archivo.display(),
+                    e
+                ))
+            })?
+        } else {
+            RepositorioServidor {
+                nombre: nombre.to_string(),
+                duenio: None,
+                fork_de: None,
+                rama_por_defecto: String::new(),
+                fecha_creacion: None,
+            }
+        };
+        repositorio_servidor.rama_por_defecto =
+            io::leer_a_string(repositorio.dir_gir().join("HEAD"))
+                .ok()
+                .and_then(|head| head.trim().strip_prefix(PREFIJO_HEAD).map(str::to_string))
+                .unwrap_or(RAMA_POR_DEFECTO.to_string());
+        Ok(repositorio_servidor)
+    }
+
+    /// Devuelve los repositorios del servidor que `usuario` puede leer, ordenados por nombre
+    pub fn listar(usuario: Option<&str>) -> Result<Vec<RepositorioServidor>, ErrorHttp> {
+        let mut repositorios = Vec::new();
+        for repositorio in Self::listar_todos()? {
+            let dir_gir = obtener_repositorio(&repositorio.nombre)
+                .dir_gir()
+                .to_path_buf();
+            if autenticacion::obtener_permiso(&dir_gir, usuario)? >= Permiso::Lectura {
+                repositorios.push(repositorio);
+            }
+        }
+        Ok(repositorios)
+    }
+
+    /// Busca el fork de `origen` cuyo dueño es `duenio`
+    pub fn buscar_fork(
+        origen: &str,
+        duenio: &str,
+    ) -> Result<Option<RepositorioServidor>, ErrorHttp> {
+        Ok(Self::listar_todos()?.into_iter().find(|repositorio| {
+            repositorio.fork_de.as_deref() == Some(origen)
+                && repositorio.duenio.as_deref() == Some(duenio)
+        }))
+    }
+
+    /// Elimina el repositorio del servidor con sus pull requests, issues y configuracion
+    ///
+    /// ## Errores
+    /// - `NotFound` si no existe el repositorio
+    pub fn eliminar(nombre: &str) -> Result<(), ErrorHttp> {
+        Self::cargar(nombre)?;
+        io::rm_directorio(obtener_repositorio(nombre).dir_trabajo())
+            .map_err(ErrorHttp::InternalServerError)
+    }
+
+    /// Devuelve si el repositorio tiene la rama `rama`
+    pub fn existe_rama(&self, rama: &str) -> bool {
+        io::existe(
+            obtener_repositorio(&self.nombre)
+                .dir_gir()
+                .join("refs/heads")
+                .join(rama),
+        )
+    }
+
+    fn listar_todos() -> Result<Vec<RepositorioServidor>, ErrorHttp> {
+        let mut repositorios = Vec::new();
+        if !io::existe(DIR_REPOSITORIOS) {
+            return Ok(repositorios);
+        }
+        for entrada in
+            io::leer_directorio(DIR_REPOSITORIOS).map_err(ErrorHttp::InternalServerError)?
+        {
+            let entrada = entrada.map_err(|e| {
+                ErrorHttp::InternalServerError(format!("Error leyendo directorio: {}", e))
+            })?;
+            // se saltean los directorios que no son repositorios o que se estan eliminando
+            let nombre = entrada.file_name().to_string_lossy().to_string();
+            if let Ok(repositorio) = Self::cargar(&nombre) {
+                repositorios.push(repositorio);
+            }
+        }
+        repositorios.sort_by(|a, b| a.nombre.cmp(&b.nombre));
+        Ok(repositorios)
+    }
+
+    /// Crea el directorio del repositorio `nombre`. Falla si ya existe, aunque otro pedido lo
+    /// este creando al mismo tiempo
+    fn crear_directorio(nombre: &str) -> Result<Repositorio, ErrorHttp> {
+        validar_nombre(nombre, "repositorio")?;
+        let repositorio = obtener_repositorio(nombre);
+        io::crear_directorio(DIR_REPOSITORIOS).map_err(ErrorHttp::InternalServerError)?;
+        match fs::create_dir(repositorio.dir_trabajo()) {
+            Ok(()) => Ok(repositorio),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(ErrorHttp::ValidationFailed(
+                format!("Ya existe el repositorio {nombre}"),
+            )),
+            Err(e) => Err(ErrorHttp::InternalServerError(format!(
+                "No se pudo crear el repositorio {nombre}: {e}"
+            ))),
+        }
+    }
+
+    /// Guarda los datos del repositorio y, si tiene dueño, sus permisos
+    fn guardar(&self) -> Result<(), ErrorHttp> {
+        let dir_gir = obtener_repositorio(&self.nombre).dir_gir().to_path_buf();
+        let contenido = serde_json::to_string(self).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se pudo serializar el repositorio: {e}"))
+        })?;
+        io::escribir_bytes(dir_gir.join(ARCHIVO_REPOSITORIO), contenido)
+            .map_err(ErrorHttp::InternalServerError)?;
+
+        if let Some(duenio) = &self.duenio {
+            let permisos = PermisosRepositorio {
+                publico: Permiso::Lectura,
+                usuarios: HashMap::from([(duenio.to_string(), Permiso::Admin)]),
+            };
+            let contenido = serde_json::to_string(&permisos).map_err(|e| {
+                ErrorHttp::InternalServerError(format!(
+                    "No se pudieron serializar los permisos: {e}"
+                ))
+            })?;
+            io::escribir_bytes(dir_gir.join(ARCHIVO_PERMISOS), contenido)
+                .map_err(ErrorHttp::InternalServerError)?;
+        }
+        Ok(())
+    }
+}
+
+/// Devuelve el repositorio `nombre` del servidor
+pub fn obtener_repositorio(nombre: &str) -> Repositorio {
+    Repositorio::new(format!("{DIR_REPOSITORIOS}/{nombre}"))
+}
+
+/// Los nombres de repositorios y ramas solo pueden tener letras, numeros, `-`, `_` y `.`, y no
+/// pueden empezar con `.`: asi no se pueden salir del directorio del servidor. Se tiene que
+/// validar todo nombre que se recibe antes de usarlo como ruta
+pub fn validar_nombre(nombre: &str, tipo: &str) -> Result<(), ErrorHttp> {
+    let es_valido = !nombre.is_empty()
+        && !nombre.starts_with('.')
+        && nombre
+            .chars()
+            .all(|caracter| caracter.is_ascii_alphanumeric() || "-_.".contains(caracter));
+    if es_valido {
+        Ok(())
+    } else {
+        Err(ErrorHttp::ValidationFailed(format!(
+            "El nombre de {tipo} '{nombre}' no es valido"
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn body(campos: &[(&str, &str)]) -> HashMap<String, String> {
+        campos
+            .iter()
+            .map(|(clave, valor)| (clave.to_string(), valor.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test01_se_crea_un_repositorio_con_duenio_y_se_elimina() {
+        let nombre = "repositorio_servidor_test01";
+        let _ = io::rm_directorio(format!("{DIR_REPOSITORIOS}/{nombre}"));
+
+        let creado = RepositorioServidor::crear(
+            &body(&[("name", nombre), ("default_branch", "main")]),
+            Some("juani".to_string()),
+        )
+        .unwrap();
+        let repetido = RepositorioServidor::crear(&body(&[("name", nombre)]), None);
+        let cargado = RepositorioServidor::cargar(nombre).unwrap();
+        let dir_gir = obtener_repositorio(nombre).dir_gir().to_path_buf();
+        let permiso_duenio = autenticacion::obtener_permiso(&dir_gir, Some("juani")).unwrap();
+        let permiso_anonimo = autenticacion::obtener_permiso(&dir_gir, None).unwrap();
+        RepositorioServidor::eliminar(nombre).unwrap();
+
+        assert_eq!(creado, cargado);
+        assert_eq!(cargado.rama_por_defecto, "main");
+        assert!(matches!(repetido, Err(ErrorHttp::ValidationFailed(_))));
+        assert_eq!(permiso_duenio, Permiso::Admin);
+        assert_eq!(permiso_anonimo, Permiso::Lectura);
+        assert!(matches!(
+            RepositorioServidor::cargar(nombre),
+            Err(ErrorHttp::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test02_los_nombres_no_pueden_salir_del_directorio_del_servidor() {
+        for nombre in ["", "..", "../repo", "a/b", ".gir"] {
+            assert!(matches!(
+                RepositorioServidor::crear(&body(&[("name", nombre)]), None),
+                Err(ErrorHttp::ValidationFailed(_))
+            ));
+        }
+        for nombre in ["..", "../srv", ".gir"] {
+            assert!(matches!(
+                RepositorioServidor::cargar(nombre),
+                Err(ErrorHttp::ValidationFailed(_))
+            ));
+            assert!(matches!(
+                RepositorioServidor::eliminar(nombre),
+                Err(ErrorHttp::ValidationFailed(_))
+            ));
+            assert!(matches!(
+                RepositorioServidor::crear_fork(nombre, &HashMap::new(), "messi"),
+                Err(ErrorHttp::ValidationFailed(_))
+            ));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test03_el_fork_copia_objetos_y_ramas_y_se_encuentra_por_duenio() {
+        let origen = "repositorio_servidor_test03";
+        let fork = format!("messi-{origen}");
+        let _ = io::rm_directorio(format!("{DIR_REPOSITORIOS}/{origen}"));
+        let _ = io::rm_directorio(format!("{DIR_REPOSITORIOS}/{fork}"));
+        RepositorioServidor::crear(&body(&[("name", origen)]), None).unwrap();
+        let dir_gir = obtener_repositorio(origen).dir_gir().to_path_buf();
+        io::escribir_bytes(dir_gir.join("objects/ab/cdef"), "objeto").unwrap();
+        io::escribir_bytes(dir_gir.join("refs/heads/master"), "abcdef").unwrap();
+
+        let creado = RepositorioServidor::crear_fork(origen, &HashMap::new(), "messi").unwrap();
+        let encontrado = RepositorioServidor::buscar_fork(origen, "messi").unwrap();
+        let de_otro = RepositorioServidor::buscar_fork(origen, "juani").unwrap();
+        let dir_gir_fork = obtener_repositorio(&fork).dir_gir().to_path_buf();
+        let objeto = io::leer_a_string(dir_gir_fork.join("objects/ab/cdef")).unwrap();
+        let tiene_master = creado.existe_rama("master");
+        RepositorioServidor::eliminar(origen).unwrap();
+        RepositorioServidor::eliminar(&fork).unwrap();
+
+        assert_eq!(creado.nombre, fork);
+        assert_eq!(creado.fork_de.as_deref(), Some(origen));
+        assert!(tiene_master);
+        assert_eq!(objeto, "objeto");
+        assert_eq!(encontrado, Some(creado));
+        assert_eq!(de_otro, None);
+    }
+}
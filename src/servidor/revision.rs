@@ -0,0 +1,214 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::{tipos_de_dato::http::error::ErrorHttp, utils::io};
+
+use super::pull_request::PullRequest;
+
+/// Resultado de una revision de un pull request
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EstadoRevision {
+    #[serde(rename = "APPROVED")]
+    Aprobada,
+    #[serde(rename = "CHANGES_REQUESTED")]
+    CambiosPedidos,
+    #[serde(rename = "COMMENTED")]
+    Comentada,
+}
+
+impl EstadoRevision {
+    /// Convierte el `event` de la request (`APPROVE`, `REQUEST_CHANGES` o `COMMENT`) en el estado de la revision
+    pub fn from_evento(evento: &str) -> Result<EstadoRevision, ErrorHttp> {
+        match evento {
+            "APPROVE" => Ok(EstadoRevision::Aprobada),
+            "REQUEST_CHANGES" => Ok(EstadoRevision::CambiosPedidos),
+            "COMMENT" => Ok(EstadoRevision::Comentada),
+            _ => Err(ErrorHttp::ValidationFailed(format!(
+                "Evento de revision invalido: {evento}"
+            ))),
+        }
+    }
+}
+
+/// Revision de un pull request hecha por un usuario sobre un commit de la rama head.
+///
+/// Las revisiones de cada pull request se guardan juntas en `./srv/{repositorio}/revisiones/{numero}`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Revision {
+    pub id: u64,
+    pub revisor: String,
+    pub estado: EstadoRevision,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub cuerpo: Option<String>,
+    /// Commit de la rama head que se reviso
+    pub commit: String,
+    pub fecha: String,
+}
+
+impl Revision {
+    /// Crea una revision del pull request a partir del body de la request y la agrega a sus revisiones.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `event` (`APPROVE`, `REQUEST_CHANGES` o `COMMENT`). Puede tener
+    ///   `body`, que es obligatorio salvo para `APPROVE`, y `commit_id`, el commit revisado.
+    ///   Si no se indica el commit, se toma el ultimo de la rama head.
+    ///
+    /// ## Errores
+    /// - Si el evento no es valido o falta el texto de la revision
+    /// - Si el revisor es el autor del pull request
+    pub fn crear(
+        pull_request: &PullRequest,
+        revisor: &str,
+        body: &HashMap<String, String>,
+    ) -> Result<Revision, ErrorHttp> {
+        let evento = body.get("event").ok_or_else(|| {
+            ErrorHttp::ValidationFailed(
+                "Falta el parametro 'event' en el body de la request".to_string(),
+            )
+        })?;
+        let estado = EstadoRevision::from_evento(evento)?;
+        let cuerpo = body.get("body").cloned();
+        if estado != EstadoRevision::Aprobada && cuerpo.is_none() {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "El evento {evento} necesita el parametro 'body'"
+            )));
+        }
+        if revisor == pull_request.autor {
+            return Err(ErrorHttp::ValidationFailed(
+                "El autor no puede revisar su propio pull request".to_string(),
+            ));
+        }
+        let commit = match body.get("commit_id") {
+            Some(commit) => commit.to_owned(),
+            None => pull_request.obtener_hash_head()?,
+        };
+
+        let mut revisiones = Self::listar(&pull_request.repositorio, pull_request.numero)?;
+        let revision = Revision {
+            id: revisiones.len() as u64 + 1,
+            revisor: revisor.to_string(),
+            estado,
+            cuerpo,
+            commit,
+            fecha: Utc::now().to_rfc3339(),
+        };
+        revisiones.push(revision.clone());
+        Self::guardar_todas(&pull_request.repositorio, pull_request.numero, &revisiones)?;
+        Ok(revision)
+    }
+
+    /// Devuelve las revisiones del pull request `numero`, en el orden en que se hicieron
+    pub fn listar(repositorio: &str, numero: u64) -> Result<Vec<Revision>, ErrorHttp> {
+        let direccion = Self::obtener_dir(repositorio, numero);
+        if !io::existe(&direccion) {
+            return Ok(Vec::new());
+        }
+        let contenido = io::leer_a_string(&direccion).map_err(ErrorHttp::InternalServerError)?;
+        serde_json::from_str(&contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!(
+                "Fallo al leer las revisiones del pull request {numero}: {e}"
+            ))
+        })
+    }
+
+    fn guardar_todas(
+        repositorio: &str,
+        numero: u64,
+        revisiones: &[Revision],
+    ) -> Result<(), ErrorHttp> {
+        let contenido = serde_json::to_string(revisiones).map_err(|e| {
+            ErrorHttp::InternalServerError(format!(
+                "No se han podido serializar las revisiones: {e}"
+            ))
+        })?;
+        io::escribir_bytes(Self::obtener_dir(repositorio, numero), contenido).map_err(|e| {
+            ErrorHttp::InternalServerError(format!("No se han podido guardar las revisiones: {e}"))
+        })
+    }
+
+    fn obtener_dir(repositorio: &str, numero: u64) -> PathBuf {
+        PathBuf::from(format!("./srv/{repositorio}/revisiones/{numero}"))
+    }
+}
+
+/// Devuelve la ultima revision que aprueba o pide cambios de cada revisor.
+/// Los comentarios no cambian la opinion del revisor.
+fn obtener_ultimas_por_revisor(revisiones: &[Revision]) -> Vec<&Revision> {
+    let mut ultimas: HashMap<&str, &Revision> = HashMap::new();
+    for revision in revisiones {
+        if revision.estado != EstadoRevision::Comentada {
+            ultimas.insert(&revision.revisor, revision);
+        }
+    }
+    ultimas.into_values().collect()
+}
+
+/// Estado general de las revisiones de un pull request: `changes_requested` si algun revisor
+/// pide cambios, `approved` si alguno aprobo y ninguno pide cambios, o `pending` si todavia no
+/// hay aprobaciones ni pedidos de cambios.
+pub fn obtener_estado_general(revisiones: &[Revision]) -> &'static str {
+    let ultimas = obtener_ultimas_por_revisor(revisiones);
+    if ultimas
+        .iter()
+        .any(|revision| revision.estado == EstadoRevision::CambiosPedidos)
+    {
+        "changes_requested"
+    } else if ultimas.is_empty() {
+        "pending"
+    } else {
+        "approved"
+    }
+}
+
+/// Cuenta los revisores cuya ultima opinion es una aprobacion del commit `commit`
+pub fn contar_aprobaciones(revisiones: &[Revision], commit: &str) -> usize {
+    obtener_ultimas_por_revisor(revisiones)
+        .iter()
+        .filter(|revision| revision.estado == EstadoRevision::Aprobada && revision.commit == commit)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(id: u64, revisor: &str, estado: EstadoRevision, commit: &str) -> Revision {
+        Revision {
+            id,
+            revisor: revisor.to_string(),
+            estado,
+            cuerpo: None,
+            commit: commit.to_string(),
+            fecha: String::new(),
+        }
+    }
+
+    #[test]
+    fn test01_el_estado_general_depende_de_la_ultima_opinion_de_cada_revisor() {
+        let mut revisiones = vec![revision(1, "juani", EstadoRevision::Comentada, "a")];
+        assert_eq!(obtener_estado_general(&revisiones), "pending");
+
+        revisiones.push(revision(2, "juani", EstadoRevision::CambiosPedidos, "a"));
+        revisiones.push(revision(3, "messi", EstadoRevision::Aprobada, "a"));
+        assert_eq!(obtener_estado_general(&revisiones), "changes_requested");
+
+        revisiones.push(revision(4, "juani", EstadoRevision::Aprobada, "b"));
+        revisiones.push(revision(5, "juani", EstadoRevision::Comentada, "b"));
+        assert_eq!(obtener_estado_general(&revisiones), "approved");
+    }
+
+    #[test]
+    fn test02_solo_cuentan_las_aprobaciones_del_commit_indicado() {
+        let revisiones = vec![
+            revision(1, "juani", EstadoRevision::Aprobada, "viejo"),
+            revision(2, "messi", EstadoRevision::Aprobada, "nuevo"),
+            revision(3, "dibu", EstadoRevision::Aprobada, "nuevo"),
+            revision(4, "dibu", EstadoRevision::CambiosPedidos, "nuevo"),
+        ];
+
+        assert_eq!(contar_aprobaciones(&revisiones, "nuevo"), 1);
+        assert_eq!(contar_aprobaciones(&revisiones, "viejo"), 1);
+    }
+}
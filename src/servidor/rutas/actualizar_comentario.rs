@@ -0,0 +1,108 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::{autenticacion, comentario::Comentario, repositorio_servidor},
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            permiso::Permiso, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::responder_en_formato_json;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Patch,
+        "/repos/{repo}/issues/comments/{comment_id}".to_string(),
+        actualizar_comentario,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Patch,
+        "/repos/{repo}/pulls/comments/{comment_id}".to_string(),
+        actualizar_comentario_de_revision,
+    );
+    rutas.push(endpoint)
+}
+
+fn actualizar_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _actualizar_comentario(request, params, logger, false)
+}
+
+fn actualizar_comentario_de_revision(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _actualizar_comentario(request, params, logger, true)
+}
+
+fn _actualizar_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+    de_revision: bool,
+) -> Result<Response, ErrorHttp> {
+    let (repo, mut comentario) =
+        obtener_comentario_de_params(&params, request.usuario.as_deref(), de_revision)?;
+
+    if let Some(body) = request.body {
+        comentario.actualizar(&body);
+    }
+    comentario.guardar(&repo)?;
+
+    responder_en_formato_json(&comentario, logger, EstadoHttp::Ok)
+}
+
+///Obtiene el comentario desde los parametros, que tienen que tener el `repo` y el `comment_id`.
+/// Se devuelve junto al nombre del repositorio.
+///
+/// ## Errores
+/// - `Unauthorized` si el pedido no esta autenticado
+/// - `NotFound` si no existe el comentario o no es del tipo de la ruta (general o de revision)
+/// - `Forbidden` si el usuario no es el autor del comentario ni administrador del repositorio
+pub fn obtener_comentario_de_params(
+    params: &HashMap<String, String>,
+    usuario: Option<&str>,
+    de_revision: bool,
+) -> Result<(String, Comentario), ErrorHttp> {
+    let usuario = usuario.ok_or_else(|| {
+        ErrorHttp::Unauthorized(
+            "Se requiere autenticacion para modificar un comentario".to_string(),
+        )
+    })?;
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
+    })?;
+    let id = params
+        .get("comment_id")
+        .and_then(|id| id.parse::<u64>().ok())
+        .ok_or_else(|| ErrorHttp::NotFound("El id del comentario no es valido".to_string()))?;
+
+    let comentario = Comentario::cargar(repo, id)?;
+    if comentario.es_de_revision() != de_revision {
+        return Err(ErrorHttp::NotFound(format!(
+            "No existe el comentario {id} en el repositorio {repo}"
+        )));
+    }
+    let dir_gir = repositorio_servidor::obtener_repositorio(repo)
+        .dir_gir()
+        .to_path_buf();
+    if usuario != comentario.autor
+        && autenticacion::obtener_permiso(&dir_gir, Some(usuario))? < Permiso::Admin
+    {
+        return Err(ErrorHttp::Forbidden(format!(
+            "Solo {} o un administrador del repositorio pueden modificar el comentario {id}",
+            comentario.autor
+        )));
+    }
+    Ok((repo.to_string(), comentario))
+}
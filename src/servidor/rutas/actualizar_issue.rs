@@ -0,0 +1,34 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::tipos_de_dato::{
+    http::{
+        endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+        request::Request, response::Response,
+    },
+    logger::Logger,
+};
+
+use super::{obtener_issue::obtener_issue_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Patch,
+        "/repos/{repo}/issues/{issue_number}".to_string(),
+        actualizar_issue,
+    );
+    rutas.push(endpoint)
+}
+
+fn actualizar_issue(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let mut issue = obtener_issue_de_params(&params)?;
+    if let Some(body) = request.body {
+        if issue.actualizar(&body)? {
+            issue.guardar()?;
+        }
+    }
+    responder_en_formato_json(&issue, logger, EstadoHttp::Ok)
+}
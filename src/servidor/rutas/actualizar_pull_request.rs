@@ -1,6 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use crate::{
+    servidor::webhook,
     tipos_de_dato::{
         http::{
             endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
@@ -37,9 +38,24 @@ fn actualizar_pull_request(
         ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
     })?;
 
+    let estado_previo = pull_request.estado.clone();
+    let borrador_previo = pull_request.borrador;
+    let mut hubo_cambios = false;
     if let Some(body) = request.body {
-        pull_request.actualizar(body)?;
+        hubo_cambios = pull_request.actualizar(body)?;
     }
     guadar_pull_request_acorde_al_numero(&pull_request, repo)?;
+    if hubo_cambios {
+        let accion = match pull_request.estado.as_str() {
+            estado if estado == estado_previo => match pull_request.borrador {
+                borrador if borrador == borrador_previo => "edited",
+                true => "converted_to_draft",
+                false => "ready_for_review",
+            },
+            "closed" => "closed",
+            _ => "reopened",
+        };
+        webhook::disparar_evento_pull_request(&pull_request, accion, logger.clone());
+    }
     responder_pull_request_en_formato_json(pull_request, logger, EstadoHttp::Ok)
 }
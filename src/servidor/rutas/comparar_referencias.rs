@@ -0,0 +1,52 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::comparacion::Comparacion,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/compare/{*referencias}".to_string(),
+        comparar_referencias,
+    );
+    rutas.push(endpoint)
+}
+
+/// Compara las referencias `{base}...{head}`: devuelve el commit base, cuantos commits esta
+/// head adelante y atras de base, los commits de head y los archivos que cambian
+fn comparar_referencias(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let referencias = params.get("referencias").ok_or_else(|| {
+        ErrorHttp::InternalServerError(
+            "No se encontraron las referencias en los parametros".to_string(),
+        )
+    })?;
+    let (base, head) = separar_referencias(referencias)?;
+
+    let comparacion = Comparacion::new(base, head, logger.clone())?;
+    responder_en_formato_json(&comparacion, logger, EstadoHttp::Ok)
+}
+
+fn separar_referencias(referencias: &str) -> Result<(&str, &str), ErrorHttp> {
+    match referencias.split_once("...") {
+        Some((base, head)) if !base.is_empty() && !head.is_empty() => Ok((base, head)),
+        _ => Err(ErrorHttp::NotFound(format!(
+            "{referencias} no tiene la forma base...head"
+        ))),
+    }
+}
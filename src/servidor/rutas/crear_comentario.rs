@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::comentario::Comentario,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{
+    obtener_pull_request::obtener_pull_request_de_params,
+    respuesta::{responder_en_formato_json, AUTOR_ANONIMO},
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/issues/{pull_number}/comments".to_string(),
+        crear_comentario,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/pulls/{pull_number}/comments".to_string(),
+        crear_comentario_de_revision,
+    );
+    rutas.push(endpoint)
+}
+
+fn crear_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _crear_comentario(request, params, logger, false)
+}
+
+fn crear_comentario_de_revision(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _crear_comentario(request, params, logger, true)
+}
+
+fn _crear_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+    de_revision: bool,
+) -> Result<Response, ErrorHttp> {
+    let pull_request = obtener_pull_request_de_params(&params)?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+    let autor = request.usuario.as_deref().unwrap_or(AUTOR_ANONIMO);
+
+    let comentario = if de_revision {
+        Comentario::crear_de_revision(&pull_request.repositorio, pull_request.numero, autor, &body)?
+    } else {
+        Comentario::crear(&pull_request.repositorio, pull_request.numero, autor, &body)?
+    };
+    comentario.guardar(&pull_request.repositorio)?;
+
+    responder_en_formato_json(&comentario, logger, EstadoHttp::Created)
+}
@@ -0,0 +1,47 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::estado_commit::EstadoCommit,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::{responder_en_formato_json, AUTOR_ANONIMO};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/statuses/{sha}".to_string(),
+        crear_estado_commit,
+    );
+    rutas.push(endpoint)
+}
+
+/// Agrega el resultado de un chequeo (Ej: un script de integracion continua) al commit `sha`
+fn crear_estado_commit(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError(
+            "No se encontro el repositorio en los parametros".to_string(),
+        )
+    })?;
+    let sha = params.get("sha").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se encontro el sha en los parametros".to_string())
+    })?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+    let creador = request.usuario.as_deref().unwrap_or(AUTOR_ANONIMO);
+
+    let estado = EstadoCommit::crear(repo, sha, creador, &body)?;
+
+    responder_en_formato_json(&estado, logger, EstadoHttp::Created)
+}
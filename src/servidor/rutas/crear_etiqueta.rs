@@ -0,0 +1,38 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::etiqueta::Etiqueta,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_dir_gir_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/labels".to_string(),
+        crear_etiqueta,
+    );
+    rutas.push(endpoint)
+}
+
+fn crear_etiqueta(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let dir_gir = obtener_dir_gir_de_params(&params)?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+
+    let etiqueta = Etiqueta::crear(&dir_gir, &body)?;
+
+    responder_en_formato_json(&etiqueta, logger, EstadoHttp::Created)
+}
@@ -0,0 +1,46 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::repositorio_servidor::RepositorioServidor,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            permiso::Permiso, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::responder_en_formato_json;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/forks".to_string(),
+        crear_fork,
+    )
+    .con_permiso(Permiso::Lectura);
+    rutas.push(endpoint)
+}
+
+/// Crea un fork del repositorio. Alcanza con poder leer el repositorio, pero hay que estar
+/// autenticado: el usuario del pedido queda como dueño del fork
+fn crear_fork(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError(
+            "No se encontro el repositorio en los parametros".to_string(),
+        )
+    })?;
+    let duenio = request.usuario.ok_or_else(|| {
+        ErrorHttp::Unauthorized("Se requiere autenticacion para crear un fork".to_string())
+    })?;
+    let body = request.body.unwrap_or_default();
+
+    let fork = RepositorioServidor::crear_fork(repo, &body, &duenio)?;
+
+    responder_en_formato_json(&fork, logger, EstadoHttp::Created)
+}
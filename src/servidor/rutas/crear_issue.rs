@@ -0,0 +1,41 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::issue::Issue,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::{responder_en_formato_json, AUTOR_ANONIMO};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/issues".to_string(),
+        crear_issue,
+    );
+    rutas.push(endpoint)
+}
+
+fn crear_issue(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
+    })?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+    let autor = request.usuario.as_deref().unwrap_or(AUTOR_ANONIMO);
+
+    let issue = Issue::crear(repo, autor, &body)?;
+    issue.guardar()?;
+    responder_en_formato_json(&issue, logger, EstadoHttp::Created)
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::{
-    servidor::{pull_request::PullRequest},
+    servidor::{pull_request::PullRequest, webhook},
     tipos_de_dato::{
         http::{
             endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
@@ -38,8 +38,13 @@ fn crear_pull_request(
         }
     };
 
-    let pull_request = PullRequest::crear_pr(repo, body)?;
+    let mut pull_request = PullRequest::crear_pr(repo, body)?;
+    // si el pedido esta autenticado, el autor es el usuario y no el que dice el body
+    if let Some(usuario) = request.usuario {
+        pull_request.autor = usuario;
+    }
     guadar_pull_request_acorde_al_numero(&pull_request, repo)?;
+    webhook::disparar_evento_pull_request(&pull_request, "opened", logger.clone());
 
     responder_pull_request_en_formato_json(pull_request, logger, EstadoHttp::Created)
 }
@@ -0,0 +1,35 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::repositorio_servidor::RepositorioServidor,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::responder_en_formato_json;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(MetodoHttp::Post, "/repos".to_string(), crear_repositorio);
+    rutas.push(endpoint)
+}
+
+/// Crea un repositorio vacio en el servidor. El usuario autenticado queda como dueño del
+/// repositorio
+fn crear_repositorio(
+    request: Request,
+    _params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+
+    let repositorio = RepositorioServidor::crear(&body, request.usuario)?;
+
+    responder_en_formato_json(&repositorio, logger, EstadoHttp::Created)
+}
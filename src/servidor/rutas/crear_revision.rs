@@ -0,0 +1,43 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::revision::Revision,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{
+    obtener_pull_request::obtener_pull_request_de_params, respuesta::responder_en_formato_json,
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/pulls/{pull_number}/reviews".to_string(),
+        crear_revision,
+    );
+    rutas.push(endpoint)
+}
+
+fn crear_revision(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let pull_request = obtener_pull_request_de_params(&params)?;
+    let revisor = request.usuario.ok_or_else(|| {
+        ErrorHttp::Unauthorized("Hay que autenticarse para revisar un pull request".to_string())
+    })?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+
+    let revision = Revision::crear(&pull_request, &revisor, &body)?;
+
+    responder_en_formato_json(&revision, logger, EstadoHttp::Created)
+}
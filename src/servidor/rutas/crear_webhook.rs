@@ -0,0 +1,70 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use crate::{
+    servidor::webhook::Webhook,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            permiso::Permiso, request::Request, response::Response,
+        },
+        logger::Logger,
+        repositorio::Repositorio,
+    },
+    utils::io,
+};
+
+use super::respuesta::responder_en_formato_json;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Post,
+        "/repos/{repo}/hooks".to_string(),
+        crear_webhook,
+    )
+    .con_permiso(Permiso::Admin);
+    rutas.push(endpoint)
+}
+
+fn crear_webhook(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let dir_gir = obtener_dir_gir_de_params(&params)?;
+    let body = request.body.ok_or_else(|| {
+        ErrorHttp::BadRequest("No se ha encontrado el cuerpo de la solicitud".to_string())
+    })?;
+
+    let webhook = Webhook::crear(&dir_gir, &body)?;
+
+    responder_en_formato_json(&webhook.sin_secreto(), logger, EstadoHttp::Created)
+}
+
+/// Devuelve el directorio gir del repositorio `repo` de los parametros
+///
+/// ## Errores
+/// - Si no existe el repositorio en el servidor
+pub fn obtener_dir_gir_de_params(params: &HashMap<String, String>) -> Result<PathBuf, ErrorHttp> {
+    Ok(obtener_repositorio_de_params(params)?
+        .dir_gir()
+        .to_path_buf())
+}
+
+/// Devuelve el repositorio del servidor `repo` de los parametros
+///
+/// ## Errores
+/// - Si no existe el repositorio en el servidor
+pub fn obtener_repositorio_de_params(
+    params: &HashMap<String, String>,
+) -> Result<Repositorio, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
+    })?;
+    let repositorio = Repositorio::new(format!("srv/{repo}"));
+    if !io::existe(repositorio.dir_gir()) {
+        return Err(ErrorHttp::NotFound(format!(
+            "No existe en el server el repositorio {repo}"
+        )));
+    }
+    Ok(repositorio)
+}
@@ -0,0 +1,59 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::comentario::Comentario,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::actualizar_comentario::obtener_comentario_de_params;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Delete,
+        "/repos/{repo}/issues/comments/{comment_id}".to_string(),
+        eliminar_comentario,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Delete,
+        "/repos/{repo}/pulls/comments/{comment_id}".to_string(),
+        eliminar_comentario_de_revision,
+    );
+    rutas.push(endpoint)
+}
+
+fn eliminar_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _eliminar_comentario(request, params, logger, false)
+}
+
+fn eliminar_comentario_de_revision(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _eliminar_comentario(request, params, logger, true)
+}
+
+fn _eliminar_comentario(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+    de_revision: bool,
+) -> Result<Response, ErrorHttp> {
+    let (repo, comentario) =
+        obtener_comentario_de_params(&params, request.usuario.as_deref(), de_revision)?;
+    Comentario::eliminar(&repo, comentario.id)?;
+
+    Ok(Response::new(logger, EstadoHttp::NoContent, None))
+}
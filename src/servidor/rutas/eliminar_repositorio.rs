@@ -0,0 +1,38 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::repositorio_servidor::RepositorioServidor,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            permiso::Permiso, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Delete,
+        "/repos/{repo}".to_string(),
+        eliminar_repositorio,
+    )
+    .con_permiso(Permiso::Admin);
+    rutas.push(endpoint)
+}
+
+fn eliminar_repositorio(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError(
+            "No se encontro el repositorio en los parametros".to_string(),
+        )
+    })?;
+
+    RepositorioServidor::eliminar(repo)?;
+
+    Ok(Response::new(logger, EstadoHttp::NoContent, None))
+}
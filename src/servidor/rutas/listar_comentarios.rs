@@ -0,0 +1,71 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::comentario::Comentario,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint,
+            error::ErrorHttp,
+            metodos::MetodoHttp,
+            paginacion::{self, responder_paginado},
+            request::Request,
+            response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::obtener_pull_request::obtener_pull_request_de_params;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/issues/{pull_number}/comments".to_string(),
+        listar_comentarios,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/pulls/{pull_number}/comments".to_string(),
+        listar_comentarios_de_revision,
+    );
+    rutas.push(endpoint)
+}
+
+fn listar_comentarios(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _listar_comentarios(request, params, logger, false)
+}
+
+fn listar_comentarios_de_revision(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    _listar_comentarios(request, params, logger, true)
+}
+
+/// Lista los comentarios, por defecto del mas viejo al mas nuevo. El orden y la paginacion se
+/// leen del query string o del body
+fn _listar_comentarios(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+    de_revision: bool,
+) -> Result<Response, ErrorHttp> {
+    let pull_request = obtener_pull_request_de_params(&params)?;
+    let mut comentarios =
+        Comentario::listar(&pull_request.repositorio, pull_request.numero, de_revision)?;
+    paginacion::ordenar_por_fecha(
+        &mut comentarios,
+        &request.obtener_filtros(),
+        "asc",
+        |comentario| (&comentario.fecha_creacion, &comentario.fecha_modificacion),
+    )?;
+
+    responder_paginado(&request, comentarios, logger)
+}
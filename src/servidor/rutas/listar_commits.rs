@@ -0,0 +1,79 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::{archivo_cambiado::ArchivoCambiado, explorador},
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            paginacion::responder_paginado, request::Request, response::Response,
+        },
+        logger::Logger,
+        objetos::commit::CommitObj,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/commits".to_string(),
+        listar_commits,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/commits/{sha}".to_string(),
+        obtener_commit,
+    );
+    rutas.push(endpoint)
+}
+
+/// Lista los commits alcanzables desde la referencia `sha` (por defecto la rama actual). Con
+/// `path` solo se listan los commits que cambian ese archivo o directorio
+fn listar_commits(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let filtros = request.obtener_filtros();
+
+    let hash_commit = explorador::resolver_referencia(filtros.get("sha").map(|sha| sha.as_str()))?;
+    let commits = explorador::listar_commits(
+        &hash_commit,
+        filtros.get("path").map(|ruta| ruta.as_str()),
+        logger.clone(),
+    )
+    .map_err(ErrorHttp::InternalServerError)?;
+
+    responder_paginado(&request, commits, logger)
+}
+
+/// Devuelve el commit junto con los archivos que cambia respecto de su primer padre
+fn obtener_commit(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let referencia = params.get("sha").map(|sha| sha.as_str());
+
+    let hash_commit = explorador::resolver_referencia(referencia)?;
+    let commit = CommitObj::from_hash(hash_commit, logger.clone())
+        .map_err(ErrorHttp::InternalServerError)?;
+    let archivos = ArchivoCambiado::comparar_con_padre(&commit, logger.clone())
+        .map_err(ErrorHttp::InternalServerError)?;
+
+    let mut commit = serde_json::to_value(&commit).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se ha podido serializar el commit: {}", e))
+    })?;
+    commit["archivos"] = serde_json::to_value(archivos).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se han podido serializar los archivos: {}", e))
+    })?;
+
+    responder_en_formato_json(&commit, logger, EstadoHttp::Ok)
+}
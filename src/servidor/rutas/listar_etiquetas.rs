@@ -0,0 +1,34 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::etiqueta::Etiqueta,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_dir_gir_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/labels".to_string(),
+        listar_etiquetas,
+    );
+    rutas.push(endpoint)
+}
+
+fn listar_etiquetas(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let dir_gir = obtener_dir_gir_de_params(&params)?;
+    let etiquetas = Etiqueta::listar(&dir_gir).map_err(ErrorHttp::InternalServerError)?;
+
+    responder_en_formato_json(&etiquetas, logger, EstadoHttp::Ok)
+}
@@ -0,0 +1,52 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::issue::Issue,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint,
+            error::ErrorHttp,
+            metodos::MetodoHttp,
+            paginacion::{self, responder_paginado},
+            request::Request,
+            response::Response,
+        },
+        logger::Logger,
+    },
+    utils::io,
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/issues".to_string(),
+        listar_issues,
+    );
+    rutas.push(endpoint)
+}
+
+/// Lista los issues del repositorio. Los filtros, el orden y la paginacion se leen del query
+/// string o del body. Por defecto se ordenan del mas nuevo al mas viejo
+fn listar_issues(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
+    })?;
+    if !io::existe(format!("./srv/{repo}")) {
+        return Err(ErrorHttp::ValidationFailed(format!(
+            "No existe en el server el repositorio {repo}"
+        )));
+    }
+
+    let filtros = request.obtener_filtros();
+    let mut issues = Issue::listar(repo)?;
+    issues.retain(|issue| issue.filtrar(&filtros));
+    paginacion::ordenar_por_fecha(&mut issues, &filtros, "desc", |issue| {
+        (&issue.fecha_creacion, &issue.fecha_modificacion)
+    })?;
+
+    responder_paginado(&request, issues, logger)
+}
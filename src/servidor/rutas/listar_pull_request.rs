@@ -1,15 +1,19 @@
 use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::{
-    servidor::{pull_request::PullRequest},
+    servidor::pull_request::PullRequest,
     tipos_de_dato::{
         http::{
-            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
-            request::Request, response::Response,
+            endpoint::Endpoint,
+            error::ErrorHttp,
+            metodos::MetodoHttp,
+            paginacion::{self, responder_paginado},
+            request::Request,
+            response::Response,
         },
         logger::Logger,
     },
-    utils::{self},
+    utils::{self, io},
 };
 
 pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
@@ -21,6 +25,9 @@ pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
     rutas.push(endpoint)
 }
 
+/// Lista los pull requests del repositorio. Los filtros, el orden (`sort` y `direction`) y
+/// la paginacion (`page` y `per_page`) se leen del query string o del body. Por defecto se
+/// ordenan del mas nuevo al mas viejo
 fn listar_pull_request(
     request: Request,
     params: HashMap<String, String>,
@@ -28,33 +35,26 @@ fn listar_pull_request(
 ) -> Result<Response, ErrorHttp> {
     let mut lista_pull_request = obtener_pull_request_del_repositorio(params)?;
 
-    lista_pull_request = filtrar_pull_requests(request, lista_pull_request);
-
-    let body_respuesta = serde_json::to_string(&lista_pull_request).map_err(|e| {
-        ErrorHttp::InternalServerError(format!(
-            "No se ha podido serializar la lista de pull request: {}",
-            e
-        ))
+    let filtros = request.obtener_filtros();
+    lista_pull_request = filtrar_pull_requests(&filtros, lista_pull_request);
+    paginacion::ordenar_por_fecha(&mut lista_pull_request, &filtros, "desc", |pull_request| {
+        (
+            &pull_request.fecha_creacion,
+            &pull_request.fecha_modificacion,
+        )
     })?;
 
-    let response = Response::new(logger, EstadoHttp::Ok, Some(&body_respuesta));
-    Ok(response)
+    responder_paginado(&request, lista_pull_request, logger)
 }
 
 fn filtrar_pull_requests(
-    request: Request,
+    filtros: &HashMap<String, String>,
     lista_pull_request: Vec<PullRequest>,
 ) -> Vec<PullRequest> {
     let mut lista_filtrada_pull_request = Vec::new();
 
-    //si no hay body, no hay filtros
-    let body = match request.body {
-        Some(body) => body,
-        None => return lista_pull_request,
-    };
-
     for pull_request in lista_pull_request {
-        if pull_request.filtrar(&body) {
+        if pull_request.filtrar(filtros) {
             lista_filtrada_pull_request.push(pull_request);
         }
     }
@@ -66,7 +66,7 @@ fn obtener_pull_request_del_repositorio(
     params: HashMap<String, String>,
 ) -> Result<Vec<PullRequest>, ErrorHttp> {
     let dir_repositorio = obtener_y_verificar_repositorio_de_los_parametros(&params)?;
-    if !dir_repositorio.exists() {
+    if !io::existe(&dir_repositorio) {
         return Ok(Vec::new());
     }
     let iterador_repo_dir = utils::io::leer_directorio(&dir_repositorio).map_err(|e| {
@@ -113,7 +113,7 @@ fn obtener_y_verificar_repositorio_de_los_parametros(
 
     let dir_repositorio = PathBuf::from(format!("./srv/{repo}"));
 
-    if dir_repositorio.exists() {
+    if io::existe(&dir_repositorio) {
         Ok(dir_repositorio.join("pulls"))
     } else {
         Err(ErrorHttp::ValidationFailed(format!(
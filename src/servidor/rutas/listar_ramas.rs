@@ -0,0 +1,36 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::explorador,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, metodos::MetodoHttp,
+            paginacion::responder_paginado, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::crear_webhook::obtener_repositorio_de_params;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/branches".to_string(),
+        listar_ramas,
+    );
+    rutas.push(endpoint)
+}
+
+/// Lista las ramas del repositorio con el commit al que apunta cada una
+fn listar_ramas(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let ramas = explorador::listar_ramas().map_err(ErrorHttp::InternalServerError)?;
+
+    responder_paginado(&request, ramas, logger)
+}
@@ -0,0 +1,28 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::repositorio_servidor::RepositorioServidor,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, metodos::MetodoHttp,
+            paginacion::responder_paginado, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(MetodoHttp::Get, "/repos".to_string(), listar_repositorios);
+    rutas.push(endpoint)
+}
+
+/// Lista los repositorios del servidor que puede leer el usuario del pedido
+fn listar_repositorios(
+    request: Request,
+    _params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorios = RepositorioServidor::listar(request.usuario.as_deref())?;
+
+    responder_paginado(&request, repositorios, logger)
+}
@@ -0,0 +1,36 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::revision::Revision,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{
+    obtener_pull_request::obtener_pull_request_de_params, respuesta::responder_en_formato_json,
+};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/pulls/{pull_number}/reviews".to_string(),
+        listar_revisiones,
+    );
+    rutas.push(endpoint)
+}
+
+fn listar_revisiones(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let pull_request = obtener_pull_request_de_params(&params)?;
+    let revisiones = Revision::listar(&pull_request.repositorio, pull_request.numero)?;
+
+    responder_en_formato_json(&revisiones, logger, EstadoHttp::Ok)
+}
@@ -0,0 +1,36 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::explorador,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, metodos::MetodoHttp,
+            paginacion::responder_paginado, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::crear_webhook::obtener_repositorio_de_params;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/tags".to_string(),
+        listar_tags,
+    );
+    rutas.push(endpoint)
+}
+
+/// Lista los tags del repositorio con el commit que etiqueta cada uno
+fn listar_tags(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let tags = explorador::listar_tags().map_err(ErrorHttp::InternalServerError)?;
+
+    responder_paginado(&request, tags, logger)
+}
@@ -0,0 +1,69 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::webhook::{self, Webhook},
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            permiso::Permiso, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_dir_gir_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/hooks".to_string(),
+        listar_webhooks,
+    )
+    .con_permiso(Permiso::Admin);
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/hooks/{hook_id}/deliveries".to_string(),
+        listar_entregas,
+    )
+    .con_permiso(Permiso::Admin);
+    rutas.push(endpoint)
+}
+
+fn listar_webhooks(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let dir_gir = obtener_dir_gir_de_params(&params)?;
+    let webhooks: Vec<Webhook> = Webhook::listar(&dir_gir)
+        .map_err(ErrorHttp::InternalServerError)?
+        .iter()
+        .map(|webhook| webhook.sin_secreto())
+        .collect();
+
+    responder_en_formato_json(&webhooks, logger, EstadoHttp::Ok)
+}
+
+/// Responde el registro de entregas del webhook `hook_id`
+fn listar_entregas(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let dir_gir = obtener_dir_gir_de_params(&params)?;
+    let id = params
+        .get("hook_id")
+        .and_then(|id| id.parse::<u64>().ok())
+        .ok_or_else(|| ErrorHttp::BadRequest("El id del webhook no es valido".to_string()))?;
+    let webhooks = Webhook::listar(&dir_gir).map_err(ErrorHttp::InternalServerError)?;
+    if !webhooks.iter().any(|webhook| webhook.id == id) {
+        return Err(ErrorHttp::NotFound(format!("No existe el webhook {id}")));
+    }
+
+    let entregas =
+        webhook::listar_entregas(&dir_gir, id).map_err(ErrorHttp::InternalServerError)?;
+
+    responder_en_formato_json(&entregas, logger, EstadoHttp::Ok)
+}
@@ -1,11 +1,21 @@
 use std::{
     collections::HashMap,
     path::PathBuf,
-    sync::{Arc},
+    sync::{Arc, Mutex, MutexGuard, TryLockError},
+    thread,
+    time::{Duration, Instant},
 };
 
 use crate::{
-    servidor::{pull_request::PullRequest},
+    servidor::{
+        estado_commit::EstadoCombinado,
+        issue,
+        proteccion_ramas::ProteccionRamas,
+        pull_request::PullRequest,
+        repos_almacen::ReposAlmacen,
+        revision::{self, Revision},
+        webhook,
+    },
     tipos_de_dato::{
         comando::Ejecutar,
         comandos::{merge::Merge, rebase::Rebase},
@@ -14,12 +24,16 @@ use crate::{
             request::Request, response::Response,
         },
         logger::Logger,
+        repositorio::Repositorio,
     },
     utils::{index, io, ramas},
 };
 
 use super::obtener_pull_request::obtener_pull_request_de_params;
 
+/// Cuanto se espera el lock del fork de la rama head antes de rechazar el merge
+const ESPERA_LOCK_FORK: Duration = Duration::from_secs(5);
+
 #[derive(Debug, PartialEq, Eq)]
 enum MetodoMerge {
     Merge,
@@ -49,17 +63,115 @@ fn armar_body_merge(hash_merge: String) -> String {
 }
 
 fn verificar_sha_head(sha: &str, pull_request: &PullRequest) -> Result<bool, ErrorHttp> {
-    pull_request.entrar_a_repositorio()?;
-    let hash_head_previo_merge = ramas::obtener_hash_commit_asociado_rama(&pull_request.rama_head)
-        .map_err(|error| {
-            ErrorHttp::InternalServerError(format!(
-                "No se ha podido obtener el hash del commit de la rama {}: {}",
-                pull_request.rama_head, error
-            ))
-        })?;
-    pull_request.salir_del_repositorio()?;
+    Ok(sha == pull_request.obtener_hash_head()?)
+}
+
+/// Verifica que el pull request cumpla las reglas de proteccion de su rama base
+fn verificar_proteccion_rama_base(
+    pull_request: &PullRequest,
+    logger: Arc<Logger>,
+) -> Result<(), ErrorHttp> {
+    let repositorio = pull_request.obtener_repositorio();
+    let proteccion =
+        ProteccionRamas::cargar(repositorio.dir_gir()).map_err(ErrorHttp::InternalServerError)?;
+    let regla = match proteccion.obtener_regla(&pull_request.rama_base) {
+        Some(regla) => regla,
+        None => return Ok(()),
+    };
 
-    Ok(sha == hash_head_previo_merge)
+    if regla.requerir_rama_actualizada {
+        let _repositorio = repositorio.entrar();
+        let commit_base = Merge::obtener_commit_base_entre_dos_branches(
+            &pull_request.rama_base,
+            &pull_request.obtener_rama_head(),
+            logger,
+        )
+        .map_err(ErrorHttp::InternalServerError)?;
+        let hash_rama_base = ramas::obtener_hash_commit_asociado_rama(&pull_request.rama_base)
+            .map_err(ErrorHttp::InternalServerError)?;
+
+        if commit_base != hash_rama_base {
+            return Err(ErrorHttp::Forbidden(format!(
+                "La rama {} esta protegida: {} tiene que estar actualizada con ella",
+                pull_request.rama_base, pull_request.rama_head
+            )));
+        }
+    }
+
+    if regla.aprobaciones_requeridas > 0 {
+        let revisiones = Revision::listar(&pull_request.repositorio, pull_request.numero)?;
+        if revision::obtener_estado_general(&revisiones) == "changes_requested" {
+            return Err(ErrorHttp::Forbidden(format!(
+                "La rama {} esta protegida: hay revisiones que piden cambios",
+                pull_request.rama_base
+            )));
+        }
+        let aprobaciones =
+            revision::contar_aprobaciones(&revisiones, &pull_request.obtener_hash_head()?);
+        if aprobaciones < regla.aprobaciones_requeridas {
+            return Err(ErrorHttp::Forbidden(format!(
+                "La rama {} esta protegida: se requieren {} aprobaciones del ultimo commit de {} y hay {}",
+                pull_request.rama_base,
+                regla.aprobaciones_requeridas,
+                pull_request.rama_head,
+                aprobaciones
+            )));
+        }
+    }
+
+    if !regla.contextos_requeridos.is_empty() {
+        let hash_head = pull_request.obtener_hash_head()?;
+        let estado_combinado =
+            EstadoCombinado::from_commit(&pull_request.repositorio, hash_head.trim())?;
+        let contextos_sin_exito =
+            estado_combinado.obtener_contextos_sin_exito(&regla.contextos_requeridos);
+        if !contextos_sin_exito.is_empty() {
+            return Err(ErrorHttp::Forbidden(format!(
+                "La rama {} esta protegida: los chequeos {} no pasaron en el ultimo commit de {}",
+                pull_request.rama_base,
+                contextos_sin_exito.join(", "),
+                pull_request.rama_head
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Devuelve los textos del pull request que pueden cerrar issues: su descripcion y los
+/// mensajes de sus commits. Se tienen que obtener antes del merge, porque despues la rama head
+/// ya no tiene commits que no esten en la base.
+fn obtener_textos_que_cierran_issues(
+    pull_request: &PullRequest,
+    logger: Arc<Logger>,
+) -> Vec<String> {
+    let mut textos: Vec<String> = pull_request.descripcion.iter().cloned().collect();
+    match pull_request.obtener_commits(logger.clone()) {
+        Ok(commits) => textos.extend(commits.into_iter().map(|commit| commit.mensaje)),
+        Err(error) => logger.log(&format!(
+            "No se pudieron obtener los commits del pull request {}: {}",
+            pull_request.numero,
+            error.obtener_mensaje()
+        )),
+    }
+    textos
+}
+
+/// Cierra los issues referenciados por el pull request mergeado. Un fallo no deshace el merge,
+/// solo se loguea.
+fn cerrar_issues(pull_request: &PullRequest, textos: &[String], logger: Arc<Logger>) {
+    match issue::cerrar_issues_referenciados(&pull_request.repositorio, pull_request.numero, textos)
+    {
+        Ok(cerrados) if !cerrados.is_empty() => logger.log(&format!(
+            "El pull request {} cerro los issues {:?}",
+            pull_request.numero, cerrados
+        )),
+        Ok(_) => {}
+        Err(error) => logger.log(&format!(
+            "No se pudieron cerrar los issues del pull request {}: {}",
+            pull_request.numero,
+            error.obtener_mensaje()
+        )),
+    }
 }
 
 fn obtener_params_body(
@@ -97,6 +209,7 @@ fn obtener_params_body(
 fn pr_mergeado_con_exito(
     rama_base: &str,
     pull_request: &mut PullRequest,
+    usuario: Option<String>,
     logger: Arc<Logger>,
 ) -> Result<Response, ErrorHttp> {
     let hash_merge = ramas::obtener_hash_commit_asociado_rama(rama_base).map_err(|error| {
@@ -107,6 +220,7 @@ fn pr_mergeado_con_exito(
     })?;
     let body_response = armar_body_merge(hash_merge);
     pull_request.estado = "closed".to_string();
+    pull_request.mergeado_por = usuario;
 
     let dir_pull_request = PathBuf::from(format!("pulls/{}", pull_request.numero));
     pull_request.guardar_pr(&dir_pull_request)?;
@@ -114,8 +228,8 @@ fn pr_mergeado_con_exito(
     Ok(response)
 }
 
-fn volver_a_estado_previo_al_merge() -> Result<(), ErrorHttp> {
-    io::rm_directorio(".gir/MERGE_HEAD").map_err(|error| {
+fn volver_a_estado_previo_al_merge(repositorio: &Repositorio) -> Result<(), ErrorHttp> {
+    io::rm_directorio(repositorio.ruta_gir("MERGE_HEAD")).map_err(|error| {
         ErrorHttp::InternalServerError(format!(
             "No se ha podido eliminar el archivo MERGE_HEAD: {}",
             error
@@ -131,13 +245,14 @@ fn volver_a_estado_previo_al_merge() -> Result<(), ErrorHttp> {
 }
 
 fn mergear_pr_ejecutado_con_fallos(
+    repositorio: &Repositorio,
     logger: Arc<Logger>,
     error: String,
     merge_method: MetodoMerge,
 ) -> Result<Response, ErrorHttp> {
     let hay_conflictos = index::hay_archivos_con_conflictos(logger.clone());
     if merge_method == MetodoMerge::Merge {
-        volver_a_estado_previo_al_merge()?;
+        volver_a_estado_previo_al_merge(repositorio)?;
     } else {
         volver_a_estado_previo_al_rebase(logger.clone())?;
     }
@@ -155,10 +270,11 @@ fn mergear_pr_ejecutado_con_fallos(
 
 fn mergear_pull_request_utilizando_merge(
     pull_request: &mut PullRequest,
+    usuario: Option<String>,
     logger: Arc<Logger>,
 ) -> Result<Response, ErrorHttp> {
     let rama_base = pull_request.rama_base.clone();
-    let rama_head = pull_request.rama_head.clone();
+    let rama_head = pull_request.obtener_rama_head();
 
     let mut merge = Merge {
         logger: logger.clone(),
@@ -166,20 +282,18 @@ fn mergear_pull_request_utilizando_merge(
         branch_a_mergear: rama_head,
         abort: false,
         no_fast_forward: true,
+        repositorio: pull_request.obtener_repositorio(),
     };
 
-    pull_request.entrar_a_repositorio()?;
+    let _repositorio = merge.repositorio.entrar();
 
-    let resultado = match merge.ejecutar() {
-        Ok(_) => pr_mergeado_con_exito(&rama_base, pull_request, logger),
+    match merge.ejecutar() {
+        Ok(_) => pr_mergeado_con_exito(&rama_base, pull_request, usuario, logger),
         Err(error) => {
-            mergear_pr_ejecutado_con_fallos(logger, error.to_string(), MetodoMerge::Merge)
+            let repositorio = &merge.repositorio;
+            mergear_pr_ejecutado_con_fallos(repositorio, logger, error, MetodoMerge::Merge)
         }
-    };
-
-    pull_request.salir_del_repositorio()?;
-
-    resultado
+    }
 }
 
 fn volver_a_estado_previo_al_rebase(logger: Arc<Logger>) -> Result<(), ErrorHttp> {
@@ -194,10 +308,11 @@ fn volver_a_estado_previo_al_rebase(logger: Arc<Logger>) -> Result<(), ErrorHttp
 
 fn mergear_pull_request_utilizando_rebase(
     pull_request: &mut PullRequest,
+    usuario: Option<String>,
     logger: Arc<Logger>,
 ) -> Result<Response, ErrorHttp> {
     let rama_base = pull_request.rama_base.clone();
-    let rama_head = pull_request.rama_head.clone();
+    let rama_head = pull_request.obtener_rama_head();
 
     let mut rebase = Rebase {
         logger: logger.clone(),
@@ -205,20 +320,59 @@ fn mergear_pull_request_utilizando_rebase(
         rama: Some(rama_head),
         continue_: false,
         abort: false,
+        repositorio: pull_request.obtener_repositorio(),
     };
 
-    pull_request.entrar_a_repositorio()?;
+    let _repositorio = rebase.repositorio.entrar();
 
-    let resultado = match rebase.ejecutar() {
-        Ok(_) => pr_mergeado_con_exito(&rama_base, pull_request, logger),
+    match rebase.ejecutar() {
+        Ok(_) => pr_mergeado_con_exito(&rama_base, pull_request, usuario, logger),
         Err(error) => {
-            mergear_pr_ejecutado_con_fallos(logger, error.to_string(), MetodoMerge::Rebase)
+            let repositorio = &rebase.repositorio;
+            mergear_pr_ejecutado_con_fallos(repositorio, logger, error, MetodoMerge::Rebase)
         }
-    };
+    }
+}
 
-    pull_request.salir_del_repositorio()?;
+/// Devuelve el mutex del fork de la rama head del pull request, si la rama esta en un fork
+fn obtener_mutex_del_fork(
+    repos_almacen: Option<&ReposAlmacen>,
+    pull_request: &PullRequest,
+) -> Result<Option<Arc<Mutex<()>>>, ErrorHttp> {
+    match (repos_almacen, &pull_request.repositorio_head) {
+        (Some(repos_almacen), Some(fork)) if *fork != pull_request.repositorio => repos_almacen
+            .obtener_mutex_del_repo(fork)
+            .map(Some)
+            .map_err(ErrorHttp::InternalServerError),
+        _ => Ok(None),
+    }
+}
 
-    resultado
+/// Toma el lock del fork de la rama head. Como ya se tiene el lock del repositorio del pull
+/// request, no se espera indefinidamente: otro pedido podria tener el del fork y estar
+/// esperando el del repositorio, y se bloquearian entre si
+fn tomar_lock_del_fork<'a>(
+    mutex: &'a Mutex<()>,
+    pull_request: &PullRequest,
+) -> Result<MutexGuard<'a, ()>, ErrorHttp> {
+    let limite = Instant::now() + ESPERA_LOCK_FORK;
+    loop {
+        match mutex.try_lock() {
+            Ok(lock) => return Ok(lock),
+            Err(TryLockError::Poisoned(error)) => {
+                return Err(ErrorHttp::InternalServerError(error.to_string()))
+            }
+            Err(TryLockError::WouldBlock) if Instant::now() < limite => {
+                thread::sleep(Duration::from_millis(10))
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(ErrorHttp::ServiceUnavailable(format!(
+                    "El repositorio {} esta ocupado, intente mergear mas tarde",
+                    pull_request.repositorio_head.clone().unwrap_or_default()
+                )))
+            }
+        }
+    }
 }
 
 fn mergear_pull_request(
@@ -232,14 +386,45 @@ fn mergear_pull_request(
         let response = Response::new(logger, EstadoHttp::ValidationFailed, None);
         return Ok(response);
     }
+    if pull_request.borrador {
+        return Err(ErrorHttp::ValidationFailed(format!(
+            "El pull request {} es un borrador: hay que marcarlo como listo para mergearlo",
+            pull_request.numero
+        )));
+    }
 
+    let usuario = request.usuario.clone();
+    let mutex_fork = obtener_mutex_del_fork(request.repos_almacen.as_ref(), &pull_request)?;
     let merge_method = obtener_params_body(request, &pull_request)?;
 
-    match merge_method {
-        MetodoMerge::Merge => mergear_pull_request_utilizando_merge(&mut pull_request, logger),
-        MetodoMerge::Rebase => mergear_pull_request_utilizando_rebase(&mut pull_request, logger),
+    // traer la rama del fork escribe en el repositorio del pull request, asi que se hace
+    // con los locks de los dos repositorios
+    let _lock_fork = match &mutex_fork {
+        Some(mutex) => Some(tomar_lock_del_fork(mutex, &pull_request)?),
+        None => None,
+    };
+    pull_request
+        .sincronizar_rama_head()
+        .map_err(ErrorHttp::InternalServerError)?;
+    verificar_proteccion_rama_base(&pull_request, logger.clone())?;
+    let textos = obtener_textos_que_cierran_issues(&pull_request, logger.clone());
+
+    let respuesta = match merge_method {
+        MetodoMerge::Merge => {
+            mergear_pull_request_utilizando_merge(&mut pull_request, usuario, logger.clone())
+        }
+        MetodoMerge::Rebase => {
+            mergear_pull_request_utilizando_rebase(&mut pull_request, usuario, logger.clone())
+        }
         MetodoMerge::Squash => Err(ErrorHttp::NotImplemented(
             "Metodo squash no implementado".to_string(),
         )),
+    }?;
+
+    // si hubo conflictos el pull request sigue abierto
+    if pull_request.estado == "closed" {
+        cerrar_issues(&pull_request, &textos, logger.clone());
+        webhook::disparar_evento_pull_request(&pull_request, "closed", logger);
     }
+    Ok(respuesta)
 }
@@ -0,0 +1,53 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use crate::{
+    servidor::explorador::{self, Arbol},
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+        objetos::tree::Tree,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/git/trees/{sha}".to_string(),
+        obtener_arbol,
+    );
+    rutas.push(endpoint)
+}
+
+/// Devuelve las entradas del arbol `sha`, que puede ser el hash de un tree o una referencia
+/// a un commit. Con `recursive` se incluyen las entradas de todos los subdirectorios
+fn obtener_arbol(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let sha = params.get("sha").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se encontro el sha en los parametros".to_string())
+    })?;
+    let recursivo = request
+        .obtener_filtros()
+        .get("recursive")
+        .is_some_and(|recursivo| recursivo != "0" && recursivo != "false");
+
+    let tree = if explorador::verificar_tipo_objeto(sha, "tree").is_ok() {
+        Tree::from_hash(sha, PathBuf::from("."), logger.clone())
+    } else {
+        let hash_commit = explorador::resolver_referencia(Some(sha))?;
+        explorador::obtener_arbol_de_commit(&hash_commit, logger.clone())
+    }
+    .map_err(ErrorHttp::InternalServerError)?;
+    let arbol = Arbol::from_tree(&tree, recursivo).map_err(ErrorHttp::InternalServerError)?;
+
+    responder_en_formato_json(&arbol, logger, EstadoHttp::Ok)
+}
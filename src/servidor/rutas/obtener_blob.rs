@@ -0,0 +1,40 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::explorador::ContenidoBlob,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/git/blobs/{sha}".to_string(),
+        obtener_blob,
+    );
+    rutas.push(endpoint)
+}
+
+/// Devuelve el contenido del blob `sha` codificado en base64
+fn obtener_blob(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let sha = params.get("sha").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se encontro el sha en los parametros".to_string())
+    })?;
+
+    let blob = ContenidoBlob::from_hash(sha)?;
+    let respuesta = responder_en_formato_json(&blob, logger, EstadoHttp::Ok)?;
+    Ok(respuesta.con_transferencia_en_chunks())
+}
@@ -1,13 +1,11 @@
 use std::{collections::HashMap, sync::Arc};
 
-use crate::{
-    tipos_de_dato::{
-        http::{
-            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
-            request::Request, response::Response,
-        },
-        logger::Logger,
+use crate::tipos_de_dato::{
+    http::{
+        endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+        paginacion::responder_paginado, request::Request, response::Response,
     },
+    logger::Logger,
 };
 
 use super::obtener_pull_request::obtener_pull_request_de_params;
@@ -22,7 +20,7 @@ pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
 }
 
 fn obtener_commits_pull_request(
-    _request: Request,
+    request: Request,
     params: HashMap<String, String>,
     logger: Arc<Logger>,
 ) -> Result<Response, ErrorHttp> {
@@ -34,10 +32,5 @@ fn obtener_commits_pull_request(
         return Ok(response);
     }
 
-    let body_response = serde_json::to_string(&commits).map_err(|e| {
-        ErrorHttp::InternalServerError(format!("No se ha podido serializar el pull request: {}", e))
-    })?;
-
-    let response = Response::new(logger, EstadoHttp::Ok, Some(&body_response));
-    Ok(response)
+    responder_paginado(&request, commits, logger)
 }
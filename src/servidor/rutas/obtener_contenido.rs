@@ -0,0 +1,51 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::explorador,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/contents".to_string(),
+        obtener_contenido,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/contents/{*path}".to_string(),
+        obtener_contenido,
+    );
+    rutas.push(endpoint)
+}
+
+/// Devuelve el contenido de `path` en la referencia `ref` (por defecto la rama actual): el
+/// archivo codificado en base64 o las entradas del directorio
+fn obtener_contenido(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(&params)?;
+    let _repositorio = repositorio.entrar();
+    let filtros = request.obtener_filtros();
+    let ruta = params.get("path").map(|ruta| ruta.as_str()).unwrap_or("");
+
+    let hash_commit =
+        explorador::resolver_referencia(filtros.get("ref").map(|referencia| referencia.as_str()))?;
+    let arbol = explorador::obtener_arbol_de_commit(&hash_commit, logger.clone())
+        .map_err(ErrorHttp::InternalServerError)?;
+    let contenido = explorador::obtener_contenido(&arbol, ruta)?;
+
+    responder_en_formato_json(&contenido, logger, EstadoHttp::Ok)
+}
@@ -0,0 +1,72 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::{
+        estado_commit::{EstadoCombinado, EstadoCommit},
+        explorador,
+    },
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            paginacion::responder_paginado, request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::{crear_webhook::obtener_repositorio_de_params, respuesta::responder_en_formato_json};
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/commits/{ref}/status".to_string(),
+        obtener_estado_combinado,
+    );
+    rutas.push(endpoint);
+
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/commits/{ref}/statuses".to_string(),
+        listar_estados,
+    );
+    rutas.push(endpoint)
+}
+
+/// Devuelve el estado combinado del commit: el ultimo estado de cada contexto y su resumen
+fn obtener_estado_combinado(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let (repo, sha) = obtener_repo_y_sha_de_params(&params)?;
+    let estado_combinado = EstadoCombinado::from_commit(&repo, &sha)?;
+
+    responder_en_formato_json(&estado_combinado, logger, EstadoHttp::Ok)
+}
+
+/// Lista todos los estados del commit, del mas nuevo al mas viejo
+fn listar_estados(
+    request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let (repo, sha) = obtener_repo_y_sha_de_params(&params)?;
+    let mut estados = EstadoCommit::listar(&repo, &sha)?;
+    estados.reverse();
+
+    responder_paginado(&request, estados, logger)
+}
+
+/// Devuelve el repositorio y el hash del commit al que apunta la referencia `ref`, que puede
+/// ser una rama, un tag o el hash de un commit
+fn obtener_repo_y_sha_de_params(
+    params: &HashMap<String, String>,
+) -> Result<(String, String), ErrorHttp> {
+    let repositorio = obtener_repositorio_de_params(params)?;
+    let repo = params.get("repo").cloned().unwrap_or_default();
+    let referencia = params.get("ref").map(|referencia| referencia.as_str());
+
+    let _repositorio = repositorio.entrar();
+    let sha = explorador::resolver_referencia(referencia)?;
+    Ok((repo, sha))
+}
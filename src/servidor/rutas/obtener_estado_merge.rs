@@ -0,0 +1,46 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::tipos_de_dato::{
+    http::{
+        endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+        request::Request, response::Response,
+    },
+    logger::Logger,
+};
+
+use super::obtener_pull_request::obtener_pull_request_de_params;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/pulls/{pull_number}/mergeable".to_string(),
+        obtener_estado_merge,
+    );
+    rutas.push(endpoint)
+}
+
+/// Responde si el pull request se puede mergear y, si no, los archivos con conflictos
+fn obtener_estado_merge(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let pull_request = obtener_pull_request_de_params(&params)?;
+    if pull_request.estado != "open" {
+        return Err(ErrorHttp::ValidationFailed(format!(
+            "El pull request {} no esta abierto",
+            pull_request.numero
+        )));
+    }
+    let estado_merge = pull_request.obtener_estado_merge(logger.clone())?;
+
+    let body_response = serde_json::to_string(&estado_merge).map_err(|e| {
+        ErrorHttp::InternalServerError(format!(
+            "No se ha podido serializar el estado del merge: {}",
+            e
+        ))
+    })?;
+
+    let response = Response::new(logger, EstadoHttp::Ok, Some(&body_response));
+    Ok(response)
+}
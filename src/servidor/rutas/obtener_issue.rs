@@ -0,0 +1,47 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{
+    servidor::issue::Issue,
+    tipos_de_dato::{
+        http::{
+            endpoint::Endpoint, error::ErrorHttp, estado::EstadoHttp, metodos::MetodoHttp,
+            request::Request, response::Response,
+        },
+        logger::Logger,
+    },
+};
+
+use super::respuesta::responder_en_formato_json;
+
+pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
+    let endpoint = Endpoint::new(
+        MetodoHttp::Get,
+        "/repos/{repo}/issues/{issue_number}".to_string(),
+        obtener_issue,
+    );
+    rutas.push(endpoint)
+}
+
+fn obtener_issue(
+    _request: Request,
+    params: HashMap<String, String>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let issue = obtener_issue_de_params(&params)?;
+    responder_en_formato_json(&issue, logger, EstadoHttp::Ok)
+}
+
+/// Obtiene el issue guardado en `./srv/{repo}/issues/{issue_number}`
+///
+/// ## Errores
+/// - Si `issue_number` no es un numero o no existe el issue
+pub fn obtener_issue_de_params(params: &HashMap<String, String>) -> Result<Issue, ErrorHttp> {
+    let repo = params.get("repo").ok_or_else(|| {
+        ErrorHttp::InternalServerError("No se ha encontrado el nombre del repositorio".to_string())
+    })?;
+    let numero = params
+        .get("issue_number")
+        .and_then(|numero| numero.parse().ok())
+        .ok_or_else(|| ErrorHttp::BadRequest("El numero de issue es invalido".to_string()))?;
+    Issue::cargar(repo, numero)
+}
@@ -2,7 +2,9 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use crate::{
     servidor::{
+        estado_commit::EstadoCombinado,
         pull_request::PullRequest,
+        revision::{self, Revision},
     },
     tipos_de_dato::{
         http::{
@@ -11,10 +13,9 @@ use crate::{
         },
         logger::Logger,
     },
+    utils::io,
 };
 
-use super::crear_pull_request::responder_pull_request_en_formato_json;
-
 pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
     let endpoint = Endpoint::new(
         MetodoHttp::Get,
@@ -24,14 +25,94 @@ pub fn agregar_a_router(rutas: &mut Vec<Endpoint>) {
     rutas.push(endpoint)
 }
 
+/// Media type con el que se pide el diff del pull request en vez de su json
+const MEDIA_TYPE_DIFF: &str = "application/vnd.github.diff";
+
 fn obtener_pull_request(
-    _request: Request,
+    request: Request,
     params: HashMap<String, String>,
     logger: Arc<Logger>,
 ) -> Result<Response, ErrorHttp> {
     let pull_request = obtener_pull_request_de_params(&params)?;
+    if let Some(accept) = request.obtener_header("Accept") {
+        if accept.contains(MEDIA_TYPE_DIFF) {
+            return responder_diff(&pull_request, logger);
+        }
+    }
+    let revisiones = Revision::listar(&pull_request.repositorio, pull_request.numero)?;
+
+    let mut body_respuesta = serde_json::to_value(&pull_request).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se ha podido serializar el pull request: {}", e))
+    })?;
+    body_respuesta["estado_revision"] = revision::obtener_estado_general(&revisiones).into();
+    agregar_estado_merge(&mut body_respuesta, &pull_request, logger.clone());
+    agregar_estado_chequeos(&mut body_respuesta, &pull_request, logger.clone());
+    let respuesta = Response::new(logger, EstadoHttp::Ok, Some(&body_respuesta.to_string()));
+    Ok(respuesta)
+}
+
+/// Agrega `mergeable` y `mergeable_state` al json del pull request. Si el pull request esta
+/// cerrado o no se pudo simular el merge quedan en null, como en github
+fn agregar_estado_merge(
+    body_respuesta: &mut serde_json::Value,
+    pull_request: &PullRequest,
+    logger: Arc<Logger>,
+) {
+    body_respuesta["mergeable"] = serde_json::Value::Null;
+    body_respuesta["mergeable_state"] = serde_json::Value::Null;
+    if pull_request.estado != "open" {
+        return;
+    }
+
+    match pull_request.obtener_estado_merge(logger.clone()) {
+        Ok(estado_merge) => {
+            body_respuesta["mergeable"] = estado_merge.mergeable.into();
+            body_respuesta["mergeable_state"] = estado_merge.mergeable_state.into();
+        }
+        Err(error) => logger.log(&format!(
+            "No se pudo simular el merge del pull request {}: {}",
+            pull_request.numero,
+            error.obtener_mensaje()
+        )),
+    }
+}
+
+/// Agrega `estado_chequeos`, el estado combinado de los chequeos del ultimo commit de la rama
+/// head. Si no se pudo obtener queda en null
+fn agregar_estado_chequeos(
+    body_respuesta: &mut serde_json::Value,
+    pull_request: &PullRequest,
+    logger: Arc<Logger>,
+) {
+    body_respuesta["estado_chequeos"] = serde_json::Value::Null;
+    let estado_combinado = pull_request.obtener_hash_head().and_then(|hash_head| {
+        EstadoCombinado::from_commit(&pull_request.repositorio, hash_head.trim())
+    });
+
+    match estado_combinado {
+        Ok(estado_combinado) => body_respuesta["estado_chequeos"] = estado_combinado.estado.into(),
+        Err(error) => logger.log(&format!(
+            "No se pudo obtener el estado de los chequeos del pull request {}: {}",
+            pull_request.numero,
+            error.obtener_mensaje()
+        )),
+    }
+}
+
+fn responder_diff(pull_request: &PullRequest, logger: Arc<Logger>) -> Result<Response, ErrorHttp> {
+    let diff: String = pull_request
+        .obtener_archivos_cambiados(logger.clone())?
+        .iter()
+        .map(|archivo| archivo.obtener_diff())
+        .collect();
 
-    responder_pull_request_en_formato_json(pull_request, logger, EstadoHttp::Ok)
+    // los diffs pueden ser muy grandes, asi que se envian en chunks
+    let mut respuesta =
+        Response::new(logger, EstadoHttp::Ok, Some(&diff)).con_transferencia_en_chunks();
+    respuesta
+        .headers
+        .insert("Content-Type".to_string(), MEDIA_TYPE_DIFF.to_string());
+    Ok(respuesta)
 }
 
 ///Obtiene el objeto pull request desde los parametros. Para ello en los parametros tiene que estar
@@ -64,7 +145,7 @@ pub fn obtener_dir_pull_request(params: &HashMap<String, String>) -> Result<Path
     })?;
     let dir_pull_request = PathBuf::from(format!("./srv/{repo}/pulls/{pull_number}"));
 
-    if dir_pull_request.exists() {
+    if io::existe(&dir_pull_request) {
         Ok(dir_pull_request)
     } else {
         Err(ErrorHttp::NotFound(format!(
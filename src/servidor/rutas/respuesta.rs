@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use crate::tipos_de_dato::{
+    http::{error::ErrorHttp, estado::EstadoHttp, response::Response},
+    logger::Logger,
+};
+
+/// Autor de lo que se crea sin autenticarse (comentarios, issues, estados de commits, ...)
+pub const AUTOR_ANONIMO: &str = "anonimo";
+
+/// Arma la respuesta con `estado` y el body en formato json
+pub fn responder_en_formato_json<T: serde::Serialize>(
+    contenido: &T,
+    logger: Arc<Logger>,
+    estado: EstadoHttp,
+) -> Result<Response, ErrorHttp> {
+    let body_respuesta = serde_json::to_string(contenido).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se ha podido serializar la respuesta: {}", e))
+    })?;
+    Ok(Response::new(logger, estado, Some(&body_respuesta)))
+}
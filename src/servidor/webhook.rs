@@ -0,0 +1,721 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+
+use crate::{
+    tipos_de_dato::{http::error::ErrorHttp, logger::Logger},
+    utils::{gir_config, io, strings},
+};
+
+use super::{
+    pool_threads::{ConfiguracionPool, PoolThreads},
+    pull_request::PullRequest,
+};
+
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con sus webhooks
+pub const ARCHIVO_WEBHOOKS: &str = "webhooks.json";
+/// Archivo dentro del directorio `.gir` de cada repositorio del servidor con el registro de
+/// las entregas de sus webhooks
+pub const ARCHIVO_ENTREGAS: &str = "entregas_webhooks.json";
+
+pub const EVENTO_PUSH: &str = "push";
+pub const EVENTO_PULL_REQUEST: &str = "pull_request";
+const EVENTOS: [&str; 2] = [EVENTO_PUSH, EVENTO_PULL_REQUEST];
+
+/// Cantidad maxima de veces que se intenta entregar un evento
+const INTENTOS_ENTREGA: u32 = 3;
+/// Espera antes del primer reintento. Se duplica en cada reintento
+const ESPERA_INICIAL_REINTENTO: Duration = Duration::from_secs(1);
+const TIEMPO_LIMITE_ENTREGA: Duration = Duration::from_secs(10);
+/// Cantidad maxima de entregas registradas por repositorio. Al llegar al maximo se descartan
+/// las mas viejas
+const MAXIMO_ENTREGAS_REGISTRADAS: usize = 100;
+
+/// Las entregas se hacen desde varios threads y todas se registran en el mismo archivo
+static BLOQUEO_ENTREGAS: Mutex<()> = Mutex::new(());
+/// Pool de threads que hacen las entregas. Se crea con el primer evento que se dispara
+static POOL_ENTREGAS: OnceLock<Result<PoolThreads<EntregaPendiente>, String>> = OnceLock::new();
+
+/// Entrega de un evento a un webhook que espera a que la haga un thread del pool
+struct EntregaPendiente {
+    webhook: Webhook,
+    dir_gir: PathBuf,
+    evento: String,
+    payload: String,
+    logger: Arc<Logger>,
+}
+
+/// Webhook de un repositorio: cada evento al que esta suscripto se le envia a `url` con un POST.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Webhook {
+    pub id: u64,
+    /// Url http a la que se envian los eventos (Ej: `http://ci.ejemplo.com:8080/eventos`)
+    pub url: String,
+    /// Secreto con el que se firman las entregas. No se muestra en las respuestas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secreto: Option<String>,
+    /// Eventos a los que esta suscripto: `push` y/o `pull_request`
+    pub eventos: Vec<String>,
+}
+
+/// Registro de la entrega de un evento a un webhook
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Entrega {
+    pub id: u64,
+    pub webhook: u64,
+    pub evento: String,
+    pub url: String,
+    /// Cantidad de veces que se intento entregar el evento
+    pub intentos: u32,
+    pub exitosa: bool,
+    /// Estado http de la ultima respuesta recibida
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estado_respuesta: Option<usize>,
+    /// Error del ultimo intento fallido
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub fecha: String,
+}
+
+impl Webhook {
+    /// Crea un webhook a partir del body de la request y lo guarda en el repositorio cuyo
+    /// directorio gir es `dir_gir`.
+    ///
+    /// ## Argumentos
+    /// - body: tiene que tener `url`. Puede tener `secret` y `events`, los eventos separados
+    ///   por comas (por defecto `push`)
+    ///
+    /// ## Errores
+    /// - Si falta la url o no es http, o si algun evento no existe
+    /// - Si el host de la url no es publico (Ej: `localhost` o una ip privada) y no esta en los
+    ///   hosts permitidos del config (ver `hosts_permitidos`)
+    pub fn crear(dir_gir: &Path, body: &HashMap<String, String>) -> Result<Webhook, ErrorHttp> {
+        Self::crear_permitiendo(dir_gir, body, &hosts_permitidos())
+    }
+
+    fn crear_permitiendo(
+        dir_gir: &Path,
+        body: &HashMap<String, String>,
+        hosts_permitidos: &[String],
+    ) -> Result<Webhook, ErrorHttp> {
+        let url = body.get("url").ok_or_else(|| {
+            ErrorHttp::ValidationFailed(
+                "Falta el parametro 'url' en el body de la request".to_string(),
+            )
+        })?;
+        let (direccion, _) = parsear_url(url).map_err(ErrorHttp::ValidationFailed)?;
+        resolver_destino(&direccion, hosts_permitidos).map_err(ErrorHttp::ValidationFailed)?;
+
+        let eventos: Vec<String> = body
+            .get("events")
+            .map(|eventos| eventos.as_str())
+            .unwrap_or(EVENTO_PUSH)
+            .split(',')
+            .map(|evento| evento.trim().to_string())
+            .filter(|evento| !evento.is_empty())
+            .collect();
+        if let Some(evento) = eventos
+            .iter()
+            .find(|evento| !EVENTOS.contains(&evento.as_str()))
+        {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "El evento {evento} no existe"
+            )));
+        }
+
+        let mut webhooks = Self::listar(dir_gir).map_err(ErrorHttp::InternalServerError)?;
+        let webhook = Webhook {
+            id: webhooks.iter().map(|webhook| webhook.id).max().unwrap_or(0) + 1,
+            url: url.to_string(),
+            secreto: body.get("secret").cloned(),
+            eventos,
+        };
+        webhooks.push(webhook.clone());
+        escribir_json(&dir_gir.join(ARCHIVO_WEBHOOKS), &webhooks)
+            .map_err(ErrorHttp::InternalServerError)?;
+        Ok(webhook)
+    }
+
+    /// Devuelve los webhooks del repositorio cuyo directorio gir es `dir_gir`
+    pub fn listar(dir_gir: &Path) -> Result<Vec<Webhook>, String> {
+        leer_json(&dir_gir.join(ARCHIVO_WEBHOOKS))
+    }
+
+    /// Devuelve el webhook sin su secreto, para mostrarlo en una respuesta
+    pub fn sin_secreto(&self) -> Webhook {
+        Webhook {
+            secreto: None,
+            ..self.clone()
+        }
+    }
+
+    /// Envia el evento al webhook, reintentando con esperas crecientes si falla, y registra la
+    /// entrega en el repositorio. Bloquea hasta terminar.
+    pub fn entregar(
+        &self,
+        dir_gir: &Path,
+        evento: &str,
+        payload: &str,
+        espera_inicial: Duration,
+    ) -> Result<Entrega, String> {
+        self.entregar_permitiendo(
+            dir_gir,
+            evento,
+            payload,
+            espera_inicial,
+            &hosts_permitidos(),
+        )
+    }
+
+    fn entregar_permitiendo(
+        &self,
+        dir_gir: &Path,
+        evento: &str,
+        payload: &str,
+        espera_inicial: Duration,
+        hosts_permitidos: &[String],
+    ) -> Result<Entrega, String> {
+        let mut entrega = self.nueva_entrega(evento);
+
+        let mut espera = espera_inicial;
+        while !entrega.exitosa && entrega.intentos < INTENTOS_ENTREGA {
+            if entrega.intentos > 0 {
+                thread::sleep(espera);
+                espera *= 2;
+            }
+            entrega.intentos += 1;
+            match self.enviar(evento, payload, hosts_permitidos) {
+                Ok(estado) => {
+                    entrega.estado_respuesta = Some(estado);
+                    entrega.exitosa = (200..300).contains(&estado);
+                    entrega.error = None;
+                }
+                Err(error) => entrega.error = Some(error),
+            }
+        }
+
+        registrar_entrega(dir_gir, &mut entrega)?;
+        Ok(entrega)
+    }
+
+    /// Registro de una entrega del evento que todavia no se intento hacer
+    fn nueva_entrega(&self, evento: &str) -> Entrega {
+        Entrega {
+            id: 0,
+            webhook: self.id,
+            evento: evento.to_string(),
+            url: self.url.clone(),
+            intentos: 0,
+            exitosa: false,
+            estado_respuesta: None,
+            error: None,
+            fecha: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Hace el POST del evento a la url del webhook. Devuelve el estado http de la respuesta.
+    /// El host se vuelve a verificar en cada envio, ya que la ip a la que resuelve puede cambiar
+    fn enviar(
+        &self,
+        evento: &str,
+        payload: &str,
+        hosts_permitidos: &[String],
+    ) -> Result<usize, String> {
+        let (direccion, ruta) = parsear_url(&self.url)?;
+        let socket = resolver_destino(&direccion, hosts_permitidos)?;
+        let mut stream = TcpStream::connect_timeout(&socket, TIEMPO_LIMITE_ENTREGA)
+            .map_err(|e| format!("No se pudo conectar a {direccion}: {e}"))?;
+        stream
+            .set_read_timeout(Some(TIEMPO_LIMITE_ENTREGA))
+            .map_err(|e| e.to_string())?;
+
+        let mut pedido = format!(
+            "POST {ruta} HTTP/1.1\r\nHost: {direccion}\r\nUser-Agent: gir-webhook\r\nContent-Type: application/json\r\nContent-Length: {}\r\nX-Gir-Event: {evento}\r\n",
+            payload.len()
+        );
+        if let Some(secreto) = &self.secreto {
+            pedido.push_str(&format!(
+                "X-Hub-Signature: {}\r\n",
+                firmar(secreto, payload)
+            ));
+        }
+        pedido.push_str("Connection: close\r\n\r\n");
+        pedido.push_str(payload);
+        stream
+            .write_all(pedido.as_bytes())
+            .map_err(|e| format!("No se pudo enviar el evento a {direccion}: {e}"))?;
+
+        let mut primera_linea = String::new();
+        BufReader::new(&stream)
+            .read_line(&mut primera_linea)
+            .map_err(|e| format!("No se pudo leer la respuesta de {direccion}: {e}"))?;
+        primera_linea
+            .split_whitespace()
+            .nth(1)
+            .and_then(|estado| estado.parse().ok())
+            .ok_or_else(|| format!("Respuesta invalida de {direccion}: {primera_linea}"))
+    }
+}
+
+/// Devuelve las entregas registradas del webhook `id`
+pub fn listar_entregas(dir_gir: &Path, id: u64) -> Result<Vec<Entrega>, String> {
+    let entregas: Vec<Entrega> = leer_json(&dir_gir.join(ARCHIVO_ENTREGAS))?;
+    Ok(entregas
+        .into_iter()
+        .filter(|entrega| entrega.webhook == id)
+        .collect())
+}
+
+/// Envia el evento a todos los webhooks del repositorio suscriptos a el. Las entregas se encolan
+/// en un pool de threads (configurado con `workers_webhooks` y `cola_webhooks`), para no demorar
+/// al que genero el evento. Si la cola esta llena, la entrega se registra como fallida.
+pub fn disparar_evento(
+    dir_gir: &Path,
+    evento: &str,
+    payload: serde_json::Value,
+    logger: Arc<Logger>,
+) {
+    let webhooks = match Webhook::listar(dir_gir) {
+        Ok(webhooks) => webhooks,
+        Err(error) => {
+            logger.log(&format!("No se pudieron leer los webhooks: {error}"));
+            return;
+        }
+    };
+
+    let pool = POOL_ENTREGAS.get_or_init(|| {
+        PoolThreads::new(
+            "webhooks",
+            ConfiguracionPool::desde_config("webhooks"),
+            logger.clone(),
+            hacer_entrega,
+        )
+    });
+
+    for webhook in webhooks
+        .into_iter()
+        .filter(|webhook| webhook.eventos.iter().any(|e| e == evento))
+    {
+        let pendiente = EntregaPendiente {
+            webhook,
+            dir_gir: dir_gir.to_path_buf(),
+            evento: evento.to_string(),
+            payload: payload.to_string(),
+            logger: logger.clone(),
+        };
+        let (pendiente, motivo) = match pool {
+            Ok(pool) => match pool.encolar(pendiente) {
+                Ok(()) => continue,
+                Err(pendiente) => (pendiente, "la cola de entregas esta llena".to_string()),
+            },
+            Err(error) => (pendiente, error.clone()),
+        };
+        descartar_entrega(pendiente, &motivo);
+    }
+}
+
+/// Hace una entrega encolada, desde un thread del pool
+fn hacer_entrega(pendiente: EntregaPendiente) {
+    let EntregaPendiente {
+        webhook,
+        dir_gir,
+        evento,
+        payload,
+        logger,
+    } = pendiente;
+    match webhook.entregar(&dir_gir, &evento, &payload, ESPERA_INICIAL_REINTENTO) {
+        Ok(entrega) if !entrega.exitosa => logger.log(&format!(
+            "No se pudo entregar el evento {} a {}",
+            evento, webhook.url
+        )),
+        Err(error) => logger.log(&format!(
+            "No se pudo registrar la entrega del evento {evento}: {error}"
+        )),
+        _ => {}
+    }
+}
+
+/// Registra como fallida una entrega que no se pudo encolar
+fn descartar_entrega(pendiente: EntregaPendiente, motivo: &str) {
+    pendiente.logger.log(&format!(
+        "Se descarta la entrega del evento {} a {}: {motivo}",
+        pendiente.evento, pendiente.webhook.url
+    ));
+    let mut entrega = pendiente.webhook.nueva_entrega(&pendiente.evento);
+    entrega.error = Some(motivo.to_string());
+    if let Err(error) = registrar_entrega(&pendiente.dir_gir, &mut entrega) {
+        pendiente.logger.log(&format!(
+            "No se pudo registrar la entrega del evento {}: {error}",
+            pendiente.evento
+        ));
+    }
+}
+
+/// Dispara el evento `pull_request` con la accion indicada (`opened`, `edited`, `closed` o `reopened`)
+pub fn disparar_evento_pull_request(pull_request: &PullRequest, accion: &str, logger: Arc<Logger>) {
+    let payload = serde_json::json!({
+        "action": accion,
+        "number": pull_request.numero,
+        "pull_request": pull_request,
+        "repository": pull_request.repositorio,
+    });
+    let repositorio = pull_request.obtener_repositorio();
+    disparar_evento(repositorio.dir_gir(), EVENTO_PULL_REQUEST, payload, logger);
+}
+
+/// Dispara el evento `push` por la actualizacion de `referencia` de `viejo` a `nuevo`
+pub fn disparar_evento_push(
+    dir_gir: &Path,
+    referencia: &str,
+    viejo: &str,
+    nuevo: &str,
+    logger: Arc<Logger>,
+) {
+    let repositorio = dir_gir
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map(|nombre| nombre.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let payload = serde_json::json!({
+        "ref": referencia,
+        "before": viejo,
+        "after": nuevo,
+        "repository": repositorio,
+    });
+    disparar_evento(dir_gir, EVENTO_PUSH, payload, logger);
+}
+
+/// Devuelve la firma del payload con el secreto, como la espera el header `X-Hub-Signature`:
+/// `sha1=<hmac-sha1 en hexadecimal>`
+pub fn firmar(secreto: &str, payload: &str) -> String {
+    let firma: String = hmac_sha1(secreto.as_bytes(), payload.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("sha1={firma}")
+}
+
+/// HMAC (RFC 2104) con sha1, que tiene bloques de 64 bytes
+fn hmac_sha1(clave: &[u8], mensaje: &[u8]) -> Vec<u8> {
+    let mut bloque_clave = if clave.len() > 64 {
+        Sha1::digest(clave).to_vec()
+    } else {
+        clave.to_vec()
+    };
+    bloque_clave.resize(64, 0);
+
+    let mut interno = Sha1::new();
+    interno.update(bloque_clave.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+    interno.update(mensaje);
+
+    let mut externo = Sha1::new();
+    externo.update(bloque_clave.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+    externo.update(interno.finalize());
+    externo.finalize().to_vec()
+}
+
+/// Separa una url `http://host[:puerto][/ruta]` en la direccion `host:puerto` y la ruta
+fn parsear_url(url: &str) -> Result<(String, String), String> {
+    let sin_esquema = url
+        .strip_prefix("http://")
+        .ok_or_else(|| format!("La url {url} no es http"))?;
+    let (direccion, ruta) = match sin_esquema.find('/') {
+        Some(indice) => sin_esquema.split_at(indice),
+        None => (sin_esquema, "/"),
+    };
+    if direccion.is_empty() {
+        return Err(format!("La url {url} no tiene host"));
+    }
+    let direccion = if direccion.contains(':') {
+        direccion.to_string()
+    } else {
+        format!("{direccion}:80")
+    };
+    Ok((direccion, ruta.to_string()))
+}
+
+/// Devuelve los hosts a los que se pueden enviar eventos aunque no sean publicos (Ej: un
+/// servicio de la red interna), configurados con `hosts_webhooks_permitidos` separados por comas
+fn hosts_permitidos() -> Vec<String> {
+    gir_config::conseguir_hosts_webhooks_permitidos()
+        .map(|hosts| strings::separar_lista(&hosts))
+        .unwrap_or_default()
+}
+
+/// Resuelve la direccion `host:puerto` de un webhook. Para que no se puedan usar los webhooks
+/// para hacer pedidos a la red interna del servidor, todas las ips a las que resuelve el host
+/// tienen que ser publicas, salvo que el host este en `hosts_permitidos`.
+fn resolver_destino(direccion: &str, hosts_permitidos: &[String]) -> Result<SocketAddr, String> {
+    let sockets: Vec<SocketAddr> = direccion
+        .to_socket_addrs()
+        .map_err(|e| format!("No se pudo resolver {direccion}: {e}"))?
+        .collect();
+    let host = direccion
+        .rsplit_once(':')
+        .map_or(direccion, |(host, _)| host);
+    let permitido = hosts_permitidos
+        .iter()
+        .any(|permitido| permitido.eq_ignore_ascii_case(host));
+    if !permitido {
+        if let Some(socket) = sockets.iter().find(|socket| !es_ip_publica(socket.ip())) {
+            return Err(format!(
+                "No se pueden enviar eventos a {direccion}: resuelve a la ip no publica {}",
+                socket.ip()
+            ));
+        }
+    }
+    sockets
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("No se pudo resolver {direccion}"))
+}
+
+/// Devuelve si la ip es publica: no es de loopback, privada, link-local, multicast, etc.
+fn es_ip_publica(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [primero, segundo, ..] = ip.octets();
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || primero == 0
+                // red compartida de los proveedores de internet (100.64.0.0/10)
+                || (primero == 100 && (64..128).contains(&segundo))
+                // reservadas para uso futuro (240.0.0.0/4)
+                || primero >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ipv4) = ip.to_ipv4_mapped() {
+                return es_ip_publica(IpAddr::V4(ipv4));
+            }
+            let primer_segmento = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // locales unicas (fc00::/7) y link-local (fe80::/10)
+                || (primer_segmento & 0xfe00) == 0xfc00
+                || (primer_segmento & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Agrega la entrega al registro del repositorio, descartando las mas viejas si se pasa de
+/// `MAXIMO_ENTREGAS_REGISTRADAS`
+fn registrar_entrega(dir_gir: &Path, entrega: &mut Entrega) -> Result<(), String> {
+    let _bloqueo = BLOQUEO_ENTREGAS
+        .lock()
+        .map_err(|_| "No se pudo bloquear el registro de entregas".to_string())?;
+    let archivo = dir_gir.join(ARCHIVO_ENTREGAS);
+    let mut entregas: Vec<Entrega> = leer_json(&archivo)?;
+    entrega.id = entregas.last().map_or(0, |ultima| ultima.id) + 1;
+    entregas.push(entrega.clone());
+    if entregas.len() > MAXIMO_ENTREGAS_REGISTRADAS {
+        entregas.drain(..entregas.len() - MAXIMO_ENTREGAS_REGISTRADAS);
+    }
+    escribir_json(&archivo, &entregas)
+}
+
+fn leer_json<T: serde::de::DeserializeOwned>(archivo: &PathBuf) -> Result<Vec<T>, String> {
+    if !io::existe(archivo) {
+        return Ok(Vec::new());
+    }
+    let contenido = io::leer_a_string(archivo)?;
+    serde_json::from_str(&contenido)
+        .map_err(|e| format!("El archivo {} no es valido: {}", archivo.display(), e))
+}
+
+fn escribir_json<T: Serialize>(archivo: &PathBuf, valores: &[T]) -> Result<(), String> {
+    let contenido = serde_json::to_string(valores)
+        .map_err(|e| format!("No se pudo serializar {}: {}", archivo.display(), e))?;
+    io::escribir_bytes(archivo, contenido)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, net::TcpListener};
+
+    use serial_test::serial;
+
+    use super::*;
+
+    /// Los tests reciben los eventos en un listener local, que no es publico
+    fn permitir_local() -> Vec<String> {
+        vec!["127.0.0.1".to_string()]
+    }
+
+    fn body(campos: &[(&str, &str)]) -> HashMap<String, String> {
+        campos
+            .iter()
+            .map(|(clave, valor)| (clave.to_string(), valor.to_string()))
+            .collect()
+    }
+
+    /// Acepta `respuestas.len()` conexiones, contestando a cada una con el estado indicado,
+    /// y devuelve los pedidos recibidos
+    fn escuchar(listener: TcpListener, respuestas: Vec<usize>) -> thread::JoinHandle<Vec<String>> {
+        thread::spawn(move || {
+            let mut pedidos = Vec::new();
+            for estado in respuestas {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut buffer = [0; 4096];
+                let leidos = stream.read(&mut buffer).unwrap();
+                pedidos.push(String::from_utf8_lossy(&buffer[..leidos]).to_string());
+                stream
+                    .write_all(format!("HTTP/1.1 {estado} X\r\n\r\n").as_bytes())
+                    .unwrap();
+            }
+            pedidos
+        })
+    }
+
+    #[test]
+    fn test01_la_firma_es_el_hmac_sha1_del_payload() {
+        // ejemplo conocido de hmac-sha1
+        assert_eq!(
+            firmar("key", "The quick brown fox jumps over the lazy dog"),
+            "sha1=de7c9b85b8b78aa6bc8a7a36f70a90701c9db4d9"
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test02_se_registra_un_webhook_y_se_le_entrega_el_evento_firmado() {
+        let dir_gir = PathBuf::from("tmp/webhook_test02/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/eventos", listener.local_addr().unwrap());
+        let webhook = Webhook::crear_permitiendo(
+            &dir_gir,
+            &body(&[
+                ("url", &url),
+                ("secret", "secreto"),
+                ("events", "push, pull_request"),
+            ]),
+            &permitir_local(),
+        )
+        .unwrap();
+        let servidor = escuchar(listener, vec![200]);
+
+        let payload = r#"{"ref":"refs/heads/master"}"#;
+        let entrega = webhook
+            .entregar_permitiendo(
+                &dir_gir,
+                EVENTO_PUSH,
+                payload,
+                Duration::ZERO,
+                &permitir_local(),
+            )
+            .unwrap();
+        let pedidos = servidor.join().unwrap();
+
+        assert_eq!(webhook.eventos, vec!["push", "pull_request"]);
+        assert!(entrega.exitosa && entrega.intentos == 1);
+        assert!(pedidos[0].starts_with("POST /eventos HTTP/1.1\r\n"));
+        assert!(pedidos[0].contains(&format!("X-Hub-Signature: {}", firmar("secreto", payload))));
+        assert!(pedidos[0].ends_with(payload));
+        assert_eq!(
+            listar_entregas(&dir_gir, webhook.id).unwrap(),
+            vec![entrega]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test03_las_entregas_fallidas_se_reintentan() {
+        let dir_gir = PathBuf::from("tmp/webhook_test03/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let webhook =
+            Webhook::crear_permitiendo(&dir_gir, &body(&[("url", &url)]), &permitir_local())
+                .unwrap();
+        let servidor = escuchar(listener, vec![500, 200]);
+
+        let entrega = webhook
+            .entregar_permitiendo(
+                &dir_gir,
+                EVENTO_PUSH,
+                "{}",
+                Duration::from_millis(10),
+                &permitir_local(),
+            )
+            .unwrap();
+        servidor.join().unwrap();
+
+        assert!(entrega.exitosa);
+        assert_eq!(entrega.intentos, 2);
+        assert_eq!(entrega.estado_respuesta, Some(200));
+        assert!(Webhook::crear(&dir_gir, &body(&[("url", "https://a.com")])).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test04_no_se_pueden_crear_webhooks_a_hosts_no_publicos() {
+        let dir_gir = PathBuf::from("tmp/webhook_test04/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+
+        for url in [
+            "http://localhost:8080/eventos",
+            "http://127.0.0.1/eventos",
+            "http://10.0.0.5/eventos",
+            "http://192.168.1.1/eventos",
+            "http://169.254.169.254/latest/meta-data",
+            "http://[::1]:8080/eventos",
+        ] {
+            assert!(
+                matches!(
+                    Webhook::crear_permitiendo(&dir_gir, &body(&[("url", url)]), &[]),
+                    Err(ErrorHttp::ValidationFailed(_))
+                ),
+                "se acepto {url}"
+            );
+        }
+        assert!(es_ip_publica("93.184.216.34".parse().unwrap()));
+        assert!(!es_ip_publica("::ffff:10.0.0.1".parse().unwrap()));
+        assert!(Webhook::crear_permitiendo(
+            &dir_gir,
+            &body(&[("url", "http://localhost:8080/eventos")]),
+            &["localhost".to_string()]
+        )
+        .is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test05_el_registro_de_entregas_tiene_un_maximo() {
+        let dir_gir = PathBuf::from("tmp/webhook_test05/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+        let webhook = Webhook {
+            id: 1,
+            url: "http://93.184.216.34/".to_string(),
+            secreto: None,
+            eventos: vec![EVENTO_PUSH.to_string()],
+        };
+
+        for _ in 0..MAXIMO_ENTREGAS_REGISTRADAS + 5 {
+            registrar_entrega(&dir_gir, &mut webhook.nueva_entrega(EVENTO_PUSH)).unwrap();
+        }
+        let entregas = listar_entregas(&dir_gir, webhook.id).unwrap();
+
+        assert_eq!(entregas.len(), MAXIMO_ENTREGAS_REGISTRADAS);
+        assert_eq!(entregas[0].id, 6);
+        assert_eq!(
+            entregas.last().unwrap().id,
+            MAXIMO_ENTREGAS_REGISTRADAS as u64 + 5
+        );
+    }
+}
@@ -3,15 +3,25 @@ use std::sync::Arc;
 use super::{
     comandos::{
         add::Add, branch::Branch, cat_file::CatFile, check_ignore::CheckIgnore, checkout::Checkout,
-        clone::Clone, commit::Commit, fetch::Fetch, hash_object::HashObject, init::Init, log::Log,
-        ls_files::LsFiles, ls_tree::LsTree, merge::Merge, pull::Pull, push::Push, rebase::Rebase,
-        remote::Remote, rm::Remove, show_ref::ShowRef, status::Status, tag::Tag, version::Version,
+        clone::Clone, commit::Commit, fetch::Fetch, fsck::Fsck, gc::Gc, hash_object::HashObject,
+        init::Init, log::Log, ls_files::LsFiles, ls_tree::LsTree, merge::Merge, prune::Prune,
+        pull::Pull, push::Push, rebase::Rebase, remote::Remote, repack::Repack, rm::Remove,
+        show_ref::ShowRef, status::Status, tag::Tag, version::Version,
     },
     logger::Logger,
+    repositorio::Repositorio,
 };
 
+/// Comando a ejecutar junto al repositorio sobre el que trabaja.
+/// Tanto el parseo de los argumentos como la ejecucion se hacen dentro del repositorio,
+/// sin depender del directorio actual del proceso.
+pub struct Comando {
+    repositorio: Repositorio,
+    tipo: TipoComando,
+}
+
 /// Enum que contiene los posibles comandos de nuestro programa.
-pub enum Comando {
+pub enum TipoComando {
     Init(Init),
     Version(Version),
     HashObject(HashObject),
@@ -35,6 +45,10 @@ pub enum Comando {
     LsTree(LsTree),
     LsFiles(LsFiles),
     Rebase(Rebase),
+    Gc(Gc),
+    Prune(Prune),
+    Repack(Repack),
+    Fsck(Fsck),
     Unknown,
 }
 
@@ -43,68 +57,96 @@ pub trait Ejecutar {
 }
 
 impl Comando {
-    pub fn new(input: Vec<String>, logger: Arc<Logger>) -> Result<Comando, String> {
+    /// Crea el comando a partir de la linea de comandos, para ejecutarlo sobre `repositorio`.
+    pub fn new(
+        input: Vec<String>,
+        repositorio: Repositorio,
+        logger: Arc<Logger>,
+    ) -> Result<Comando, String> {
+        let tipo = {
+            let _repositorio = repositorio.entrar();
+            TipoComando::new(input, logger)?
+        };
+        Ok(Comando { repositorio, tipo })
+    }
+
+    /// Ejecuta el comando sobre su repositorio.
+    pub fn ejecutar(&mut self) -> Result<String, String> {
+        let _repositorio = self.repositorio.entrar();
+        self.tipo.ejecutar()
+    }
+}
+
+impl TipoComando {
+    fn new(input: Vec<String>, logger: Arc<Logger>) -> Result<TipoComando, String> {
         let (comando, args) = input.split_first().ok_or("No se ingreso ningun comando")?;
 
         let mut vector_args = args.to_vec();
 
         let comando = match comando.as_str() {
-            "version" => Comando::Version(Version::from(vector_args)?),
-            "init" => Comando::Init(Init::from(vector_args, logger)?),
-            "hash-object" => Comando::HashObject(HashObject::from(&mut vector_args, logger)?),
-            "cat-file" => Comando::CatFile(CatFile::from(&mut vector_args, logger)?),
-            "add" => Comando::Add(Add::from(vector_args, logger)?),
-            "rm" => Comando::Remove(Remove::from(vector_args, logger)?),
-            "branch" => Comando::Branch(Branch::from(&mut vector_args, logger)?),
-            "checkout" => Comando::Checkout(Checkout::from(vector_args, logger)?),
-            "commit" => Comando::Commit(Commit::from(&mut vector_args, logger)?),
-            "fetch" => Comando::Fetch(Fetch::new(vector_args, logger)?),
-            "clone" => Comando::Clone(Clone::from(&mut vector_args, logger, false)?),
-            "push" => Comando::Push(Push::new(&mut vector_args, logger)?),
-            "pull" => Comando::Pull(Pull::from(vector_args, logger)?),
-            "log" => Comando::Log(Log::from(&mut vector_args, logger)?),
-            "status" => Comando::Status(Status::from(logger)?),
-            "remote" => Comando::Remote(Remote::from(&mut vector_args, logger)?),
-            "merge" => Comando::Merge(Merge::from(&mut vector_args, logger)?),
-            "ls-tree" => Comando::LsTree(LsTree::from(logger, &mut vector_args)?),
-            "tag" => Comando::Tag(Tag::from(vector_args, logger)?),
-            "show-ref" => Comando::ShowRef(ShowRef::from(vector_args, logger)?),
-            "ls-files" => Comando::LsFiles(LsFiles::from(logger, &mut vector_args)?),
-            "check-ignore" => Comando::CheckIgnore(CheckIgnore::from(vector_args, logger)?),
-            "rebase" => Comando::Rebase(Rebase::from(vector_args, logger)?),
-            _ => Comando::Unknown,
+            "version" => TipoComando::Version(Version::from(vector_args)?),
+            "init" => TipoComando::Init(Init::from(vector_args, logger)?),
+            "hash-object" => TipoComando::HashObject(HashObject::from(&mut vector_args, logger)?),
+            "cat-file" => TipoComando::CatFile(CatFile::from(&mut vector_args, logger)?),
+            "add" => TipoComando::Add(Add::from(vector_args, logger)?),
+            "rm" => TipoComando::Remove(Remove::from(vector_args, logger)?),
+            "branch" => TipoComando::Branch(Branch::from(&mut vector_args, logger)?),
+            "checkout" => TipoComando::Checkout(Checkout::from(vector_args, logger)?),
+            "commit" => TipoComando::Commit(Commit::from(&mut vector_args, logger)?),
+            "fetch" => TipoComando::Fetch(Fetch::new(vector_args, logger)?),
+            "clone" => TipoComando::Clone(Clone::from(&mut vector_args, logger, false)?),
+            "push" => TipoComando::Push(Push::new(&mut vector_args, logger)?),
+            "pull" => TipoComando::Pull(Pull::from(vector_args, logger)?),
+            "log" => TipoComando::Log(Log::from(&mut vector_args, logger)?),
+            "status" => TipoComando::Status(Status::from(logger)?),
+            "remote" => TipoComando::Remote(Remote::from(&mut vector_args, logger)?),
+            "merge" => TipoComando::Merge(Merge::from(&mut vector_args, logger)?),
+            "ls-tree" => TipoComando::LsTree(LsTree::from(logger, &mut vector_args)?),
+            "tag" => TipoComando::Tag(Tag::from(vector_args, logger)?),
+            "show-ref" => TipoComando::ShowRef(ShowRef::from(vector_args, logger)?),
+            "ls-files" => TipoComando::LsFiles(LsFiles::from(logger, &mut vector_args)?),
+            "check-ignore" => TipoComando::CheckIgnore(CheckIgnore::from(vector_args, logger)?),
+            "rebase" => TipoComando::Rebase(Rebase::from(vector_args, logger)?),
+            "gc" => TipoComando::Gc(Gc::from(vector_args, logger)?),
+            "prune" => TipoComando::Prune(Prune::from(vector_args, logger)?),
+            "repack" => TipoComando::Repack(Repack::from(vector_args, logger)?),
+            "fsck" => TipoComando::Fsck(Fsck::from(vector_args, logger)?),
+            _ => TipoComando::Unknown,
         };
 
         Ok(comando)
     }
     /// Ejecuta el comando.
-
-    pub fn ejecutar(&mut self) -> Result<String, String> {
+    fn ejecutar(&mut self) -> Result<String, String> {
         match self {
-            Comando::Init(init) => init.ejecutar(),
-            Comando::Version(version) => version.ejecutar(),
-            Comando::HashObject(hash_object) => hash_object.ejecutar(),
-            Comando::CatFile(cat_file) => cat_file.ejecutar(),
-            Comando::Add(ref mut add) => add.ejecutar(),
-            Comando::Remove(ref mut remove) => remove.ejecutar(),
-            Comando::Checkout(ref mut checkout) => checkout.ejecutar(),
-            Comando::Branch(ref mut branch) => branch.ejecutar(),
-            Comando::Commit(ref mut commit) => commit.ejecutar(),
-            Comando::Fetch(ref mut fetch) => fetch.ejecutar(),
-            Comando::Clone(clone) => clone.ejecutar(),
-            Comando::Push(ref mut push) => push.ejecutar(),
-            Comando::Log(ref mut log) => log.ejecutar(),
-            Comando::Status(ref mut status) => status.ejecutar(),
-            Comando::Remote(ref mut remote) => remote.ejecutar(),
-            Comando::Merge(ref mut merge) => merge.ejecutar(),
-            Comando::Pull(ref mut pull) => pull.ejecutar(),
-            Comando::LsTree(ref mut ls_tree) => ls_tree.ejecutar(),
-            Comando::Tag(ref mut tag) => tag.ejecutar(),
-            Comando::ShowRef(ref mut show_ref) => show_ref.ejecutar(),
-            Comando::Rebase(ref mut rebase) => rebase.ejecutar(),
-            Comando::LsFiles(ref mut ls_files) => ls_files.ejecutar(),
-            Comando::CheckIgnore(ref mut check_ignore) => check_ignore.ejecutar(),
-            Comando::Unknown => Err("Comando desconocido".to_string()),
+            TipoComando::Init(init) => init.ejecutar(),
+            TipoComando::Version(version) => version.ejecutar(),
+            TipoComando::HashObject(hash_object) => hash_object.ejecutar(),
+            TipoComando::CatFile(cat_file) => cat_file.ejecutar(),
+            TipoComando::Add(ref mut add) => add.ejecutar(),
+            TipoComando::Remove(ref mut remove) => remove.ejecutar(),
+            TipoComando::Checkout(ref mut checkout) => checkout.ejecutar(),
+            TipoComando::Branch(ref mut branch) => branch.ejecutar(),
+            TipoComando::Commit(ref mut commit) => commit.ejecutar(),
+            TipoComando::Fetch(ref mut fetch) => fetch.ejecutar(),
+            TipoComando::Clone(clone) => clone.ejecutar(),
+            TipoComando::Push(ref mut push) => push.ejecutar(),
+            TipoComando::Log(ref mut log) => log.ejecutar(),
+            TipoComando::Status(ref mut status) => status.ejecutar(),
+            TipoComando::Remote(ref mut remote) => remote.ejecutar(),
+            TipoComando::Merge(ref mut merge) => merge.ejecutar(),
+            TipoComando::Pull(ref mut pull) => pull.ejecutar(),
+            TipoComando::LsTree(ref mut ls_tree) => ls_tree.ejecutar(),
+            TipoComando::Tag(ref mut tag) => tag.ejecutar(),
+            TipoComando::ShowRef(ref mut show_ref) => show_ref.ejecutar(),
+            TipoComando::Rebase(ref mut rebase) => rebase.ejecutar(),
+            TipoComando::LsFiles(ref mut ls_files) => ls_files.ejecutar(),
+            TipoComando::CheckIgnore(ref mut check_ignore) => check_ignore.ejecutar(),
+            TipoComando::Gc(ref mut gc) => gc.ejecutar(),
+            TipoComando::Prune(ref mut prune) => prune.ejecutar(),
+            TipoComando::Repack(ref mut repack) => repack.ejecutar(),
+            TipoComando::Fsck(ref mut fsck) => fsck.ejecutar(),
+            TipoComando::Unknown => Err("Comando desconocido".to_string()),
         }
     }
 }
@@ -2,7 +2,10 @@ use std::{path::PathBuf, sync::Arc};
 
 use crate::{
     tipos_de_dato::{comando::Ejecutar, logger::Logger, objeto::Objeto},
-    utils::index::{crear_index, escribir_index, leer_index, ObjetoIndex},
+    utils::{
+        index::{crear_index, escribir_index, leer_index, ObjetoIndex},
+        io,
+    },
 };
 
 use super::{check_ignore::CheckIgnore, status::obtener_arbol_del_commit_head};
@@ -23,16 +26,16 @@ impl Add {
     pub fn obtener_ubicaciones_hoja(ubicaciones: Vec<PathBuf>) -> Result<Vec<PathBuf>, String> {
         let mut ubicaciones_hoja: Vec<PathBuf> = Vec::new();
         for ubicacion in ubicaciones {
-            if ubicacion.is_file() {
+            if io::es_archivo(&ubicacion) {
                 ubicaciones_hoja.push(ubicacion);
-            } else if ubicacion.is_dir() {
-                let mut directorios = std::fs::read_dir(ubicacion)
+            } else if io::es_dir(&ubicacion) {
+                let mut directorios = io::leer_directorio(&ubicacion)
                     .map_err(|_| "Error al obtener directorios hoja".to_string())?;
                 while let Some(Ok(directorio)) = directorios.next() {
-                    let path = directorio.path();
-                    if path.is_file() {
+                    let path = ubicacion.join(directorio.file_name());
+                    if io::es_archivo(&path) {
                         ubicaciones_hoja.push(path);
-                    } else if path.is_dir() {
+                    } else if io::es_dir(&path) {
                         ubicaciones_hoja.append(&mut Self::obtener_ubicaciones_hoja(vec![path])?);
                     }
                 }
@@ -126,7 +129,7 @@ impl Ejecutar for Add {
                     .to_str()
                     .ok_or_else(|| "Path invalido".to_string())?,
             ));
-            if ubicacion.is_dir() {
+            if io::es_dir(&ubicacion) {
                 Err("No se puede agregar un directorio")?;
             }
             self.aniadir_ubicacion_pedida_al_index(ubicacion)?;
@@ -1,7 +1,10 @@
-use std::{path::PathBuf, sync::Arc};
+use std::sync::Arc;
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger},
+    tipos_de_dato::{
+        comando::Ejecutar, logger::Logger, repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
+    },
     utils::{io, path_buf::obtener_nombre, ramas},
 };
 
@@ -44,9 +47,14 @@ impl Branch {
 
     /// Devuelve un vector con las ramas que existen en el repositorio
     pub fn obtener_ramas() -> Result<Vec<String>, String> {
-        let directorio = ".gir/refs/heads";
-        let entradas = std::fs::read_dir(directorio)
-            .map_err(|e| format!("No se pudo leer el directorio:{}\n {}", directorio, e))?;
+        let directorio = Repositorio::actual().ruta_gir("refs/heads");
+        let entradas = io::leer_directorio(&directorio).map_err(|e| {
+            format!(
+                "No se pudo leer el directorio:{}\n {}",
+                directorio.display(),
+                e
+            )
+        })?;
 
         let mut ramas: Vec<String> = Vec::new();
 
@@ -88,13 +96,16 @@ impl Branch {
             .take()
             .ok_or("No se pudo obtener el nombre de la rama")?;
 
-        let direccion_rama_nueva = format!(".gir/refs/heads/{}", rama_nueva);
+        let repositorio = Repositorio::actual();
+        let direccion_rama_nueva = repositorio.ruta_gir(format!("refs/heads/{}", rama_nueva));
 
-        if PathBuf::from(&direccion_rama_nueva).exists() {
+        if io::existe(&direccion_rama_nueva) {
             return Err(format!("La rama {} ya existe", rama_nueva));
         }
         let ultimo_commit = ramas::obtener_hash_commit_asociado_rama_actual()?;
-        io::escribir_bytes(direccion_rama_nueva, ultimo_commit)?;
+        TransaccionRefs::new(repositorio.dir_gir())
+            .actualizar(&format!("refs/heads/{}", rama_nueva), &ultimo_commit)
+            .confirmar()?;
         Ok(format!("Se creó la rama {}", rama_nueva))
     }
 }
@@ -116,8 +127,8 @@ mod tests {
     use crate::tipos_de_dato::comandos::commit::Commit;
     use crate::tipos_de_dato::comandos::init::Init;
     use crate::tipos_de_dato::logger::Logger;
-    use crate::utils;
     use crate::utils::gir_config::obtener_gir_config_path;
+    use crate::utils::{self, io};
     use serial_test::serial;
     use std::path::PathBuf;
     use std::sync::Arc;
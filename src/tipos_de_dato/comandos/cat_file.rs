@@ -1,7 +1,7 @@
 use crate::{
     tipos_de_dato::{
         comando::Ejecutar, logger::Logger, objeto::flag_es_un_objeto_, objetos::tree::Tree,
-        visualizaciones::Visualizaciones,
+        repositorio::Repositorio, visualizaciones::Visualizaciones,
     },
     utils::compresion::descomprimir_objeto,
 };
@@ -29,7 +29,6 @@ pub fn obtener_tipo_objeto_de(hash_objeto: &str, dir: &str) -> Result<String, St
 /// Obtiene el contenido de un objeto ubicado en cierto directorio a partir de su hash.
 /// En caso de no encontrar el objeto devuelve error.
 fn obtener_contenido_objeto_de(hash: &str, dir: &str) -> Result<(String, String), String> {
-    // sacar el hardcode de esto
     let objeto = descomprimir_objeto(hash, dir)?;
     match objeto.split_once('\0') {
         Some((header, contenido)) => Ok((header.to_string(), contenido.to_string())),
@@ -40,12 +39,7 @@ fn obtener_contenido_objeto_de(hash: &str, dir: &str) -> Result<(String, String)
 /// Obtiene el contenido de un objeto ubicado en el directorio de objetos del .gir a partir de su hash.
 /// En caso de no encontrar el objeto devuelve error.
 pub fn obtener_contenido_objeto(hash: &str) -> Result<(String, String), String> {
-    // sacar el hardcode de esto
-    let objeto = descomprimir_objeto(hash, ".gir/objects/")?;
-    match objeto.split_once('\0') {
-        Some((header, contenido)) => Ok((header.to_string(), contenido.to_string())),
-        None => Err("Objeto invalido".to_string()),
-    }
+    obtener_contenido_objeto_de(hash, &Repositorio::actual().dir_objetos())
 }
 
 /// Obtiene el tipo de objeto a partir de su header.
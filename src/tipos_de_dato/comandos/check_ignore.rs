@@ -5,7 +5,10 @@ use std::{
 
 use crate::{
     tipos_de_dato::{comando::Ejecutar, logger::Logger},
-    utils::{self, io, path_buf::esta_directorio_habilitado},
+    utils::{
+        girignore::ReglasIgnore, io, path_buf::esta_directorio_habilitado,
+        ubicacion_repositorio::DIR_GIR,
+    },
 };
 
 pub struct CheckIgnore {
@@ -13,13 +16,15 @@ pub struct CheckIgnore {
     logger: Arc<Logger>,
     /// Paths de los archivos a verificar si son ignorados.
     paths: Vec<String>,
+    /// Si es true, por cada path se muestra la regla que coincidio (`-v`).
+    verbose: bool,
 }
 
 impl CheckIgnore {
     /// Dada una ubicacion de un directorio/archivo, devuele un booleano indicando
     /// si la ubicacion esta dentro de los archivos a ignorar.
     pub fn es_directorio_a_ignorar(ubicacion: &Path, logger: Arc<Logger>) -> Result<bool, String> {
-        if esta_directorio_habilitado(ubicacion, &vec![PathBuf::from(".gir")]) {
+        if esta_directorio_habilitado(ubicacion, &vec![PathBuf::from(DIR_GIR)]) {
             return Ok(true);
         }
 
@@ -37,46 +42,58 @@ impl CheckIgnore {
     }
 
     /// Devueve un CheckIgnore con los paths de los archivos a verificar.
+    /// Con `-v` o `--verbose` muestra ademas la regla que coincidio con cada path.
     pub fn from(args: Vec<String>, logger: Arc<Logger>) -> Result<CheckIgnore, String> {
-        if args.is_empty() {
+        let verbose = args.iter().any(|arg| arg == "-v" || arg == "--verbose");
+        let paths: Vec<String> = args
+            .into_iter()
+            .filter(|arg| arg != "-v" && arg != "--verbose")
+            .collect();
+        if paths.is_empty() {
             return Err("Ingrese la ruta del archivo buscado como parametro".to_string());
         }
-        let paths = args;
-        Ok(CheckIgnore { logger, paths })
+        Ok(CheckIgnore {
+            logger,
+            paths,
+            verbose,
+        })
+    }
+
+    /// Devuelve la linea de salida para `path`, o None si no hay que mostrarlo.
+    /// Sin `-v` solo se muestran los paths ignorados. Con `-v` se muestran todos los que
+    /// coinciden con alguna regla, incluso si es una negada, con el formato
+    /// `<archivo>:<linea>:<patron>\t<path>`.
+    fn verificar_path(&self, path: &str) -> Option<String> {
+        let ruta = PathBuf::from(path);
+        let es_directorio = path.ends_with('/') || io::es_dir(&ruta);
+        let reglas = ReglasIgnore::cargar(&ruta);
+        let regla = reglas.buscar_regla(&ruta, es_directorio)?;
+
+        if self.verbose {
+            Some(format!("{}\t{}", regla, path))
+        } else if regla.ignora() {
+            Some(path.to_string())
+        } else {
+            None
+        }
     }
 }
 
 /// Ejecuta el comando check-ignore.
 /// Devuelve un string con todos los archivos consultados que resultaron estar ignorados.
 /// Si no hay archivos ignorados, devuelve un string vacio.
+/// Las reglas salen del archivo global de excludes, de `.gir/info/exclude` y de los
+/// `.girignore` de la raiz y de cada directorio, con la semantica de gitignore.
 impl Ejecutar for CheckIgnore {
     fn ejecutar(&mut self) -> Result<String, String> {
         self.logger.log("Buscando archivos ignorados");
-        let archivos_ignorados = match io::leer_a_string(".girignore") {
-            Ok(archivos_ignorados) => archivos_ignorados.trim().to_string(),
-            Err(_) => return Ok("".to_string()),
-        };
-        if archivos_ignorados.is_empty() {
-            return Ok("".to_string());
-        }
 
-        let archivos_ignorados_separados: Vec<PathBuf> = archivos_ignorados
-            .split('\n')
-            .filter(|x| !x.is_empty())
-            .map(PathBuf::from)
+        let archivos_encontrados: Vec<String> = self
+            .paths
+            .iter()
+            .filter_map(|path| self.verificar_path(path))
             .collect();
 
-        let mut archivos_encontrados: Vec<String> = Vec::new();
-
-        for path in &self.paths {
-            if utils::path_buf::esta_directorio_habilitado(
-                &PathBuf::from(path),
-                &archivos_ignorados_separados,
-            ) {
-                archivos_encontrados.push(path.to_string());
-            }
-        }
-
         self.logger.log("Check ignore finalizado");
 
         Ok(archivos_encontrados.join("\n"))
@@ -201,4 +218,67 @@ mod tests {
         girignore_original();
         assert!(resultado.is_empty());
     }
+
+    #[test]
+    #[serial]
+    fn test07_los_girignore_de_cada_directorio_aplican_desde_su_directorio() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/check_ignore_test07")).unwrap());
+        let directorio = "tmp/check_ignore_test07_repo";
+        io::escribir_bytes(
+            format!("{directorio}/.girignore"),
+            "# temporales\n*.tmp\n!conservar.tmp\n/solo_aca.txt",
+        )
+        .unwrap();
+        io::escribir_bytes(".gir/info/exclude", "*.local").unwrap();
+
+        let mut check_ignore = CheckIgnore::from(
+            vec![
+                format!("{directorio}/a.tmp"),
+                format!("{directorio}/sub/b.tmp"),
+                format!("{directorio}/conservar.tmp"),
+                format!("{directorio}/solo_aca.txt"),
+                format!("{directorio}/sub/solo_aca.txt"),
+                "config.local".to_string(),
+                "a.tmp".to_string(),
+            ],
+            logger,
+        )
+        .unwrap();
+        let resultado = check_ignore.ejecutar().unwrap();
+        io::rm_directorio(".gir/info/exclude").unwrap();
+
+        assert_eq!(
+            resultado,
+            format!(
+                "{directorio}/a.tmp\n{directorio}/sub/b.tmp\n{directorio}/solo_aca.txt\nconfig.local"
+            )
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test08_con_verbose_se_muestra_la_regla_que_coincidio() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/check_ignore_test08")).unwrap());
+        let directorio = "tmp/check_ignore_test08_repo";
+        io::escribir_bytes(format!("{directorio}/.girignore"), "*.tmp\n!conservar.tmp").unwrap();
+
+        let mut check_ignore = CheckIgnore::from(
+            vec![
+                "-v".to_string(),
+                format!("{directorio}/a.tmp"),
+                format!("{directorio}/conservar.tmp"),
+                format!("{directorio}/otro.txt"),
+            ],
+            logger,
+        )
+        .unwrap();
+        let resultado = check_ignore.ejecutar().unwrap();
+
+        assert_eq!(
+            resultado,
+            format!(
+                "{directorio}/.girignore:1:*.tmp\t{directorio}/a.tmp\n{directorio}/.girignore:2:!conservar.tmp\t{directorio}/conservar.tmp"
+            )
+        );
+    }
 }
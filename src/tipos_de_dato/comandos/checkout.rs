@@ -3,15 +3,14 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 use crate::{
     tipos_de_dato::{
         comando::Ejecutar, comandos::branch::Branch, config::Config, info_ramas::RamasInfo,
-        logger::Logger, objeto::Objeto, objetos::tree::Tree, tipo_de_rama::TipoRama,
+        logger::Logger, objeto::Objeto, objetos::tree::Tree, repositorio::Repositorio,
+        tipo_de_rama::TipoRama, transaccion_refs::TransaccionRefs,
     },
     utils::{self, io},
 };
 
 use super::{show_ref::ShowRef, write_tree::conseguir_arbol_en_directorio};
 
-const PATH_HEAD: &str = "./.gir/HEAD";
-
 pub struct Checkout {
     /// Si es true, se crea una nueva rama.
     crear_rama: bool,
@@ -67,9 +66,14 @@ impl Checkout {
 
     /// Devuelve un vector con los nombres de las ramas existentes en el repositorio.
     pub fn obtener_ramas() -> Result<Vec<String>, String> {
-        let directorio = ".gir/refs/heads";
-        let entradas = std::fs::read_dir(directorio)
-            .map_err(|e| format!("No se pudo leer el directorio:{}\n {}", directorio, e))?;
+        let directorio = Repositorio::actual().ruta_gir("refs/heads");
+        let entradas = io::leer_directorio(&directorio).map_err(|e| {
+            format!(
+                "No se pudo leer el directorio:{}\n {}",
+                directorio.display(),
+                e
+            )
+        })?;
 
         let mut output = Vec::new();
 
@@ -87,7 +91,7 @@ impl Checkout {
     /// Devuelve un hashmap con las ramas remotas.
     pub fn obtener_ramas_remotas(&self) -> Result<HashMap<String, String>, String> {
         let show_ref = ShowRef::from(vec![], self.logger.clone())?;
-        let ramas = show_ref.obtener_referencias(PathBuf::from(".gir/refs/remotes"))?;
+        let ramas = show_ref.obtener_referencias(Repositorio::actual().ruta_gir("refs/remotes"))?;
         Ok(ramas)
     }
     /// Verifica si la rama a cambiar ya existe.
@@ -124,20 +128,16 @@ impl Checkout {
 
     /// Cambia la referencia de la rama en el archivo HEAD.
     fn cambiar_ref_en_head(&self) -> Result<(), String> {
-        let contenido_head = io::leer_a_string(PATH_HEAD)?;
-
-        let rama_actual = Self::conseguir_rama_actual(&contenido_head)?;
-
-        let nuevo_head = contenido_head.replace(&rama_actual, &self.rama_a_cambiar);
-
-        io::escribir_bytes(PATH_HEAD, nuevo_head)?;
-
-        Ok(())
+        TransaccionRefs::new(Repositorio::actual().dir_gir())
+            .apuntar_simbolicamente("HEAD", &format!("refs/heads/{}", self.rama_a_cambiar))
+            .confirmar()
     }
 
     /// Crea una nueva rama desde el remote.
     fn crear_rama_desde_remote(&self, commit: &str) -> Result<(), String> {
-        io::escribir_bytes(format!(".gir/refs/heads/{}", self.rama_a_cambiar), commit)
+        TransaccionRefs::new(Repositorio::actual().dir_gir())
+            .actualizar(&format!("refs/heads/{}", self.rama_a_cambiar), commit)
+            .confirmar()
     }
 
     /// Configura el remote para la rama actual.
@@ -194,10 +194,13 @@ impl Checkout {
 
     /// Devuelve el arbol del ultimo commit de la rama actual.
     pub fn obtener_arbol_commit_actual(logger: Arc<Logger>) -> Result<Tree, String> {
-        let ref_actual = io::leer_a_string(PATH_HEAD)?;
+        let repositorio = Repositorio::actual();
+        let ref_actual = io::leer_a_string(repositorio.ruta_gir("HEAD"))?;
         let rama_actual = Self::conseguir_rama_actual(&ref_actual)?;
-        let head_commit = io::leer_a_string(format!(".gir/refs/heads/{}", rama_actual))?;
-        let hash_tree_padre = conseguir_arbol_en_directorio(&head_commit, ".gir/objects/")?;
+        let head_commit =
+            io::leer_a_string(repositorio.ruta_gir(format!("refs/heads/{}", rama_actual)))?;
+        let hash_tree_padre =
+            conseguir_arbol_en_directorio(&head_commit, &repositorio.dir_objetos())?;
         Tree::from_hash(&hash_tree_padre, PathBuf::from("."), logger)
     }
 
@@ -1,7 +1,9 @@
 use crate::tipos_de_dato::comando::Ejecutar;
 use crate::tipos_de_dato::logger::Logger;
 use crate::tipos_de_dato::objetos::tree::Tree;
-use crate::utils::{self, io};
+use crate::tipos_de_dato::repositorio::Repositorio;
+use crate::tipos_de_dato::transaccion_refs::TransaccionRefs;
+use crate::utils;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -13,7 +15,7 @@ use super::remote::Remote;
 use super::set_upstream::SetUpstream;
 use super::write_tree;
 
-const UBICACION_RAMA_MASTER: &str = "./.gir/refs/heads/master";
+const REF_RAMA_MASTER: &str = "refs/heads/master";
 const GIR_CLONE: &str = "gir clone <ip:puerto/repositorio/>";
 pub struct Clone {
     logger: Arc<Logger>,
@@ -55,7 +57,7 @@ impl Clone {
     /// Verifica si el repositorio ya existe en el sistema
     /// Si existe verifica que este vacio
     fn verificar_si_ya_existe_repositorio(&self, repositorio: &str) -> Result<(), String> {
-        if PathBuf::from(repositorio).exists() {
+        if utils::io::existe(repositorio) {
             //me fijo si tiene contenido
             if utils::io::leer_directorio(&repositorio)?.count() > 0 {
                 return Err(format!("Error el directorio {} no esta vacio", repositorio));
@@ -68,7 +70,8 @@ impl Clone {
     /// Obtiene la rama predeterminada del repositorio
     /// Si no existe la rama master, devuelve la ultima rama que se creo
     pub fn obtener_rama_predeterminada() -> Result<String, String> {
-        let ramas = utils::io::leer_directorio(".gir/refs/remotes/origin")?;
+        let ramas =
+            utils::io::leer_directorio(&Repositorio::actual().ruta_gir("refs/remotes/origin"))?;
         let mut rama_predeterminada = String::new();
 
         for rama in ramas {
@@ -87,7 +90,9 @@ impl Clone {
     /// se obtiene el arbol del commit de la rama master remota y se lo escribe en el directorio
     /// de trabajo.
     fn fast_forward_de_cero(&self, commit_head_remoto: &str) -> Result<bool, String> {
-        io::escribir_bytes(UBICACION_RAMA_MASTER, commit_head_remoto)?;
+        TransaccionRefs::new(Repositorio::actual().dir_gir())
+            .actualizar(REF_RAMA_MASTER, commit_head_remoto)
+            .confirmar()?;
         let hash_tree_padre = write_tree::conseguir_arbol(commit_head_remoto)?;
         let tree_branch_a_mergear =
             Tree::from_hash(&hash_tree_padre, PathBuf::from("."), self.logger.clone())?;
@@ -103,13 +108,14 @@ impl Clone {
     ///Busca el archivo correspondiente que contien el HEAD del remoto (el NOMBREREMOTO_HEAD)y lo obtiene. En caso de no
     /// existir dicho archivo toma por defecto devulevor el commit de master del remoto.   
     fn obtener_head_remoto(&self, remoto: &str, rama_remota: &str) -> Result<String, String> {
-        let path_remoto = PathBuf::from(format!("./.gir/{}_HEAD", remoto.to_uppercase()));
+        let repositorio = Repositorio::actual();
+        let path_remoto = repositorio.ruta_gir(format!("{}_HEAD", remoto.to_uppercase()));
 
-        if path_remoto.exists() {
+        if utils::io::existe(&path_remoto) {
             utils::io::leer_a_string(path_remoto)
         } else {
             let path_master_remoto =
-                PathBuf::from(format!("./.gir/refs/remotes/{}/{}", remoto, rama_remota));
+                repositorio.ruta_gir(format!("refs/remotes/{}/{}", remoto, rama_remota));
 
             utils::io::leer_a_string(path_master_remoto)
         }
@@ -147,19 +153,14 @@ impl Ejecutar for Clone {
 
         self.verificar_si_ya_existe_repositorio(&repositorio)?;
 
-        if !self.clonar_en_dir_actual {
+        if self.clonar_en_dir_actual {
+            self.crear_repositorio()?;
+        } else {
             utils::io::crear_carpeta(&repositorio)?;
-            utils::io::cambiar_directorio(&repositorio)?;
+            let _repositorio = Repositorio::new(&repositorio).entrar();
+            self.crear_repositorio()?;
         }
 
-        let resutado = self.crear_repositorio();
-
-        if !self.clonar_en_dir_actual {
-            utils::io::cambiar_directorio("..")?;
-        }
-
-        resutado?;
-
         let mensaje = "Clone ejecutado con exito".to_string();
         self.logger.log(&mensaje);
         Ok(mensaje)
@@ -1,18 +1,32 @@
-use std::{path, sync::Arc};
+use std::{path, process, sync::Arc};
 
 use chrono::TimeZone;
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger},
+    tipos_de_dato::{
+        comando::Ejecutar,
+        logger::Logger,
+        objeto::Objeto,
+        objetos::{blob::Blob, commit::CommitObj, tree::Tree},
+        repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
+    },
     utils::{
         compresion::comprimir_contenido,
         gir_config::{armar_config_con_mail_y_nombre, conseguir_nombre_y_mail_del_config},
-        index::limpiar_archivo_index,
+        hooks,
+        index::{escribir_index, leer_index, limpiar_archivo_index, ObjetoIndex},
         io, ramas,
     },
 };
 
-use super::{hash_object::HashObject, merge::Merge, write_tree};
+use super::{add::Add, hash_object::HashObject, merge::Merge, status, write_tree};
+
+/// Archivo, dentro de `.gir`, en el que se escribe el mensaje del commit cuando no se pasa `-m` ni `-F`
+const ARCHIVO_MENSAJE: &str = "COMMIT_EDITMSG";
+
+/// Instrucciones que se agregan al archivo del mensaje al abrir el editor
+const INSTRUCCIONES_EDITOR: &str = "\n# Ingrese el mensaje del commit. Las lineas que empiezan con '#' se ignoran\n# y un mensaje vacio aborta el commit.\n";
 
 pub struct Commit {
     /// Logger para imprimir mensajes en el archivo log.
@@ -20,6 +34,21 @@ pub struct Commit {
     /// Mensaje del commit.
     pub mensaje: String,
     pub rama_actual: String,
+    /// Si es true, reemplaza al commit al que apunta la rama en lugar de agregar uno nuevo (`--amend`).
+    pub amend: bool,
+    /// Si es true, antes de commitear agrega al index los archivos trackeados modificados o borrados (`-a`).
+    pub todos: bool,
+    /// Si es true, permite crear un commit sin cambios (`--allow-empty`).
+    pub permitir_vacio: bool,
+    /// Nombre y mail del autor, si se paso `--author`. Si no, se toman del config.
+    pub autor: Option<(String, String)>,
+    /// Fecha del autor en formato `<timestamp> <offset>`, si se paso `--date`.
+    pub fecha: Option<String>,
+    /// Si es false no se ejecutan el hook previo ni `commit-msg` (`--no-verify`).
+    pub verificar: bool,
+    /// Hook que se ejecuta antes de armar el commit. Es `pre-commit`, salvo en el commit
+    /// de un merge automatico, que es `pre-merge-commit`.
+    pub hook_previo: &'static str,
 }
 
 /// Arma el timestamp del commit en formato unix.
@@ -45,10 +74,132 @@ fn armar_timestamp_commit() -> Result<String, String> {
     Ok(format!("{} {}", timestamp, offset_format))
 }
 
+/// Convierte la fecha recibida por `--date` al formato `<timestamp> <offset>` del commit.
+/// Acepta el formato interno (`1614550000 -0300`), `@<timestamp>`, RFC 3339
+/// (`2021-02-28T19:06:40-03:00`) y `AAAA-MM-DD HH:MM:SS`, que se toma en la zona horaria local.
+fn parsear_fecha(fecha: &str) -> Result<String, String> {
+    let fecha = fecha.trim();
+    if let Some(timestamp) = fecha.strip_prefix('@') {
+        if timestamp.parse::<i64>().is_ok() {
+            return Ok(format!("{} +0000", timestamp));
+        }
+    }
+    if let Some((timestamp, offset)) = fecha.split_once(' ') {
+        let offset_valido = offset.len() == 5
+            && offset.starts_with(['+', '-'])
+            && offset[1..].chars().all(|c| c.is_ascii_digit());
+        if timestamp.parse::<i64>().is_ok() && offset_valido {
+            return Ok(fecha.to_string());
+        }
+    }
+    if let Ok(fecha_rfc) = chrono::DateTime::parse_from_rfc3339(fecha) {
+        return Ok(format!(
+            "{} {}",
+            fecha_rfc.timestamp(),
+            fecha_rfc.format("%z")
+        ));
+    }
+
+    for formato in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(fecha_local) = chrono::NaiveDateTime::parse_from_str(fecha, formato) {
+            // si la hora se repite por un cambio de horario se toma la primera
+            if let Some(fecha) = chrono::Local.from_local_datetime(&fecha_local).earliest() {
+                return Ok(format!("{} {}", fecha.timestamp(), fecha.format("%z")));
+            }
+        }
+    }
+    Err(format!("Fecha invalida: {}", fecha))
+}
+
+/// Separa un autor con el formato `Nombre <mail>` en su nombre y su mail.
+fn parsear_autor(autor: &str) -> Result<(String, String), String> {
+    let error = || format!("Autor invalido, se espera 'Nombre <mail>': {}", autor);
+    let (nombre, resto) = autor.split_once('<').ok_or_else(error)?;
+    let mail = resto.strip_suffix('>').ok_or_else(error)?;
+    let nombre = nombre.trim();
+    if nombre.is_empty() || mail.trim().is_empty() {
+        return Err(error());
+    }
+    Ok((nombre.to_string(), mail.trim().to_string()))
+}
+
+/// Abre el editor para que el usuario escriba el mensaje del commit en `.gir/COMMIT_EDITMSG`.
+/// El editor es el de `GIR_EDITOR`, `VISUAL` o `EDITOR`, en ese orden, y si no hay ninguno `vi`.
+/// Devuelve el mensaje sin las lineas de comentario. Si queda vacio, devuelve error.
+fn pedir_mensaje_al_editor(mensaje_inicial: &str) -> Result<String, String> {
+    let ruta_mensaje = Repositorio::actual().ruta_gir(ARCHIVO_MENSAJE);
+    io::escribir_bytes(
+        &ruta_mensaje,
+        format!("{}{}", mensaje_inicial, INSTRUCCIONES_EDITOR),
+    )?;
+
+    let editor = ["GIR_EDITOR", "VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|variable| std::env::var(variable).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    // se pasa por sh para que el editor pueda tener argumentos (Ej: "code --wait")
+    let estado = process::Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor))
+        .arg(&editor)
+        .arg(&ruta_mensaje)
+        .status()
+        .map_err(|e| format!("No se pudo abrir el editor {}: {}", editor, e))?;
+    if !estado.success() {
+        return Err(format!("El editor {} termino con error", editor));
+    }
+
+    let contenido = io::leer_a_string(ruta_mensaje)?;
+    let mensaje = contenido
+        .lines()
+        .filter(|linea| !linea.starts_with('#'))
+        .collect::<Vec<&str>>()
+        .join("\n")
+        .trim()
+        .to_string();
+    if mensaje.is_empty() {
+        return Err("Se aborta el commit por tener un mensaje vacio".to_string());
+    }
+    Ok(mensaje)
+}
+
+/// Devuelve el valor de la opcion `flag`, que es el argumento siguiente.
+fn obtener_valor(args: &mut std::vec::IntoIter<String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("No se especifico un valor luego del flag {}", flag))
+}
+
 impl Commit {
+    /// Crea un commit con el mensaje dado sobre la rama indicada, sin opciones.
+    pub fn new(mensaje: String, rama_actual: &str, logger: Arc<Logger>) -> Commit {
+        Commit {
+            logger,
+            mensaje,
+            rama_actual: rama_actual.to_string(),
+            amend: false,
+            todos: false,
+            permitir_vacio: false,
+            autor: None,
+            fecha: None,
+            verificar: true,
+            hook_previo: hooks::PRE_COMMIT,
+        }
+    }
+
     /// Crea un commit a partir de los argumentos pasados por linea de comandos.
     /// En caso de no tener argumentos y haber un merge en curso, crea un commit de merge.
-    /// Se espera que contenga el flag -m y un mensaje.
+    /// Acepta los flags:
+    /// - `-m <mensaje>`: mensaje del commit. Si se repite, cada uno es un parrafo.
+    /// - `-F <archivo>`: toma el mensaje del archivo.
+    /// - `-a`: agrega al index los archivos trackeados modificados o borrados.
+    /// - `--amend`: reemplaza al ultimo commit de la rama. Sin mensaje, conserva el anterior.
+    /// - `--allow-empty`: permite crear un commit sin cambios.
+    /// - `--author "Nombre <mail>"` y `--date <fecha>`: autor y fecha del commit.
+    /// - `-n` o `--no-verify`: no ejecuta los hooks `pre-commit` ni `commit-msg`.
+    ///
+    /// Si no se indica el mensaje, se abre un editor sobre `.gir/COMMIT_EDITMSG`.
     /// En caso de tener argumentos invalidos devuelve error.
     pub fn from(args: &mut Vec<String>, logger: Arc<Logger>) -> Result<Commit, String> {
         if args.is_empty() && Merge::hay_merge_en_curso()? {
@@ -59,103 +210,279 @@ impl Commit {
             return Commit::from_merge(logger, &rama_actual);
         }
 
-        if args.len() != 2 {
-            return Err("La cantidad de argumentos es invalida, -m esperado".to_string());
-        }
-        let mensaje = args
-            .pop()
-            .ok_or_else(|| "No se especifico un mensaje luego del flag -m".to_string())?;
-        let flag = args
-            .pop()
-            .ok_or_else(|| "No se especifico un flag".to_string())?;
-        if flag != "-m" {
-            return Err(format!("Flag desconocido {}", flag));
+        let rama_actual = ramas::obtener_rama_actual()?;
+        let mut commit = Commit::new(String::new(), &rama_actual, logger);
+        let mut mensajes: Vec<String> = Vec::new();
+
+        let mut args = std::mem::take(args).into_iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "-m" => mensajes.push(obtener_valor(&mut args, &flag)?),
+                "-F" => {
+                    let archivo = obtener_valor(&mut args, &flag)?;
+                    mensajes.push(io::leer_a_string(&archivo)?.trim_end().to_string());
+                }
+                "-a" | "--all" => commit.todos = true,
+                "--amend" => commit.amend = true,
+                "--allow-empty" => commit.permitir_vacio = true,
+                "-n" | "--no-verify" => commit.verificar = false,
+                "--author" => {
+                    commit.autor = Some(parsear_autor(&obtener_valor(&mut args, &flag)?)?)
+                }
+                "--date" => commit.fecha = Some(parsear_fecha(&obtener_valor(&mut args, &flag)?)?),
+                _ => return Err(format!("Flag desconocido {}", flag)),
+            }
         }
 
-        let rama_actual = ramas::obtener_rama_actual()?;
-        Ok(Commit {
-            mensaje,
-            logger,
-            rama_actual,
-        })
+        commit.mensaje = if !mensajes.is_empty() {
+            mensajes.join("\n\n")
+        } else if commit.amend {
+            commit.commit_a_enmendar()?.mensaje
+        } else {
+            pedir_mensaje_al_editor("")?
+        };
+        Ok(commit)
+    }
+
+    /// Crea un commit con el mensaje ingresado en la interfaz grafica. Nunca abre el editor:
+    /// sin mensaje solo se puede commitear un merge en curso, si no devuelve error.
+    pub fn from_gui(mensaje: &str, logger: Arc<Logger>) -> Result<Commit, String> {
+        let mensaje = mensaje.trim();
+        if !mensaje.is_empty() {
+            return Commit::from(&mut vec!["-m".to_string(), mensaje.to_string()], logger);
+        }
+        if !Merge::hay_merge_en_curso()? {
+            return Err("Se aborta el commit por tener un mensaje vacio".to_string());
+        }
+        Commit::from(&mut Vec::new(), logger)
     }
 
     /// Crea un commit a partir del mensaje en el archivo COMMIT_EDITMSG.
     pub fn from_merge(logger: Arc<Logger>, rama_actual: &str) -> Result<Commit, String> {
-        let mensaje = io::leer_a_string(path::Path::new(".gir/COMMIT_EDITMSG"))?;
-        Ok(Commit {
-            mensaje,
-            logger,
-            rama_actual: rama_actual.to_string(),
-        })
+        let mensaje = io::leer_a_string(Repositorio::actual().ruta_gir(ARCHIVO_MENSAJE))?;
+        Ok(Commit::new(mensaje, rama_actual, logger))
+    }
+
+    /// Ejecuta el hook `commit-msg` con la ruta de `.gir/COMMIT_EDITMSG`, donde se escribe el mensaje.
+    /// Si el hook modifico el archivo, el commit pasa a usar el mensaje nuevo.
+    fn ejecutar_hook_commit_msg(&mut self) -> Result<(), String> {
+        let ruta_mensaje = Repositorio::actual().ruta_gir(ARCHIVO_MENSAJE);
+        io::escribir_bytes(&ruta_mensaje, &self.mensaje)?;
+        hooks::ejecutar_hook(
+            hooks::COMMIT_MSG,
+            &[ruta_mensaje.to_string_lossy().to_string()],
+            None,
+            self.logger.clone(),
+        )?;
+
+        let mensaje = io::leer_a_string(ruta_mensaje)?;
+        if mensaje != self.mensaje {
+            self.mensaje = mensaje.trim_end().to_string();
+        }
+        if self.mensaje.trim().is_empty() {
+            return Err("Se aborta el commit por tener un mensaje vacio".to_string());
+        }
+        Ok(())
+    }
+
+    /// Devuelve el commit al que apunta la rama actual, que es el que reemplaza `--amend`.
+    fn commit_a_enmendar(&self) -> Result<CommitObj, String> {
+        let hash = Merge::obtener_commit_de_branch(&self.rama_actual)?;
+        if hash.is_empty() {
+            return Err("No hay ningun commit para enmendar".to_string());
+        }
+        CommitObj::from_hash(hash, self.logger.clone())
+    }
+
+    /// Agrega al index los archivos del ultimo commit que fueron modificados o borrados
+    /// en el directorio de trabajo, salvo los que ya estan en el index.
+    fn agregar_trackeados_al_index(&self) -> Result<(), String> {
+        let tree_head = match status::obtener_arbol_del_commit_head(self.logger.clone()) {
+            Some(tree) => tree,
+            None => return Ok(()),
+        };
+        let mut index = leer_index(self.logger.clone())?;
+        let mut modificados = Vec::new();
+
+        for objeto in tree_head.obtener_objetos_hoja() {
+            let blob = match objeto {
+                Objeto::Blob(blob) => blob,
+                Objeto::Tree(_) => continue,
+            };
+            let ubicacion = blob
+                .ubicacion
+                .strip_prefix("./")
+                .unwrap_or(&blob.ubicacion)
+                .to_path_buf();
+            let esta_en_index = index.iter().any(|objeto_index| {
+                let path_index = objeto_index.objeto.obtener_path();
+                path_index.strip_prefix("./").unwrap_or(&path_index) == ubicacion
+            });
+            if esta_en_index {
+                continue;
+            }
+
+            if !io::es_archivo(&ubicacion) {
+                index.push(ObjetoIndex {
+                    merge: false,
+                    es_eliminado: true,
+                    objeto: Objeto::Blob(Blob { ubicacion, ..blob }),
+                });
+            } else if Blob::from_directorio(ubicacion.clone(), self.logger.clone())?.hash
+                != blob.hash
+            {
+                modificados.push(ubicacion.to_string_lossy().to_string());
+            }
+        }
+
+        escribir_index(self.logger.clone(), &mut index)?;
+        if !modificados.is_empty() {
+            Add::from(modificados, self.logger.clone())?.ejecutar()?;
+        }
+        Ok(())
+    }
+
+    /// Devuelve el hash del arbol del commit.
+    /// Si el index esta vacio, solo se puede commitear con `--allow-empty` o `--amend`,
+    /// y se usa el mismo arbol que el commit base.
+    fn crear_arbol(&self, hash_base: &str) -> Result<String, String> {
+        let base = if hash_base.is_empty() {
+            None
+        } else {
+            Some(hash_base.to_string())
+        };
+        if !leer_index(self.logger.clone())?.is_empty() {
+            return write_tree::crear_arbol_commit(base, self.logger.clone());
+        }
+        if !self.permitir_vacio && !self.amend {
+            return Err("No hay archivos trackeados para commitear".to_string());
+        }
+        match base {
+            Some(hash) => write_tree::conseguir_arbol(&hash),
+            None => {
+                let arbol_vacio = Tree {
+                    directorio: path::PathBuf::from("./"),
+                    objetos: Vec::new(),
+                    logger: self.logger.clone(),
+                };
+                arbol_vacio.escribir_en_base()?;
+                arbol_vacio.obtener_hash()
+            }
+        }
+    }
+
+    /// Devuelve la linea de autor (`<nombre> <mail> <timestamp> <offset>`).
+    /// Con `--amend` se conservan el autor y la fecha del commit reemplazado, salvo que
+    /// se indiquen con `--author` o `--date`.
+    fn armar_linea_autor(&self, commit_enmendado: Option<&CommitObj>) -> Result<String, String> {
+        let (nombre, mail) = match (&self.autor, commit_enmendado) {
+            (Some(autor), _) => autor.clone(),
+            (None, Some(commit)) => (commit.autor.clone(), commit.mail.clone()),
+            (None, None) => conseguir_nombre_y_mail_del_config()?,
+        };
+        let fecha = match (&self.fecha, commit_enmendado) {
+            (Some(fecha), _) => fecha.clone(),
+            (None, Some(commit)) => format!("{} {}", commit.date.tiempo, commit.date.offset),
+            (None, None) => armar_timestamp_commit()?,
+        };
+        Ok(format!("{} {} {}", nombre, mail, fecha))
     }
 
     /// Formatea el contenido del commit.
     /// Devuelve el contenido del commit en formato git.
-    /// Toma el nombre y mail del archivo de configuracion.
+    /// El committer se toma del archivo de configuracion.
     fn formatear_contenido_commit(
         &self,
         hash_arbol: &str,
-        hash_padre_commit: &str,
+        padres: &[String],
+        linea_autor: &str,
     ) -> Result<String, String> {
         let mut contenido_commit = String::new();
         contenido_commit.push_str(&format!("tree {}\n", hash_arbol));
-        if !hash_padre_commit.is_empty() {
-            contenido_commit.push_str(&format!("parent {}\n", hash_padre_commit));
-        }
-        if Merge::hay_merge_en_curso()? {
-            let padre_mergeado = io::leer_a_string(path::Path::new(".gir/MERGE_HEAD"))?;
-            contenido_commit.push_str(&format!("parent {}\n", padre_mergeado));
+        for padre in padres {
+            contenido_commit.push_str(&format!("parent {}\n", padre));
         }
         let (nombre, mail) = conseguir_nombre_y_mail_del_config()?;
-        let linea_autor = format!("{} {}", nombre, mail);
+        let linea_committer = format!("{} {}", nombre, mail);
         let timestamp = armar_timestamp_commit()?;
         contenido_commit.push_str(&format!(
-            "author {} {}\ncommitter {} {}\n\n{}",
-            linea_autor, timestamp, linea_autor, timestamp, self.mensaje
+            "author {}\ncommitter {} {}\n\n{}",
+            linea_autor, linea_committer, timestamp, self.mensaje
         ));
         Ok(contenido_commit)
     }
 
     /// Crea el contenido del commit.
-    /// Devuelve el hash del arbol y el contenido total del commit.
-    fn crear_contenido_commit(&self) -> Result<(String, String), String> {
-        let hash_commit = Merge::obtener_commit_de_branch(&self.rama_actual)?;
-        let hash_arbol = match hash_commit.as_str() {
-            "" => write_tree::crear_arbol_commit(None, self.logger.clone())?,
-            _ => {
-                write_tree::crear_arbol_commit(Some(hash_commit.to_string()), self.logger.clone())?
+    /// Devuelve el hash del arbol, el hash al que apuntaba la rama y el contenido total del commit.
+    /// Con `--amend`, el commit nuevo tiene los padres del que reemplaza.
+    fn crear_contenido_commit(&self) -> Result<(String, String, String), String> {
+        let hash_rama = Merge::obtener_commit_de_branch(&self.rama_actual)?;
+        let hash_arbol = self.crear_arbol(&hash_rama)?;
+
+        let commit_enmendado = if self.amend {
+            Some(self.commit_a_enmendar()?)
+        } else {
+            None
+        };
+        let padres = match commit_enmendado {
+            Some(ref commit) => commit.padres.clone(),
+            None => {
+                let mut padres = Vec::new();
+                if !hash_rama.is_empty() {
+                    padres.push(hash_rama.clone());
+                }
+                if Merge::hay_merge_en_curso()? {
+                    padres.push(io::leer_a_string(
+                        Repositorio::actual().ruta_gir("MERGE_HEAD"),
+                    )?);
+                }
+                padres
             }
         };
-        let contenido_commit = self.formatear_contenido_commit(&hash_arbol, &hash_commit)?;
+
+        let sin_cambios =
+            padres.len() == 1 && write_tree::conseguir_arbol(&padres[0])? == hash_arbol;
+        if sin_cambios && !self.permitir_vacio && !self.amend {
+            return Err("No hay cambios para commitear".to_string());
+        }
+
+        let linea_autor = self.armar_linea_autor(commit_enmendado.as_ref())?;
+        let contenido_commit =
+            self.formatear_contenido_commit(&hash_arbol, &padres, &linea_autor)?;
         let header = format!("commit {}\0", contenido_commit.len());
         let contenido_total = format!("{}{}", header, contenido_commit);
-        Ok((hash_arbol, contenido_total))
+        Ok((hash_arbol, hash_rama, contenido_total))
     }
 
     /// Escribe el objeto commit en el repositorio.
     /// El objeto commit se escribe en .gir/objects/.
     fn escribir_objeto_commit(hash: &str, contenido_comprimido: Vec<u8>) -> Result<(), String> {
-        let ruta = format!(".gir/objects/{}/{}", &hash[..2], &hash[2..]);
+        let ruta = Repositorio::actual().ruta_gir(format!("objects/{}/{}", &hash[..2], &hash[2..]));
         io::escribir_bytes(ruta, contenido_comprimido)?;
         Ok(())
     }
 
     /// Actualiza el archivo head/ref de la branch actual con el hash del commit creado.
-    /// En caso de no poder abrir o escribir en el archivo devuelve un error.
-    fn updatear_ref_head(&self, hash: &str) -> Result<(), String> {
-        let ruta = format!(".gir/refs/heads/{}", self.rama_actual);
-        io::escribir_bytes(ruta, hash)?;
-        Ok(())
+    /// Falla si la rama dejo de apuntar a `hash_anterior` mientras se armaba el commit
+    /// o si otro proceso la esta modificando.
+    fn updatear_ref_head(&self, hash_anterior: &str, hash: &str) -> Result<(), String> {
+        TransaccionRefs::new(Repositorio::actual().dir_gir())
+            .actualizar_verificando(
+                &format!("refs/heads/{}", self.rama_actual),
+                hash_anterior,
+                hash,
+            )
+            .confirmar()
     }
 
     /// Ejecuta el comando commit.
-    /// Devuelve un mensaje indicando si se pudo crear el commit o no.
-    fn ejecutar_wrapper(&self, contenido_total: &str) -> Result<(), String> {
+    /// Primero escribe el objeto y recien despues mueve la rama, para que la rama
+    /// nunca apunte a un commit inexistente.
+    fn ejecutar_wrapper(&self, hash_anterior: &str, contenido_total: &str) -> Result<(), String> {
         let contenido_comprimido = comprimir_contenido(contenido_total)?;
         let hash = HashObject::hashear_contenido_objeto(&contenido_total.as_bytes().to_vec());
-        Self::updatear_ref_head(self, &hash)?;
         Self::escribir_objeto_commit(&hash, contenido_comprimido)?;
+        Self::updatear_ref_head(self, hash_anterior, &hash)?;
         self.logger.log(&format!(
             "commit {}\n Author: {}\n{} ",
             hash, "", self.mensaje
@@ -168,27 +495,46 @@ impl Commit {
 impl Ejecutar for Commit {
     /// Ejecuta el comando commit en su totalidad.
     /// Utiliza un ejecutar wrapper para que en caso de error limpiar los archivos creados.
+    /// Los hooks `pre-commit` (o `pre-merge-commit`) y `commit-msg` pueden abortar el commit,
+    /// `post-commit` se ejecuta al final y solo se loguea si falla.
     fn ejecutar(&mut self) -> Result<String, String> {
         armar_config_con_mail_y_nombre()?;
-        let (hash_arbol, contenido_total) = self.crear_contenido_commit()?;
-        match self.ejecutar_wrapper(&contenido_total) {
+        if self.todos {
+            self.agregar_trackeados_al_index()?;
+        }
+        if self.verificar {
+            hooks::ejecutar_hook(self.hook_previo, &[], None, self.logger.clone())?;
+            self.ejecutar_hook_commit_msg()?;
+        }
+        let (hash_arbol, hash_anterior, contenido_total) = self.crear_contenido_commit()?;
+        match self.ejecutar_wrapper(&hash_anterior, &contenido_total) {
             Ok(_) => (),
             Err(_) => {
-                io::rm_directorio(format!(
-                    ".gir/objects/{}/{}",
-                    &hash_arbol[..2],
-                    &hash_arbol[2..]
-                ))?;
+                // el arbol puede ser el mismo que el de la rama (Ej: --allow-empty), en ese caso no se borra
+                let arbol_anterior =
+                    write_tree::conseguir_arbol(&hash_anterior).unwrap_or_default();
+                if hash_arbol != arbol_anterior {
+                    io::rm_directorio(Repositorio::actual().ruta_gir(format!(
+                        "objects/{}/{}",
+                        &hash_arbol[..2],
+                        &hash_arbol[2..]
+                    )))?;
+                }
                 return Err("No se pudo ejecutar el commit".to_string());
             }
         };
         Merge::limpiar_merge_post_commit()?;
+        if let Err(error) = hooks::ejecutar_hook(hooks::POST_COMMIT, &[], None, self.logger.clone())
+        {
+            self.logger.log(&error);
+        }
         Ok("Commit creado".to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeZone;
     use serial_test::serial;
     use std::{path::PathBuf, sync::Arc};
 
@@ -197,11 +543,12 @@ mod tests {
             comando::Ejecutar,
             comandos::{add::Add, hash_object::HashObject},
             logger::Logger,
+            objetos::commit::CommitObj,
         },
         utils::{
             compresion::{descomprimir_objeto, descomprimir_objeto_gir},
             io,
-            testing::{addear_archivos_y_comittear, limpiar_archivo_gir},
+            testing::{addear_archivos_y_comittear, crear_hook, limpiar_archivo_gir},
         },
     };
 
@@ -330,4 +677,176 @@ mod tests {
         io::escribir_bytes("test_dir/muchos_objetos/archivo.txt", "mas contenido").unwrap();
         assert_eq!(hash_arbol_git, hash_arbol);
     }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    #[serial]
+    fn test06_amend_reemplaza_el_ultimo_commit_conservando_su_padre() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/commit_test06")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        addear_archivos_y_comittear(vec!["test_file.txt".to_string()], logger.clone());
+        let hash_primero = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        addear_archivos_y_comittear(vec!["test_file2.txt".to_string()], logger.clone());
+        let hash_segundo = io::leer_a_string(".gir/refs/heads/master").unwrap();
+
+        Commit::from(&mut args(&["--amend", "-m", "corregido"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+
+        let hash_enmendado = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        let enmendado = CommitObj::from_hash(hash_enmendado.clone(), logger.clone()).unwrap();
+        assert_ne!(hash_enmendado, hash_segundo);
+        assert_eq!(enmendado.padres, vec![hash_primero]);
+        assert_eq!(enmendado.mensaje, "corregido");
+        assert_eq!(
+            enmendado.hash_tree,
+            conseguir_arbol_commit("master").unwrap()
+        );
+
+        Commit::from(&mut args(&["--amend"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        let hash_reenmendado = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        let reenmendado = CommitObj::from_hash(hash_reenmendado, logger).unwrap();
+        assert_eq!(reenmendado.mensaje, "corregido");
+        assert_eq!(reenmendado.hash_tree, enmendado.hash_tree);
+    }
+
+    #[test]
+    #[serial]
+    fn test07_con_a_se_commitean_los_trackeados_modificados_y_sin_cambios_hace_falta_allow_empty() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/commit_test07")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        addear_archivos_y_comittear(vec!["test_file.txt".to_string()], logger.clone());
+        let arbol_original = conseguir_arbol_commit("master").unwrap();
+
+        io::escribir_bytes("test_file.txt", "modificado con -a").unwrap();
+        Commit::from(&mut args(&["-a", "-m", "todos"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        let arbol_modificado = conseguir_arbol_commit("master").unwrap();
+        io::escribir_bytes("test_file.txt", "test file modified").unwrap();
+
+        assert_ne!(arbol_modificado, arbol_original);
+        assert!(Commit::from(&mut args(&["-m", "vacio"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .is_err());
+
+        Commit::from(&mut args(&["--allow-empty", "-m", "vacio"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        assert_eq!(conseguir_arbol_commit("master").unwrap(), arbol_modificado);
+    }
+
+    #[test]
+    #[serial]
+    fn test08_autor_fecha_y_mensaje_desde_el_editor() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/commit_test08")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let mut add = Add::from(vec!["test_file.txt".to_string()], logger.clone()).unwrap();
+        add.ejecutar().unwrap();
+
+        std::env::set_var(
+            "GIR_EDITOR",
+            "printf 'titulo\\n\\ncuerpo\\n# comentario\\n' >",
+        );
+        let commit = Commit::from(
+            &mut args(&[
+                "--author",
+                "Ada Lovelace <ada@mail.com>",
+                "--date",
+                "2021-02-28 19:06:40",
+            ]),
+            logger.clone(),
+        );
+        std::env::remove_var("GIR_EDITOR");
+        commit.unwrap().ejecutar().unwrap();
+
+        let hash = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        let commit = CommitObj::from_hash(hash, logger).unwrap();
+        assert_eq!(commit.autor, "Ada Lovelace");
+        assert_eq!(commit.mail, "ada@mail.com");
+        let fecha_local =
+            chrono::NaiveDateTime::parse_from_str("2021-02-28 19:06:40", "%Y-%m-%d %H:%M:%S")
+                .unwrap();
+        let fecha = chrono::Local.from_local_datetime(&fecha_local).unwrap();
+        assert_eq!(commit.date.tiempo, fecha.timestamp().to_string());
+        assert_eq!(commit.date.offset, fecha.format("%z").to_string());
+        assert_eq!(commit.mensaje, "titulo\n\ncuerpo");
+    }
+
+    #[test]
+    #[serial]
+    fn test09_desde_la_gui_un_mensaje_vacio_no_abre_el_editor() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/commit_test09")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let mut add = Add::from(vec!["test_file.txt".to_string()], logger.clone()).unwrap();
+        add.ejecutar().unwrap();
+
+        std::env::set_var("GIR_EDITOR", "printf 'desde el editor' >");
+        let commit = Commit::from_gui("  ", logger.clone());
+        std::env::remove_var("GIR_EDITOR");
+
+        assert!(commit.is_err());
+        Commit::from_gui("desde la gui", logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        let hash = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        let commit = CommitObj::from_hash(hash, logger).unwrap();
+        assert_eq!(commit.mensaje, "desde la gui");
+    }
+
+    #[test]
+    #[serial]
+    fn test10_los_hooks_pueden_abortar_el_commit_y_cambiar_el_mensaje() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/commit_test10")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let mut add = Add::from(vec!["test_file.txt".to_string()], logger.clone()).unwrap();
+        add.ejecutar().unwrap();
+
+        crear_hook("pre-commit", "#!/bin/sh\necho formato invalido\nexit 1\n");
+        let error = Commit::from(&mut args(&["-m", "mensaje"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap_err();
+        assert!(error.contains("formato invalido"));
+        assert!(io::leer_a_string(".gir/refs/heads/master")
+            .unwrap()
+            .is_empty());
+
+        Commit::from(&mut args(&["-n", "-m", "sin verificar"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+
+        io::escribir_bytes("test_file.txt", "cambio para el hook").unwrap();
+        Add::from(vec!["test_file.txt".to_string()], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        io::rm_directorio(".gir/hooks/pre-commit").unwrap();
+        crear_hook(
+            "commit-msg",
+            "#!/bin/sh\necho \"[gir] $(cat \"$1\")\" > \"$1\"\n",
+        );
+        Commit::from(&mut args(&["-m", "mensaje"]), logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        io::escribir_bytes("test_file.txt", "test file modified").unwrap();
+        io::rm_directorio(".gir/hooks/commit-msg").unwrap();
+
+        let hash = io::leer_a_string(".gir/refs/heads/master").unwrap();
+        let commit = CommitObj::from_hash(hash, logger).unwrap();
+        assert_eq!(commit.mensaje, "[gir] mensaje");
+    }
 }
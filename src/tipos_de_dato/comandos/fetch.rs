@@ -4,6 +4,9 @@ use crate::tipos_de_dato::config::Config;
 use crate::tipos_de_dato::logger::Logger;
 use crate::tipos_de_dato::packfile::Packfile;
 use crate::tipos_de_dato::referencia_commit::ReferenciaCommit;
+use crate::tipos_de_dato::repositorio::Repositorio;
+use crate::tipos_de_dato::transaccion_refs::TransaccionRefs;
+use crate::utils::ubicacion_repositorio::DIR_OBJETOS;
 use crate::utils::{self, io, objects};
 use std::io::{Read, Write};
 use std::net::TcpStream;
@@ -85,10 +88,11 @@ impl Fetch {
         &self,
         commits_y_tags_asosiados: &Vec<(String, PathBuf)>,
     ) -> Result<(), String> {
+        let mut transaccion = TransaccionRefs::new(Repositorio::actual().dir_gir());
         for (commit, ref_tag) in commits_y_tags_asosiados {
-            let dir_tag = PathBuf::from("./.gir/").join(ref_tag);
-            utils::io::escribir_bytes(dir_tag, commit)?
+            transaccion = transaccion.actualizar(&ref_tag.to_string_lossy(), commit);
         }
+        transaccion.confirmar()?;
 
         self.logger.log("Escritura de tags en fetch exitosa");
         Ok(())
@@ -138,7 +142,7 @@ impl Fetch {
             ));
         }
         self.logger.log("Recepcion del pack file en fetch exitoso");
-        Packfile::leer_packfile_y_escribir(&packfile, "./.gir/objects/".to_string()).unwrap();
+        Packfile::leer_packfile_y_escribir(&packfile, Repositorio::actual().dir_objetos()).unwrap();
         Ok(())
     }
 
@@ -156,14 +160,9 @@ impl Fetch {
         commit_head_remoto: &Option<String>,
     ) -> Result<(), String> {
         if let Some(hash) = commit_head_remoto {
-            let ubicacion_archivo_head_remoto =
-                format!("./.gir/{}_HEAD", self.remoto.to_uppercase());
-
-            println!(
-                "ubicacion_archivo_head_remoto: {}",
-                ubicacion_archivo_head_remoto
-            );
-            io::escribir_bytes(ubicacion_archivo_head_remoto, hash)?;
+            TransaccionRefs::new(Repositorio::actual().dir_gir())
+                .actualizar(&format!("{}_HEAD", self.remoto.to_uppercase()), hash)
+                .confirmar()?;
         }
 
         Ok(())
@@ -174,7 +173,8 @@ impl Fetch {
         &self,
         comunicacion: &mut Comunicacion<TcpStream>,
     ) -> Result<(), String> {
-        let objetos = objects::obtener_objetos_del_dir(&PathBuf::from("./.gir/objects"))?;
+        let objetos =
+            objects::obtener_objetos_del_dir(&Repositorio::actual().ruta_gir(DIR_OBJETOS))?;
 
         if !objetos.is_empty() {
             comunicacion.enviar_lo_que_tengo_al_servidor_pkt(&objetos)?;
@@ -256,7 +256,7 @@ impl Fetch {
                     dir_rama_asosiada,
                 )?;
 
-            if !dir_rama_asosiada_local.exists() {
+            if !io::existe(&dir_rama_asosiada_local) {
                 commits_de_cabeza_de_rama_faltantes.push(commit_cabeza_remoto.to_string());
                 continue;
             }
@@ -290,9 +290,9 @@ impl Fetch {
         let mut commits_de_tags_faltantes: Vec<String> = Vec::new();
 
         for (commit_cabeza_remoto, tag_asosiado) in commit_y_tags_asosiado {
-            let dir_tag = PathBuf::from("./.gir").join(tag_asosiado);
+            let dir_tag = Repositorio::actual().ruta_gir(tag_asosiado);
 
-            if !dir_tag.exists() {
+            if !io::existe(&dir_tag) {
                 commits_de_tags_faltantes.push(commit_cabeza_remoto.to_string());
                 continue;
             }
@@ -366,15 +366,24 @@ impl Fetch {
         &self,
         commits_cabezas_y_dir_rama_asosiado: &Vec<(String, PathBuf)>,
     ) -> Result<(), String> {
+        let repositorio = Repositorio::actual();
+        let mut transaccion = TransaccionRefs::new(repositorio.dir_gir());
         for (commit_cabeza_de_rama, dir_rama_remota) in commits_cabezas_y_dir_rama_asosiado {
             let dir_rama_local_del_remoto =
                 utils::ramas::convertir_de_dir_rama_remota_a_dir_rama_local(
                     &self.remoto,
                     dir_rama_remota,
                 )?;
+            let ref_rama_local_del_remoto = dir_rama_local_del_remoto
+                .strip_prefix(repositorio.dir_gir())
+                .map_err(|e| e.to_string())?;
 
-            io::escribir_bytes(dir_rama_local_del_remoto, commit_cabeza_de_rama)?;
+            transaccion = transaccion.actualizar(
+                &ref_rama_local_del_remoto.to_string_lossy(),
+                commit_cabeza_de_rama,
+            );
         }
+        transaccion.confirmar()?;
 
         self.logger
             .log("Actualizacion de ramas remotas en fetch exitosa");
@@ -0,0 +1,437 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use flate2::Crc;
+
+use crate::{
+    tipos_de_dato::{
+        comando::Ejecutar, comandos::hash_object::HashObject, indice_pack::IndicePack,
+        logger::Logger, packfile::Packfile, repositorio::Repositorio,
+    },
+    utils::{alcanzabilidad, compresion, io, objects, packs},
+};
+
+/// Datos de un objeto que paso la verificacion, necesarios para revisar la conectividad
+struct ObjetoVerificado {
+    tipo: String,
+    referencias: Vec<String>,
+}
+
+pub struct Fsck {
+    /// Logger para registrar los eventos ocurridos durante la ejecucion del comando.
+    logger: Arc<Logger>,
+    /// Si es true se listan todos los objetos inalcanzables y no solo los colgantes
+    mostrar_inalcanzables: bool,
+}
+
+impl Fsck {
+    /// Crea un comando fsck a partir de los argumentos pasados por linea de comandos.
+    /// Opciones: `--unreachable` para listar todos los objetos inalcanzables.
+    pub fn from(args: Vec<String>, logger: Arc<Logger>) -> Result<Fsck, String> {
+        let mut mostrar_inalcanzables = false;
+        for arg in args {
+            match arg.as_str() {
+                "--unreachable" => mostrar_inalcanzables = true,
+                _ => {
+                    return Err(format!(
+                        "Opcion no conocida '{}'\ngir fsck [--unreachable]",
+                        arg
+                    ))
+                }
+            }
+        }
+        Ok(Fsck {
+            logger,
+            mostrar_inalcanzables,
+        })
+    }
+
+    /// Verifica la integridad del repositorio en `dir_gir`:
+    /// - que cada objeto, suelto o empaquetado, se pueda descomprimir y su SHA-1 coincida con su nombre
+    /// - que los packfiles y sus `.idx` tengan los checksums y CRCs correctos
+    /// - que los trees, commits y tags apunten a objetos existentes
+    /// - que las refs apunten a commits (o a tags, en el caso de `refs/tags`)
+    ///
+    /// Devuelve los errores encontrados y, aparte, los objetos colgantes (inalcanzables que ningun otro
+    /// objeto inalcanzable referencia) o todos los inalcanzables si `mostrar_inalcanzables` es true.
+    pub fn verificar(
+        dir_gir: &Path,
+        mostrar_inalcanzables: bool,
+    ) -> Result<(Vec<String>, Vec<String>), String> {
+        let dir_objetos = alcanzabilidad::obtener_dir_objetos(dir_gir);
+        let mut errores = Vec::new();
+
+        let objetos = Self::verificar_objetos(&dir_objetos, &mut errores)?;
+        Self::verificar_conectividad(&objetos, &mut errores);
+        Self::verificar_refs(dir_gir, &objetos, &mut errores)?;
+
+        let alcanzables = Self::obtener_alcanzables(dir_gir, &objetos)?;
+        let inalcanzables =
+            Self::listar_inalcanzables(&objetos, &alcanzables, mostrar_inalcanzables);
+        Ok((errores, inalcanzables))
+    }
+
+    // Verifica los objetos sueltos y los de cada packfile. Devuelve los objetos validos
+    fn verificar_objetos(
+        dir_objetos: &str,
+        errores: &mut Vec<String>,
+    ) -> Result<HashMap<String, ObjetoVerificado>, String> {
+        let mut objetos = HashMap::new();
+
+        for hash in objects::obtener_objetos_sueltos(&PathBuf::from(dir_objetos))? {
+            let ruta = format!("{}{}/{}", dir_objetos, &hash[..2], &hash[2..]);
+            let verificado = io::leer_bytes(ruta)
+                .and_then(|bytes| compresion::descomprimir_contenido_u8(&bytes))
+                .and_then(|objeto| Self::verificar_objeto(&hash, &objeto));
+            match verificado {
+                Ok(objeto) => {
+                    objetos.insert(hash, objeto);
+                }
+                Err(e) => errores.push(format!("error: objeto suelto {} corrupto: {}", hash, e)),
+            }
+        }
+
+        for pack in packs::obtener_packs(dir_objetos)? {
+            Self::verificar_pack(&pack, &mut objetos, errores)?;
+        }
+        Ok(objetos)
+    }
+
+    // Verifica el checksum del packfile, el de su indice y cada uno de los objetos que contiene
+    fn verificar_pack(
+        pack: &Path,
+        objetos: &mut HashMap<String, ObjetoVerificado>,
+        errores: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let nombre = pack.display();
+        let bytes = io::leer_bytes(pack.with_extension("pack"))?;
+        if bytes.len() < 32 || !Packfile::verificar_checksum(&bytes) {
+            errores.push(format!(
+                "error: el checksum del pack {} no coincide",
+                nombre
+            ));
+            return Ok(());
+        }
+        let indice = match IndicePack::leer(&pack.with_extension("idx").to_string_lossy()) {
+            Ok(indice) => indice,
+            Err(e) => {
+                errores.push(format!("error: {}", e));
+                return Ok(());
+            }
+        };
+        if indice.checksum_pack() != &bytes[bytes.len() - 20..] {
+            errores.push(format!("error: el indice de {} es de otro pack", nombre));
+            return Ok(());
+        }
+
+        // el largo de cada entrada va hasta el comienzo de la siguiente, o hasta el checksum del pack
+        let mut offsets: Vec<u64> = indice
+            .entradas()
+            .iter()
+            .map(|entrada| entrada.offset)
+            .collect();
+        offsets.sort();
+        offsets.push((bytes.len() - 20) as u64);
+
+        for entrada in indice.entradas() {
+            let posicion = offsets.partition_point(|offset| *offset <= entrada.offset);
+            let (inicio, fin) = (entrada.offset as usize, offsets[posicion] as usize);
+            if inicio >= fin {
+                errores.push(format!(
+                    "error: offset invalido para {} en {}",
+                    entrada.hash, nombre
+                ));
+                continue;
+            }
+            let mut crc = Crc::new();
+            crc.update(&bytes[inicio..fin]);
+            if crc.sum() != entrada.crc {
+                errores.push(format!(
+                    "error: el CRC de {} en {} no coincide",
+                    entrada.hash, nombre
+                ));
+                continue;
+            }
+            match Packfile::leer_objeto_en_offset(&bytes, inicio)
+                .and_then(|objeto| Self::verificar_objeto(&entrada.hash, &objeto))
+            {
+                Ok(objeto) => {
+                    objetos.insert(entrada.hash.clone(), objeto);
+                }
+                Err(e) => errores.push(format!(
+                    "error: objeto {} corrupto en {}: {}",
+                    entrada.hash, nombre, e
+                )),
+            }
+        }
+        Ok(())
+    }
+
+    // Verifica que el hash del objeto coincida con su nombre y que el header sea valido
+    fn verificar_objeto(hash: &str, objeto: &Vec<u8>) -> Result<ObjetoVerificado, String> {
+        let hash_calculado = HashObject::hashear_contenido_objeto(objeto);
+        if hash_calculado != hash {
+            return Err(format!("su hash es {}", hash_calculado));
+        }
+
+        let posicion_null = objeto
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("no tiene header")?;
+        let header = String::from_utf8_lossy(&objeto[..posicion_null]);
+        let (tipo, largo) = header
+            .split_once(' ')
+            .ok_or(format!("header invalido '{}'", header))?;
+        if largo.parse::<usize>().ok() != Some(objeto.len() - posicion_null - 1) {
+            return Err(format!("el largo del header '{}' no coincide", header));
+        }
+
+        let referencias = alcanzabilidad::obtener_objetos_referenciados(objeto)?;
+        Ok(ObjetoVerificado {
+            tipo: tipo.to_string(),
+            referencias,
+        })
+    }
+
+    // Verifica que todo objeto referenciado por un tree, commit o tag exista
+    fn verificar_conectividad(
+        objetos: &HashMap<String, ObjetoVerificado>,
+        errores: &mut Vec<String>,
+    ) {
+        let mut faltantes = Vec::new();
+        for (hash, objeto) in objetos {
+            for referencia in &objeto.referencias {
+                if !objetos.contains_key(referencia) {
+                    faltantes.push(format!(
+                        "error: falta el objeto {}, referenciado por {} {}",
+                        referencia, objeto.tipo, hash
+                    ));
+                }
+            }
+        }
+        faltantes.sort();
+        errores.extend(faltantes);
+    }
+
+    // Verifica que las refs y HEAD apunten a commits existentes. Las ramas sin commits se ignoran
+    fn verificar_refs(
+        dir_gir: &Path,
+        objetos: &HashMap<String, ObjetoVerificado>,
+        errores: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let mut refs = alcanzabilidad::obtener_refs(dir_gir)?;
+        let head = io::leer_a_string(dir_gir.join("HEAD")).unwrap_or_default();
+        if !head.starts_with("ref: ") {
+            refs.push(("HEAD".to_string(), head.trim().to_string()));
+        }
+
+        for (referencia, valor) in refs {
+            if valor.is_empty() {
+                continue;
+            }
+            if !alcanzabilidad::es_hash(&valor) {
+                errores.push(format!(
+                    "error: la referencia {} tiene un valor invalido '{}'",
+                    referencia, valor
+                ));
+                continue;
+            }
+            let tipos_validos: &[&str] = if referencia.starts_with("refs/tags/") {
+                &["commit", "tag"]
+            } else {
+                &["commit"]
+            };
+            match objetos.get(&valor) {
+                None => errores.push(format!(
+                    "error: la referencia {} apunta a {}, que no existe",
+                    referencia, valor
+                )),
+                Some(objeto) if !tipos_validos.contains(&objeto.tipo.as_str()) => {
+                    errores.push(format!(
+                        "error: la referencia {} apunta a un {} y no a un commit",
+                        referencia, objeto.tipo
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    // Recorre el grafo desde las raices usando los objetos validos, salteando los que faltan
+    fn obtener_alcanzables(
+        dir_gir: &Path,
+        objetos: &HashMap<String, ObjetoVerificado>,
+    ) -> Result<HashSet<String>, String> {
+        let mut alcanzables = HashSet::new();
+        let mut pendientes = alcanzabilidad::obtener_raices(dir_gir)?;
+        while let Some(hash) = pendientes.pop() {
+            if let Some(objeto) = objetos.get(&hash) {
+                if alcanzables.insert(hash) {
+                    pendientes.extend(objeto.referencias.iter().cloned());
+                }
+            }
+        }
+        Ok(alcanzables)
+    }
+
+    // Lista los objetos colgantes o, si se pide, todos los inalcanzables
+    fn listar_inalcanzables(
+        objetos: &HashMap<String, ObjetoVerificado>,
+        alcanzables: &HashSet<String>,
+        mostrar_inalcanzables: bool,
+    ) -> Vec<String> {
+        let inalcanzables: Vec<&String> = objetos
+            .keys()
+            .filter(|hash| !alcanzables.contains(*hash))
+            .collect();
+        let referenciados_por_inalcanzables: HashSet<&String> = inalcanzables
+            .iter()
+            .flat_map(|hash| objetos[*hash].referencias.iter())
+            .collect();
+
+        let mut lineas: Vec<String> = inalcanzables
+            .into_iter()
+            .filter_map(|hash| {
+                let tipo = &objetos[hash].tipo;
+                if mostrar_inalcanzables {
+                    Some(format!("unreachable {} {}", tipo, hash))
+                } else if !referenciados_por_inalcanzables.contains(hash) {
+                    Some(format!("dangling {} {}", tipo, hash))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        lineas.sort();
+        lineas
+    }
+}
+
+impl Ejecutar for Fsck {
+    /// Ejecuta el comando fsck sobre el repositorio actual.
+    /// Si se encontraron errores se devuelven como error, junto al resto del reporte.
+    fn ejecutar(&mut self) -> Result<String, String> {
+        let (errores, inalcanzables) =
+            Self::verificar(Repositorio::actual().dir_gir(), self.mostrar_inalcanzables)?;
+
+        let reporte = errores
+            .iter()
+            .chain(inalcanzables.iter())
+            .cloned()
+            .collect::<Vec<String>>()
+            .join("\n");
+
+        if !errores.is_empty() {
+            self.logger
+                .log(&format!("Fsck encontro {} errores", errores.len()));
+            return Err(reporte);
+        }
+        self.logger.log("Fsck ejecutado con exito");
+        Ok(reporte)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use serial_test::serial;
+
+    use crate::{
+        tipos_de_dato::{
+            comando::Ejecutar,
+            comandos::{fsck::Fsck, hash_object::HashObject, repack::Repack},
+            logger::Logger,
+        },
+        utils::{
+            compresion, io, packs, ramas,
+            testing::{addear_archivos_y_comittear, limpiar_archivo_gir},
+        },
+    };
+
+    fn crear_repo_con_commit(logger: Arc<Logger>) -> String {
+        limpiar_archivo_gir(logger.clone());
+        io::escribir_bytes("tmp/fsck_archivo", "contenido").unwrap();
+        addear_archivos_y_comittear(vec!["tmp/fsck_archivo".to_string()], logger);
+        HashObject::hashear_contenido_objeto(&b"blob 9\0contenido".to_vec())
+    }
+
+    fn ruta_objeto(hash: &str) -> String {
+        format!(".gir/objects/{}/{}", &hash[..2], &hash[2..])
+    }
+
+    #[test]
+    #[serial]
+    fn test01_un_repositorio_sano_no_tiene_errores() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/fsck_test01")).unwrap());
+        crear_repo_con_commit(logger.clone());
+
+        let reporte = Fsck::from(vec![], logger).unwrap().ejecutar().unwrap();
+
+        assert!(reporte.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_se_detecta_un_objeto_cuyo_hash_no_coincide_y_el_faltante() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/fsck_test02")).unwrap());
+        let blob = crear_repo_con_commit(logger.clone());
+        let otro_contenido = compresion::comprimir_contenido_u8(b"blob 4\0otro").unwrap();
+        io::escribir_bytes(ruta_objeto(&blob), otro_contenido).unwrap();
+
+        let reporte = Fsck::from(vec![], logger).unwrap().ejecutar().unwrap_err();
+
+        assert!(reporte.contains(&format!("error: objeto suelto {} corrupto", blob)));
+        assert!(reporte.contains(&format!("error: falta el objeto {}", blob)));
+    }
+
+    #[test]
+    #[serial]
+    fn test03_se_reportan_los_objetos_colgantes() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/fsck_test03")).unwrap());
+        crear_repo_con_commit(logger.clone());
+        io::escribir_bytes("tmp/fsck_colgante", "nadie me referencia").unwrap();
+        let colgante = HashObject::from(
+            &mut vec!["-w".to_string(), "tmp/fsck_colgante".to_string()],
+            logger.clone(),
+        )
+        .unwrap()
+        .ejecutar()
+        .unwrap();
+
+        let reporte = Fsck::from(vec![], logger).unwrap().ejecutar().unwrap();
+
+        assert_eq!(reporte, format!("dangling blob {}", colgante));
+    }
+
+    #[test]
+    #[serial]
+    fn test04_se_verifican_los_objetos_empaquetados() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/fsck_test04")).unwrap());
+        crear_repo_con_commit(logger.clone());
+        Repack::from(vec![], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        assert!(Fsck::from(vec![], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .is_ok());
+
+        let pack = packs::obtener_packs(".gir/objects/").unwrap()[0].with_extension("pack");
+        let mut bytes = io::leer_bytes(&pack).unwrap();
+        bytes[20] ^= 0xff;
+        io::escribir_bytes(&pack, bytes).unwrap();
+
+        let reporte = Fsck::from(vec![], logger).unwrap().ejecutar().unwrap_err();
+        assert!(reporte.contains("error: el checksum del pack"));
+        assert!(reporte.contains(&format!(
+            "error: la referencia refs/heads/master apunta a {}",
+            ramas::obtener_hash_commit_asociado_rama_actual().unwrap()
+        )));
+    }
+}
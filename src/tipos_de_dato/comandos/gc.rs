@@ -0,0 +1,109 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
+use crate::tipos_de_dato::{
+    comando::Ejecutar,
+    comandos::{prune::Prune, repack::Repack},
+    logger::Logger,
+    repositorio::Repositorio,
+};
+
+pub struct Gc {
+    /// Logger para registrar los eventos ocurridos durante la ejecucion del comando.
+    logger: Arc<Logger>,
+    /// Antiguedad minima de los objetos inalcanzables a borrar
+    expiracion: Duration,
+}
+
+impl Gc {
+    /// Crea un comando gc a partir de los argumentos pasados por linea de comandos.
+    /// Opciones: `--prune=<dias>|now` (por defecto los objetos inalcanzables se borran a los 14 dias).
+    pub fn from(args: Vec<String>, logger: Arc<Logger>) -> Result<Gc, String> {
+        let mut expiracion = Prune::expiracion_default();
+
+        for arg in args {
+            match arg.strip_prefix("--prune=") {
+                Some(valor) => expiracion = Prune::parsear_expiracion(valor)?,
+                None => {
+                    return Err(format!(
+                        "Opcion no conocida '{}'\ngir gc [--prune=<dias>|now]",
+                        arg
+                    ))
+                }
+            }
+        }
+
+        Ok(Gc { logger, expiracion })
+    }
+
+    /// Corre el mantenimiento completo sobre `dir_gir`: empaqueta los objetos alcanzables
+    /// y luego borra los objetos sueltos inalcanzables mas viejos que `expiracion`.
+    /// Devuelve un resumen de lo realizado.
+    pub fn recolectar(
+        dir_gir: &Path,
+        expiracion: Duration,
+        logger: Arc<Logger>,
+    ) -> Result<String, String> {
+        let empaquetados = Repack::repackear(dir_gir, expiracion, logger.clone())?;
+        let podados = Prune::podar(dir_gir, expiracion, false)?;
+
+        let resumen = format!(
+            "{} objetos empaquetados, {} objetos inalcanzables borrados",
+            empaquetados,
+            podados.len()
+        );
+        logger.log(&format!("Gc en {}: {}", dir_gir.display(), resumen));
+        Ok(resumen)
+    }
+}
+
+impl Ejecutar for Gc {
+    /// Ejecuta el comando gc sobre el repositorio actual.
+    fn ejecutar(&mut self) -> Result<String, String> {
+        Self::recolectar(
+            Repositorio::actual().dir_gir(),
+            self.expiracion,
+            self.logger.clone(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use serial_test::serial;
+
+    use crate::{
+        tipos_de_dato::{comando::Ejecutar, comandos::gc::Gc, logger::Logger},
+        utils::{
+            io, objects,
+            testing::{addear_archivos_y_comittear, limpiar_archivo_gir},
+        },
+    };
+
+    #[test]
+    #[serial]
+    fn test01_gc_deja_solo_el_pack_con_los_objetos_alcanzables() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/gc_test01")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        io::escribir_bytes("tmp/gc_archivo", "contenido").unwrap();
+        addear_archivos_y_comittear(vec!["tmp/gc_archivo".to_string()], logger.clone());
+        io::escribir_bytes("tmp/gc_archivo", "contenido descartado").unwrap();
+        addear_archivos_y_comittear(vec!["tmp/gc_archivo".to_string()], logger.clone());
+        let cantidad_objetos = objects::obtener_objetos_del_dir(&PathBuf::from(".gir/objects/"))
+            .unwrap()
+            .len();
+
+        let resumen = Gc::from(vec!["--prune=now".to_string()], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+
+        assert!(resumen.contains(&format!("{} objetos empaquetados", cantidad_objetos)));
+        assert!(
+            objects::obtener_objetos_sueltos(&PathBuf::from(".gir/objects/"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
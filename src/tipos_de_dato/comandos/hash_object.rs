@@ -1,5 +1,6 @@
 use crate::tipos_de_dato::comando::Ejecutar;
 use crate::tipos_de_dato::logger::Logger;
+use crate::tipos_de_dato::repositorio::Repositorio;
 use crate::utils::compresion::comprimir_contenido_u8;
 use crate::utils::io;
 use sha1::{Digest, Sha1};
@@ -82,7 +83,8 @@ impl Ejecutar for HashObject {
         let hash = Self::hashear_contenido_objeto(&contenido);
 
         if self.escribir {
-            let ruta = format!(".gir/objects/{}/{}", &hash[..2], &hash[2..]);
+            let ruta =
+                Repositorio::actual().ruta_gir(format!("objects/{}/{}", &hash[..2], &hash[2..]));
             io::escribir_bytes(ruta, comprimir_contenido_u8(&contenido)?)?;
         }
         let mensaje = format!(
@@ -1,8 +1,11 @@
-use std::{fs, path::Path, sync::Arc};
+use std::sync::Arc;
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger},
-    utils::io,
+    tipos_de_dato::{
+        comando::Ejecutar, logger::Logger, repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
+    },
+    utils::{io, ubicacion_repositorio::DIR_GIR},
 };
 
 pub struct Init {
@@ -43,12 +46,12 @@ impl Init {
     }
 
     /// Obtiene desde los argumentos el path donde se creara el directorio .gir.
-    /// Si no se especifica un directorio, devuelve el directorio actual.
+    /// Si no se especifica un directorio, devuelve el del repositorio actual.
     fn obtener_path(args: Vec<String>) -> String {
         if args.is_empty() {
-            "./.gir".to_string()
+            Repositorio::actual().dir_gir().display().to_string()
         } else {
-            format!("{}{}", args[0], "/.gir")
+            format!("{}/{}", args[0], DIR_GIR)
         }
     }
 
@@ -69,7 +72,7 @@ impl Init {
 
     /// Borra el directorio .gir creado.
     fn borrar_directorios_y_archivos_git(&self) {
-        let _ = fs::remove_dir_all(self.path.clone());
+        let _ = io::rm_directorio(&self.path);
     }
 
     /// Crea los directorios y archivos necesarios para el funcionamiento de gir.
@@ -82,6 +85,7 @@ impl Init {
         io::crear_directorio(self.path.clone() + "/refs/heads")?;
         io::crear_directorio(self.path.clone() + "/refs/tags")?;
         io::crear_directorio(self.path.clone() + "/refs/remotes")?;
+        io::crear_directorio(self.path.clone() + "/hooks")?;
         io::crear_archivo(self.path.clone() + "/config")?;
         io::crear_archivo(self.path.clone() + "/refs/heads/master")?;
         io::crear_archivo(self.path.clone() + "/index")?;
@@ -91,16 +95,14 @@ impl Init {
     /// Crea el archivo .gir/HEAD apuntando a la rama actual, la cual es la default master.
     /// En caso de ocurrir un error al crear el archivo, devuelve un error.
     fn crear_archivo_head(&self) -> Result<(), String> {
-        let dir_archivo_head = self.path.clone() + "/HEAD";
-        let contenido_inicial_head = "ref: refs/heads/master";
-
-        io::crear_archivo(dir_archivo_head.clone())?;
-        io::escribir_bytes(dir_archivo_head, contenido_inicial_head)
+        TransaccionRefs::new(&self.path)
+            .apuntar_simbolicamente("HEAD", "refs/heads/master")
+            .confirmar()
     }
 
     /// Verifica si ya existe un directorio .gir en el lugar indicado por el path.
     fn verificar_si_ya_esta_creado_directorio_gir(&self) -> bool {
-        Path::new(&self.path).exists()
+        io::existe(&self.path)
     }
 }
 
@@ -4,6 +4,7 @@ use std::sync::Arc;
 
 use crate::tipos_de_dato::comando::Ejecutar;
 use crate::tipos_de_dato::logger::Logger;
+use crate::tipos_de_dato::repositorio::Repositorio;
 
 use crate::tipos_de_dato::comandos::checkout::Checkout;
 use crate::tipos_de_dato::objetos::commit::CommitObj;
@@ -14,6 +15,8 @@ pub struct Log {
     branch: String,
     /// Logger para registrar los eventos ocurridos durante la ejecucion del comando.
     logger: Arc<Logger>,
+    /// Repositorio del que se obtienen los commits.
+    repositorio: Repositorio,
 }
 
 impl Log {
@@ -36,12 +39,17 @@ impl Log {
             None => ramas::obtener_rama_actual()
                 .map_err(|e| format!("No se pudo obtener la rama actual\n{}", e))?,
         };
-        Ok(Log { branch, logger })
+        Ok(Log {
+            branch,
+            logger,
+            repositorio: Repositorio::actual(),
+        })
     }
 
-    /// Obtiene el hash del commit al que apunta la rama pasada por parametro.
-    fn obtener_commit_branch(branch: &str) -> Result<String, String> {
-        let hash_commit = io::leer_a_string(format!(".gir/refs/heads/{}", branch))?;
+    /// Obtiene el hash del commit al que apunta la rama pasada por parametro en el repositorio.
+    fn obtener_commit_branch(repositorio: &Repositorio, branch: &str) -> Result<String, String> {
+        let hash_commit =
+            io::leer_a_string(repositorio.ruta_gir(format!("refs/heads/{}", branch)))?;
         Ok(hash_commit.to_string())
     }
 
@@ -79,8 +87,9 @@ impl Ejecutar for Log {
     /// Devuelve un string con el log de los commits de la rama.
     /// En caso de no haber commits devuelve un mensaje y corta la ejecucion.
     fn ejecutar(&mut self) -> Result<String, String> {
+        let _repositorio = self.repositorio.entrar();
         self.logger.log("Ejecutando comando log");
-        let hash_commit = Self::obtener_commit_branch(&self.branch)?;
+        let hash_commit = Self::obtener_commit_branch(&self.repositorio, &self.branch)?;
         if hash_commit.is_empty() {
             return Ok(format!("La rama {} no tiene commits", self.branch));
         }
@@ -136,7 +145,7 @@ mod tests {
     #[serial]
     fn test03_obtener_commit_branch() {
         io::escribir_bytes(".gir/refs/heads/rama", "hash".as_bytes()).unwrap();
-        let hash = Log::obtener_commit_branch("rama").unwrap();
+        let hash = Log::obtener_commit_branch(&Repositorio::actual(), "rama").unwrap();
         assert_eq!(hash, "hash");
         std::fs::remove_file(".gir/refs/heads/rama").unwrap();
     }
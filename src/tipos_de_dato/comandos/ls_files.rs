@@ -2,7 +2,10 @@ use std::{path::PathBuf, sync::Arc};
 
 use crate::{
     tipos_de_dato::{comando::Ejecutar, logger::Logger, objetos::tree::Tree},
-    utils::index::{leer_index, ObjetoIndex},
+    utils::{
+        index::{leer_index, ObjetoIndex},
+        io,
+    },
 };
 
 use super::status::obtener_arbol_del_commit_head;
@@ -31,7 +34,7 @@ impl LsFiles {
 
         for arg in args {
             let path = PathBuf::from(arg.to_string());
-            if path.is_dir() {
+            if io::es_dir(&path) {
                 trees_directorios.push(arg.to_string());
             } else {
                 archivos.push(arg.to_string());
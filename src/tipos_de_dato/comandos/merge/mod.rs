@@ -7,11 +7,14 @@ use crate::{
         lado_conflicto::LadoConflicto,
         objeto::Objeto,
         region::{unificar_regiones, Region},
+        repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
     },
-    utils::{index, ramas},
+    utils::{hooks, index, ramas},
 };
 use std::{
-    path::{self, Path, PathBuf},
+    collections::{BTreeSet, HashMap},
+    path::PathBuf,
     sync::Arc,
 };
 
@@ -45,6 +48,7 @@ pub struct Merge {
     pub branch_a_mergear: String,
     pub abort: bool,
     pub no_fast_forward: bool,
+    pub repositorio: Repositorio,
 }
 
 impl Merge {
@@ -60,6 +64,7 @@ impl Merge {
                 branch_a_mergear: "".to_string(),
                 abort: true,
                 no_fast_forward: false,
+                repositorio: Repositorio::actual(),
             });
         }
 
@@ -76,13 +81,20 @@ impl Merge {
             branch_a_mergear,
             abort: false,
             no_fast_forward: false,
+            repositorio: Repositorio::actual(),
         })
     }
 
     pub fn obtener_arbol_commit_actual(branch: &str, logger: Arc<Logger>) -> Result<Tree, String> {
         let head_commit = Self::obtener_commit_de_branch(branch)?;
-        let hash_tree_padre = conseguir_arbol_en_directorio(&head_commit, ".gir/objects/")?;
-        Tree::from_hash(&hash_tree_padre, PathBuf::from("."), logger.clone())
+        Self::obtener_arbol_de_commit(&head_commit, logger)
+    }
+
+    /// Devuelve el arbol del commit `hash_commit`
+    fn obtener_arbol_de_commit(hash_commit: &str, logger: Arc<Logger>) -> Result<Tree, String> {
+        let hash_tree =
+            conseguir_arbol_en_directorio(hash_commit, &Repositorio::actual().dir_objetos())?;
+        Tree::from_hash(&hash_tree, PathBuf::from("."), logger)
     }
 
     /// Devuelve el commit base mas cercano entre dos ramas
@@ -94,9 +106,20 @@ impl Merge {
     ) -> Result<String, String> {
         let hash_commit_actual = Self::obtener_commit_de_branch(branch_1)?;
         let hash_commit_a_mergear = Self::obtener_commit_de_branch(branch_2)?;
+        Self::obtener_commit_base_entre_commits(&hash_commit_actual, &hash_commit_a_mergear, logger)
+    }
 
-        let commit_obj_actual = CommitObj::from_hash(hash_commit_actual, logger.clone())?;
-        let commit_obj_a_mergear = CommitObj::from_hash(hash_commit_a_mergear, logger.clone())?;
+    /// Igual que `obtener_commit_base_entre_dos_branches`, pero a partir de los hashes de los
+    /// ultimos commits de cada rama
+    pub fn obtener_commit_base_entre_commits(
+        hash_commit_actual: &str,
+        hash_commit_a_mergear: &str,
+        logger: Arc<Logger>,
+    ) -> Result<String, String> {
+        let commit_obj_actual =
+            CommitObj::from_hash(hash_commit_actual.to_string(), logger.clone())?;
+        let commit_obj_a_mergear =
+            CommitObj::from_hash(hash_commit_a_mergear.to_string(), logger.clone())?;
 
         let commits_branch_actual =
             Log::obtener_listas_de_commits(commit_obj_actual, logger.clone())?;
@@ -322,8 +345,10 @@ impl Merge {
         &self,
         commit_base: &str,
     ) -> Result<(Tree, Tree, Tree), String> {
-        let hash_tree_base =
-            write_tree::conseguir_arbol_en_directorio(commit_base, ".gir/objects/")?;
+        let hash_tree_base = write_tree::conseguir_arbol_en_directorio(
+            commit_base,
+            &self.repositorio.dir_objetos(),
+        )?;
         let tree_base = Tree::from_hash(&hash_tree_base, PathBuf::from("."), self.logger.clone())?;
 
         let tree_branch_actual =
@@ -361,6 +386,23 @@ impl Merge {
         objeto_actual: &Objeto,
         paths_con_conflictos: &mut Vec<String>,
     ) -> Result<bool, String> {
+        let (resultado, hubo_conflictos) =
+            Self::mergear_objetos(objeto_base, objeto_a_mergear, objeto_actual)?;
+
+        io::escribir_bytes(objeto_base.obtener_path(), resultado)?;
+        if hubo_conflictos {
+            paths_con_conflictos.push(format!("{}\n", objeto_base.obtener_path().display()));
+        }
+        Ok(hubo_conflictos)
+    }
+
+    /// Mergea en memoria las versiones de un archivo de cada rama respecto a la base.
+    /// Devuelve el contenido mergeado y un booleano que indica si hubo conflictos
+    fn mergear_objetos(
+        objeto_base: &Objeto,
+        objeto_a_mergear: &Objeto,
+        objeto_actual: &Objeto,
+    ) -> Result<(String, bool), String> {
         let diff_a_mergear = Self::obtener_diffs_entre_dos_objetos(
             &objeto_base.obtener_hash(),
             &objeto_a_mergear.obtener_hash(),
@@ -373,14 +415,98 @@ impl Merge {
 
         let contenido_base = descomprimir_objeto_gir(&objeto_base.obtener_hash())?;
 
-        let (resultado, hubo_conflictos) =
-            Self::mergear_diffs(diff_actual, diff_a_mergear, &contenido_base);
+        Ok(Self::mergear_diffs(
+            diff_actual,
+            diff_a_mergear,
+            &contenido_base,
+        ))
+    }
 
-        io::escribir_bytes(objeto_base.obtener_path(), resultado)?;
-        if hubo_conflictos {
-            paths_con_conflictos.push(format!("{}\n", objeto_base.obtener_path().display()));
+    /// Simula el merge de `branch_a_mergear` en `branch_actual` sin modificar el index, el
+    /// directorio de trabajo ni las ramas. Devuelve los paths de los archivos que tendrian
+    /// conflictos (vacio si el merge se puede hacer sin conflictos).
+    ///
+    /// Se revisan todos los archivos de la base y de las dos ramas. Ademas de los que ambas
+    /// ramas modificaron en las mismas lineas, son conflictos los que ambas agregaron con
+    /// distinto contenido y los que una rama borro y la otra modifico.
+    pub fn obtener_conflictos_sin_mergear(
+        branch_actual: &str,
+        branch_a_mergear: &str,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<String>, String> {
+        let commit_actual = Self::obtener_commit_de_branch(branch_actual)?;
+        let commit_a_mergear = Self::obtener_commit_de_branch(branch_a_mergear)?;
+        Self::obtener_conflictos_entre_commits(&commit_actual, &commit_a_mergear, logger)
+    }
+
+    /// Igual que `obtener_conflictos_sin_mergear`, pero a partir de los hashes de los ultimos
+    /// commits de cada rama
+    pub fn obtener_conflictos_entre_commits(
+        commit_actual: &str,
+        commit_a_mergear: &str,
+        logger: Arc<Logger>,
+    ) -> Result<Vec<String>, String> {
+        let commit_base = Self::obtener_commit_base_entre_commits(
+            commit_actual,
+            commit_a_mergear,
+            logger.clone(),
+        )?;
+        if commit_base == commit_actual || commit_base == commit_a_mergear {
+            return Ok(Vec::new());
         }
-        Ok(hubo_conflictos)
+
+        let hojas_base = Self::obtener_hojas_por_path(&Self::obtener_arbol_de_commit(
+            &commit_base,
+            logger.clone(),
+        )?);
+        let hojas_actual = Self::obtener_hojas_por_path(&Self::obtener_arbol_de_commit(
+            commit_actual,
+            logger.clone(),
+        )?);
+        let hojas_a_mergear =
+            Self::obtener_hojas_por_path(&Self::obtener_arbol_de_commit(commit_a_mergear, logger)?);
+
+        let paths: BTreeSet<&PathBuf> = hojas_base
+            .keys()
+            .chain(hojas_actual.keys())
+            .chain(hojas_a_mergear.keys())
+            .collect();
+        let mut paths_con_conflictos = Vec::new();
+        for path in paths {
+            let (objeto_base, objeto_actual, objeto_a_mergear) = (
+                hojas_base.get(path),
+                hojas_actual.get(path),
+                hojas_a_mergear.get(path),
+            );
+            let hash = |objeto: Option<&Objeto>| objeto.map(Objeto::obtener_hash);
+            // si alguna rama no cambio el archivo o ambas lo cambiaron igual, no hay conflicto
+            if hash(objeto_actual) == hash(objeto_base)
+                || hash(objeto_a_mergear) == hash(objeto_base)
+                || hash(objeto_actual) == hash(objeto_a_mergear)
+            {
+                continue;
+            }
+
+            let hubo_conflictos = match (objeto_base, objeto_actual, objeto_a_mergear) {
+                (Some(objeto_base), Some(objeto_actual), Some(objeto_a_mergear)) => {
+                    Self::mergear_objetos(objeto_base, objeto_a_mergear, objeto_actual)?.1
+                }
+                // ambas ramas lo agregaron con distinto contenido, o una lo borro y la otra lo modifico
+                _ => true,
+            };
+            if hubo_conflictos {
+                paths_con_conflictos.push(path.display().to_string());
+            }
+        }
+        Ok(paths_con_conflictos)
+    }
+
+    /// Devuelve los archivos del arbol indexados por su path
+    fn obtener_hojas_por_path(tree: &Tree) -> HashMap<PathBuf, Objeto> {
+        tree.obtener_objetos_hoja()
+            .into_iter()
+            .map(|objeto| (objeto.obtener_path(), objeto))
+            .collect()
     }
 
     /// Crea un objeto index dependiendo asiganndo si hubo conflictos o no
@@ -484,6 +610,7 @@ impl Merge {
             ))
         } else {
             let mut commit = Commit::from_merge(self.logger.clone(), &self.branch_actual)?;
+            commit.hook_previo = hooks::PRE_MERGE_COMMIT;
             commit.ejecutar()?;
             Ok("Merge completado".to_string())
         }
@@ -523,10 +650,12 @@ impl Merge {
     pub fn fast_forward(&self) -> Result<String, String> {
         let commit_banch_a_mergear = Self::obtener_commit_de_branch(&self.branch_a_mergear)?;
 
-        io::escribir_bytes(
-            Path::new(&format!(".gir/refs/heads/{}", self.branch_actual)),
-            commit_banch_a_mergear,
-        )?;
+        TransaccionRefs::new(self.repositorio.dir_gir())
+            .actualizar(
+                &format!("refs/heads/{}", self.branch_actual),
+                &commit_banch_a_mergear,
+            )
+            .confirmar()?;
 
         let tree_branch_a_mergear =
             Self::obtener_arbol_commit_actual(&self.branch_a_mergear, self.logger.clone())?;
@@ -538,8 +667,8 @@ impl Merge {
     /// Busca en el index si hay archivos con el flag merge en true
     /// indicando que hubieron conflictos y no se resolvieron
     pub fn hay_archivos_sin_mergear(logger: Arc<Logger>) -> Result<bool, String> {
-        let ruta_index = Path::new(".gir/index");
-        if !ruta_index.exists() {
+        let ruta_index = Repositorio::actual().ruta_gir("index");
+        if !io::existe(ruta_index) {
             return Ok(false);
         }
         let contenido_index = leer_index(logger.clone())?;
@@ -552,25 +681,29 @@ impl Merge {
     /// Busca en el merge head si hay un commit para
     /// definir si hay un merge en curso
     pub fn hay_merge_en_curso() -> Result<bool, String> {
-        let path = Path::new(".gir/MERGE_HEAD");
-        if !path.exists() {
+        let path = Repositorio::actual().ruta_gir("MERGE_HEAD");
+        if !io::existe(&path) {
             return Ok(false);
         }
 
-        let merge = io::leer_a_string(".gir/MERGE_HEAD")?;
+        let merge = io::leer_a_string(path)?;
 
         Ok(!merge.is_empty())
     }
 
     pub fn obtener_commit_de_branch(branch: &str) -> Result<String, String> {
+        let repositorio = Repositorio::actual();
         let branch_split = branch.split('/').collect::<Vec<&str>>();
         if branch_split.len() == 1 {
-            let ruta = format!(".gir/refs/heads/{}", branch);
-            let padre_commit = io::leer_a_string(path::Path::new(&ruta))?;
+            let ruta = repositorio.ruta_gir(format!("refs/heads/{}", branch));
+            let padre_commit = io::leer_a_string(ruta)?;
             Ok(padre_commit)
         } else if branch_split.len() == 2 {
-            let ruta = format!(".gir/refs/remotes/{}/{}", branch_split[0], branch_split[1]);
-            let padre_commit = io::leer_a_string(path::Path::new(&ruta))?;
+            let ruta = repositorio.ruta_gir(format!(
+                "refs/remotes/{}/{}",
+                branch_split[0], branch_split[1]
+            ));
+            let padre_commit = io::leer_a_string(ruta)?;
             Ok(padre_commit)
         } else {
             Err("Nombre de la rama ambigua".to_string())
@@ -578,15 +711,15 @@ impl Merge {
     }
 
     fn escribir_merge_head(&self) -> Result<(), String> {
-        let ruta_merge = Path::new(".gir/MERGE_HEAD");
         let commit = Self::obtener_commit_de_branch(&self.branch_a_mergear)?;
-        io::escribir_bytes(ruta_merge, commit)?;
-        Ok(())
+        TransaccionRefs::new(self.repositorio.dir_gir())
+            .actualizar("MERGE_HEAD", &commit)
+            .confirmar()
     }
 
     /// Escribe el mensaje del merge en el archivo COMMIT_EDITMSG.
     fn escribir_mensaje_merge(&self) -> Result<(), String> {
-        let ruta_merge_msg = Path::new(".gir/COMMIT_EDITMSG");
+        let ruta_merge_msg = self.repositorio.ruta_gir("COMMIT_EDITMSG");
         io::escribir_bytes(
             ruta_merge_msg,
             format!(
@@ -599,8 +732,8 @@ impl Merge {
 
     /// Limpia los archivos que quedan luego de un merge
     pub fn limpiar_merge_post_commit() -> Result<(), String> {
-        let ruta_merge = Path::new(".gir/MERGE_HEAD");
-        if ruta_merge.exists() {
+        let ruta_merge = Repositorio::actual().ruta_gir("MERGE_HEAD");
+        if io::existe(&ruta_merge) {
             io::rm_directorio(ruta_merge)?;
         }
         Ok(())
@@ -609,6 +742,7 @@ impl Merge {
 
 impl Ejecutar for Merge {
     fn ejecutar(&mut self) -> Result<String, String> {
+        let _repositorio = self.repositorio.entrar();
         self.logger.log("Ejecutando comando merge");
 
         if Self::hay_merge_en_curso()? {
@@ -616,7 +750,7 @@ impl Ejecutar for Merge {
         }
 
         if self.abort {
-            io::rm_directorio(".gir/MERGE_HEAD")?;
+            io::rm_directorio(self.repositorio.ruta_gir("MERGE_HEAD"))?;
             index::limpiar_archivo_index()?;
         }
 
@@ -648,6 +782,10 @@ impl Ejecutar for Merge {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        tipos_de_dato::comandos::checkout::Checkout,
+        utils::testing::{addear_archivos_y_comittear, escribir_rama_local, limpiar_archivo_gir},
+    };
     use serial_test::serial;
 
     #[test]
@@ -804,4 +942,104 @@ mod tests {
             "primera linea\n<<<<<< HEAD\n3 linea\n======\n2da linea\n3ra linea\n>>>>>> Entrante\ncuarta linea\nquinta linea\n"
         )
     }
+
+    #[test]
+    #[serial]
+    fn test06_simular_un_merge_con_conflictos_no_modifica_el_repositorio() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/merge_test06")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let archivo = "tmp/merge_test06_archivo".to_string();
+        io::escribir_bytes(&archivo, "uno\ndos\ntres\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+        escribir_rama_local("rama", logger.clone());
+
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        io::escribir_bytes(&archivo, "uno\n2\ntres\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+        Checkout::from(vec!["rama".to_string()], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+        io::escribir_bytes(&archivo, "uno\ndos!\ntres\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+
+        let index_previo = io::leer_bytes(".gir/index").unwrap();
+        let conflictos =
+            Merge::obtener_conflictos_sin_mergear("master", "rama", logger.clone()).unwrap();
+        let sin_conflictos =
+            Merge::obtener_conflictos_sin_mergear("rama", "rama", logger.clone()).unwrap();
+
+        assert_eq!(conflictos, vec![archivo.clone()]);
+        assert!(sin_conflictos.is_empty());
+        assert_eq!(io::leer_bytes(".gir/index").unwrap(), index_previo);
+        assert_eq!(io::leer_a_string(&archivo).unwrap(), "uno\ndos!\ntres\n");
+        assert!(!Merge::hay_merge_en_curso().unwrap());
+    }
+
+    // Deja un commit base con `archivo` en master y en `rama`, y vuelve a master
+    fn preparar_ramas_desde_base(archivo: &str, logger: Arc<Logger>) {
+        limpiar_archivo_gir(logger.clone());
+        io::escribir_bytes(archivo, "uno\ndos\ntres\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.to_string()], logger.clone());
+        escribir_rama_local("rama", logger);
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+
+    fn cambiar_a_rama(rama: &str, logger: Arc<Logger>) {
+        Checkout::from(vec![rama.to_string()], logger)
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+        std::thread::sleep(std::time::Duration::from_secs(1));
+    }
+
+    #[test]
+    #[serial]
+    fn test07_agregar_el_mismo_archivo_con_distinto_contenido_en_ambas_ramas_es_conflicto() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/merge_test07")).unwrap());
+        let archivo = "tmp/merge_test07_archivo".to_string();
+        let nuevo = "tmp/merge_test07_nuevo".to_string();
+        let igual = "tmp/merge_test07_igual".to_string();
+        preparar_ramas_desde_base(&archivo, logger.clone());
+
+        io::escribir_bytes(&nuevo, "desde master\n").unwrap();
+        io::escribir_bytes(&igual, "mismo contenido\n").unwrap();
+        addear_archivos_y_comittear(vec![nuevo.clone(), igual.clone()], logger.clone());
+        cambiar_a_rama("rama", logger.clone());
+        io::escribir_bytes(&nuevo, "desde rama\n").unwrap();
+        io::escribir_bytes(&igual, "mismo contenido\n").unwrap();
+        addear_archivos_y_comittear(vec![nuevo.clone(), igual.clone()], logger.clone());
+
+        let conflictos = Merge::obtener_conflictos_sin_mergear("master", "rama", logger).unwrap();
+
+        assert_eq!(conflictos, vec![nuevo]);
+    }
+
+    #[test]
+    #[serial]
+    fn test08_borrar_un_archivo_que_la_otra_rama_modifico_es_conflicto() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/merge_test08")).unwrap());
+        let archivo = "tmp/merge_test08_archivo".to_string();
+        preparar_ramas_desde_base(&archivo, logger.clone());
+
+        io::escribir_bytes(&archivo, "uno\n2\ntres\n").unwrap();
+        addear_archivos_y_comittear(vec![archivo.clone()], logger.clone());
+        cambiar_a_rama("rama", logger.clone());
+        io::rm_directorio(&archivo).unwrap();
+        Commit::from(
+            &mut vec!["-a".to_string(), "-m".to_string(), "borrado".to_string()],
+            logger.clone(),
+        )
+        .unwrap()
+        .ejecutar()
+        .unwrap();
+
+        let conflictos =
+            Merge::obtener_conflictos_sin_mergear("master", "rama", logger.clone()).unwrap();
+        let al_reves = Merge::obtener_conflictos_sin_mergear("rama", "master", logger).unwrap();
+
+        assert_eq!(conflictos, vec![archivo.clone()]);
+        assert_eq!(al_reves, vec![archivo]);
+    }
 }
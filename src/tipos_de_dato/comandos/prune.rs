@@ -0,0 +1,216 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    tipos_de_dato::{
+        comando::Ejecutar,
+        logger::Logger,
+        repositorio::{resolver, Repositorio},
+    },
+    utils::{alcanzabilidad, io, objects},
+};
+
+/// Antiguedad minima por defecto que tiene que tener un objeto inalcanzable para borrarse
+const DIAS_EXPIRACION_DEFAULT: u64 = 14;
+const SEGUNDOS_POR_DIA: u64 = 24 * 60 * 60;
+
+pub struct Prune {
+    /// Logger para registrar los eventos ocurridos durante la ejecucion del comando.
+    logger: Arc<Logger>,
+    /// Antiguedad minima de los objetos inalcanzables a borrar
+    expiracion: Duration,
+    /// Si es true solo se listan los objetos que se borrarian
+    simular: bool,
+}
+
+impl Prune {
+    /// Crea un comando prune a partir de los argumentos pasados por linea de comandos.
+    /// Opciones: `--expire <dias>|now` y `-n` / `--dry-run`.
+    pub fn from(args: Vec<String>, logger: Arc<Logger>) -> Result<Prune, String> {
+        let mut expiracion = Self::expiracion_default();
+        let mut simular = false;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-n" | "--dry-run" => simular = true,
+                "--expire" => {
+                    let valor = args.next().ok_or(
+                        "Falta el valor de --expire\ngir prune [-n] [--expire <dias>|now]",
+                    )?;
+                    expiracion = Self::parsear_expiracion(&valor)?;
+                }
+                _ => {
+                    return Err(format!(
+                        "Opcion no conocida '{}'\ngir prune [-n] [--expire <dias>|now]",
+                        arg
+                    ))
+                }
+            }
+        }
+
+        Ok(Prune {
+            logger,
+            expiracion,
+            simular,
+        })
+    }
+
+    /// Devuelve la expiracion que se usa cuando no se indica otra
+    pub fn expiracion_default() -> Duration {
+        Duration::from_secs(DIAS_EXPIRACION_DEFAULT * SEGUNDOS_POR_DIA)
+    }
+
+    /// Interpreta una expiracion expresada en dias o `now` (sin periodo de gracia)
+    pub fn parsear_expiracion(valor: &str) -> Result<Duration, String> {
+        if valor == "now" {
+            return Ok(Duration::ZERO);
+        }
+        let dias = valor
+            .parse::<u64>()
+            .map_err(|_| format!("Expiracion invalida '{}', se esperaba <dias> o now", valor))?;
+        Ok(Duration::from_secs(dias * SEGUNDOS_POR_DIA))
+    }
+
+    /// Borra los objetos sueltos de `dir_gir` que no son alcanzables y cuya ultima modificacion
+    /// es mas vieja que `expiracion`. El periodo de gracia evita borrar objetos recien escritos
+    /// por un comando que todavia no actualizo las refs.
+    /// Devuelve los hashes de los objetos borrados (o que se borrarian si `simular` es true).
+    pub fn podar(
+        dir_gir: &Path,
+        expiracion: Duration,
+        simular: bool,
+    ) -> Result<Vec<String>, String> {
+        let alcanzables = alcanzabilidad::obtener_objetos_alcanzables(dir_gir)?;
+        let dir_objetos = PathBuf::from(alcanzabilidad::obtener_dir_objetos(dir_gir));
+        let ahora = SystemTime::now();
+
+        let mut podados = Vec::new();
+        for hash in objects::obtener_objetos_sueltos(&dir_objetos)? {
+            if alcanzables.contains(&hash) {
+                continue;
+            }
+            let ruta = dir_objetos.join(&hash[..2]).join(&hash[2..]);
+            let antiguedad = fs::metadata(resolver(&ruta))
+                .and_then(|metadata| metadata.modified())
+                .map(|modificacion| ahora.duration_since(modificacion).unwrap_or_default())
+                .map_err(|e| format!("No se pudo leer la fecha del objeto {}: {}", hash, e))?;
+            if antiguedad < expiracion {
+                continue;
+            }
+            if !simular {
+                io::rm_directorio(&ruta)?;
+            }
+            podados.push(hash);
+        }
+
+        if !simular {
+            Self::eliminar_directorios_vacios(&dir_objetos)?;
+        }
+        Ok(podados)
+    }
+
+    /// Borra los directorios de objetos sueltos que quedaron vacios
+    pub fn eliminar_directorios_vacios(dir_objetos: &Path) -> Result<(), String> {
+        for entrada in io::leer_directorio(&dir_objetos)? {
+            let ruta = entrada
+                .map_err(|e| format!("Error leyendo directorio: {}", e))?
+                .path();
+            let nombre = ruta.file_name().unwrap_or_default().to_string_lossy();
+            if io::es_dir(&ruta) && nombre.len() == 2 && io::cantidad_entradas_dir(&ruta)? == 0 {
+                io::rm_directorio(&ruta)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Ejecutar for Prune {
+    /// Ejecuta el comando prune sobre el repositorio actual.
+    fn ejecutar(&mut self) -> Result<String, String> {
+        let podados = Self::podar(
+            Repositorio::actual().dir_gir(),
+            self.expiracion,
+            self.simular,
+        )?;
+        self.logger.log(&format!(
+            "Prune ejecutado con exito, {} objetos inalcanzables",
+            podados.len()
+        ));
+        Ok(podados.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use serial_test::serial;
+
+    use crate::{
+        tipos_de_dato::{
+            comando::Ejecutar,
+            comandos::{hash_object::HashObject, prune::Prune},
+            logger::Logger,
+        },
+        utils::{
+            io,
+            testing::{addear_archivos_y_comittear, limpiar_archivo_gir},
+        },
+    };
+
+    fn crear_objeto_inalcanzable(logger: Arc<Logger>) -> String {
+        io::escribir_bytes("tmp/prune_inalcanzable", "nadie me referencia").unwrap();
+        HashObject::from(
+            &mut vec!["-w".to_string(), "tmp/prune_inalcanzable".to_string()],
+            logger,
+        )
+        .unwrap()
+        .ejecutar()
+        .unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test01_prune_borra_los_objetos_inalcanzables() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/prune_test01")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        io::escribir_bytes("tmp/prune_archivo", "contenido").unwrap();
+        addear_archivos_y_comittear(vec!["tmp/prune_archivo".to_string()], logger.clone());
+        let inalcanzable = crear_objeto_inalcanzable(logger.clone());
+
+        let podados = Prune::from(
+            vec!["--expire".to_string(), "now".to_string()],
+            logger.clone(),
+        )
+        .unwrap()
+        .ejecutar()
+        .unwrap();
+
+        assert_eq!(podados, inalcanzable);
+        let ruta = format!(".gir/objects/{}/{}", &inalcanzable[..2], &inalcanzable[2..]);
+        assert!(!PathBuf::from(ruta).exists());
+        assert!(crate::utils::ramas::obtener_hash_commit_asociado_rama_actual().is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_prune_respeta_el_periodo_de_gracia() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/prune_test02")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let inalcanzable = crear_objeto_inalcanzable(logger.clone());
+
+        let podados = Prune::from(vec![], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+
+        assert!(podados.is_empty());
+        let ruta = format!(".gir/objects/{}/{}", &inalcanzable[..2], &inalcanzable[2..]);
+        assert!(PathBuf::from(ruta).exists());
+    }
+}
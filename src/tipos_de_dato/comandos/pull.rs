@@ -3,7 +3,7 @@ use std::{path::PathBuf, sync::Arc};
 use crate::{
     tipos_de_dato::{
         comando::Ejecutar, comandos::write_tree, config::Config, logger::Logger,
-        objetos::tree::Tree,
+        objetos::tree::Tree, repositorio::Repositorio, transaccion_refs::TransaccionRefs,
     },
     utils::{
         self,
@@ -14,7 +14,7 @@ use crate::{
 
 use super::{fetch::Fetch, merge::Merge, set_upstream::SetUpstream};
 
-const UBICACION_RAMA_MASTER: &str = "./.gir/refs/heads/master";
+const REF_RAMA_MASTER: &str = "refs/heads/master";
 const GIR_PULL: &str = "gir pull <remoto> <rama>";
 const GIR_PULL_U: &str = "gir pull -u <remoto> <rama-remota>";
 const FLAG_SET_UPSTREAM: &str = "--set-upstream";
@@ -129,15 +129,14 @@ impl Pull {
     ///Busca el archivo correspondiente que contien el HEAD del remoto (el NOMBREREMOTO_HEAD)y lo obtiene. En caso de no
     /// existir dicho archivo toma por defecto devulevor el commit de master del remoto.   
     fn obtener_head_remoto(&self) -> Result<String, String> {
-        let path_remoto = PathBuf::from(format!("./.gir/{}_HEAD", self.remoto.to_uppercase()));
+        let repositorio = Repositorio::actual();
+        let path_remoto = repositorio.ruta_gir(format!("{}_HEAD", self.remoto.to_uppercase()));
 
-        if path_remoto.exists() {
+        if io::existe(&path_remoto) {
             leer_a_string(path_remoto)
         } else {
-            let path_master_remoto = PathBuf::from(format!(
-                "./.gir/refs/remotes/{}/{}",
-                self.remoto, self.rama_merge
-            ));
+            let path_master_remoto =
+                repositorio.ruta_gir(format!("refs/remotes/{}/{}", self.remoto, self.rama_merge));
 
             leer_a_string(path_master_remoto)
         }
@@ -147,9 +146,14 @@ impl Pull {
     /// se obtiene el arbol del commit de la rama master remota y se lo escribe en el directorio
     /// de trabajo.
     fn fast_forward_de_cero(&self, commit_head_remoto: &str) -> Result<bool, String> {
-        io::escribir_bytes(UBICACION_RAMA_MASTER, commit_head_remoto)?;
-        let hash_tree_padre =
-            write_tree::conseguir_arbol_en_directorio(commit_head_remoto, ".gir/objects/")?;
+        let repositorio = Repositorio::actual();
+        TransaccionRefs::new(repositorio.dir_gir())
+            .actualizar(REF_RAMA_MASTER, commit_head_remoto)
+            .confirmar()?;
+        let hash_tree_padre = write_tree::conseguir_arbol_en_directorio(
+            commit_head_remoto,
+            &repositorio.dir_objetos(),
+        )?;
         let tree_branch_a_mergear =
             Tree::from_hash(&hash_tree_padre, PathBuf::from("."), self.logger.clone())?;
 
@@ -196,7 +200,7 @@ impl Ejecutar for Pull {
 
         let commit_head_remoto = self.obtener_head_remoto()?;
 
-        if io::esta_vacio(UBICACION_RAMA_MASTER) {
+        if io::esta_vacio(Repositorio::actual().ruta_gir(REF_RAMA_MASTER)) {
             self.fast_forward_de_cero(&commit_head_remoto)?;
         } else {
             self.mergear_rama()?;
@@ -1,15 +1,17 @@
 use super::set_upstream::SetUpstream;
 use crate::tipos_de_dato::comando::Ejecutar;
 use crate::tipos_de_dato::comandos::write_tree;
-use crate::tipos_de_dato::comunicacion::Comunicacion;
+use crate::tipos_de_dato::comunicacion::{Comunicacion, REPORT_STATUS, SIDE_BAND_64K};
 use crate::tipos_de_dato::config::Config;
 use crate::tipos_de_dato::logger::Logger;
 use crate::tipos_de_dato::objetos::commit::CommitObj;
 use crate::tipos_de_dato::objetos::tree::Tree;
 use crate::tipos_de_dato::packfile::Packfile;
+use crate::tipos_de_dato::repositorio::Repositorio;
 
 use crate::tipos_de_dato::referencia::Referencia;
 use crate::utils;
+use crate::utils::hooks;
 use crate::utils::io;
 use crate::utils::path_buf::obtener_nombre;
 
@@ -23,6 +25,11 @@ const FLAG_SET_UPSTREAM: &str = "--set-upstream";
 const FLAG_U: &str = "-u";
 const GIR_PUSH: &str = "\tgir push <remoto> <rama-local>:<rama-remota>\n";
 const GIR_PUSH_U: &str = "gir push --set-upstream/-u <nombre-remoto> <nombre-rama-local>";
+// capacidades de receive-pack que se le piden al servidor si las anuncia
+const CAPACIDADES_LOCAL: [&str; 2] = [REPORT_STATUS, SIDE_BAND_64K];
+
+/// Commits cabeza de rama o tag en el servidor y su referencia
+type CommitsYRefs = Vec<(String, PathBuf)>;
 pub struct Push {
     referencia: Referencia,
     remoto: String,
@@ -177,10 +184,91 @@ impl Push {
         // el server pide que se le mande un packfile vacio
         comunicacion.enviar_pack_file(Packfile::obtener_pack_con_archivos(
             vec![],
-            "./.gir/objects/",
+            &Repositorio::actual().dir_objetos(),
         )?)
     }
 
+    /// Ejecuta el hook pre-push con el nombre y la url del remoto. Por entrada estandar le pasa
+    /// la referencia a actualizar: `<ref local> <commit nuevo> <ref remota> <commit viejo>`.
+    /// Si el hook falla, termina la comunicacion sin enviar nada y devuelve el error.
+    fn ejecutar_hook_pre_push(
+        &self,
+        referencia_actualizar: &(String, String, PathBuf),
+        comunicacion: &mut Comunicacion<TcpStream>,
+    ) -> Result<(), String> {
+        let (commit_viejo, commit_nuevo, referencia_remota) = referencia_actualizar;
+        let entrada = format!(
+            "{} {} {} {}\n",
+            self.referencia.dar_ref_local().display(),
+            commit_nuevo,
+            referencia_remota.display(),
+            commit_viejo
+        );
+        let args = [self.remoto.clone(), self.obtener_url(&self.remoto)?];
+
+        if let Err(error) =
+            hooks::ejecutar_hook(hooks::PRE_PUSH, &args, Some(&entrada), self.logger.clone())
+        {
+            self.terminar_y_mandar_pack_file_vacio(comunicacion)?;
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    /// Lee lo que envia el servidor despues de recibir el packfile, segun las capacidades que se
+    /// negociaron: con `side-band-64k` la salida de los hooks del servidor llega por la banda de
+    /// progreso y el reporte de estado por la de datos; sin `report-status` no hay reporte.
+    /// Devuelve las lineas `remote: ...` que imprimieron los hooks del servidor, o un error
+    /// con esas lineas y los motivos si el servidor rechazo alguna referencia.
+    fn leer_reporte_de_estado(
+        &self,
+        comunicacion: &mut Comunicacion<TcpStream>,
+        capacidades: &[String],
+    ) -> Result<String, String> {
+        let report_status = capacidades
+            .iter()
+            .any(|capacidad| capacidad == REPORT_STATUS);
+        let (reporte, progreso) = if capacidades
+            .iter()
+            .any(|capacidad| capacidad == SIDE_BAND_64K)
+        {
+            let (datos, progreso) = comunicacion.obtener_bandas_hasta_flush()?;
+            let datos = String::from_utf8(datos).map_err(|e| e.to_string())?;
+            (utils::strings::separar_lineas_pkt(&datos)?, progreso)
+        } else if report_status {
+            (comunicacion.obtener_lineas_hasta_flush()?, Vec::new())
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        let mut salida_remota: Vec<String> = progreso
+            .iter()
+            .map(|linea| format!("remote: {}", linea))
+            .collect();
+        let mut rechazos = Vec::new();
+
+        for linea in reporte {
+            let linea = linea.trim_end_matches('\n');
+            if let Some(rechazo) = linea.strip_prefix("ng ") {
+                rechazos.push(format!(" ! [rechazado] {}", rechazo));
+            } else if let Some(error) = linea.strip_prefix("unpack ") {
+                if error != "ok" {
+                    rechazos.push(format!(" ! [error al desempaquetar] {}", error));
+                }
+            }
+        }
+
+        self.logger.log(&format!(
+            "Reporte del push: {:?} {:?}",
+            salida_remota, rechazos
+        ));
+        if !rechazos.is_empty() {
+            salida_remota.append(&mut rechazos);
+            return Err(salida_remota.join("\n"));
+        }
+        Ok(salida_remota.join("\n"))
+    }
+
     fn es_necesario_actualizar(&self, referencia_actualizar: &(String, String, PathBuf)) -> bool {
         referencia_actualizar.0 != referencia_actualizar.1
     }
@@ -248,15 +336,16 @@ impl Push {
         let commit_viejo = "0".repeat(40);
         let nombre_referencia = self.referencia.dar_ref_remota();
         let commit_nuevo =
-            io::leer_a_string(PathBuf::from("./.gir").join(self.referencia.dar_ref_local()))?;
+            io::leer_a_string(Repositorio::actual().ruta_gir(self.referencia.dar_ref_local()))?;
         Ok((commit_viejo, commit_nuevo, nombre_referencia))
     }
 
-    ///Le envia las referencia a acualizar al servidor junto con todos sus objetos asosiados
-    /// dentro del pack file. Finaliza la comunicacion
+    ///Le envia las referencia a acualizar al servidor, con las capacidades pedidas, junto con
+    /// todos sus objetos asosiados dentro del pack file. Finaliza la comunicacion
     fn enviar_actualizaciones_y_objetos(
         &self,
         referencia_actualizar: (String, String, PathBuf),
+        capacidades: &[String],
         objetos_a_enviar: HashSet<String>,
         comunicacion: &mut Comunicacion<TcpStream>,
     ) -> Result<(), String> {
@@ -265,7 +354,7 @@ impl Push {
             referencia_actualizar
         ));
 
-        comunicacion.enviar_referencia(referencia_actualizar)?;
+        comunicacion.enviar_referencia(referencia_actualizar, capacidades)?;
 
         self.logger.log(&format!(
             "Se envia en push los objetos: {:?}",
@@ -274,7 +363,7 @@ impl Push {
 
         comunicacion.enviar_pack_file(Packfile::obtener_pack_con_archivos(
             objetos_a_enviar.into_iter().collect(),
-            "./.gir/objects/",
+            &Repositorio::actual().dir_objetos(),
         )?)?;
         Ok(())
     }
@@ -288,14 +377,15 @@ impl Push {
     ///
     /// # Resultado
     ///
+    /// - las capacidades que el servidor anuncio y que se le van a pedir
     /// - vector de tuplas con los commit cabeza de rama y la ref de la
     ///     del tag o la rama oen el servidor(ojo!! la direccion para el servidor no para el local)
     fn fase_de_descubrimiento(
         &self,
         comunicacion: &mut Comunicacion<TcpStream>,
-    ) -> Result<Vec<(String, PathBuf)>, String> {
+    ) -> Result<(Vec<String>, CommitsYRefs), String> {
         let (
-            _capacidades_servidor,
+            capacidades_servidor,
             _commit_head_remoto,
             commits_cabezas_y_ref_rama_asosiado,
             commits_y_tags_asosiados,
@@ -303,11 +393,19 @@ impl Push {
 
         self.logger.log("Fase de descubrimiento ejecuta con exito");
 
-        Ok([
-            &commits_cabezas_y_ref_rama_asosiado[..],
-            &commits_y_tags_asosiados[..],
-        ]
-        .concat())
+        let capacidades = capacidades_servidor
+            .into_iter()
+            .filter(|capacidad| CAPACIDADES_LOCAL.contains(&capacidad.as_str()))
+            .collect();
+
+        Ok((
+            capacidades,
+            [
+                &commits_cabezas_y_ref_rama_asosiado[..],
+                &commits_y_tags_asosiados[..],
+            ]
+            .concat(),
+        ))
     }
 }
 // funcion para obtener los commits que faltan para llegar al commit limite y los objetos asociados a cada commit
@@ -318,9 +416,8 @@ fn obtener_commits_y_objetos_asociados(
     commit_limite: &str,
     logger: Arc<Logger>,
 ) -> Result<HashSet<String>, String> {
-    //let ruta = format!(".gir/{}", referencia);
-    let ruta = format!(".gir/{}", referencia.to_string_lossy());
-    let ultimo_commit = io::leer_a_string(Path::new(&ruta))?;
+    let repositorio = Repositorio::actual();
+    let ultimo_commit = io::leer_a_string(repositorio.ruta_gir(referencia))?;
     if ultimo_commit.is_empty() {
         return Ok(HashSet::new());
     }
@@ -351,7 +448,8 @@ fn obtener_commits_y_objetos_asociados(
             break;
         }
         objetos_a_agregar.insert(commit.hash.clone());
-        let hash_tree = write_tree::conseguir_arbol_en_directorio(&commit.hash, "./.gir/objects/")?;
+        let hash_tree =
+            write_tree::conseguir_arbol_en_directorio(&commit.hash, &repositorio.dir_objetos())?;
         let tree = Tree::from_hash(&hash_tree, PathBuf::from("."), logger.clone())?;
         objetos_a_agregar.insert(hash_tree.clone());
         objetos_a_agregar.extend(
@@ -377,11 +475,13 @@ impl Ejecutar for Push {
     fn ejecutar(&mut self) -> Result<String, String> {
         let mut comunicacion = self.iniciar_git_recive_pack_con_servidor()?;
 
-        let commits_y_refs_asosiado = self.fase_de_descubrimiento(&mut comunicacion)?;
+        let (capacidades, commits_y_refs_asosiado) =
+            self.fase_de_descubrimiento(&mut comunicacion)?;
 
         let referencia_acualizar = self.obtener_referencia_acualizar(&commits_y_refs_asosiado)?;
 
         let mensaje = if self.es_necesario_actualizar(&referencia_acualizar) {
+            self.ejecutar_hook_pre_push(&referencia_acualizar, &mut comunicacion)?;
             let objetos_a_enviar = self.obtener_objetos_a_enviar(
                 &self.referencia.dar_ref_local(),
                 &referencia_acualizar.0,
@@ -390,10 +490,16 @@ impl Ejecutar for Push {
 
             self.enviar_actualizaciones_y_objetos(
                 referencia_acualizar,
+                &capacidades,
                 objetos_a_enviar,
                 &mut comunicacion,
             )?;
-            "Push ejecutado con exito".to_string()
+            let salida_remota = self.leer_reporte_de_estado(&mut comunicacion, &capacidades)?;
+            if salida_remota.is_empty() {
+                "Push ejecutado con exito".to_string()
+            } else {
+                format!("{}\nPush ejecutado con exito", salida_remota)
+            }
         } else {
             self.terminar_y_mandar_pack_file_vacio(&mut comunicacion)?;
             "Nada que actualizar".to_string()
@@ -2,6 +2,7 @@ use std::io::prelude::*;
 use std::{fs::OpenOptions, path::PathBuf, sync::Arc};
 
 use crate::tipos_de_dato::comando::Ejecutar;
+use crate::utils::hooks;
 use crate::utils::index::{self, escribir_index};
 use crate::utils::ramas;
 use crate::{
@@ -9,6 +10,8 @@ use crate::{
         comandos::write_tree::conseguir_arbol_en_directorio,
         logger::Logger,
         objetos::{commit::CommitObj, tree::Tree},
+        repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
     },
     utils::io,
 };
@@ -16,6 +19,9 @@ use crate::{
 use super::checkout::Checkout;
 use super::{commit::Commit, log::Log, merge::Merge};
 
+/// Directorio, dentro de `.gir`, con el estado del rebase en curso
+pub const DIR_REBASE: &str = "rebase-merge";
+
 pub struct Rebase {
     pub rama: Option<String>,
     /// Guarda la rama actual.
@@ -26,6 +32,8 @@ pub struct Rebase {
     pub abort: bool,
     /// Indica si se debe continuar el rebase.
     pub continue_: bool,
+    /// Repositorio sobre el que se hace el rebase.
+    pub repositorio: Repositorio,
 }
 
 impl Rebase {
@@ -44,6 +52,7 @@ impl Rebase {
                 logger,
                 abort: true,
                 continue_: false,
+                repositorio: Repositorio::actual(),
             }),
             "--continue" => Ok(Rebase {
                 rama: None,
@@ -51,6 +60,7 @@ impl Rebase {
                 logger,
                 abort: false,
                 continue_: true,
+                repositorio: Repositorio::actual(),
             }),
             _ => Ok(Rebase {
                 rama: Some(arg.clone()),
@@ -58,10 +68,16 @@ impl Rebase {
                 logger,
                 abort: false,
                 continue_: false,
+                repositorio: Repositorio::actual(),
             }),
         }
     }
 
+    /// Devuelve el directorio en el que se guarda el estado del rebase en curso
+    fn dir_rebase(&self) -> PathBuf {
+        self.repositorio.ruta_gir(DIR_REBASE)
+    }
+
     /// Obtiene el hash del commit base entre la rama actual y la rama pasada por parametro.
     /// El commit base es el primer commit que tienen en comun las dos ramas.
     /// En caso de no encontrar un commit base devuelve un error.
@@ -111,14 +127,17 @@ impl Rebase {
         commits_a_aplicar: &[CommitObj],
         tip_nuevo: &str,
     ) -> Result<(), String> {
-        io::crear_directorio(".gir/rebase-merge")?;
-        io::escribir_bytes(".gir/rebase-merge/end", commits_a_aplicar.len().to_string())?;
+        io::crear_directorio(self.dir_rebase())?;
+        io::escribir_bytes(
+            self.dir_rebase().join("end"),
+            commits_a_aplicar.len().to_string(),
+        )?;
 
         let mut archivo_to_do = OpenOptions::new()
             .write(true)
             .append(true)
             .create(true)
-            .open(".gir/rebase-merge/git-rebase-todo")
+            .open(self.dir_rebase().join("git-rebase-todo"))
             .map_err(|_| "No se pudo abrir el archivo .gir/rebase-merge/git-rebase-todo")?;
 
         for commit in commits_a_aplicar.iter() {
@@ -127,13 +146,13 @@ impl Rebase {
             })?;
         }
 
-        let ref_head = io::leer_a_string(".gir/HEAD")?;
-        io::escribir_bytes(".gir/rebase-merge/head-name", ref_head)?;
+        let ref_head = io::leer_a_string(self.repositorio.ruta_gir("HEAD"))?;
+        io::escribir_bytes(self.dir_rebase().join("head-name"), ref_head)?;
 
         let head = ramas::obtener_hash_commit_asociado_rama_actual()?;
-        io::escribir_bytes(".gir/rebase-merge/orig-head", head)?;
-        io::escribir_bytes(".gir/rebase-merge/msgnum", 0.to_string())?;
-        io::escribir_bytes(".gir/rebase-merge/onto", tip_nuevo)?;
+        io::escribir_bytes(self.dir_rebase().join("orig-head"), head)?;
+        io::escribir_bytes(self.dir_rebase().join("msgnum"), 0.to_string())?;
+        io::escribir_bytes(self.dir_rebase().join("onto"), tip_nuevo)?;
 
         Ok(())
     }
@@ -145,29 +164,29 @@ impl Rebase {
     /// Actualiza el archivo .gir/rebase-merge/message con el mensaje del commit que se acaba de aplicar.
     /// Actualiza el archivo .gir/rebase-merge/msgnum con el numero de commit que se acaba de aplicar.
     fn actualizar_carpeta_rebase(&self, commit: &CommitObj) -> Result<(), String> {
-        let to_do = io::leer_a_string(".gir/rebase-merge/git-rebase-todo")?;
+        let to_do = io::leer_a_string(self.dir_rebase().join("git-rebase-todo"))?;
         let mut to_do = to_do.lines().collect::<Vec<&str>>();
         to_do.remove(0);
         let to_do = to_do.join("\n");
-        io::escribir_bytes(".gir/rebase-merge/git-rebase-todo", to_do)?;
+        io::escribir_bytes(self.dir_rebase().join("git-rebase-todo"), to_do)?;
 
         let mut archivo_done = OpenOptions::new()
             .write(true)
             .append(true)
             .create(true)
-            .open(".gir/rebase-merge/done")
+            .open(self.dir_rebase().join("done"))
             .map_err(|_| "No se pudo abrir el archivo .gir/rebase-merge/done")?;
 
         writeln!(archivo_done, "pick {} {}", commit.hash, commit.mensaje)
             .map_err(|_| "No se pudo escribir en el archivo .gir/rebase-merge/done")?;
 
-        let msgnum = io::leer_a_string(".gir/rebase-merge/msgnum")?;
+        let msgnum = io::leer_a_string(self.dir_rebase().join("msgnum"))?;
         let msgnum = msgnum
             .parse::<usize>()
             .map_err(|_| "No se pudo parsear msgnum")?;
         let msgnum = msgnum + 1;
-        io::escribir_bytes(".gir/rebase-merge/msgnum", msgnum.to_string())?;
-        io::escribir_bytes(".gir/rebase-merge/message", commit.mensaje.clone())?;
+        io::escribir_bytes(self.dir_rebase().join("msgnum"), msgnum.to_string())?;
+        io::escribir_bytes(self.dir_rebase().join("message"), commit.mensaje.clone())?;
 
         Ok(())
     }
@@ -179,7 +198,7 @@ impl Rebase {
             .write(true)
             .append(true)
             .create(true)
-            .open(".gir/rebase-merge/rewritten-list")
+            .open(self.dir_rebase().join("rewritten-list"))
             .map_err(|_| "No se pudo abrir el archivo .gir/rebase-merge/rewritten-list")?;
 
         let tip = ramas::obtener_hash_commit_asociado_rama_actual()?;
@@ -193,22 +212,32 @@ impl Rebase {
     /// Realiza el rebase por primera vez.
     /// Crea la carpeta .gir/rebase-merge y los archivos necesarios para realizar el rebase.
     fn primera_vez(&self) -> Result<String, String> {
-        if PathBuf::from(".gir/rebase-merge").exists() {
+        if io::existe(self.dir_rebase()) {
             return Err("Hay rebase en progreso".to_string());
         }
 
         let rama = self.rama.as_ref().ok_or("No se especifico una rama")?;
+        // como se rebasea la rama actual, git no le pasa la rama como segundo argumento
+        hooks::ejecutar_hook(
+            hooks::PRE_REBASE,
+            &[rama.to_string()],
+            None,
+            self.logger.clone(),
+        )?;
 
         self.logger.log("Rebaseando...");
         let commits_a_aplicar = self.obtener_commits_a_aplicar(rama)?;
 
-        let tip_nuevo = io::leer_a_string(format!(".gir/refs/heads/{}", rama))?;
+        let tip_nuevo = Merge::obtener_commit_de_branch(rama)?;
         self.crear_carpeta_rebase(&commits_a_aplicar, &tip_nuevo)?;
 
         let branch_actual = self.rama_actual.clone();
-        io::escribir_bytes(format!(".gir/refs/heads/{branch_actual}"), &tip_nuevo)?;
+        TransaccionRefs::new(self.repositorio.dir_gir())
+            .actualizar(&format!("refs/heads/{branch_actual}"), &tip_nuevo)
+            .confirmar()?;
 
-        let hash_arbol_commit = conseguir_arbol_en_directorio(&tip_nuevo, ".gir/objects/")?;
+        let hash_arbol_commit =
+            conseguir_arbol_en_directorio(&tip_nuevo, &self.repositorio.dir_objetos())?;
         let arbol = Tree::from_hash(&hash_arbol_commit, PathBuf::from("./"), self.logger.clone())?;
 
         arbol.escribir_en_directorio()?;
@@ -231,7 +260,7 @@ impl Rebase {
 
             let conflictos = commit.aplicar_a_directorio()?;
             if !conflictos.is_empty() {
-                io::escribir_bytes(".gir/rebase-merge/stopped-sha", commit.hash)?;
+                io::escribir_bytes(self.dir_rebase().join("stopped-sha"), commit.hash)?;
                 let mut index = index::leer_index(self.logger.clone())?;
 
                 let mut index_nuevo: Vec<_> = index
@@ -251,11 +280,10 @@ impl Rebase {
 
             self.actualizar_lista_de_commits_aplicados(&commit.hash)?;
 
-            let mut comando_commit = Commit {
-                mensaje: commit.mensaje,
-                logger: self.logger.clone(),
-                rama_actual: self.rama_actual.clone(),
-            };
+            // como en git, al reaplicar los commits no se ejecutan los hooks del commit
+            let mut comando_commit =
+                Commit::new(commit.mensaje, &self.rama_actual, self.logger.clone());
+            comando_commit.verificar = false;
             comando_commit.ejecutar()?;
         }
 
@@ -266,21 +294,23 @@ impl Rebase {
     /// Vuelve al estado original previo a comenzar con el rebase.
     /// Borra toda la informacion que se creo para el rebase.
     fn abortar(&self) -> Result<String, String> {
-        let head_name = io::leer_a_string(".gir/rebase-merge/head-name")?;
-        let orig_head = io::leer_a_string(".gir/rebase-merge/orig-head")?;
+        let head_name = io::leer_a_string(self.dir_rebase().join("head-name"))?;
+        let orig_head = io::leer_a_string(self.dir_rebase().join("orig-head"))?;
 
         let rama = head_name
             .split('/')
             .last()
             .ok_or("No se pudo obtener la rama")?;
 
-        io::escribir_bytes(format!(".gir/refs/heads/{}", rama), orig_head)?;
+        TransaccionRefs::new(self.repositorio.dir_gir())
+            .actualizar(&format!("refs/heads/{}", rama), &orig_head)
+            .confirmar()?;
 
         let tree = Checkout::obtener_arbol_commit_actual(self.logger.clone())?;
 
         tree.escribir_en_directorio()?;
 
-        io::rm_directorio(".gir/rebase-merge")?;
+        io::rm_directorio(self.dir_rebase())?;
 
         index::limpiar_archivo_index()?;
 
@@ -293,17 +323,17 @@ impl Rebase {
     /// En caso de no haber mas commits para aplicar, termina el rebase.
     /// En caso de encontrar un conflicto, se detiene el rebase y se guarda el estado actual.
     fn continuar(&self) -> Result<String, String> {
-        if !PathBuf::from(".gir/rebase-merge").exists() {
+        if !io::existe(self.dir_rebase()) {
             return Err("No hay rebase en progreso".to_string());
         }
-        let mensaje_commit = io::leer_a_string(".gir/rebase-merge/message")?;
+        let mensaje_commit = io::leer_a_string(self.dir_rebase().join("message"))?;
         let mut commit = Commit::from(
             &mut vec!["-m".to_string(), mensaje_commit],
             self.logger.clone(),
         )?;
         commit.ejecutar()?;
 
-        let contenido_to_do = io::leer_a_string(".gir/rebase-merge/git-rebase-todo")?;
+        let contenido_to_do = io::leer_a_string(self.dir_rebase().join("git-rebase-todo"))?;
         let lineas_to_do = contenido_to_do.lines().collect::<Vec<&str>>();
 
         let mut commits_restantes = Vec::new();
@@ -319,10 +349,10 @@ impl Rebase {
         }
 
         self.rebasear_commits(commits_restantes)?;
-        let msg_num = io::leer_a_string(".gir/rebase-merge/msgnum")?;
-        let end = io::leer_a_string(".gir/rebase-merge/end")?;
+        let msg_num = io::leer_a_string(self.dir_rebase().join("msgnum"))?;
+        let end = io::leer_a_string(self.dir_rebase().join("end"))?;
         if msg_num == end {
-            io::rm_directorio(".gir/rebase-merge")?;
+            io::rm_directorio(self.dir_rebase())?;
             index::limpiar_archivo_index()?;
         }
         Ok("Rebase terminado con extito".to_string())
@@ -332,6 +362,7 @@ impl Rebase {
 impl Ejecutar for Rebase {
     /// Ejecuta el comando rebase.
     fn ejecutar(&mut self) -> Result<String, String> {
+        let _repositorio = self.repositorio.entrar();
         if self.abort {
             return self.abortar();
         }
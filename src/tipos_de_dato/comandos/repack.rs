@@ -0,0 +1,245 @@
+use std::{
+    collections::HashSet,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    tipos_de_dato::{
+        comando::Ejecutar,
+        comandos::prune::Prune,
+        indice_pack::IndicePack,
+        logger::Logger,
+        repositorio::{resolver, Repositorio},
+    },
+    utils::{
+        alcanzabilidad, compresion, io,
+        packs::{self, Packs},
+    },
+};
+
+pub struct Repack {
+    /// Logger para registrar los eventos ocurridos durante la ejecucion del comando.
+    logger: Arc<Logger>,
+}
+
+impl Repack {
+    /// Crea un comando repack. No recibe argumentos: siempre se empaquetan
+    /// todos los objetos alcanzables en un unico pack.
+    pub fn from(args: Vec<String>, logger: Arc<Logger>) -> Result<Repack, String> {
+        if !args.is_empty() {
+            return Err("Argumentos desconocidos\ngir repack".to_string());
+        }
+        Ok(Repack { logger })
+    }
+
+    /// Empaqueta todos los objetos alcanzables de `dir_gir` en un unico packfile con deltas y su `.idx`.
+    /// Una vez escrito el pack nuevo se borran los packs anteriores y los objetos sueltos que
+    /// quedaron empaquetados. Los objetos sueltos inalcanzables se dejan para `prune`.
+    ///
+    /// Los inalcanzables que estaban en packs viejos y todavia no vencieron (el pack es mas nuevo
+    /// que `expiracion`) se dejan sueltos con la fecha del pack, para que `prune` los borre recien
+    /// cuando pase el periodo de gracia. Los de packs mas viejos se descartan junto con ellos.
+    /// Devuelve la cantidad de objetos empaquetados.
+    pub fn repackear(
+        dir_gir: &Path,
+        expiracion: Duration,
+        logger: Arc<Logger>,
+    ) -> Result<usize, String> {
+        let dir_objetos = alcanzabilidad::obtener_dir_objetos(dir_gir);
+        let conjunto_alcanzables = alcanzabilidad::obtener_objetos_alcanzables(dir_gir)?;
+        let mut alcanzables: Vec<String> = conjunto_alcanzables.iter().cloned().collect();
+        if alcanzables.is_empty() {
+            logger.log("Repack: no hay objetos para empaquetar");
+            return Ok(0);
+        }
+        alcanzables.sort();
+
+        let packs_cargados = Packs::cargar(&dir_objetos)?;
+        let objetos = alcanzables
+            .iter()
+            .map(|hash| {
+                let objeto =
+                    compresion::leer_objeto_descomprimido_con_packs(hash, &packs_cargados)?;
+                Ok((hash.clone(), objeto))
+            })
+            .collect::<Result<Vec<(String, Vec<u8>)>, String>>()?;
+
+        let packs_viejos = packs::obtener_packs(&dir_objetos)?;
+        let pack_nuevo = packs::escribir_pack(&dir_objetos, objetos)?;
+        let packs_viejos: Vec<PathBuf> = packs_viejos
+            .into_iter()
+            .filter(|pack| *pack != pack_nuevo)
+            .collect();
+
+        let desempaquetados = Self::desempaquetar_inalcanzables(
+            &dir_objetos,
+            &packs_viejos,
+            &conjunto_alcanzables,
+            expiracion,
+        )?;
+        for pack in &packs_viejos {
+            packs::eliminar_pack(pack)?;
+        }
+        for hash in &alcanzables {
+            let ruta = PathBuf::from(&dir_objetos)
+                .join(&hash[..2])
+                .join(&hash[2..]);
+            if io::existe(&ruta) {
+                io::rm_directorio(&ruta)?;
+            }
+        }
+        Prune::eliminar_directorios_vacios(Path::new(&dir_objetos))?;
+
+        logger.log(&format!(
+            "Repack: {} objetos empaquetados en {}, {} inalcanzables quedaron sueltos",
+            alcanzables.len(),
+            pack_nuevo.display(),
+            desempaquetados
+        ));
+        Ok(alcanzables.len())
+    }
+
+    /// Escribe como objetos sueltos los inalcanzables de `packs_viejos` cuyo pack es mas nuevo
+    /// que `expiracion`. Cada objeto queda con la fecha de modificacion de su pack, que es la
+    /// que mira `prune`. Devuelve la cantidad de objetos escritos.
+    fn desempaquetar_inalcanzables(
+        dir_objetos: &str,
+        packs_viejos: &[PathBuf],
+        alcanzables: &HashSet<String>,
+        expiracion: Duration,
+    ) -> Result<usize, String> {
+        let ahora = SystemTime::now();
+        let todos_los_packs = Packs::cargar(dir_objetos)?;
+        let mut desempaquetados = 0;
+
+        for pack in packs_viejos {
+            let ruta_pack = pack.with_extension("pack");
+            let modificacion = fs::metadata(resolver(&ruta_pack))
+                .and_then(|metadata| metadata.modified())
+                .map_err(|e| {
+                    format!(
+                        "No se pudo leer la fecha del pack {}: {}",
+                        ruta_pack.display(),
+                        e
+                    )
+                })?;
+            if ahora.duration_since(modificacion).unwrap_or_default() >= expiracion {
+                continue;
+            }
+
+            let indice = IndicePack::leer(&pack.with_extension("idx").to_string_lossy())?;
+            for entrada in indice.entradas() {
+                let hash = &entrada.hash;
+                let ruta = PathBuf::from(dir_objetos).join(&hash[..2]).join(&hash[2..]);
+                if alcanzables.contains(hash) || io::existe(&ruta) {
+                    continue;
+                }
+                let objeto = todos_los_packs
+                    .leer_objeto(hash)?
+                    .ok_or_else(|| format!("No se encontro el objeto {} en los packs", hash))?;
+                io::escribir_bytes(&ruta, compresion::comprimir_contenido_u8(&objeto)?)?;
+                File::options()
+                    .write(true)
+                    .open(resolver(&ruta))
+                    .and_then(|archivo| archivo.set_modified(modificacion))
+                    .map_err(|e| format!("No se pudo fechar el objeto {}: {}", hash, e))?;
+                desempaquetados += 1;
+            }
+        }
+        Ok(desempaquetados)
+    }
+}
+
+impl Ejecutar for Repack {
+    /// Ejecuta el comando repack sobre el repositorio actual.
+    fn ejecutar(&mut self) -> Result<String, String> {
+        let cantidad = Self::repackear(
+            Repositorio::actual().dir_gir(),
+            Prune::expiracion_default(),
+            self.logger.clone(),
+        )?;
+        Ok(format!("{} objetos empaquetados", cantidad))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use serial_test::serial;
+
+    use crate::{
+        tipos_de_dato::{
+            comando::Ejecutar,
+            comandos::{prune::Prune, repack::Repack},
+            logger::Logger,
+        },
+        utils::{
+            compresion, io, objects, packs, ramas,
+            testing::{addear_archivos_y_comittear, limpiar_archivo_gir},
+        },
+    };
+
+    #[test]
+    #[serial]
+    fn test01_repack_empaqueta_los_objetos_y_borra_los_sueltos() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/repack_test01")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        let contenido = "una linea bastante larga que se repite en el archivo\n".repeat(30);
+        io::escribir_bytes("tmp/repack_archivo", &contenido).unwrap();
+        addear_archivos_y_comittear(vec!["tmp/repack_archivo".to_string()], logger.clone());
+        io::escribir_bytes("tmp/repack_archivo", contenido + "otra linea\n").unwrap();
+        addear_archivos_y_comittear(vec!["tmp/repack_archivo".to_string()], logger.clone());
+
+        Repack::from(vec![], logger.clone())
+            .unwrap()
+            .ejecutar()
+            .unwrap();
+
+        let dir_objetos = PathBuf::from(".gir/objects/");
+        assert!(objects::obtener_objetos_sueltos(&dir_objetos)
+            .unwrap()
+            .is_empty());
+        assert_eq!(packs::obtener_packs(".gir/objects/").unwrap().len(), 1);
+        let commit = ramas::obtener_hash_commit_asociado_rama_actual().unwrap();
+        assert!(compresion::descomprimir_objeto_gir(&commit).is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_los_inalcanzables_de_packs_viejos_quedan_sueltos_hasta_que_vencen() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/repack_test02")).unwrap());
+        limpiar_archivo_gir(logger.clone());
+        addear_archivos_y_comittear(vec!["test_file.txt".to_string()], logger.clone());
+        let hash = "f".repeat(40);
+        let objeto = b"blob 12\0inalcanzable".to_vec();
+        let suelto = PathBuf::from(".gir/objects/ff").join(&hash[2..]);
+
+        packs::escribir_pack(".gir/objects/", vec![(hash.clone(), objeto.clone())]).unwrap();
+        Repack::repackear(
+            Path::new(".gir"),
+            Prune::expiracion_default(),
+            logger.clone(),
+        )
+        .unwrap();
+
+        assert!(io::existe(&suelto));
+        assert_eq!(
+            compresion::leer_objeto_descomprimido(&hash, ".gir/objects/").unwrap(),
+            objeto
+        );
+
+        io::rm_directorio(&suelto).unwrap();
+        packs::escribir_pack(".gir/objects/", vec![(hash.clone(), objeto)]).unwrap();
+        Repack::repackear(Path::new(".gir"), Duration::ZERO, logger).unwrap();
+
+        assert!(!packs::existe_objeto(&hash, ".gir/objects/"));
+    }
+}
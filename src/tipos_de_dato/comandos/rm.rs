@@ -36,11 +36,11 @@ impl Remove {
         }
 
         for ubicacion in ubicaciones_a_corroborar {
-            if !ubicacion.is_dir() {
+            if !io::es_dir(&ubicacion) {
                 continue;
             }
 
-            let hijos = std::fs::read_dir(&ubicacion)
+            let hijos = io::leer_directorio(&ubicacion)
                 .map_err(|_| "Error al obtener hijos de directorio".to_string())
                 .unwrap();
 
@@ -58,19 +58,19 @@ impl Remove {
     ) -> Result<Vec<PathBuf>, String> {
         let mut ubicaciones_hoja: Vec<PathBuf> = Vec::new();
         for ubicacion in ubicaciones {
-            if ubicacion.is_file() {
+            if io::es_archivo(&ubicacion) {
                 ubicaciones_hoja.push(ubicacion);
-            } else if ubicacion.is_dir() {
+            } else if io::es_dir(&ubicacion) {
                 if !recursivo {
                     Err("No se puede borrar un directorio sin la opcion -r".to_string())?;
                 }
-                let mut directorios = std::fs::read_dir(ubicacion)
+                let mut directorios = io::leer_directorio(&ubicacion)
                     .map_err(|_| "Error al obtener directorios hoja".to_string())?;
                 while let Some(Ok(directorio)) = directorios.next() {
-                    let path = directorio.path();
-                    if path.is_file() {
+                    let path = ubicacion.join(directorio.file_name());
+                    if io::es_archivo(&path) {
                         ubicaciones_hoja.push(path);
-                    } else if path.is_dir() {
+                    } else if io::es_dir(&path) {
                         ubicaciones_hoja
                             .append(&mut Self::obtener_ubicaciones_hoja(vec![path], true)?);
                     }
@@ -125,7 +125,7 @@ impl Ejecutar for Remove {
         self.logger.log("Ejecutando remove");
 
         for ubicacion in self.ubicaciones.clone() {
-            if ubicacion.is_dir() {
+            if io::es_dir(&ubicacion) {
                 Err("No se puede borrar un directorio sin la opcion -r".to_string())?;
             }
             let nuevo_objeto =
@@ -167,7 +167,7 @@ mod tests {
 
     use crate::tipos_de_dato::{
         comando::Ejecutar,
-        comandos::{add::Add, commit::Commit},
+        comandos::{add::Add, commit::Commit, init::Init},
     };
 
     use super::*;
@@ -180,8 +180,15 @@ mod tests {
         PathBuf::from("tmp/rm_test.txt").exists()
     }
 
-    fn clear_index() {
-        let _ = std::fs::remove_file(".gir/index");
+    /// Arranca cada test en un repositorio nuevo, asi el commit del test siempre tiene cambios
+    fn reiniciar_repositorio() {
+        let _ = io::rm_directorio(".gir");
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_init")).unwrap());
+        let mut init = Init {
+            path: "./.gir".to_string(),
+            logger,
+        };
+        init.ejecutar().unwrap();
     }
 
     fn crear_archivo_en_dir(contenido: &str) {
@@ -199,7 +206,8 @@ mod tests {
     #[test]
     #[serial]
     fn test01_remove_ejecutar() {
-        clear_index();
+        reiniciar_repositorio();
+        io::escribir_bytes("test_file.txt", "test file modified").unwrap();
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_test01")).unwrap());
         Add::from(vec!["test_file.txt".to_string()], logger.clone())
             .unwrap()
@@ -227,7 +235,7 @@ mod tests {
     #[test]
     #[serial]
     fn test02_remove_recursivo() {
-        clear_index();
+        reiniciar_repositorio();
         crear_archivo_en_dir("test02");
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_test01")).unwrap());
         Add::from(vec!["tmp/test_dir".to_string()], logger.clone())
@@ -261,7 +269,7 @@ mod tests {
     #[test]
     #[serial]
     fn test03_remove_sin_cached() {
-        clear_index();
+        reiniciar_repositorio();
         crear_test_file("test03");
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_test01")).unwrap());
         Add::from(vec!["tmp/rm_test.txt".to_string()], logger.clone())
@@ -293,7 +301,7 @@ mod tests {
     #[test]
     #[serial]
     fn test04_remove_recursivo_sin_cached() {
-        clear_index();
+        reiniciar_repositorio();
         crear_archivo_en_dir("test04");
 
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_test01")).unwrap());
@@ -328,7 +336,7 @@ mod tests {
     #[serial]
     #[should_panic(expected = "No se puede borrar un directorio sin la opcion -r")]
     fn test05_remove_directorio_no_recursivo_falla() {
-        clear_index();
+        reiniciar_repositorio();
         crear_archivo_en_dir("test05");
 
         let logger = Arc::new(Logger::new(PathBuf::from("tmp/rm_test01")).unwrap());
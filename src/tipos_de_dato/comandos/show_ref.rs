@@ -5,7 +5,7 @@ use std::{
 };
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger},
+    tipos_de_dato::{comando::Ejecutar, logger::Logger, repositorio::Repositorio},
     utils::{io, path_buf},
 };
 
@@ -81,32 +81,36 @@ impl ShowRef {
 
     /// Agrega la referencia que esta apuntando HEAD actualmente al hashmap de refs.
     fn agregar_head(&self, refs: &mut HashMap<String, String>) -> Result<(), String> {
-        let binding = io::leer_a_string(PathBuf::from(".gir/HEAD"))?;
+        let repositorio = Repositorio::actual();
+        let binding = io::leer_a_string(repositorio.ruta_gir("HEAD"))?;
         let head_dir = binding.split(' ').nth(1).ok_or("Error al parsear HEAD")?;
-        let contenido = io::leer_a_string(PathBuf::from(format!(".gir/{}", head_dir)))?;
+        let contenido = io::leer_a_string(repositorio.ruta_gir(head_dir))?;
         refs.insert("HEAD".to_string(), contenido);
         Ok(())
     }
 
-    /// Dado un path, devuelve un hashmap con las referencias que se encuentran en ese path.
+    /// Dado un path dentro del `.gir` del repositorio actual, devuelve un hashmap con las
+    /// referencias que se encuentran en ese path.
     /// Si el path es un directorio, se llama recursivamente a la funcion para obtener las referencias
     /// de los hijos.
     pub fn obtener_referencias(&self, path: PathBuf) -> Result<HashMap<String, String>, String> {
         let mut refs: HashMap<String, String> = HashMap::new();
 
-        let entries = std::fs::read_dir(path)
+        let entries = io::leer_directorio(&path)
             .map_err(|e| format!("Error al leer el directorio de refs: {}", e))?;
 
         for ref_entry in entries {
-            let ref_path = ref_entry
-                .map_err(|e| format!("Error al leer el directorio de refs: {}", e))?
-                .path();
+            let ref_path = path.join(
+                ref_entry
+                    .map_err(|e| format!("Error al leer el directorio de refs: {}", e))?
+                    .file_name(),
+            );
 
             if !self.hay_que_ver_path(&ref_path)? {
                 continue;
             }
 
-            if ref_path.is_dir() {
+            if io::es_dir(&ref_path) {
                 let hijos = self.obtener_referencias(ref_path)?;
                 refs.extend(hijos);
                 continue;
@@ -118,7 +122,8 @@ impl ShowRef {
                 return Err(format!("el ref {} esta vacio", ref_path.display()));
             }
 
-            let ref_path_sin_prefix = ref_path.strip_prefix(".gir/").map_err(|e| {
+            let dir_gir = Repositorio::actual().dir_gir().to_path_buf();
+            let ref_path_sin_prefix = ref_path.strip_prefix(dir_gir).map_err(|e| {
                 format!(
                     "Error al obtener el path del ref {}: {}",
                     ref_path.display(),
@@ -136,7 +141,7 @@ impl Ejecutar for ShowRef {
     /// Ejecuta el comando show-ref.
     fn ejecutar(&mut self) -> Result<String, String> {
         self.logger.log("Ejecutando comando show-ref");
-        let mut refs = self.obtener_referencias(PathBuf::from(".gir/refs/"))?;
+        let mut refs = self.obtener_referencias(Repositorio::actual().ruta_gir("refs"))?;
 
         if self.show_head {
             self.agregar_head(&mut refs)?;
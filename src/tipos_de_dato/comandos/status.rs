@@ -1,14 +1,14 @@
-use std::{
-    path::{self, PathBuf},
-    sync::Arc,
-};
+use std::{path::PathBuf, sync::Arc};
 
 const ROJO: &str = "\x1B[31m";
 const VERDE: &str = "\x1B[32m";
 const RESET: &str = "\x1B[0m";
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger, objeto::Objeto, objetos::tree::Tree},
+    tipos_de_dato::{
+        comando::Ejecutar, logger::Logger, objeto::Objeto, objetos::tree::Tree,
+        repositorio::Repositorio,
+    },
     utils::{
         index::{leer_index, ObjetoIndex},
         io, ramas,
@@ -31,16 +31,14 @@ pub struct Status {
 /// Obtiene el arbol del commit al que apunta la rama actual.
 /// En caso de no haber un commit devuelve None.
 pub fn obtener_arbol_del_commit_head(logger: Arc<Logger>) -> Option<Tree> {
-    let ruta = match ramas::obtener_gir_dir_rama_actual() {
-        Ok(ruta) => format!("{}", ruta.display()),
-        Err(_) => return None,
-    };
-    let padre_commit = io::leer_a_string(path::Path::new(&ruta)).unwrap_or_else(|_| "".to_string());
+    let ruta = ramas::obtener_gir_dir_rama_actual().ok()?;
+    let padre_commit = io::leer_a_string(ruta).unwrap_or_else(|_| "".to_string());
     if padre_commit.is_empty() {
         None
     } else {
         let hash_arbol_commit =
-            conseguir_arbol_en_directorio(&padre_commit, ".gir/objects/").ok()?;
+            conseguir_arbol_en_directorio(&padre_commit, &Repositorio::actual().dir_objetos())
+                .ok()?;
         let tree =
             Tree::from_hash(&hash_arbol_commit, PathBuf::from("./"), logger.clone()).unwrap();
         Some(tree)
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
 use crate::{
-    tipos_de_dato::{comando::Ejecutar, logger::Logger},
-    utils::{self, io, ramas},
+    tipos_de_dato::{
+        comando::Ejecutar, logger::Logger, repositorio::Repositorio,
+        transaccion_refs::TransaccionRefs,
+    },
+    utils::{self, ramas},
 };
 
 pub struct Tag {
@@ -48,10 +51,11 @@ impl Tag {
             return Err(format!("El tag {} ya existe", tag));
         }
 
-        let ubicacion = format!(".gir/refs/tags/{}", tag);
         let commit = ramas::obtener_hash_commit_asociado_rama_actual()?;
 
-        io::escribir_bytes(ubicacion, commit)?;
+        TransaccionRefs::new(Repositorio::actual().dir_gir())
+            .actualizar(&format!("refs/tags/{}", tag), &commit)
+            .confirmar()?;
 
         self.logger.log(&format!("Tag {} creado con exito", tag));
 
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use crate::tipos_de_dato::logger::Logger;
 use crate::tipos_de_dato::objeto::Objeto;
 use crate::tipos_de_dato::objetos::tree::Tree;
+use crate::tipos_de_dato::repositorio::Repositorio;
 use crate::utils::compresion::descomprimir_objeto;
 use crate::utils::index::{generar_objetos_raiz, leer_index, ObjetoIndex};
 
@@ -23,7 +24,7 @@ pub fn conseguir_arbol_en_directorio(hash_commit_padre: &str, dir: &str) -> Resu
 /// Dado un hash de un commit devuelve el hash del arbol de ese commit.
 /// Se espera que el contenido del commit tenga el formato correcto.
 pub fn conseguir_arbol(hash_commit_padre: &str) -> Result<String, String> {
-    conseguir_arbol_en_directorio(hash_commit_padre, ".gir/objects/")
+    conseguir_arbol_en_directorio(hash_commit_padre, &Repositorio::actual().dir_objetos())
 }
 
 /// Devuelve el arbol mergeado entre el arbol padre y los cambios trackeados en el index.
@@ -69,7 +70,7 @@ pub fn crear_arbol_commit(
     }
 
     let objetos_a_utilizar = if let Some(hash) = commit_padre {
-        let hash_arbol_padre = conseguir_arbol_en_directorio(&hash, ".gir/objects/")?;
+        let hash_arbol_padre = conseguir_arbol(&hash)?;
         let arbol_padre = Tree::from_hash(&hash_arbol_padre, PathBuf::from("./"), logger.clone())?;
         let objetos_arbol_nuevo_commit =
             aplicar_index_a_arbol(&objetos_index, &arbol_padre.objetos);
@@ -10,6 +10,19 @@ use std::sync::Arc;
 use super::logger::Logger;
 use super::respuesta_pedido::RespuestaDePedido;
 
+/// Capacidad de receive-pack con la que el cliente pide el reporte de estado del push
+pub const REPORT_STATUS: &str = "report-status";
+/// Capacidad con la que se multiplexan los datos y los mensajes de progreso en bandas
+pub const SIDE_BAND_64K: &str = "side-band-64k";
+/// Banda por la que viajan los datos
+pub const BANDA_DATOS: u8 = 1;
+/// Banda por la que viajan los mensajes para el usuario (`remote: ...`)
+pub const BANDA_PROGRESO: u8 = 2;
+/// Banda por la que viaja un error fatal
+pub const BANDA_ERROR: u8 = 3;
+// largo maximo de una linea con side-band-64k, sin contar el largo ni el byte de la banda
+const MAXIMO_CONTENIDO_BANDA: usize = 65520 - 5;
+
 pub struct Comunicacion<T: Read + Write> {
     flujo: T,
     repositorio: Option<String>,
@@ -204,6 +217,23 @@ impl<T: Write + Read> Comunicacion<T> {
         Ok(lineas)
     }
 
+    /// Obtiene las lineas en formato PKT que se reciben hasta un flush pkt, sin cortar antes
+    /// por su contenido como `obtener_lineas`. Se usa para el reporte de estado del push.
+    ///
+    /// # Resultado
+    /// - Devuelve cada linea recibida (sin el largo)
+    pub fn obtener_lineas_hasta_flush(&mut self) -> Result<Vec<String>, String> {
+        let mut lineas: Vec<String> = Vec::new();
+        loop {
+            let tamanio = self.obtener_largo_de_la_linea()?;
+            if tamanio == 0 {
+                break;
+            }
+            lineas.push(self.obtener_contenido_linea(tamanio)?);
+        }
+        Ok(lineas)
+    }
+
     ///Escribe por el flujo las lineas recibidas
     pub fn responder(&mut self, lineas: &Vec<String>) -> Result<(), String> {
         if lineas.is_empty() {
@@ -367,16 +397,23 @@ impl<T: Write + Read> Comunicacion<T> {
     }
 
     ///Envia la referencia a actulizar al servidor con el formato correspondiente. La parte
-    /// del envio de referencia en push
+    /// del envio de referencia en push. Si hay capacidades, se agregan despues de un `\0`
     pub fn enviar_referencia(
         &mut self,
         referencia_actualizar: (String, String, PathBuf),
+        capacidades: &[String],
     ) -> Result<(), String> {
+        let capacidades = if capacidades.is_empty() {
+            String::new()
+        } else {
+            format!("\0{}", capacidades.join(" "))
+        };
         self.enviar(&utils::strings::obtener_linea_con_largo_hex(&format!(
-            "{} {} {}\n",
+            "{} {} {}{}\n",
             referencia_actualizar.0,
             referencia_actualizar.1,
-            referencia_actualizar.2.to_string_lossy()
+            referencia_actualizar.2.to_string_lossy(),
+            capacidades
         )))?;
 
         self.enviar_flush_pkt()?;
@@ -384,6 +421,46 @@ impl<T: Write + Read> Comunicacion<T> {
     }
 
     ///recibi el hash de un objeto y le da el formato correcto para hacer el have
+    /// Envia el contenido por la banda indicada (side-band-64k), partiendolo en tantas lineas
+    /// como haga falta para no pasarse del largo maximo de una linea
+    pub fn enviar_por_banda(&mut self, banda: u8, contenido: &[u8]) -> Result<(), String> {
+        for parte in contenido.chunks(MAXIMO_CONTENIDO_BANDA) {
+            let mut linea = format!("{:04x}", parte.len() + 5).into_bytes();
+            linea.push(banda);
+            linea.extend_from_slice(parte);
+            self.enviar_bytes(&linea)?;
+        }
+        Ok(())
+    }
+
+    /// Lee las lineas multiplexadas en bandas (side-band-64k) hasta un flush pkt.
+    ///
+    /// # Resultado
+    /// - Devuelve el contenido de la banda de datos y las lineas de la banda de progreso
+    /// - Devuelve error si llega algo por la banda de error
+    pub fn obtener_bandas_hasta_flush(&mut self) -> Result<(Vec<u8>, Vec<String>), String> {
+        let mut datos = Vec::new();
+        let mut progreso = String::new();
+        loop {
+            let tamanio = self.obtener_largo_de_la_linea()?;
+            if tamanio == 0 {
+                break;
+            }
+            let linea = self.leer_del_flujo_tantos_bytes((tamanio - 4) as usize)?;
+            let (banda, contenido) = linea
+                .split_first()
+                .ok_or("Se recibio una linea sin banda".to_string())?;
+            match *banda {
+                BANDA_DATOS => datos.extend_from_slice(contenido),
+                BANDA_PROGRESO => progreso.push_str(&String::from_utf8_lossy(contenido)),
+                BANDA_ERROR => return Err(String::from_utf8_lossy(contenido).to_string()),
+                banda => return Err(format!("Banda desconocida: {}", banda)),
+            }
+        }
+        let progreso = progreso.lines().map(|linea| linea.to_string()).collect();
+        Ok((datos, progreso))
+    }
+
     fn dar_formato_have(&self, hash_commit: &str) -> String {
         strings::obtener_linea_con_largo_hex(&("have ".to_string() + hash_commit + "\n"))
     }
@@ -532,7 +609,7 @@ mod tests {
         );
 
         Comunicacion::new_para_testing(&mut mock, logger)
-            .enviar_referencia(referencia_actulizar)
+            .enviar_referencia(referencia_actulizar, &[])
             .unwrap();
 
         let contenido_esperado_enviar_lo_que_tengo = "\
@@ -544,4 +621,64 @@ mod tests {
             mock.escritura_data.as_slice()
         )
     }
+
+    #[test]
+    #[serial]
+    fn test06_se_envian_las_capacidades_con_la_referencia_actualizar() {
+        let mut mock = MockTcpStream {
+            lectura_data: Vec::new(),
+            escritura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/comunicacion_test06")).unwrap());
+
+        let referencia_actulizar = (
+            "0".repeat(40),
+            "1".repeat(40),
+            PathBuf::from("refs/heads/master"),
+        );
+        let capacidades = vec![REPORT_STATUS.to_string(), SIDE_BAND_64K.to_string()];
+
+        Comunicacion::new_para_testing(&mut mock, logger)
+            .enviar_referencia(referencia_actulizar, &capacidades)
+            .unwrap();
+
+        let linea_esperada = utils::strings::obtener_linea_con_largo_hex(&format!(
+            "{} {} refs/heads/master\0report-status side-band-64k\n",
+            "0".repeat(40),
+            "1".repeat(40)
+        )) + "0000";
+
+        assert_eq!(linea_esperada.as_bytes(), mock.escritura_data.as_slice())
+    }
+
+    #[test]
+    #[serial]
+    fn test07_se_separan_correctamente_las_bandas() {
+        let mut enviado = MockTcpStream {
+            lectura_data: Vec::new(),
+            escritura_data: Vec::new(),
+        };
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/comunicacion_test07")).unwrap());
+        {
+            let mut comunicacion = Comunicacion::new_para_testing(&mut enviado, logger.clone());
+            comunicacion
+                .enviar_por_banda(BANDA_PROGRESO, b"mensaje del hook\n")
+                .unwrap();
+            comunicacion
+                .enviar_por_banda(BANDA_DATOS, b"000eunpack ok\n")
+                .unwrap();
+            comunicacion.enviar_flush_pkt().unwrap();
+        }
+
+        let mut mock = MockTcpStream {
+            lectura_data: enviado.escritura_data,
+            escritura_data: Vec::new(),
+        };
+        let (datos, progreso) = Comunicacion::new_para_testing(&mut mock, logger)
+            .obtener_bandas_hasta_flush()
+            .unwrap();
+
+        assert_eq!(datos, b"000eunpack ok\n");
+        assert_eq!(progreso, vec!["mensaje del hook".to_string()]);
+    }
 }
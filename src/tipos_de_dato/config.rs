@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use crate::utils::{self, io};
 
-use super::info_ramas::RamasInfo;
+use super::{info_ramas::RamasInfo, repositorio::Repositorio};
 
 #[derive(Debug, Clone)]
 pub struct RemoteInfo {
@@ -21,7 +21,7 @@ impl Config {
     /// Por cada branch que lee crea su respectivo BranchInfo.
     /// Si el archivo no existe, devuelve un Config vacio.
     pub fn leer_config() -> Result<Config, String> {
-        let contenido_config = io::leer_a_string(".gir/config")?;
+        let contenido_config = io::leer_a_string(Repositorio::actual().ruta_gir("config"))?;
         let contenido_spliteado = contenido_config.split('[').collect::<Vec<&str>>();
         let mut remotos: Vec<RemoteInfo> = Vec::new();
         let mut ramas: Vec<RamasInfo> = Vec::new();
@@ -161,7 +161,7 @@ impl Config {
             contenido.push_str(&format!("   merge = {}\n", branch.merge.to_string_lossy()));
         }
 
-        io::escribir_bytes(PathBuf::from(".gir/config"), contenido)?;
+        io::escribir_bytes(Repositorio::actual().ruta_gir("config"), contenido)?;
 
         Ok(())
     }
@@ -1,26 +1,61 @@
 use std::collections::HashMap;
 
-use super::{endpoint_handler::EndpointHandler, metodos::MetodoHttp};
+use super::{endpoint_handler::EndpointHandler, metodos::MetodoHttp, permiso::Permiso};
 #[derive(Debug)]
 
 pub struct Endpoint {
     pub metodo: MetodoHttp,
     pub patron: String,
     pub handler: EndpointHandler,
+    /// Permiso que necesita el usuario sobre el repositorio para usar el endpoint
+    pub permiso: Permiso,
 }
 
 impl Endpoint {
+    /// Crea el endpoint. Los `GET` requieren permiso de lectura y el resto de escritura
     pub fn new(metodo: MetodoHttp, patron: String, handler: EndpointHandler) -> Self {
+        let permiso = match metodo {
+            MetodoHttp::Get => Permiso::Lectura,
+            _ => Permiso::Escritura,
+        };
         Self {
             metodo,
             patron,
             handler,
+            permiso,
         }
     }
 
+    /// Cambia el permiso que requiere el endpoint
+    pub fn con_permiso(mut self, permiso: Permiso) -> Self {
+        self.permiso = permiso;
+        self
+    }
+
+    /// Devuelve los parametros de la ruta si matchea con el patron del endpoint. Si el ultimo
+    /// segmento del patron es `{*clave}`, `clave` se queda con todo el resto de la ruta, que
+    /// puede tener varios segmentos (Ej: `/contents/{*path}` con `/contents/src/main.rs`)
     pub fn matchea_con_patron(&self, ruta: &str) -> Option<HashMap<String, String>> {
-        let ruta_endpoint = self.patron.split('/').collect::<Vec<&str>>();
-        let ruta_request = ruta.split('/').collect::<Vec<&str>>();
+        let mut ruta_endpoint = self.patron.split('/').collect::<Vec<&str>>();
+        let mut ruta_request = ruta.split('/').collect::<Vec<&str>>();
+
+        let comodin = ruta_endpoint
+            .last()
+            .and_then(|ultimo| ultimo.strip_prefix("{*"))
+            .and_then(|ultimo| ultimo.strip_suffix('}'));
+        if let Some(clave) = comodin {
+            if ruta_request.len() < ruta_endpoint.len() {
+                return None;
+            }
+            let resto = ruta_request.split_off(ruta_endpoint.len() - 1).join("/");
+            ruta_endpoint.pop();
+            let mut params = Self::matchear_segmentos(&ruta_endpoint, &ruta_request)?;
+            if resto.is_empty() || resto.ends_with('/') {
+                return None;
+            }
+            params.insert(clave.to_string(), resto);
+            return Some(params);
+        }
 
         if ruta_endpoint.len() != ruta_request.len() {
             return None;
@@ -34,6 +69,13 @@ impl Endpoint {
             return None;
         }
 
+        Self::matchear_segmentos(&ruta_endpoint, &ruta_request)
+    }
+
+    fn matchear_segmentos(
+        ruta_endpoint: &[&str],
+        ruta_request: &[&str],
+    ) -> Option<HashMap<String, String>> {
         let mut params = HashMap::new();
 
         for (entrada_endpoint, entrada_request) in ruta_endpoint.iter().zip(ruta_request.iter()) {
@@ -118,4 +160,39 @@ mod tests {
         let params = endpoint.matchea_con_patron("/repos/messi/typo/1");
         assert!(params.is_none());
     }
+
+    #[test]
+    fn extraer_parametros_de_ruta_con_comodin() {
+        let endpoint = Endpoint::new(
+            MetodoHttp::Get,
+            "/repos/{repo}/contents/{*path}".to_string(),
+            |_, _, _| {
+                Ok(Response::new(
+                    Arc::new(Logger::new(std::path::PathBuf::from("server_logger.txt")).unwrap()),
+                    EstadoHttp::Ok,
+                    None,
+                ))
+            },
+        );
+
+        let params = endpoint
+            .matchea_con_patron("/repos/messi/contents/src/main.rs")
+            .unwrap();
+        assert_eq!(params.get("repo").unwrap(), "messi");
+        assert_eq!(params.get("path").unwrap(), "src/main.rs");
+
+        let params = endpoint
+            .matchea_con_patron("/repos/messi/contents/README")
+            .unwrap();
+        assert_eq!(params.get("path").unwrap(), "README");
+
+        let params = endpoint.matchea_con_patron("/repos/messi/contents/");
+        assert!(params.is_none());
+
+        let params = endpoint.matchea_con_patron("/repos/messi/contents");
+        assert!(params.is_none());
+
+        let params = endpoint.matchea_con_patron("/repos/messi/typo/README");
+        assert!(params.is_none());
+    }
 }
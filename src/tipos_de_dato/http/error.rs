@@ -5,10 +5,12 @@ pub enum ErrorHttp {
     NotFound(String),
     InternalServerError(String),
     ValidationFailed(String),
+    Unauthorized(String),
     Forbidden(String),
     BadRequest(String),
     Conflict(String),
     NotImplemented(String),
+    ServiceUnavailable(String),
 }
 
 impl ToString for ErrorHttp {
@@ -17,10 +19,12 @@ impl ToString for ErrorHttp {
             Self::NotFound(mensaje) => format!("404 Not Found: {}", mensaje),
             Self::InternalServerError(mensaje) => format!("500 Internal Server Error: {}", mensaje),
             Self::ValidationFailed(mensaje) => format!("422 Validation Failed: {}", mensaje),
+            Self::Unauthorized(mensaje) => format!("401 Unauthorized: {}", mensaje),
             Self::Forbidden(mensaje) => format!("403 Forbidden: {}", mensaje),
             Self::BadRequest(mensaje) => format!("400 Bad Request: {}", mensaje),
             Self::Conflict(mensaje) => format!("409 Conflict: {}", mensaje),
             Self::NotImplemented(mensaje) => format!("501 Not Implemented: {}", mensaje),
+            Self::ServiceUnavailable(mensaje) => format!("503 Service Unavailable: {}", mensaje),
         }
     }
 }
@@ -31,10 +35,12 @@ impl ErrorHttp {
             Self::NotFound(_) => EstadoHttp::NotFound,
             Self::InternalServerError(_) => EstadoHttp::InternalServerError,
             Self::ValidationFailed(_) => EstadoHttp::ValidationFailed,
+            Self::Unauthorized(_) => EstadoHttp::Unauthorized,
             Self::Forbidden(_) => EstadoHttp::Forbidden,
             Self::BadRequest(_) => EstadoHttp::BadRequest,
             Self::Conflict(_) => EstadoHttp::Conflict,
             Self::NotImplemented(_) => EstadoHttp::MethodNotAllowed,
+            Self::ServiceUnavailable(_) => EstadoHttp::ServiceUnavailable,
         }
     }
 
@@ -43,10 +49,12 @@ impl ErrorHttp {
             Self::NotFound(mensaje) => mensaje.to_string(),
             Self::InternalServerError(mensaje) => mensaje.to_string(),
             Self::ValidationFailed(mensaje) => mensaje.to_string(),
+            Self::Unauthorized(mensaje) => mensaje.to_string(),
             Self::Forbidden(mensaje) => mensaje.to_string(),
             Self::BadRequest(mensaje) => mensaje.to_string(),
             Self::Conflict(mensaje) => mensaje.to_string(),
             Self::NotImplemented(mensaje) => mensaje.to_string(),
+            Self::ServiceUnavailable(mensaje) => mensaje.to_string(),
         }
     }
 }
@@ -10,7 +10,9 @@ pub enum EstadoHttp {
     Created,
     Conflict,
     ValidationFailed,
+    Unauthorized,
     Forbidden,
+    ServiceUnavailable,
 }
 
 impl EstadoHttp {
@@ -25,7 +27,9 @@ impl EstadoHttp {
             EstadoHttp::BadRequest => (400, "Bad Request".to_string()),
             EstadoHttp::Created => (201, "Created".to_string()),
             EstadoHttp::ValidationFailed => (422, "Validacion Failed".to_string()),
+            EstadoHttp::Unauthorized => (401, "Unauthorized".to_string()),
             EstadoHttp::Forbidden => (403, "Forbidden".to_string()),
+            EstadoHttp::ServiceUnavailable => (503, "Service Unavailable".to_string()),
         }
     }
 }
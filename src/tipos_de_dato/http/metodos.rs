@@ -6,6 +6,7 @@ pub enum MetodoHttp {
     Post,
     Put,
     Patch,
+    Delete,
 }
 
 impl MetodoHttp {
@@ -15,6 +16,7 @@ impl MetodoHttp {
             "POST" => Ok(MetodoHttp::Post),
             "PUT" => Ok(MetodoHttp::Put),
             "PATCH" => Ok(MetodoHttp::Patch),
+            "DELETE" => Ok(MetodoHttp::Delete),
             _ => Err(ErrorHttp::Forbidden(
                 "El acceso ha sido denegado".to_string(),
             )),
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::Serialize;
+
+use super::{error::ErrorHttp, estado::EstadoHttp, request::Request, response::Response};
+use crate::tipos_de_dato::logger::Logger;
+
+const POR_PAGINA_POR_DEFECTO: usize = 30;
+const POR_PAGINA_MAXIMO: usize = 100;
+
+/// Pagina pedida de un listado con los parametros `page` y `per_page`, como en github
+#[derive(Debug, PartialEq, Eq)]
+pub struct Paginacion {
+    /// Numero de pagina, empezando en 1
+    pub pagina: usize,
+    /// Cantidad de elementos por pagina, como maximo `POR_PAGINA_MAXIMO`
+    pub por_pagina: usize,
+}
+
+impl Paginacion {
+    /// Obtiene la paginacion de los filtros del pedido. Si no estan se pide la primera pagina
+    /// de `POR_PAGINA_POR_DEFECTO` elementos
+    ///
+    /// ## Errores
+    /// - Si `page` o `per_page` no son numeros positivos
+    pub fn from_filtros(filtros: &HashMap<String, String>) -> Result<Self, ErrorHttp> {
+        let pagina = Self::obtener_numero(filtros, "page", 1)?;
+        let por_pagina = Self::obtener_numero(filtros, "per_page", POR_PAGINA_POR_DEFECTO)?
+            .min(POR_PAGINA_MAXIMO);
+        Ok(Self { pagina, por_pagina })
+    }
+
+    fn obtener_numero(
+        filtros: &HashMap<String, String>,
+        parametro: &str,
+        por_defecto: usize,
+    ) -> Result<usize, ErrorHttp> {
+        match filtros.get(parametro) {
+            Some(valor) => match valor.parse::<usize>() {
+                Ok(numero) if numero > 0 => Ok(numero),
+                _ => Err(ErrorHttp::ValidationFailed(format!(
+                    "El parametro {parametro} tiene que ser un numero positivo, no {valor}"
+                ))),
+            },
+            None => Ok(por_defecto),
+        }
+    }
+
+    /// Devuelve los elementos de la pagina
+    pub fn paginar<T>(&self, elementos: Vec<T>) -> Vec<T> {
+        elementos
+            .into_iter()
+            .skip((self.pagina - 1) * self.por_pagina)
+            .take(self.por_pagina)
+            .collect()
+    }
+
+    /// Devuelve la cantidad de paginas que ocupan `total` elementos. Siempre hay al menos una
+    pub fn cantidad_paginas(&self, total: usize) -> usize {
+        total.div_ceil(self.por_pagina).max(1)
+    }
+
+    /// Arma el header `Link` (RFC 5988) con las paginas `first`, `prev`, `next` y `last` que
+    /// correspondan a la pagina actual. Las urls mantienen el resto del query string del
+    /// pedido. Si todo entra en una pagina no hay header
+    pub fn obtener_header_link(&self, request: &Request, total: usize) -> Option<String> {
+        let ultima = self.cantidad_paginas(total);
+        if ultima == 1 {
+            return None;
+        }
+
+        let mut enlaces = Vec::new();
+        if self.pagina > 1 {
+            enlaces.push((1, "first"));
+            enlaces.push(((self.pagina - 1).min(ultima), "prev"));
+        }
+        if self.pagina < ultima {
+            enlaces.push((self.pagina + 1, "next"));
+            enlaces.push((ultima, "last"));
+        }
+
+        let enlaces: Vec<String> = enlaces
+            .iter()
+            .map(|(pagina, relacion)| {
+                format!("<{}>; rel=\"{relacion}\"", self.armar_url(request, *pagina))
+            })
+            .collect();
+        Some(enlaces.join(", "))
+    }
+
+    fn armar_url(&self, request: &Request, pagina: usize) -> String {
+        let mut query = request.query.clone();
+        query.insert("page".to_string(), pagina.to_string());
+        query.insert("per_page".to_string(), self.por_pagina.to_string());
+        let mut parametros: Vec<(String, String)> = query.into_iter().collect();
+        parametros.sort();
+
+        let query_string: Vec<String> = parametros
+            .iter()
+            .map(|(clave, valor)| format!("{}={}", codificar_url(clave), codificar_url(valor)))
+            .collect();
+        let base = match request.obtener_header("Host") {
+            Some(host) => format!("http://{host}{}", request.ruta),
+            None => request.ruta.clone(),
+        };
+        format!("{base}?{}", query_string.join("&"))
+    }
+}
+
+/// Ordena los elementos segun los parametros `sort` (`created` o `updated`) y `direction`
+/// (`asc` o `desc`) de los filtros. Por defecto se ordena por fecha de creacion en la
+/// `direccion_por_defecto`.
+///
+/// ## Argumentos
+/// - fechas: devuelve las fechas de creacion y de modificacion de un elemento, en rfc3339
+///
+/// ## Errores
+/// - Si `sort` o `direction` no son validos
+pub fn ordenar_por_fecha<T>(
+    elementos: &mut [T],
+    filtros: &HashMap<String, String>,
+    direccion_por_defecto: &str,
+    fechas: impl Fn(&T) -> (&str, &str),
+) -> Result<(), ErrorHttp> {
+    let por_modificacion = match filtros.get("sort").map(|orden| orden.as_str()) {
+        None | Some("created") => false,
+        Some("updated") => true,
+        Some(orden) => {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "No se puede ordenar por {orden}: tiene que ser `created` o `updated`"
+            )))
+        }
+    };
+    let descendente = match filtros
+        .get("direction")
+        .map(|direccion| direccion.as_str())
+        .unwrap_or(direccion_por_defecto)
+    {
+        "asc" => false,
+        "desc" => true,
+        direccion => {
+            return Err(ErrorHttp::ValidationFailed(format!(
+                "La direccion {direccion} no es valida: tiene que ser `asc` o `desc`"
+            )))
+        }
+    };
+
+    elementos.sort_by(|a, b| {
+        let (fecha_a, fecha_b) = if por_modificacion {
+            (fechas(a).1, fechas(b).1)
+        } else {
+            (fechas(a).0, fechas(b).0)
+        };
+        if descendente {
+            fecha_b.cmp(fecha_a)
+        } else {
+            fecha_a.cmp(fecha_b)
+        }
+    });
+    Ok(())
+}
+
+/// Responde la pagina pedida del listado en formato json, con el header `Link` para navegar
+/// las demas paginas
+pub fn responder_paginado<T: Serialize>(
+    request: &Request,
+    elementos: Vec<T>,
+    logger: Arc<Logger>,
+) -> Result<Response, ErrorHttp> {
+    let paginacion = Paginacion::from_filtros(&request.obtener_filtros())?;
+    let total = elementos.len();
+    let pagina = paginacion.paginar(elementos);
+
+    let body_respuesta = serde_json::to_string(&pagina).map_err(|e| {
+        ErrorHttp::InternalServerError(format!("No se ha podido serializar el listado: {}", e))
+    })?;
+    let mut respuesta = Response::new(logger, EstadoHttp::Ok, Some(&body_respuesta));
+    if let Some(link) = paginacion.obtener_header_link(request, total) {
+        respuesta.headers.insert("Link".to_string(), link);
+    }
+    Ok(respuesta)
+}
+
+/// Codifica un componente de una url, dejando solo los caracteres no reservados
+fn codificar_url(componente: &str) -> String {
+    componente
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::BufReader, path::PathBuf};
+
+    use crate::utils::testing::MockTcpStream;
+
+    use super::*;
+
+    fn request_get(ruta: &str) -> Request {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/paginacion_test")).unwrap());
+        let mut mock_tcp = MockTcpStream {
+            lectura_data: format!("GET {ruta} HTTP/1.1\nHost: localhost:8000\n\r\n").into_bytes(),
+            escritura_data: vec![],
+        };
+        let mut reader = BufReader::new(&mut mock_tcp);
+        Request::from(&mut reader, logger).unwrap()
+    }
+
+    #[test]
+    fn test01_se_devuelve_la_pagina_pedida() {
+        let request = request_get("/repos/repo/pulls?per_page=2&page=2");
+        let paginacion = Paginacion::from_filtros(&request.obtener_filtros()).unwrap();
+
+        let pagina = paginacion.paginar((1..=5).collect::<Vec<u32>>());
+        let fuera_de_rango = paginacion.paginar(vec![1, 2]);
+
+        assert_eq!(pagina, vec![3, 4]);
+        assert!(fuera_de_rango.is_empty());
+        assert_eq!(paginacion.cantidad_paginas(5), 3);
+        assert!(Paginacion::from_filtros(&request_get("/x?page=0").query).is_err());
+    }
+
+    #[test]
+    fn test02_el_header_link_tiene_las_paginas_vecinas_y_conserva_los_filtros() {
+        let request = request_get("/repos/repo/pulls?state=open&per_page=2&page=2");
+        let paginacion = Paginacion::from_filtros(&request.query).unwrap();
+
+        let link = paginacion.obtener_header_link(&request, 5).unwrap();
+        let una_sola_pagina = paginacion.obtener_header_link(&request, 2);
+
+        assert_eq!(
+            link,
+            "<http://localhost:8000/repos/repo/pulls?page=1&per_page=2&state=open>; rel=\"first\", \
+             <http://localhost:8000/repos/repo/pulls?page=1&per_page=2&state=open>; rel=\"prev\", \
+             <http://localhost:8000/repos/repo/pulls?page=3&per_page=2&state=open>; rel=\"next\", \
+             <http://localhost:8000/repos/repo/pulls?page=3&per_page=2&state=open>; rel=\"last\""
+        );
+        assert_eq!(una_sola_pagina, None);
+    }
+
+    #[test]
+    fn test03_se_ordena_por_fecha_segun_los_filtros() {
+        let mut elementos = vec![("2023-01-02", "2023-01-05"), ("2023-01-01", "2023-01-09")];
+        let mut filtros = HashMap::new();
+
+        ordenar_por_fecha(&mut elementos, &filtros, "desc", |e| (e.0, e.1)).unwrap();
+        let por_creacion = elementos.clone();
+        filtros.insert("sort".to_string(), "updated".to_string());
+        ordenar_por_fecha(&mut elementos, &filtros, "desc", |e| (e.0, e.1)).unwrap();
+        let por_modificacion = elementos.clone();
+        filtros.insert("sort".to_string(), "popularity".to_string());
+
+        assert_eq!(por_creacion[0].0, "2023-01-02");
+        assert_eq!(por_modificacion[0].0, "2023-01-01");
+        assert!(ordenar_por_fecha(&mut elementos, &filtros, "desc", |e| (e.0, e.1)).is_err());
+    }
+}
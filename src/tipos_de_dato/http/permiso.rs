@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Nivel de acceso de un usuario a un repositorio del servidor.
+/// Cada nivel incluye a los anteriores: quien puede escribir tambien puede leer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Permiso {
+    /// No puede acceder al repositorio
+    #[default]
+    #[serde(rename = "none")]
+    Ninguno,
+    /// Puede consultar el repositorio y sus pull requests
+    #[serde(rename = "read")]
+    Lectura,
+    /// Puede crear, actualizar y mergear pull requests
+    #[serde(rename = "write")]
+    Escritura,
+    /// Puede administrar el repositorio
+    #[serde(rename = "admin")]
+    Admin,
+}
@@ -6,14 +6,27 @@ use std::{
 };
 
 use super::{error::ErrorHttp, metodos::MetodoHttp, tipo_contenido::TipoContenido};
-use crate::tipos_de_dato::logger::Logger;
+use crate::{servidor::repos_almacen::ReposAlmacen, tipos_de_dato::logger::Logger};
+
+/// Largo maximo del body de una request, para no reservar memoria de mas por un
+/// `Content-Length` o un tamanio de chunk enorme
+const LARGO_MAXIMO_BODY: usize = 10 * 1024 * 1024;
+
 pub struct Request {
     pub metodo: MetodoHttp,
+    /// Ruta del pedido, sin el query string
     pub ruta: String,
+    /// Parametros del query string (`?clave=valor&...`), ya decodificados
+    pub query: HashMap<String, String>,
     pub version: String,
     pub headers: HashMap<String, String>,
     pub body: Option<HashMap<String, String>>,
     pub logger: Arc<Logger>,
+    /// Usuario autenticado que hizo el pedido, si se autentico
+    pub usuario: Option<String>,
+    /// Locks de los repositorios del servidor, para las rutas que ademas del lock de su
+    /// repositorio necesitan el de otro
+    pub repos_almacen: Option<ReposAlmacen>,
 }
 
 impl Request {
@@ -45,8 +58,8 @@ impl Request {
     fn obtener_headers_contenido(
         headers: &HashMap<String, String>,
     ) -> Result<Option<(usize, TipoContenido)>, ErrorHttp> {
-        let option_largo = headers.get("Content-Length");
-        let option_tipo = headers.get("Content-Type");
+        let option_largo = buscar_header(headers, "Content-Length");
+        let option_tipo = buscar_header(headers, "Content-Type");
         if option_largo.is_none() && option_tipo.is_none() {
             return Ok(None);
         }
@@ -55,6 +68,11 @@ impl Request {
         if largo == 0 {
             return Ok(None);
         }
+        if largo > LARGO_MAXIMO_BODY {
+            return Err(ErrorHttp::BadRequest(format!(
+                "El body no puede tener mas de {LARGO_MAXIMO_BODY} bytes"
+            )));
+        }
 
         let tipo = Self::parsear_header_tipo(option_tipo)?;
 
@@ -66,6 +84,7 @@ impl Request {
         T: Read + Write,
     {
         let (metodo, ruta, version) = Self::obtener_primera_linea(reader)?;
+        let (ruta, query) = Self::separar_query(&ruta);
 
         let metodo = MetodoHttp::from_string(&metodo)?;
 
@@ -75,13 +94,58 @@ impl Request {
         Ok(Self {
             metodo,
             ruta,
+            query,
             version,
             headers,
             body,
             logger,
+            usuario: None,
+            repos_almacen: None,
         })
     }
 
+    /// Devuelve si el cliente quiere seguir usando la conexion despues de esta request. En
+    /// HTTP/1.1 la conexion se mantiene salvo que se pida `Connection: close`, y en HTTP/1.0
+    /// solo si se pide `Connection: keep-alive`
+    pub fn mantener_conexion(&self) -> bool {
+        match self.obtener_header("Connection") {
+            Some(conexion) if conexion.eq_ignore_ascii_case("close") => false,
+            Some(conexion) if conexion.eq_ignore_ascii_case("keep-alive") => true,
+            _ => self.version == "HTTP/1.1",
+        }
+    }
+
+    /// Devuelve los filtros del pedido: los parametros del query string y los del body, que
+    /// se siguen aceptando para los clientes que los mandan ahi. Si un parametro esta en los
+    /// dos se usa el del query string
+    pub fn obtener_filtros(&self) -> HashMap<String, String> {
+        let mut filtros = self.body.clone().unwrap_or_default();
+        filtros.extend(self.query.clone());
+        filtros
+    }
+
+    /// Separa la ruta de su query string
+    ///
+    /// ## Ejemplo
+    /// - recibe: `/repos/repo/pulls?state=open&per_page=2`
+    /// - devuelve: (`/repos/repo/pulls`, {state: open, per_page: 2})
+    fn separar_query(ruta_completa: &str) -> (String, HashMap<String, String>) {
+        let (ruta, query_string) = match ruta_completa.split_once('?') {
+            Some((ruta, query_string)) => (ruta, query_string),
+            None => return (ruta_completa.to_string(), HashMap::new()),
+        };
+
+        let query = query_string
+            .split('&')
+            .filter(|parametro| !parametro.is_empty())
+            .map(|parametro| {
+                let (clave, valor) = parametro.split_once('=').unwrap_or((parametro, ""));
+                (decodificar_url(clave), decodificar_url(valor))
+            })
+            .collect();
+        (ruta.to_string(), query)
+    }
+
     fn obtener_headers<T>(
         reader: &mut BufReader<&mut T>,
     ) -> Result<HashMap<String, String>, ErrorHttp>
@@ -92,7 +156,14 @@ impl Request {
 
         loop {
             let mut line = String::new();
-            reader.read_line(&mut line).unwrap();
+            let leidos = reader
+                .read_line(&mut line)
+                .map_err(|e| ErrorHttp::BadRequest(format!("Error leyendo headers: {e}")))?;
+            if leidos == 0 {
+                return Err(ErrorHttp::BadRequest(
+                    "Se cerro la conexion antes de terminar los headers".to_string(),
+                ));
+            }
             if line == "\r\n" {
                 break;
             }
@@ -110,6 +181,11 @@ impl Request {
         Ok(headers)
     }
 
+    /// Devuelve el valor del header `nombre`, sin importar mayusculas y minusculas en su nombre
+    pub fn obtener_header(&self, nombre: &str) -> Option<&String> {
+        buscar_header(&self.headers, nombre)
+    }
+
     fn obtener_primera_linea<T>(
         reader: &mut BufReader<&mut T>,
     ) -> Result<(String, String, String), ErrorHttp>
@@ -143,28 +219,156 @@ impl Request {
     where
         T: Read + Write,
     {
-        let headers = Self::obtener_headers_contenido(headers)?;
-
-        let (largo, tipo) = match headers {
-            Some((largo, tipo)) => (largo, tipo),
-            None => return Ok(None),
+        let (body_buf, tipo) = if Self::tiene_body_en_chunks(headers) {
+            let tipo = Self::parsear_header_tipo(buscar_header(headers, "Content-Type"))?;
+            Self::responder_continuar(reader, headers)?;
+            (Self::leer_body_en_chunks(reader)?, tipo)
+        } else {
+            let (largo, tipo) = match Self::obtener_headers_contenido(headers)? {
+                Some((largo, tipo)) => (largo, tipo),
+                None => return Ok(None),
+            };
+            Self::responder_continuar(reader, headers)?;
+            let mut body_buf = vec![0; largo];
+            Self::leer_exacto(reader, &mut body_buf)?;
+            (body_buf, tipo)
         };
 
-        let mut body_buf = vec![0; largo];
-        let leidos = reader
-            .read(&mut body_buf)
-            .map_err(|e| ErrorHttp::InternalServerError(e.to_string()))?;
+        if body_buf.is_empty() {
+            return Ok(None);
+        }
+        let body = tipo.parsear_contenido(&body_buf)?;
+
+        Ok(Some(body))
+    }
 
-        if leidos != largo {
+    fn tiene_body_en_chunks(headers: &HashMap<String, String>) -> bool {
+        buscar_header(headers, "Transfer-Encoding")
+            .is_some_and(|transferencia| transferencia.eq_ignore_ascii_case("chunked"))
+    }
+
+    /// Si el cliente mando `Expect: 100-continue` le responde `100 Continue` para que envie el
+    /// body
+    fn responder_continuar<T>(
+        reader: &mut BufReader<&mut T>,
+        headers: &HashMap<String, String>,
+    ) -> Result<(), ErrorHttp>
+    where
+        T: Read + Write,
+    {
+        let espera_continuar = buscar_header(headers, "Expect")
+            .is_some_and(|expect| expect.eq_ignore_ascii_case("100-continue"));
+        if !espera_continuar {
+            return Ok(());
+        }
+
+        let stream = reader.get_mut();
+        stream
+            .write_all(b"HTTP/1.1 100 Continue\r\n\r\n")
+            .and_then(|_| stream.flush())
+            .map_err(|e| {
+                ErrorHttp::InternalServerError(format!("Error al enviar 100 Continue: {e}"))
+            })
+    }
+
+    /// Lee un body enviado con `Transfer-Encoding: chunked`. Cada chunk empieza con una linea con
+    /// su tamanio en hexadecimal y el ultimo tiene tamanio 0, seguido de trailers opcionales que
+    /// se descartan
+    fn leer_body_en_chunks<T>(reader: &mut BufReader<&mut T>) -> Result<Vec<u8>, ErrorHttp>
+    where
+        T: Read + Write,
+    {
+        let mut body = Vec::new();
+        loop {
+            let linea = Self::leer_linea(reader)?;
+            let tamanio_hexa = linea.split(';').next().unwrap_or_default().trim();
+            let tamanio = usize::from_str_radix(tamanio_hexa, 16).map_err(|_| {
+                ErrorHttp::BadRequest(format!("Tamanio de chunk invalido: {tamanio_hexa}"))
+            })?;
+            if tamanio == 0 {
+                break;
+            }
+            if body.len() + tamanio > LARGO_MAXIMO_BODY {
+                return Err(ErrorHttp::BadRequest(format!(
+                    "El body no puede tener mas de {LARGO_MAXIMO_BODY} bytes"
+                )));
+            }
+
+            let mut chunk = vec![0; tamanio];
+            Self::leer_exacto(reader, &mut chunk)?;
+            body.extend(chunk);
+            if !Self::leer_linea(reader)?.is_empty() {
+                return Err(ErrorHttp::BadRequest(
+                    "El chunk es mas largo que su tamanio".to_string(),
+                ));
+            }
+        }
+
+        while !Self::leer_linea(reader)?.is_empty() {}
+        Ok(body)
+    }
+
+    /// Lee una linea sin su fin de linea. Falla si se cerro la conexion
+    fn leer_linea<T>(reader: &mut BufReader<&mut T>) -> Result<String, ErrorHttp>
+    where
+        T: Read + Write,
+    {
+        let mut linea = String::new();
+        let leidos = reader
+            .read_line(&mut linea)
+            .map_err(|e| ErrorHttp::BadRequest(format!("Error leyendo el body: {e}")))?;
+        if leidos == 0 {
             return Err(ErrorHttp::BadRequest(
-                "No se pudo leer el body completo".to_string(),
+                "Se cerro la conexion antes de terminar el body".to_string(),
             ));
         }
+        Ok(linea.trim_end_matches(['\r', '\n']).to_string())
+    }
 
-        let body = tipo.parsear_contenido(&body_buf)?;
+    fn leer_exacto<T>(reader: &mut BufReader<&mut T>, buf: &mut [u8]) -> Result<(), ErrorHttp>
+    where
+        T: Read + Write,
+    {
+        reader
+            .read_exact(buf)
+            .map_err(|_| ErrorHttp::BadRequest("No se pudo leer el body completo".to_string()))
+    }
+}
 
-        Ok(Some(body))
+/// Devuelve el valor del header `nombre`, sin importar mayusculas y minusculas en su nombre
+fn buscar_header<'a>(headers: &'a HashMap<String, String>, nombre: &str) -> Option<&'a String> {
+    headers
+        .iter()
+        .find(|(clave, _)| clave.eq_ignore_ascii_case(nombre))
+        .map(|(_, valor)| valor)
+}
+
+/// Decodifica un componente de una url: los `+` son espacios y los `%XX` son bytes en
+/// hexadecimal. Si una secuencia `%` no es valida se deja como esta
+fn decodificar_url(componente: &str) -> String {
+    let bytes = componente.as_bytes();
+    let mut decodificado = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decodificado.push(b' '),
+            b'%' => {
+                let byte = componente
+                    .get(i + 1..i + 3)
+                    .and_then(|hexa| u8::from_str_radix(hexa, 16).ok());
+                match byte {
+                    Some(byte) => {
+                        decodificado.push(byte);
+                        i += 2;
+                    }
+                    None => decodificado.push(b'%'),
+                }
+            }
+            byte => decodificado.push(byte),
+        }
+        i += 1;
     }
+    String::from_utf8_lossy(&decodificado).to_string()
 }
 
 impl Debug for Request {
@@ -172,9 +376,11 @@ impl Debug for Request {
         f.debug_struct("HttpRequest")
             .field("metodo", &self.metodo)
             .field("ruta", &self.ruta)
+            .field("query", &self.query)
             .field("version", &self.version)
             .field("headers", &self.headers)
             .field("body", &self.body)
+            .field("usuario", &self.usuario)
             .finish()
     }
 }
@@ -270,6 +476,30 @@ mod tests {
         assert_eq!(request.body, None);
     }
 
+    #[test]
+    fn test06_from_separa_el_query_string_de_la_ruta() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/request_test06")).unwrap());
+
+        let mut mock_tcp = MockTcpStream {
+            lectura_data: b"GET /repos/repo/pulls?state=open&base=rama%2Fnueva&labels=bug+urgente&per_page=2 HTTP/1.1\nHost: localhost:8000\n\r\n".to_vec(),
+            escritura_data: vec![],
+        };
+
+        let mut reader = BufReader::new(&mut mock_tcp);
+
+        let request = Request::from(&mut reader, logger).unwrap();
+
+        let mut query = HashMap::new();
+        query.insert("state".to_string(), "open".to_string());
+        query.insert("base".to_string(), "rama/nueva".to_string());
+        query.insert("labels".to_string(), "bug urgente".to_string());
+        query.insert("per_page".to_string(), "2".to_string());
+
+        assert_eq!(request.ruta, "/repos/repo/pulls".to_string());
+        assert_eq!(request.query, query);
+        assert_eq!(request.obtener_filtros(), query);
+    }
+
     #[test]
     #[should_panic]
     fn test03_from_sin_content_type_panickea() {
@@ -313,4 +543,44 @@ mod tests {
 
         Request::from(&mut reader, logger).unwrap();
     }
+    #[test]
+    fn test07_from_lee_el_body_en_chunks_y_responde_continue() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/request_test07")).unwrap());
+
+        let mut mock_tcp = MockTcpStream {
+            lectura_data: b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\nContent-Type: application/json\r\nExpect: 100-continue\r\n\r\n9;nombre=valor\r\n{\"title\":\r\n8\r\n\"titulo\"\r\n1\r\n}\r\n0\r\nTrailer: ignorado\r\n\r\nGET / HTTP/1.1\r\n\r\n".to_vec(),
+            escritura_data: vec![],
+        };
+
+        let mut reader = BufReader::new(&mut mock_tcp);
+        let request = Request::from(&mut reader, logger.clone()).unwrap();
+        let siguiente = Request::from(&mut reader, logger).unwrap();
+
+        let mut body = HashMap::new();
+        body.insert("title".to_string(), "titulo".to_string());
+        assert_eq!(request.body.unwrap(), body);
+        assert_eq!(siguiente.metodo, MetodoHttp::Get);
+        assert_eq!(mock_tcp.escritura_data, b"HTTP/1.1 100 Continue\r\n\r\n");
+    }
+
+    #[test]
+    fn test08_mantener_conexion_depende_de_la_version_y_del_header_connection() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/request_test08")).unwrap());
+        let requests: [(&[u8], bool); 4] = [
+            (b"GET / HTTP/1.1\r\n\r\n", true),
+            (b"GET / HTTP/1.1\r\nconnection: close\r\n\r\n", false),
+            (b"GET / HTTP/1.0\r\n\r\n", false),
+            (b"GET / HTTP/1.0\r\nConnection: Keep-Alive\r\n\r\n", true),
+        ];
+
+        for (contenido, mantener_conexion) in requests {
+            let mut mock_tcp = MockTcpStream {
+                lectura_data: contenido.to_vec(),
+                escritura_data: vec![],
+            };
+            let mut reader = BufReader::new(&mut mock_tcp);
+            let request = Request::from(&mut reader, logger.clone()).unwrap();
+            assert_eq!(request.mantener_conexion(), mantener_conexion);
+        }
+    }
 }
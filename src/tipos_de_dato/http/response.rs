@@ -6,6 +6,13 @@ use std::{
 
 use super::{error::ErrorHttp, estado::EstadoHttp};
 use crate::tipos_de_dato::logger::Logger;
+
+const HEADER_LARGO: &str = "Content-Length";
+const HEADER_TRANSFERENCIA: &str = "Transfer-Encoding";
+const HEADER_CONEXION: &str = "Connection";
+/// Tamanio maximo de cada chunk cuando el body se envia con `Transfer-Encoding: chunked`
+const TAMANIO_CHUNK: usize = 8192;
+
 #[derive(Debug)]
 pub struct Response {
     pub estado: usize,
@@ -21,7 +28,15 @@ impl Response {
         let msj_error = error.obtener_mensaje();
         let body = format!(r#"{{"message":"{}"}}"#, msj_error);
 
-        Response::new(logger.clone(), error.obtener_estado(), Some(&body))
+        let mut response = Response::new(logger.clone(), error.obtener_estado(), Some(&body));
+        if let ErrorHttp::Unauthorized(_) = error {
+            // le indica al cliente como autenticarse
+            response.headers.insert(
+                "WWW-Authenticate".to_string(),
+                r#"Basic realm="gir""#.to_string(),
+            );
+        }
+        response
     }
 
     pub fn new(logger: Arc<Logger>, estado: EstadoHttp, body: Option<&str>) -> Self {
@@ -29,7 +44,7 @@ impl Response {
         let mut headers: HashMap<String, String> = HashMap::with_hasher(s);
 
         if let Some(body) = &body {
-            headers.insert("Content-Lenght".to_string(), body.len().to_string());
+            headers.insert(HEADER_LARGO.to_string(), body.len().to_string());
             headers.insert("Content-Type".to_string(), "application/json".to_string());
         }
 
@@ -45,6 +60,61 @@ impl Response {
         }
     }
 
+    /// Hace que el body se envie en chunks (`Transfer-Encoding: chunked`) en lugar de con su
+    /// largo. Sirve para las respuestas grandes, como los diffs, que el cliente puede ir
+    /// procesando a medida que llegan
+    pub fn con_transferencia_en_chunks(mut self) -> Self {
+        if self.body.is_some() {
+            self.headers.remove(HEADER_LARGO);
+            self.headers
+                .insert(HEADER_TRANSFERENCIA.to_string(), "chunked".to_string());
+        }
+        self
+    }
+
+    /// Devuelve si el body se envia con `Transfer-Encoding: chunked`
+    pub fn es_en_chunks(&self) -> bool {
+        self.headers
+            .get(HEADER_TRANSFERENCIA)
+            .is_some_and(|transferencia| transferencia == "chunked")
+    }
+
+    /// Agrega los headers que le indican al cliente si la conexion sigue abierta despues de
+    /// esta respuesta y donde termina el body.
+    ///
+    /// ## Argumentos
+    /// - version: version de HTTP de la request. Si no es `HTTP/1.1` el body no se puede
+    ///   enviar en chunks y se envia con su largo
+    /// - mantener_conexion: si la conexion se mantiene abierta (`keep-alive`) o se cierra
+    pub fn preparar_para_conexion(&mut self, version: &str, mantener_conexion: bool) {
+        if self.es_en_chunks() && version != "HTTP/1.1" {
+            self.headers.remove(HEADER_TRANSFERENCIA);
+            let largo = self.body.as_ref().map_or(0, |body| body.len());
+            self.headers
+                .insert(HEADER_LARGO.to_string(), largo.to_string());
+        }
+        // sin largo el cliente no sabe que la respuesta termino hasta que se cierra la conexion
+        if self.body.is_none() && self.estado != 204 && self.estado != 304 {
+            self.headers
+                .insert(HEADER_LARGO.to_string(), "0".to_string());
+        }
+
+        let conexion = if mantener_conexion {
+            "keep-alive"
+        } else {
+            "close"
+        };
+        self.headers
+            .insert(HEADER_CONEXION.to_string(), conexion.to_string());
+    }
+
+    /// Devuelve si despues de enviar la respuesta la conexion sigue abierta
+    pub fn mantiene_conexion(&self) -> bool {
+        self.headers
+            .get(HEADER_CONEXION)
+            .is_some_and(|conexion| conexion == "keep-alive")
+    }
+
     pub fn enviar<T>(&self, stream: &mut T) -> Result<(), ErrorHttp>
     where
         T: Read + Write,
@@ -54,23 +124,42 @@ impl Response {
             version = self.version,
             estado = self.estado,
             mensaje_estado = self.mensaje_estado
-        );
+        )
+        .into_bytes();
 
         for (key, value) in &self.headers {
-            response.push_str(&format!("{}: {}\r\n", key, value));
+            response.extend(format!("{}: {}\r\n", key, value).as_bytes());
         }
-        response.push_str("\r\n");
+        response.extend(b"\r\n");
 
         if let Some(body) = &self.body {
-            response.push_str(body);
+            if self.es_en_chunks() {
+                Self::agregar_chunks(&mut response, body.as_bytes());
+            } else {
+                response.extend(body.as_bytes());
+            }
         }
 
-        stream.write_all(response.as_bytes()).map_err(|e| {
-            ErrorHttp::InternalServerError(format!("Error al enviar la respuesta: {}", e))
-        })?;
+        stream
+            .write_all(&response)
+            .and_then(|_| stream.flush())
+            .map_err(|e| {
+                ErrorHttp::InternalServerError(format!("Error al enviar la respuesta: {}", e))
+            })?;
 
         Ok(())
     }
+
+    /// Agrega el body en chunks de a lo sumo `TAMANIO_CHUNK` bytes, cada uno precedido por su
+    /// tamanio en hexadecimal, y termina con el chunk vacio
+    fn agregar_chunks(response: &mut Vec<u8>, body: &[u8]) {
+        for chunk in body.chunks(TAMANIO_CHUNK) {
+            response.extend(format!("{:X}\r\n", chunk.len()).as_bytes());
+            response.extend(chunk);
+            response.extend(b"\r\n");
+        }
+        response.extend(b"0\r\n\r\n");
+    }
 }
 
 #[cfg(test)]
@@ -117,7 +206,7 @@ mod test {
         let verison_esperada = "HTTP/1.1".to_string();
         let mut header_esperado = HashMap::new();
         header_esperado.insert(
-            "Content-Lenght".to_string(),
+            "Content-Length".to_string(),
             contenido_body.len().to_string(),
         );
         header_esperado.insert("Content-Type".to_string(), "application/json".to_string());
@@ -187,7 +276,7 @@ mod test {
             "\
         Content-Type: application/json\r\n"
         );
-        let header_esperado2 = format!("Content-Lenght: {}\r\n", contenido_body.len());
+        let header_esperado2 = format!("Content-Length: {}\r\n", contenido_body.len());
         let body_esperado = format!("\r\n{}", contenido_body);
 
         assert!(String::from_utf8_lossy(&mock_tcp.escritura_data)
@@ -215,7 +304,7 @@ mod test {
         let verison_esperada = "HTTP/1.1".to_string();
         let mut header_esperado = HashMap::new();
         header_esperado.insert(
-            "Content-Lenght".to_string(),
+            "Content-Length".to_string(),
             body_esperado.len().to_string(),
         );
         header_esperado.insert("Content-Type".to_string(), "application/json".to_string());
@@ -228,4 +317,45 @@ mod test {
         assert_eq!(response.headers, header_esperado);
         assert_eq!(response.body, Some(body_esperado));
     }
+    #[test]
+    fn test_06_se_envia_el_body_en_chunks() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/response_test06")).unwrap());
+        let mut mock_tcp = MockTcpStream {
+            lectura_data: vec![],
+            escritura_data: vec![],
+        };
+        let body = "a".repeat(8200);
+
+        let response =
+            Response::new(logger, EstadoHttp::Ok, Some(&body)).con_transferencia_en_chunks();
+        response.enviar(&mut mock_tcp).unwrap();
+
+        let enviado = String::from_utf8_lossy(&mock_tcp.escritura_data).to_string();
+        let body_esperado = format!(
+            "\r\n\r\n2000\r\n{}\r\n8\r\n{}\r\n0\r\n\r\n",
+            "a".repeat(8192),
+            "a".repeat(8)
+        );
+        assert!(response.es_en_chunks());
+        assert!(!enviado.contains("Content-Length"));
+        assert!(enviado.contains("Transfer-Encoding: chunked\r\n"));
+        assert!(enviado.ends_with(&body_esperado));
+    }
+
+    #[test]
+    fn test_07_se_preparan_los_headers_de_la_conexion() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/response_test07")).unwrap());
+
+        let mut sin_body = Response::new(logger.clone(), EstadoHttp::Ok, None);
+        sin_body.preparar_para_conexion("HTTP/1.1", true);
+        let mut en_chunks =
+            Response::new(logger, EstadoHttp::Ok, Some("body")).con_transferencia_en_chunks();
+        en_chunks.preparar_para_conexion("HTTP/1.0", false);
+
+        assert!(sin_body.mantiene_conexion());
+        assert_eq!(sin_body.headers.get("Content-Length").unwrap(), "0");
+        assert!(!en_chunks.mantiene_conexion());
+        assert!(!en_chunks.es_en_chunks());
+        assert_eq!(en_chunks.headers.get("Content-Length").unwrap(), "4");
+    }
 }
@@ -0,0 +1,215 @@
+use sha1::{Digest, Sha1};
+
+use crate::{tipos_de_dato::objetos::tree::Tree, utils::io};
+
+const FIRMA_IDX: [u8; 4] = [0xff, b't', b'O', b'c'];
+const VERSION_IDX: u32 = 2;
+const TAMANIO_FANOUT: usize = 256 * 4;
+const TAMANIO_HASH: usize = 20;
+// Si el bit mas significativo del offset esta prendido, el resto es un indice a la tabla de offsets de 64 bits
+const BIT_OFFSET_GRANDE: u32 = 0x8000_0000;
+
+/// Datos de un objeto dentro de un packfile necesarios para indexarlo
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntradaIndicePack {
+    /// Hash del objeto
+    pub hash: String,
+    /// Offset del objeto dentro del packfile
+    pub offset: u64,
+    /// CRC32 de la entrada del objeto en el packfile (header y datos comprimidos)
+    pub crc: u32,
+}
+
+/// Indice (`.idx`) de un packfile en el formato version 2 de git.
+/// Permite encontrar en que offset del packfile esta un objeto sin recorrer el pack entero.
+pub struct IndicePack {
+    /// Entradas ordenadas por hash
+    entradas: Vec<EntradaIndicePack>,
+    /// Checksum del packfile al que pertenece el indice
+    checksum_pack: Vec<u8>,
+}
+
+impl IndicePack {
+    /// Crea el indice de un packfile a partir de sus entradas y del checksum del pack
+    /// (los ultimos 20 bytes del packfile)
+    pub fn new(mut entradas: Vec<EntradaIndicePack>, checksum_pack: &[u8]) -> IndicePack {
+        entradas.sort_by(|a, b| a.hash.cmp(&b.hash));
+        IndicePack {
+            entradas,
+            checksum_pack: checksum_pack.to_vec(),
+        }
+    }
+
+    /// Lee el indice ubicado en `ruta`
+    pub fn leer(ruta: &str) -> Result<IndicePack, String> {
+        let bytes = io::leer_bytes(ruta)?;
+        Self::from_bytes(&bytes).map_err(|e| format!("Indice {} invalido: {}", ruta, e))
+    }
+
+    /// Parsea el contenido de un `.idx` version 2
+    pub fn from_bytes(bytes: &[u8]) -> Result<IndicePack, String> {
+        if bytes.len() < 8 + TAMANIO_FANOUT + 2 * TAMANIO_HASH
+            || bytes[..4] != FIRMA_IDX
+            || Self::leer_u32(bytes, 4) != VERSION_IDX
+        {
+            return Err("no es un indice version 2".to_string());
+        }
+        Self::verificar_checksum(bytes)?;
+
+        let cantidad = Self::leer_u32(bytes, 8 + TAMANIO_FANOUT - 4) as usize;
+        let inicio_hashes = 8 + TAMANIO_FANOUT;
+        let inicio_crcs = inicio_hashes + cantidad * TAMANIO_HASH;
+        let inicio_offsets = inicio_crcs + cantidad * 4;
+        let inicio_offsets_grandes = inicio_offsets + cantidad * 4;
+        if inicio_offsets_grandes + 2 * TAMANIO_HASH > bytes.len() {
+            return Err("el indice esta truncado".to_string());
+        }
+
+        let mut entradas = Vec::with_capacity(cantidad);
+        for i in 0..cantidad {
+            let inicio_hash = inicio_hashes + i * TAMANIO_HASH;
+            let hash = Tree::encode_hex(&bytes[inicio_hash..inicio_hash + TAMANIO_HASH]);
+            let crc = Self::leer_u32(bytes, inicio_crcs + i * 4);
+            let offset = Self::leer_u32(bytes, inicio_offsets + i * 4);
+            let offset = if offset & BIT_OFFSET_GRANDE != 0 {
+                let posicion = inicio_offsets_grandes + (offset & !BIT_OFFSET_GRANDE) as usize * 8;
+                if posicion + 8 > bytes.len() - 2 * TAMANIO_HASH {
+                    return Err("offset de 64 bits fuera del indice".to_string());
+                }
+                let mut offset_grande = [0u8; 8];
+                offset_grande.copy_from_slice(&bytes[posicion..posicion + 8]);
+                u64::from_be_bytes(offset_grande)
+            } else {
+                offset as u64
+            };
+            entradas.push(EntradaIndicePack { hash, offset, crc });
+        }
+
+        let fin_checksum_pack = bytes.len() - TAMANIO_HASH;
+        Ok(IndicePack {
+            entradas,
+            checksum_pack: bytes[fin_checksum_pack - TAMANIO_HASH..fin_checksum_pack].to_vec(),
+        })
+    }
+
+    /// Serializa el indice en el formato `.idx` version 2
+    pub fn a_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        bytes.extend(FIRMA_IDX);
+        bytes.extend(VERSION_IDX.to_be_bytes());
+
+        let mut fanout = [0u32; 256];
+        for entrada in &self.entradas {
+            let primer_byte = u8::from_str_radix(&entrada.hash[..2], 16)
+                .map_err(|_| format!("Hash invalido {}", entrada.hash))?;
+            for acumulado in fanout.iter_mut().skip(primer_byte as usize) {
+                *acumulado += 1;
+            }
+        }
+        fanout
+            .iter()
+            .for_each(|acumulado| bytes.extend(acumulado.to_be_bytes()));
+
+        for entrada in &self.entradas {
+            bytes.extend(Tree::decode_hex(&entrada.hash)?);
+        }
+        for entrada in &self.entradas {
+            bytes.extend(entrada.crc.to_be_bytes());
+        }
+
+        let mut offsets_grandes: Vec<u64> = Vec::new();
+        for entrada in &self.entradas {
+            if entrada.offset < BIT_OFFSET_GRANDE as u64 {
+                bytes.extend((entrada.offset as u32).to_be_bytes());
+            } else {
+                bytes.extend((BIT_OFFSET_GRANDE | offsets_grandes.len() as u32).to_be_bytes());
+                offsets_grandes.push(entrada.offset);
+            }
+        }
+        offsets_grandes
+            .iter()
+            .for_each(|offset| bytes.extend(offset.to_be_bytes()));
+
+        bytes.extend(&self.checksum_pack);
+        let checksum: Vec<u8> = Sha1::digest(&bytes).to_vec();
+        bytes.extend(checksum);
+        Ok(bytes)
+    }
+
+    /// Devuelve el offset del objeto dentro del packfile, o None si el pack no lo contiene
+    pub fn buscar_offset(&self, hash: &str) -> Option<u64> {
+        self.entradas
+            .binary_search_by(|entrada| entrada.hash.as_str().cmp(hash))
+            .ok()
+            .map(|posicion| self.entradas[posicion].offset)
+    }
+
+    /// Devuelve las entradas del indice, ordenadas por hash
+    pub fn entradas(&self) -> &[EntradaIndicePack] {
+        &self.entradas
+    }
+
+    /// Devuelve el checksum del packfile al que pertenece el indice
+    pub fn checksum_pack(&self) -> &[u8] {
+        &self.checksum_pack
+    }
+
+    fn verificar_checksum(bytes: &[u8]) -> Result<(), String> {
+        let (contenido, checksum) = bytes.split_at(bytes.len() - TAMANIO_HASH);
+        if Sha1::digest(contenido).as_slice() != checksum {
+            return Err("el checksum no coincide".to_string());
+        }
+        Ok(())
+    }
+
+    fn leer_u32(bytes: &[u8], posicion: usize) -> u32 {
+        u32::from_be_bytes([
+            bytes[posicion],
+            bytes[posicion + 1],
+            bytes[posicion + 2],
+            bytes[posicion + 3],
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::{EntradaIndicePack, IndicePack};
+
+    fn entrada(hash: &str, offset: u64) -> EntradaIndicePack {
+        EntradaIndicePack {
+            hash: hash.repeat(40),
+            offset,
+            crc: offset as u32,
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test01_el_indice_se_escribe_y_se_lee_igual() {
+        let indice = IndicePack::new(
+            vec![entrada("b", 12), entrada("a", 40), entrada("c", 1 << 33)],
+            &[7; 20],
+        );
+
+        let leido = IndicePack::from_bytes(&indice.a_bytes().unwrap()).unwrap();
+
+        assert_eq!(leido.entradas(), indice.entradas());
+        assert_eq!(leido.checksum_pack(), &[7; 20]);
+        assert_eq!(leido.buscar_offset(&"a".repeat(40)), Some(40));
+        assert_eq!(leido.buscar_offset(&"c".repeat(40)), Some(1 << 33));
+        assert_eq!(leido.buscar_offset(&"d".repeat(40)), None);
+    }
+
+    #[test]
+    #[serial]
+    fn test02_un_indice_modificado_es_invalido() {
+        let indice = IndicePack::new(vec![entrada("a", 12)], &[7; 20]);
+        let mut bytes = indice.a_bytes().unwrap();
+        bytes[10] ^= 1;
+
+        assert!(IndicePack::from_bytes(&bytes).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use std::{path::PathBuf, sync::Arc};
 
+use crate::utils::io;
+
 use super::{
     logger::Logger,
     objetos::{blob::Blob, tree::Tree},
@@ -90,10 +92,10 @@ impl Objeto {
             };
         }
 
-        if directorio.is_dir() {
+        if io::es_dir(&directorio) {
             let tree = Tree::from_directorio(directorio.clone(), hijos_especificados, logger)?;
             Ok(Objeto::Tree(tree))
-        } else if directorio.is_file() {
+        } else if io::es_archivo(&directorio) {
             let blob = Blob::from_directorio(directorio.clone(), logger)?;
             Ok(Objeto::Blob(blob))
         } else {
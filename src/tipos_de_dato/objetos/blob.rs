@@ -3,8 +3,10 @@ use crate::{
         comando::Ejecutar,
         comandos::{cat_file::conseguir_tamanio, hash_object::HashObject},
         logger::Logger,
+        repositorio::Repositorio,
     },
     utils::compresion::descomprimir_objeto,
+    utils::io,
     utils::path_buf::obtener_nombre,
 };
 use std::{fmt::Display, path::PathBuf, sync::Arc};
@@ -38,7 +40,7 @@ impl Blob {
     /// Devuelve el tamanio del blob.
     /// Para obtener el tamanio del blob, se descomprime el objeto y se lee el header.
     pub fn obtener_tamanio(&self) -> Result<usize, String> {
-        let contenido_blob = descomprimir_objeto(&self.hash, ".gir/objects/")?;
+        let contenido_blob = descomprimir_objeto(&self.hash, &Repositorio::actual().dir_objetos())?;
         let header = contenido_blob
             .split('\0')
             .next()
@@ -53,7 +55,7 @@ impl Blob {
 
     /// Crea un objeto blob a partir de un archivo.
     pub fn from_directorio(directorio: PathBuf, logger: Arc<Logger>) -> Result<Blob, String> {
-        if directorio.is_dir() {
+        if io::es_dir(&directorio) {
             return Err("No se puede crear un blob a partir de un directorio".to_string());
         }
         let hash = HashObject {
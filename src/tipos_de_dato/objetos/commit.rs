@@ -143,12 +143,14 @@ impl CommitObj {
             let linea_splitteada = linea.split(' ').collect::<Vec<&str>>();
             match linea_splitteada[0] {
                 "parent" => padres.push(linea_splitteada[1].to_string()),
-                "author" => {
-                    autor_option = Some(linea_splitteada[1].to_string());
-                    mail_option = Some(linea_splitteada[2].to_string());
+                // el nombre puede tener espacios, asi que el mail y la fecha se toman desde el final
+                "author" if linea_splitteada.len() >= 5 => {
+                    let cantidad = linea_splitteada.len();
+                    autor_option = Some(linea_splitteada[1..cantidad - 3].join(" "));
+                    mail_option = Some(linea_splitteada[cantidad - 3].to_string());
                     date_option = Some(Date {
-                        tiempo: linea_splitteada[3].to_string(),
-                        offset: linea_splitteada[4].to_string(),
+                        tiempo: linea_splitteada[cantidad - 2].to_string(),
+                        offset: linea_splitteada[cantidad - 1].to_string(),
                     });
                 }
                 "tree" => {
@@ -1,7 +1,6 @@
 use std::{
     collections::HashMap,
     fmt::{Display, Write},
-    fs,
     num::ParseIntError,
     path::{Path, PathBuf},
     sync::Arc,
@@ -15,6 +14,7 @@ use crate::{
         comandos::{cat_file, check_ignore::CheckIgnore, hash_object::HashObject, merge::Merge},
         logger::Logger,
         objeto::Objeto,
+        repositorio::Repositorio,
         tipo_diff::TipoDiff,
     },
     utils::path_buf::{esta_directorio_habilitado, obtener_nombre},
@@ -125,7 +125,8 @@ impl Tree {
         for objeto in objetos {
             match objeto {
                 Objeto::Blob(blob) => {
-                    let objeto = descomprimir_objeto(&blob.hash, ".gir/objects/")?;
+                    let objeto =
+                        descomprimir_objeto(&blob.hash, &Repositorio::actual().dir_objetos())?;
                     let contenido = objeto.split('\0').collect::<Vec<&str>>()[1];
                     io::escribir_bytes(blob.ubicacion, contenido).unwrap();
                 }
@@ -205,7 +206,7 @@ impl Tree {
     ) -> Result<Tree, String> {
         let mut objetos: Vec<Objeto> = Vec::new();
 
-        let entradas = match fs::read_dir(&directorio) {
+        let entradas = match io::leer_directorio(&directorio) {
             Ok(entradas) => entradas,
             Err(_) => Err(format!("Error al leer el directorio {directorio:#?}"))?,
         };
@@ -213,7 +214,7 @@ impl Tree {
         for entrada in entradas {
             let entrada = entrada
                 .map_err(|_| format!("Error al leer entrada el directorio {directorio:#?}"))?;
-            let path = entrada.path();
+            let path = directorio.join(entrada.file_name());
 
             if CheckIgnore::es_directorio_a_ignorar(&path, logger.clone())? {
                 continue;
@@ -225,10 +226,10 @@ impl Tree {
                 }
             }
 
-            let objeto = match fs::metadata(&path) {
-                Ok(_) => Objeto::from_directorio(path, hijos_especificados, logger.clone())?,
-                Err(_) => Err("Error al leer el archivo".to_string())?,
-            };
+            if !io::existe(&path) {
+                Err("Error al leer el archivo".to_string())?;
+            }
+            let objeto = Objeto::from_directorio(path, hijos_especificados, logger.clone())?;
             objetos.push(objeto);
         }
 
@@ -299,7 +300,7 @@ impl Tree {
     /// Lee el objeto tree de la base de datos en base a un hash pasado por parametro junto con
     /// el directorio en el que se encuentra el tree y lo devuelve como un objeto Tree
     pub fn from_hash(hash: &str, directorio: PathBuf, logger: Arc<Logger>) -> Result<Tree, String> {
-        let contenido = descomprimir_objeto(hash, ".gir/objects/")?;
+        let contenido = descomprimir_objeto(hash, &Repositorio::actual().dir_objetos())?;
         let contenido_parseado = Self::obtener_datos_de_contenido(&contenido)?;
         let mut objetos: Vec<Objeto> = Vec::new();
 
@@ -417,7 +418,7 @@ impl Tree {
     /// Escribe el arbol junto a todos sus hijos en la base de datos.
     pub fn escribir_en_base(&self) -> Result<(), String> {
         let hash = self.obtener_hash()?;
-        let ruta = format!(".gir/objects/{}/{}", &hash[..2], &hash[2..]);
+        let ruta = Repositorio::actual().ruta_gir(format!("objects/{}/{}", &hash[..2], &hash[2..]));
 
         let contenido = Self::obtener_contenido(&self.objetos)?;
 
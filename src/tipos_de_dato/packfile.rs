@@ -1,9 +1,11 @@
 use crate::tipos_de_dato::comandos::cat_file;
+use crate::tipos_de_dato::indice_pack::EntradaIndicePack;
 use crate::tipos_de_dato::logger::Logger;
 use crate::utils::{self, io};
 use crate::utils::{compresion, objects};
-use flate2::{Decompress, FlushDecompress};
+use flate2::{Crc, Decompress, FlushDecompress};
 use sha1::{Digest, Sha1};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str;
 use std::sync::Arc;
@@ -15,6 +17,16 @@ const TAG: u8 = 4;
 const OFS_DELTA: u8 = 6;
 const REF_DELTA: u8 = 7;
 
+// Cantidad de objetos anteriores contra los que se intenta armar un delta
+const VENTANA_DELTAS: usize = 10;
+// Largo maximo de una cadena de deltas, para que leer un objeto no sea demasiado costoso
+const PROFUNDIDAD_MAXIMA_DELTAS: usize = 10;
+// Tamanio de los bloques del objeto base que se buscan en el objeto a deltificar
+const TAMANIO_BLOQUE_DELTA: usize = 16;
+// El lector solo interpreta los dos primeros bytes del tamanio de una copia
+const MAXIMO_COPIA_DELTA: usize = 0xffff;
+const MAXIMO_INSERCION_DELTA: usize = 0x7f;
+
 pub struct Packfile;
 
 impl Packfile {
@@ -204,6 +216,7 @@ impl Packfile {
             1 => format!("{} {}\0", "commit", tamanio).as_bytes().to_vec(),
             2 => format!("{} {}\0", "tree", tamanio).as_bytes().to_vec(),
             3 => format!("{} {}\0", "blob", tamanio).as_bytes().to_vec(),
+            4 => format!("{} {}\0", "tag", tamanio).as_bytes().to_vec(),
             _ => {
                 return Err("Tipo de objeto invalido".to_string());
             }
@@ -212,6 +225,249 @@ impl Packfile {
         Ok(header)
     }
 
+    /// Dado el contenido de un packfile y el offset de uno de sus objetos (obtenido del `.idx`),
+    /// devuelve el objeto descomprimido con su header, resolviendo los deltas si los hubiera.
+    pub fn leer_objeto_en_offset(bytes: &[u8], offset: usize) -> Result<Vec<u8>, String> {
+        if offset >= bytes.len() {
+            return Err(format!("Offset {} fuera del packfile", offset));
+        }
+        let (tipo, mut objeto) = Self::leer_objeto_del_packfile(bytes, &mut { offset })?;
+        Self::obtener_objeto_con_header(tipo, objeto.len() as u32, &mut objeto)
+    }
+
+    /// Lee un objeto a partir de sus bytes dentro del pack (desde su offset hasta el del objeto
+    /// siguiente), sin necesitar el resto del packfile. Si es un OFS_DELTA, obtiene su base con
+    /// `leer_base`, que recibe la distancia desde el offset de la base hasta el del objeto.
+    /// Devuelve el tipo y el contenido descomprimido del objeto.
+    pub fn leer_objeto_aislado<F>(bytes: &[u8], leer_base: F) -> Result<(u8, Vec<u8>), String>
+    where
+        F: FnOnce(usize) -> Result<(u8, Vec<u8>), String>,
+    {
+        if bytes.is_empty() {
+            return Err("Objeto vacio en el packfile".to_string());
+        }
+        let mut offset = 0;
+        let (tipo, tamanio) = Self::decodificar_bytes(bytes, &mut offset);
+        if tipo != OFS_DELTA {
+            let objeto_descomprimido = Self::descomprimir_objeto(bytes, &mut offset, tamanio)?;
+            return Ok((tipo, objeto_descomprimido));
+        }
+
+        let distancia_base = Self::leer_vli_be(bytes, &mut offset, true);
+        let (tipo_base, mut objeto_base) = leer_base(distancia_base)?;
+        Self::crear_delta_obj(bytes, &mut offset, tipo_base, &mut objeto_base, tamanio)
+    }
+
+    /// Devuelve el objeto de tipo `tipo` con su header (Ej: `blob 5\0hola\n`)
+    pub fn agregar_header(tipo: u8, mut contenido: Vec<u8>) -> Result<Vec<u8>, String> {
+        Self::obtener_objeto_con_header(tipo, contenido.len() as u32, &mut contenido)
+    }
+
+    /// Arma un packfile con los objetos dados (hash y objeto descomprimido con header),
+    /// guardando como OFS_DELTA a los objetos que se parecen lo suficiente a alguno anterior del mismo tipo.
+    /// Devuelve el packfile junto a las entradas necesarias para armar su `.idx`.
+    pub fn armar_packfile_con_deltas(
+        objetos: Vec<(String, Vec<u8>)>,
+    ) -> Result<(Vec<u8>, Vec<EntradaIndicePack>), String> {
+        let mut objetos = objetos
+            .into_iter()
+            .map(|(hash, objeto)| {
+                let (tipo, contenido) = Self::separar_header(&objeto)?;
+                Ok((hash, tipo, contenido))
+            })
+            .collect::<Result<Vec<(String, u8, Vec<u8>)>, String>>()?;
+        // agrupados por tipo y de mayor a menor, asi las versiones de un mismo archivo quedan cerca
+        objetos.sort_by(|a, b| a.1.cmp(&b.1).then(b.2.len().cmp(&a.2.len())));
+
+        let mut cuerpo: Vec<u8> = Vec::new();
+        let mut entradas = Vec::new();
+        let mut offsets: Vec<usize> = Vec::new();
+        let mut profundidades: Vec<usize> = Vec::new();
+
+        for (i, (hash, tipo, contenido)) in objetos.iter().enumerate() {
+            let offset = 12 + cuerpo.len();
+            let entrada = match Self::buscar_mejor_delta(&objetos, &profundidades, i) {
+                Some((base, delta)) => {
+                    profundidades.push(profundidades[base] + 1);
+                    let mut entrada = Self::codificar_bytes(OFS_DELTA, delta.len() as u32);
+                    entrada.extend(Self::codificar_offset_delta(offset - offsets[base]));
+                    entrada.extend(compresion::comprimir_contenido_u8(&delta)?);
+                    entrada
+                }
+                None => {
+                    profundidades.push(0);
+                    let mut entrada = Self::codificar_bytes(*tipo, contenido.len() as u32);
+                    entrada.extend(compresion::comprimir_contenido_u8(contenido)?);
+                    entrada
+                }
+            };
+
+            let mut crc = Crc::new();
+            crc.update(&entrada);
+            entradas.push(EntradaIndicePack {
+                hash: hash.clone(),
+                offset: offset as u64,
+                crc: crc.sum(),
+            });
+            offsets.push(offset);
+            cuerpo.extend(entrada);
+        }
+
+        Ok((Self::armar_packfile(cuerpo, objetos.len() as u32), entradas))
+    }
+
+    // Separa el header de un objeto descomprimido, devolviendo el tipo codificado y el contenido
+    fn separar_header(objeto: &[u8]) -> Result<(u8, Vec<u8>), String> {
+        let (header, contenido) = match objeto.iter().position(|&byte| byte == 0) {
+            Some(posicion) => (&objeto[..posicion], &objeto[posicion + 1..]),
+            None => return Err("Objeto sin header".to_string()),
+        };
+        let header = String::from_utf8_lossy(header);
+        let tipo = match header.split_whitespace().next() {
+            Some("commit") => COMMIT,
+            Some("tree") => TREE,
+            Some("blob") => BLOB,
+            Some("tag") => TAG,
+            _ => return Err(format!("Tipo de objeto invalido en el header {}", header)),
+        };
+        Ok((tipo, contenido.to_vec()))
+    }
+
+    // Busca entre los objetos anteriores dentro de la ventana el que genera el delta mas chico.
+    // Solo se usa el delta si ocupa menos de la mitad que el objeto original
+    fn buscar_mejor_delta(
+        objetos: &[(String, u8, Vec<u8>)],
+        profundidades: &[usize],
+        i: usize,
+    ) -> Option<(usize, Vec<u8>)> {
+        let (_, tipo, contenido) = &objetos[i];
+        let mut mejor: Option<(usize, Vec<u8>)> = None;
+
+        for base in i.saturating_sub(VENTANA_DELTAS)..i {
+            let (_, tipo_base, contenido_base) = &objetos[base];
+            if tipo_base != tipo || profundidades[base] >= PROFUNDIDAD_MAXIMA_DELTAS {
+                continue;
+            }
+            let delta = Self::crear_delta(contenido_base, contenido);
+            let limite = mejor
+                .as_ref()
+                .map_or(contenido.len() / 2, |(_, mejor_delta)| mejor_delta.len());
+            if delta.len() < limite {
+                mejor = Some((base, delta));
+            }
+        }
+        mejor
+    }
+
+    // Arma las instrucciones para reconstruir `objetivo` a partir de `base`: copias de bloques
+    // que estan en la base e inserciones de los bytes que no estan
+    fn crear_delta(base: &[u8], objetivo: &[u8]) -> Vec<u8> {
+        let mut delta = Self::codificar_varint_le(base.len());
+        delta.extend(Self::codificar_varint_le(objetivo.len()));
+
+        let mut bloques: HashMap<&[u8], usize> = HashMap::new();
+        for inicio in
+            (0..base.len().saturating_sub(TAMANIO_BLOQUE_DELTA - 1)).step_by(TAMANIO_BLOQUE_DELTA)
+        {
+            bloques
+                .entry(&base[inicio..inicio + TAMANIO_BLOQUE_DELTA])
+                .or_insert(inicio);
+        }
+
+        let mut insercion: Vec<u8> = Vec::new();
+        let mut i = 0;
+        while i < objetivo.len() {
+            let coincidencia = objetivo
+                .get(i..i + TAMANIO_BLOQUE_DELTA)
+                .and_then(|bloque| bloques.get(bloque));
+            match coincidencia {
+                Some(&inicio_base) => {
+                    let mut largo = TAMANIO_BLOQUE_DELTA;
+                    while inicio_base + largo < base.len()
+                        && i + largo < objetivo.len()
+                        && base[inicio_base + largo] == objetivo[i + largo]
+                    {
+                        largo += 1;
+                    }
+                    Self::agregar_insercion(&mut delta, &mut insercion);
+                    Self::agregar_copia(&mut delta, inicio_base, largo);
+                    i += largo;
+                }
+                None => {
+                    insercion.push(objetivo[i]);
+                    i += 1;
+                }
+            }
+        }
+        Self::agregar_insercion(&mut delta, &mut insercion);
+        delta
+    }
+
+    // Agrega las instrucciones de insercion de los bytes pendientes, de a 127 bytes como maximo
+    fn agregar_insercion(delta: &mut Vec<u8>, insercion: &mut Vec<u8>) {
+        for parte in insercion.chunks(MAXIMO_INSERCION_DELTA) {
+            delta.push(parte.len() as u8);
+            delta.extend(parte);
+        }
+        insercion.clear();
+    }
+
+    // Agrega las instrucciones de copia de `largo` bytes de la base desde `inicio`.
+    // Solo se escriben los bytes del offset y del tamanio que no son cero
+    fn agregar_copia(delta: &mut Vec<u8>, mut inicio: usize, mut largo: usize) {
+        while largo > 0 {
+            let parte = largo.min(MAXIMO_COPIA_DELTA);
+            let mut instruccion: u8 = 0x80;
+            let mut argumentos = Vec::new();
+            for (i, byte) in (inicio as u32).to_le_bytes().iter().enumerate() {
+                if *byte != 0 {
+                    instruccion |= 1 << i;
+                    argumentos.push(*byte);
+                }
+            }
+            for (i, byte) in (parte as u16).to_le_bytes().iter().enumerate() {
+                if *byte != 0 {
+                    instruccion |= 1 << (4 + i);
+                    argumentos.push(*byte);
+                }
+            }
+            delta.push(instruccion);
+            delta.extend(argumentos);
+            inicio += parte;
+            largo -= parte;
+        }
+    }
+
+    // Codifica un variable length integer en formato little endian, inversa de leer_varint_le
+    fn codificar_varint_le(mut valor: usize) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let mut byte = (valor & 0x7f) as u8;
+            valor >>= 7;
+            if valor > 0 {
+                byte |= 0x80;
+            }
+            bytes.push(byte);
+            if valor == 0 {
+                break;
+            }
+        }
+        bytes
+    }
+
+    // Codifica la distancia hacia el objeto base de un OFS_DELTA, inversa de leer_vli_be
+    fn codificar_offset_delta(mut distancia: usize) -> Vec<u8> {
+        let mut bytes = vec![(distancia & 0x7f) as u8];
+        distancia >>= 7;
+        while distancia > 0 {
+            distancia -= 1;
+            bytes.push(0x80 | (distancia & 0x7f) as u8);
+            distancia >>= 7;
+        }
+        bytes.reverse();
+        bytes
+    }
+
     // Verifica el checksum de un packfile
     pub fn verificar_checksum(packfile: &[u8]) -> bool {
         let expected_hash = &packfile[packfile.len() - 20..];
@@ -547,4 +803,33 @@ mod tests {
             assert!(objeto.is_ok());
         }
     }
+
+    #[test]
+    #[serial]
+    fn test10_los_objetos_parecidos_se_guardan_como_delta_y_se_pueden_leer() {
+        let base = "linea que se repite en las dos versiones del archivo\n".repeat(20);
+        let modificado = base.clone() + "linea nueva\n";
+        let objetos: Vec<(String, Vec<u8>)> = [base, modificado]
+            .iter()
+            .enumerate()
+            .map(|(i, contenido)| {
+                let objeto = format!("blob {}\0{}", contenido.len(), contenido);
+                (i.to_string(), objeto.into_bytes())
+            })
+            .collect();
+
+        let (packfile, entradas) = Packfile::armar_packfile_con_deltas(objetos.clone()).unwrap();
+
+        assert!(Packfile::verificar_checksum(&packfile));
+        let cant_deltas = entradas
+            .iter()
+            .filter(|entrada| (packfile[entrada.offset as usize] >> 4) & 0x07 == OFS_DELTA)
+            .count();
+        assert_eq!(cant_deltas, 1);
+        for entrada in entradas {
+            let objeto = Packfile::leer_objeto_en_offset(&packfile, entrada.offset as usize);
+            let esperado = &objetos[entrada.hash.parse::<usize>().unwrap()].1;
+            assert_eq!(&objeto.unwrap(), esperado);
+        }
+    }
 }
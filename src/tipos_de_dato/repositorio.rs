@@ -0,0 +1,361 @@
+use std::{
+    cell::{Cell, RefCell},
+    path::{self, Component, Path, PathBuf},
+    thread,
+};
+
+use crate::utils::ubicacion_repositorio::{DIR_GIR, DIR_OBJETOS};
+
+thread_local! {
+    // Repositorio sobre el que trabaja el thread. Si no hay ninguno, las rutas relativas
+    // se toman desde el directorio actual del proceso (el caso del cliente, que se posiciona
+    // en la raiz del repositorio al arrancar)
+    static REPOSITORIO_ACTUAL: RefCell<Option<Repositorio>> = const { RefCell::new(None) };
+    // Los threads del servidor no tienen un repositorio por defecto: el directorio actual del
+    // proceso no es ninguno de los repositorios que atienden
+    static THREAD_DE_SERVIDOR: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Ubicacion de un repositorio: su directorio de trabajo y su directorio `.gir`.
+///
+/// Los archivos del directorio `.gir` se ubican con `ruta_gir` y `dir_objetos`, y los del
+/// working tree con rutas relativas a la raiz del repositorio. En lugar de cambiar el directorio
+/// actual del proceso, que es compartido por todos los threads, se entra al repositorio con
+/// `entrar` y las funciones de `utils::io` resuelven esas rutas a partir de su raiz.
+/// Asi el servidor puede atender pedidos sobre distintos repositorios al mismo tiempo.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repositorio {
+    dir_trabajo: PathBuf,
+    dir_gir: PathBuf,
+    /// Directorios de objetos de otros repositorios en los que se buscan los objetos que no
+    /// estan en el propio
+    objetos_alternativos: Vec<String>,
+}
+
+/// Mientras exista, el thread trabaja sobre el repositorio con el que se creo.
+/// Al dropearse se vuelve al repositorio en el que se estaba antes.
+#[must_use = "si se dropea en el momento se sale del repositorio"]
+pub struct RepositorioActivo {
+    anterior: Option<Repositorio>,
+}
+
+impl Repositorio {
+    /// Crea el repositorio cuyo directorio de trabajo es `dir_trabajo` y su directorio gir
+    /// es `<dir_trabajo>/.gir`. Si `dir_trabajo` es relativo, se toma desde el directorio actual.
+    pub fn new<P: AsRef<Path>>(dir_trabajo: P) -> Repositorio {
+        // las rutas ya resueltas quedan absolutas, asi resolverlas de nuevo no las modifica
+        let dir_trabajo =
+            path::absolute(&dir_trabajo).unwrap_or_else(|_| dir_trabajo.as_ref().to_path_buf());
+        Repositorio {
+            dir_gir: dir_trabajo.join(DIR_GIR),
+            dir_trabajo,
+            objetos_alternativos: Vec::new(),
+        }
+    }
+
+    /// Devuelve el repositorio con el mismo directorio de trabajo pero cuyo directorio gir es
+    /// `dir_gir`, que puede tener cualquier nombre (Ej: el que indica `GIR_DIR`)
+    pub fn con_dir_gir<P: AsRef<Path>>(mut self, dir_gir: P) -> Repositorio {
+        self.dir_gir = path::absolute(&dir_gir).unwrap_or_else(|_| dir_gir.as_ref().to_path_buf());
+        self
+    }
+
+    /// Devuelve el repositorio que, cuando no tiene un objeto, lo lee del directorio de objetos
+    /// de `otro`. Sirve para leer los commits de un fork sin copiar sus objetos
+    pub fn con_objetos_de(self, otro: &Repositorio) -> Repositorio {
+        self.con_objetos_en(&otro.dir_objetos())
+    }
+
+    /// Igual que `con_objetos_de`, pero con un directorio de objetos que no es el de un
+    /// repositorio (Ej: la cuarentena de un push). Tiene que terminar en `/`
+    pub fn con_objetos_en(mut self, dir_objetos: &str) -> Repositorio {
+        self.objetos_alternativos.push(dir_objetos.to_string());
+        self
+    }
+
+    /// Devuelve los directorios de objetos de otros repositorios en los que se buscan los
+    /// objetos que no estan en este
+    pub fn objetos_alternativos(&self) -> &[String] {
+        &self.objetos_alternativos
+    }
+
+    /// Devuelve el repositorio sobre el que esta trabajando el thread. Si no se entro a ninguno,
+    /// es el del directorio actual.
+    ///
+    /// # Panics
+    /// Si el thread es del servidor (ver `marcar_thread_de_servidor`) y no entro a ningun
+    /// repositorio, en lugar de trabajar sobre el directorio actual del proceso.
+    pub fn actual() -> Repositorio {
+        Self::entrado().unwrap_or_else(|| {
+            Self::verificar_thread_sin_repositorio(Path::new("."));
+            Repositorio::new(".")
+        })
+    }
+
+    /// Devuelve el repositorio al que entro el thread, si entro a alguno
+    pub fn entrado() -> Option<Repositorio> {
+        REPOSITORIO_ACTUAL.with(|actual| actual.borrow().clone())
+    }
+
+    /// Marca al thread actual como un thread del servidor. A partir de ahi, usar el repositorio
+    /// actual sin haber entrado a uno es un error: `actual` y `resolver` panickean en lugar de
+    /// usar el directorio actual del proceso
+    pub fn marcar_thread_de_servidor() {
+        THREAD_DE_SERVIDOR.with(|de_servidor| de_servidor.set(true));
+    }
+
+    // Panickea si el thread es del servidor, porque se quiso usar `ruta` del repositorio actual
+    // sin haber entrado a ninguno
+    fn verificar_thread_sin_repositorio(ruta: &Path) {
+        if THREAD_DE_SERVIDOR.with(|de_servidor| de_servidor.get()) {
+            panic!(
+                "El thread {} del servidor uso {} sin entrar a un repositorio",
+                thread::current().name().unwrap_or("sin nombre"),
+                ruta.display()
+            );
+        }
+    }
+
+    /// Devuelve el directorio de trabajo del repositorio
+    pub fn dir_trabajo(&self) -> &Path {
+        &self.dir_trabajo
+    }
+
+    /// Devuelve el directorio `.gir` del repositorio
+    pub fn dir_gir(&self) -> &Path {
+        &self.dir_gir
+    }
+
+    /// Devuelve la ruta a `ruta` dentro del directorio `.gir` del repositorio.
+    /// Ej: `ruta_gir("refs/heads/master")` es `<dir_trabajo>/.gir/refs/heads/master`
+    pub fn ruta_gir<P: AsRef<Path>>(&self, ruta: P) -> PathBuf {
+        self.dir_gir.join(ruta)
+    }
+
+    /// Devuelve el directorio de objetos del repositorio (`.gir/objects/`), con la `/` final
+    /// que esperan las funciones de `utils::compresion`
+    pub fn dir_objetos(&self) -> String {
+        format!("{}/", self.dir_gir.join(DIR_OBJETOS).display())
+    }
+
+    /// Devuelve si el repositorio existe, es decir, si tiene directorio `.gir`
+    pub fn existe(&self) -> bool {
+        self.dir_gir.is_dir()
+    }
+
+    /// Devuelve la ruta a `ruta` tomandola desde la raiz del repositorio. Las rutas dentro de
+    /// `.gir` se toman desde el directorio gir del repositorio, aunque tenga otro nombre.
+    /// Las rutas absolutas se devuelven sin cambios.
+    pub fn ruta<P: AsRef<Path>>(&self, ruta: P) -> PathBuf {
+        let ruta = ruta.as_ref();
+        if ruta.is_absolute() {
+            return ruta.to_path_buf();
+        }
+        let ruta = ruta.strip_prefix(".").unwrap_or(ruta);
+        match ruta.strip_prefix(DIR_GIR) {
+            Ok(ruta_en_gir) if ruta_en_gir.as_os_str().is_empty() => self.dir_gir.clone(),
+            Ok(ruta_en_gir) => self.dir_gir.join(ruta_en_gir),
+            Err(_) => self.dir_trabajo.join(ruta),
+        }
+    }
+
+    /// Hace que el thread trabaje sobre este repositorio hasta que se dropee el valor devuelto
+    pub fn entrar(&self) -> RepositorioActivo {
+        let anterior = REPOSITORIO_ACTUAL.with(|actual| actual.replace(Some(self.clone())));
+        RepositorioActivo { anterior }
+    }
+}
+
+impl Drop for RepositorioActivo {
+    fn drop(&mut self) {
+        let anterior = self.anterior.take();
+        REPOSITORIO_ACTUAL.with(|actual| *actual.borrow_mut() = anterior);
+    }
+}
+
+/// Resuelve una ruta relativa a la raiz del repositorio en el que esta el thread.
+/// Si el thread no entro a ningun repositorio, la ruta queda igual.
+///
+/// # Panics
+/// Si el thread es del servidor, no entro a ningun repositorio y la ruta es del directorio
+/// `.gir` (Ej: `.gir/HEAD`). Las demas rutas relativas son archivos propios del servidor.
+pub fn resolver<P: AsRef<Path>>(ruta: P) -> PathBuf {
+    let ruta = ruta.as_ref();
+    REPOSITORIO_ACTUAL.with(|actual| match actual.borrow().as_ref() {
+        Some(repositorio) => repositorio.ruta(ruta),
+        None => {
+            if es_ruta_gir(ruta) {
+                Repositorio::verificar_thread_sin_repositorio(ruta);
+            }
+            ruta.to_path_buf()
+        }
+    })
+}
+
+// Devuelve si `ruta` es relativa y esta dentro de un directorio `.gir` (Ej: `./.gir/HEAD`)
+fn es_ruta_gir(ruta: &Path) -> bool {
+    ruta.components()
+        .find(|componente| *componente != Component::CurDir)
+        .is_some_and(|componente| componente.as_os_str() == DIR_GIR)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc, thread};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        tipos_de_dato::{comando::Comando, logger::Logger},
+        utils::{compresion, io},
+    };
+
+    fn ejecutar(repositorio: &Repositorio, args: &[&str], logger: Arc<Logger>) -> String {
+        let args = args.iter().map(|arg| arg.to_string()).collect();
+        Comando::new(args, repositorio.clone(), logger)
+            .unwrap()
+            .ejecutar()
+            .unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test01_las_rutas_se_resuelven_desde_el_repositorio_en_el_que_se_entro() {
+        assert_eq!(resolver(".gir/HEAD"), PathBuf::from(".gir/HEAD"));
+
+        let repositorio = Repositorio::new("/srv/repo");
+        {
+            let _activo = repositorio.entrar();
+            assert_eq!(resolver(".gir/HEAD"), PathBuf::from("/srv/repo/.gir/HEAD"));
+            assert_eq!(resolver("./archivo"), PathBuf::from("/srv/repo/archivo"));
+            assert_eq!(resolver("/otro/archivo"), PathBuf::from("/otro/archivo"));
+            assert_eq!(Repositorio::actual(), repositorio);
+        }
+
+        assert_eq!(resolver(".gir/HEAD"), PathBuf::from(".gir/HEAD"));
+    }
+
+    #[test]
+    #[serial]
+    fn test02_cada_thread_trabaja_sobre_su_propio_repositorio() {
+        let threads: Vec<_> = ["uno", "dos"]
+            .into_iter()
+            .map(|nombre| {
+                thread::spawn(move || {
+                    let _activo = Repositorio::new(format!("/srv/{}", nombre)).entrar();
+                    thread::sleep(std::time::Duration::from_millis(10));
+                    resolver(".gir/index")
+                })
+            })
+            .collect();
+
+        let rutas: Vec<PathBuf> = threads.into_iter().map(|t| t.join().unwrap()).collect();
+
+        assert_eq!(
+            rutas,
+            vec![
+                PathBuf::from("/srv/uno/.gir/index"),
+                PathBuf::from("/srv/dos/.gir/index")
+            ]
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test03_los_comandos_trabajan_sobre_el_repositorio_sin_cambiar_de_directorio() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/repositorio_test03.log")).unwrap());
+        let dir_repositorio = "tmp/repositorio_test03";
+        let _ = io::rm_directorio(dir_repositorio);
+        io::crear_directorio(dir_repositorio).unwrap();
+        let repositorio = Repositorio::new(dir_repositorio);
+        let directorio_actual = std::env::current_dir().unwrap();
+
+        ejecutar(&repositorio, &["init"], logger.clone());
+        io::escribir_bytes(repositorio.ruta("archivo"), "contenido").unwrap();
+        ejecutar(&repositorio, &["add", "archivo"], logger.clone());
+        ejecutar(&repositorio, &["commit", "-m", "primero"], logger.clone());
+        ejecutar(&repositorio, &["branch", "otra"], logger.clone());
+        ejecutar(&repositorio, &["checkout", "otra"], logger.clone());
+        io::escribir_bytes(repositorio.ruta("archivo2"), "contenido2").unwrap();
+        ejecutar(&repositorio, &["add", "archivo2"], logger.clone());
+        ejecutar(&repositorio, &["commit", "-m", "segundo"], logger.clone());
+        ejecutar(&repositorio, &["checkout", "master"], logger.clone());
+        ejecutar(&repositorio, &["merge", "otra"], logger.clone());
+        let log = ejecutar(&repositorio, &["log"], logger.clone());
+
+        assert_eq!(std::env::current_dir().unwrap(), directorio_actual);
+        assert!(repositorio.existe());
+        assert_eq!(
+            io::leer_a_string(repositorio.dir_gir().join("refs/heads/master")).unwrap(),
+            io::leer_a_string(repositorio.dir_gir().join("refs/heads/otra")).unwrap()
+        );
+        assert!(repositorio.ruta("archivo2").exists());
+        assert!(log.contains("segundo") && log.contains("primero"));
+    }
+
+    #[test]
+    #[serial]
+    fn test04_se_leen_los_objetos_de_otro_repositorio_sin_copiarlos() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/repositorio_test04.log")).unwrap());
+        let (dir_base, dir_fork) = ("tmp/repositorio_test04", "tmp/repositorio_test04_fork");
+        let _ = io::rm_directorio(dir_base);
+        let _ = io::rm_directorio(dir_fork);
+        io::crear_directorio(dir_base).unwrap();
+        io::crear_directorio(dir_fork).unwrap();
+        let (base, fork) = (Repositorio::new(dir_base), Repositorio::new(dir_fork));
+        ejecutar(&base, &["init"], logger.clone());
+        ejecutar(&fork, &["init"], logger.clone());
+        io::escribir_bytes(fork.ruta("archivo"), "contenido").unwrap();
+        ejecutar(&fork, &["add", "archivo"], logger.clone());
+        ejecutar(&fork, &["commit", "-m", "primero"], logger.clone());
+        let hash = io::leer_a_string(fork.ruta_gir("refs/heads/master")).unwrap();
+
+        let sin_fork = {
+            let _activo = base.entrar();
+            compresion::descomprimir_objeto_gir(&hash)
+        };
+        let con_fork = {
+            let _activo = base.clone().con_objetos_de(&fork).entrar();
+            compresion::descomprimir_objeto_gir(&hash)
+        };
+
+        assert!(sin_fork.is_err());
+        assert!(con_fork.unwrap().contains("primero"));
+        assert!(!io::existe(base.ruta_gir(format!(
+            "objects/{}/{}",
+            &hash[..2],
+            &hash[2..]
+        ))));
+    }
+
+    #[test]
+    #[serial]
+    fn test05_un_thread_del_servidor_no_usa_el_directorio_actual_como_repositorio() {
+        let resultado = thread::spawn(|| {
+            Repositorio::marcar_thread_de_servidor();
+            let archivo_del_servidor = resolver("srv/usuarios.json");
+            let ruta_gir = std::panic::catch_unwind(|| resolver(".gir/HEAD"));
+            let actual = std::panic::catch_unwind(Repositorio::actual);
+            let _activo = Repositorio::new("/srv/repo").entrar();
+            (
+                archivo_del_servidor,
+                ruta_gir.is_err(),
+                actual.is_err(),
+                Repositorio::actual(),
+            )
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(
+            resultado,
+            (
+                PathBuf::from("srv/usuarios.json"),
+                true,
+                true,
+                Repositorio::new("/srv/repo")
+            )
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use std::path::{Path, PathBuf};
+
+use crate::utils::{archivo_lock::ArchivoLock, io};
+
+const HASH_NULO: &str = "0000000000000000000000000000000000000000";
+
+/// Cambio pendiente sobre una referencia dentro de una transaccion
+enum Operacion {
+    /// Escribe el contenido dado en la referencia
+    Escribir(String),
+    /// Borra la referencia
+    Eliminar,
+}
+
+struct ActualizacionRef {
+    /// Referencia relativa al directorio gir (Ej: `refs/heads/master` o `HEAD`)
+    referencia: String,
+    /// Valor que se espera que tenga la referencia antes de actualizarla.
+    /// `None` si no se quiere verificar
+    valor_esperado: Option<String>,
+    operacion: Operacion,
+}
+
+/// Actualiza una o varias referencias de forma atomica.
+///
+/// Todas las escrituras de refs pasan por aca: se toma el lock `<ref>.lock` de cada
+/// referencia, se verifica el valor viejo (si se pidio), se escribe el contenido nuevo
+/// en los locks y recien cuando todos estan listos se renombran sobre las refs.
+/// Si algo falla antes de renombrar, ninguna referencia se modifica. Si falla uno de los
+/// renombres, las referencias que ya se habian actualizado vuelven a su valor anterior.
+///
+/// # Ejemplo
+///
+/// ```ignore
+/// TransaccionRefs::new(".gir")
+///     .actualizar("refs/heads/master", &hash_nuevo)
+///     .confirmar()?;
+/// ```
+pub struct TransaccionRefs {
+    dir_gir: PathBuf,
+    actualizaciones: Vec<ActualizacionRef>,
+}
+
+impl TransaccionRefs {
+    /// Crea una transaccion vacia sobre el directorio gir `dir_gir` (Ej: `./.gir` o `/srv/repo/.gir/`)
+    pub fn new<P: AsRef<Path>>(dir_gir: P) -> TransaccionRefs {
+        TransaccionRefs {
+            dir_gir: dir_gir.as_ref().to_path_buf(),
+            actualizaciones: Vec::new(),
+        }
+    }
+
+    /// Agrega la escritura de `valor_nuevo` en `referencia`, sin importar su valor actual
+    pub fn actualizar(mut self, referencia: &str, valor_nuevo: &str) -> TransaccionRefs {
+        self.agregar(
+            referencia,
+            None,
+            Operacion::Escribir(valor_nuevo.to_string()),
+        );
+        self
+    }
+
+    /// Agrega la escritura de `valor_nuevo` en `referencia`, solo si su valor actual es
+    /// `valor_viejo`. Un valor viejo vacio o de ceros significa que la referencia no debe existir.
+    /// Un valor nuevo de ceros borra la referencia, como en el protocolo de git.
+    pub fn actualizar_verificando(
+        mut self,
+        referencia: &str,
+        valor_viejo: &str,
+        valor_nuevo: &str,
+    ) -> TransaccionRefs {
+        let operacion = if Self::es_valor_nulo(valor_nuevo) {
+            Operacion::Eliminar
+        } else {
+            Operacion::Escribir(valor_nuevo.to_string())
+        };
+        self.agregar(referencia, Some(valor_viejo.to_string()), operacion);
+        self
+    }
+
+    /// Agrega el borrado de `referencia`
+    pub fn eliminar(mut self, referencia: &str) -> TransaccionRefs {
+        self.agregar(referencia, None, Operacion::Eliminar);
+        self
+    }
+
+    /// Agrega una referencia simbolica: `referencia` pasa a apuntar a `destino`
+    /// (Ej: `HEAD` -> `refs/heads/master`)
+    pub fn apuntar_simbolicamente(mut self, referencia: &str, destino: &str) -> TransaccionRefs {
+        self.agregar(
+            referencia,
+            None,
+            Operacion::Escribir(format!("ref: {}", destino)),
+        );
+        self
+    }
+
+    /// Devuelve si la transaccion no tiene cambios pendientes
+    pub fn esta_vacia(&self) -> bool {
+        self.actualizaciones.is_empty()
+    }
+
+    /// Aplica todos los cambios de la transaccion o ninguno.
+    ///
+    /// ## Errores
+    /// - Si alguna referencia esta lockeada por otro proceso
+    /// - Si alguna referencia no tiene el valor viejo esperado
+    /// - Si falla la escritura de alguna de las referencias
+    pub fn confirmar(mut self) -> Result<(), String> {
+        // se ordenan para que dos transacciones tomen los locks en el mismo orden
+        self.actualizaciones
+            .sort_by(|a, b| a.referencia.cmp(&b.referencia));
+
+        let mut locks = Vec::new();
+        for actualizacion in &self.actualizaciones {
+            let ruta = self.dir_gir.join(&actualizacion.referencia);
+            let mut lock = ArchivoLock::adquirir(&ruta)?;
+            Self::verificar_valor_esperado(actualizacion, &ruta)?;
+            if let Operacion::Escribir(contenido) = &actualizacion.operacion {
+                lock.escribir(contenido)?;
+            }
+            locks.push((lock, &actualizacion.operacion));
+        }
+
+        let mut aplicadas = Vec::new();
+        for (lock, operacion) in locks {
+            let ruta = lock.ruta_destino().to_path_buf();
+            let valor_anterior = io::leer_bytes(&ruta).ok();
+            let resultado = match operacion {
+                Operacion::Escribir(_) => lock.confirmar(),
+                Operacion::Eliminar if io::existe(&ruta) => io::rm_directorio(&ruta),
+                Operacion::Eliminar => Ok(()),
+            };
+            if let Err(error) = resultado {
+                // los locks que faltaban se liberan al salir, sin tocar sus referencias
+                Self::deshacer(aplicadas);
+                return Err(error);
+            }
+            aplicadas.push((ruta, valor_anterior));
+        }
+        Ok(())
+    }
+
+    /// Devuelve las referencias ya modificadas a su valor anterior, en el orden inverso en el
+    /// que se aplicaron. Las que no existian se borran
+    fn deshacer(aplicadas: Vec<(PathBuf, Option<Vec<u8>>)>) {
+        for (ruta, valor_anterior) in aplicadas.into_iter().rev() {
+            let _ = match valor_anterior {
+                Some(valor) => io::escribir_bytes(&ruta, valor),
+                None => io::rm_directorio(&ruta),
+            };
+        }
+    }
+
+    fn agregar(&mut self, referencia: &str, valor_esperado: Option<String>, operacion: Operacion) {
+        let referencia = referencia.trim_start_matches("./").to_string();
+        self.actualizaciones
+            .retain(|actualizacion| actualizacion.referencia != referencia);
+        self.actualizaciones.push(ActualizacionRef {
+            referencia,
+            valor_esperado,
+            operacion,
+        });
+    }
+
+    fn verificar_valor_esperado(
+        actualizacion: &ActualizacionRef,
+        ruta: &Path,
+    ) -> Result<(), String> {
+        let valor_esperado = match &actualizacion.valor_esperado {
+            Some(valor) => valor.trim(),
+            None => return Ok(()),
+        };
+
+        let valor_actual = io::leer_a_string(ruta).unwrap_or_default();
+        let valor_actual = valor_actual.trim();
+
+        let coinciden = if Self::es_valor_nulo(valor_esperado) {
+            Self::es_valor_nulo(valor_actual)
+        } else {
+            valor_actual == valor_esperado
+        };
+
+        if coinciden {
+            Ok(())
+        } else {
+            Err(format!(
+                "La referencia {} cambio: se esperaba {} pero tiene {}",
+                actualizacion.referencia,
+                valor_esperado,
+                if valor_actual.is_empty() {
+                    HASH_NULO
+                } else {
+                    valor_actual
+                }
+            ))
+        }
+    }
+
+    fn es_valor_nulo(valor: &str) -> bool {
+        valor.is_empty() || valor == HASH_NULO
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use super::TransaccionRefs;
+    use crate::utils::{archivo_lock::ArchivoLock, io};
+
+    fn crear_dir_gir(nombre: &str) -> PathBuf {
+        let dir = PathBuf::from(format!("tmp/{}/.gir", nombre));
+        let _ = io::rm_directorio(&dir);
+        io::crear_directorio(dir.join("refs/heads")).unwrap();
+        dir
+    }
+
+    #[test]
+    #[serial]
+    fn test01_se_actualizan_varias_refs() {
+        let dir = crear_dir_gir("transaccion_refs_test01");
+
+        TransaccionRefs::new(&dir)
+            .actualizar("refs/heads/master", &"1".repeat(40))
+            .actualizar("refs/heads/rama", &"2".repeat(40))
+            .confirmar()
+            .unwrap();
+
+        assert_eq!(
+            io::leer_a_string(dir.join("refs/heads/master")).unwrap(),
+            "1".repeat(40)
+        );
+        assert_eq!(
+            io::leer_a_string(dir.join("refs/heads/rama")).unwrap(),
+            "2".repeat(40)
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test02_si_el_valor_viejo_no_coincide_no_se_actualiza_ninguna_ref() {
+        let dir = crear_dir_gir("transaccion_refs_test02");
+        io::escribir_bytes(dir.join("refs/heads/master"), "1".repeat(40)).unwrap();
+
+        let resultado = TransaccionRefs::new(&dir)
+            .actualizar("refs/heads/rama", &"3".repeat(40))
+            .actualizar_verificando("refs/heads/master", &"2".repeat(40), &"3".repeat(40))
+            .confirmar();
+
+        assert!(resultado.is_err());
+        assert_eq!(
+            io::leer_a_string(dir.join("refs/heads/master")).unwrap(),
+            "1".repeat(40)
+        );
+        assert!(!dir.join("refs/heads/rama").exists());
+        assert!(!dir.join("refs/heads/rama.lock").exists());
+        assert!(!dir.join("refs/heads/master.lock").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test03_no_se_actualiza_una_ref_lockeada() {
+        let dir = crear_dir_gir("transaccion_refs_test03");
+        let _lock = ArchivoLock::adquirir(dir.join("refs/heads/master")).unwrap();
+
+        let resultado = TransaccionRefs::new(&dir)
+            .actualizar("refs/heads/master", &"1".repeat(40))
+            .confirmar();
+
+        assert!(resultado.is_err());
+        assert!(!dir.join("refs/heads/master").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test04_valor_nuevo_nulo_elimina_la_ref() {
+        let dir = crear_dir_gir("transaccion_refs_test04");
+        io::escribir_bytes(dir.join("refs/heads/rama"), "1".repeat(40)).unwrap();
+
+        TransaccionRefs::new(&dir)
+            .actualizar_verificando("refs/heads/rama", &"1".repeat(40), &"0".repeat(40))
+            .confirmar()
+            .unwrap();
+
+        assert!(!dir.join("refs/heads/rama").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test05_si_falla_un_renombre_se_restauran_las_refs_ya_actualizadas() {
+        let dir = crear_dir_gir("transaccion_refs_test05");
+        io::escribir_bytes(dir.join("refs/heads/a"), "1".repeat(40)).unwrap();
+        // no se puede renombrar el lock de `b` sobre un directorio con contenido
+        io::escribir_bytes(dir.join("refs/heads/b/archivo"), "contenido").unwrap();
+
+        let resultado = TransaccionRefs::new(&dir)
+            .actualizar("refs/heads/a", &"2".repeat(40))
+            .actualizar("refs/heads/b", &"2".repeat(40))
+            .actualizar("refs/heads/c", &"2".repeat(40))
+            .confirmar();
+
+        assert!(resultado.is_err());
+        assert_eq!(
+            io::leer_a_string(dir.join("refs/heads/a")).unwrap(),
+            "1".repeat(40)
+        );
+        assert!(!dir.join("refs/heads/c").exists());
+        for lock in ["a.lock", "b.lock", "c.lock"] {
+            assert!(!dir.join("refs/heads").join(lock).exists());
+        }
+    }
+}
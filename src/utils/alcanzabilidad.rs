@@ -0,0 +1,257 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use super::{compresion, io, packs::Packs};
+
+const LARGO_HASH: usize = 40;
+const MODO_SUBMODULO: &str = "160000";
+
+/// Devuelve el directorio de objetos de un directorio gir, con la barra final que esperan
+/// las funciones de lectura de objetos (Ej: `.gir/objects/`)
+pub fn obtener_dir_objetos(dir_gir: &Path) -> String {
+    format!("{}/", dir_gir.join("objects").display())
+}
+
+/// Devuelve todos los objetos alcanzables del repositorio: los apuntados por las raices
+/// (ver `obtener_raices`) y todo lo que se llega a partir de ellos.
+///
+/// ## Errores
+/// - Si algun objeto alcanzable no existe o no se puede leer, ya que en ese caso
+///   no es seguro decidir que objetos sobran
+pub fn obtener_objetos_alcanzables(dir_gir: &Path) -> Result<HashSet<String>, String> {
+    let packs = Packs::cargar(&obtener_dir_objetos(dir_gir))?;
+    let mut alcanzables = HashSet::new();
+    let mut pendientes = obtener_raices_con_packs(dir_gir, &packs)?;
+
+    while let Some(hash) = pendientes.pop() {
+        if alcanzables.contains(&hash) {
+            continue;
+        }
+        let objeto =
+            compresion::leer_objeto_descomprimido_con_packs(&hash, &packs).map_err(|e| {
+                format!(
+                    "El objeto {} es alcanzable pero no se pudo leer: {}",
+                    hash, e
+                )
+            })?;
+        pendientes.extend(obtener_objetos_referenciados(&objeto)?);
+        alcanzables.insert(hash);
+    }
+    Ok(alcanzables)
+}
+
+/// Devuelve los hashes desde los que se recorre el grafo de objetos:
+/// - las refs de `refs/` (ramas, tags y remotos)
+/// - `HEAD` y los demas `*_HEAD` (MERGE_HEAD, `<REMOTO>_HEAD`, ...)
+/// - los archivos de un rebase en curso y los reflogs de `logs/`
+/// - los objetos del index
+pub fn obtener_raices(dir_gir: &Path) -> Result<Vec<String>, String> {
+    obtener_raices_con_packs(dir_gir, &Packs::cargar(&obtener_dir_objetos(dir_gir))?)
+}
+
+fn obtener_raices_con_packs(dir_gir: &Path, packs: &Packs) -> Result<Vec<String>, String> {
+    let mut raices = Vec::new();
+
+    for (_, valor) in obtener_refs(dir_gir)? {
+        if es_hash(&valor) {
+            raices.push(valor);
+        }
+    }
+
+    let mut archivos_auxiliares = obtener_archivos(&dir_gir.join("rebase-merge"))?;
+    archivos_auxiliares.extend(obtener_archivos(&dir_gir.join("logs"))?);
+    for entrada in io::leer_directorio(&dir_gir)? {
+        let ruta = entrada
+            .map_err(|e| format!("Error leyendo directorio: {}", e))?
+            .path();
+        let nombre = ruta.file_name().unwrap_or_default().to_string_lossy();
+        if io::es_archivo(&ruta) && (nombre == "HEAD" || nombre.ends_with("_HEAD")) {
+            archivos_auxiliares.push(ruta);
+        }
+    }
+    // estos archivos pueden mencionar objetos que ya no existen (Ej: reflogs viejos),
+    // por eso solo se toman los que estan en la base de datos
+    for archivo in archivos_auxiliares {
+        let contenido = io::leer_a_string(&archivo).unwrap_or_default();
+        raices.extend(
+            contenido
+                .split_whitespace()
+                .filter(|palabra| es_hash(palabra) && packs.existe_objeto(palabra))
+                .map(|palabra| palabra.to_string()),
+        );
+    }
+
+    raices.extend(obtener_objetos_del_index(&dir_gir.join("index"))?);
+    Ok(raices)
+}
+
+/// Devuelve todas las referencias de `refs/` junto a su valor (Ej: `refs/heads/master`, `<hash>`).
+/// Las ramas recien creadas sin commits tienen valor vacio.
+pub fn obtener_refs(dir_gir: &Path) -> Result<Vec<(String, String)>, String> {
+    let mut refs = Vec::new();
+    for archivo in obtener_archivos(&dir_gir.join("refs"))? {
+        let valor = io::leer_a_string(&archivo)?.trim().to_string();
+        let nombre = archivo
+            .strip_prefix(dir_gir)
+            .unwrap_or(&archivo)
+            .to_string_lossy()
+            .replace('\\', "/");
+        refs.push((nombre, valor));
+    }
+    refs.sort();
+    Ok(refs)
+}
+
+/// Dado un objeto descomprimido con su header, devuelve los hashes de los objetos a los que apunta:
+/// el arbol y los padres de un commit, las entradas de un tree o el objeto de un tag.
+pub fn obtener_objetos_referenciados(objeto: &[u8]) -> Result<Vec<String>, String> {
+    let posicion_null = objeto
+        .iter()
+        .position(|&byte| byte == 0)
+        .ok_or("Objeto sin header")?;
+    let header = String::from_utf8_lossy(&objeto[..posicion_null]);
+    let contenido = &objeto[posicion_null + 1..];
+
+    match header.split_whitespace().next() {
+        Some("blob") => Ok(Vec::new()),
+        Some("tree") => obtener_entradas_tree(contenido),
+        Some("commit") | Some("tag") => Ok(String::from_utf8_lossy(contenido)
+            .lines()
+            .take_while(|linea| !linea.is_empty())
+            .filter_map(|linea| linea.split_once(' '))
+            .filter(|(campo, valor)| {
+                matches!(*campo, "tree" | "parent" | "object") && es_hash(valor)
+            })
+            .map(|(_, valor)| valor.to_string())
+            .collect()),
+        _ => Err(format!("Tipo de objeto invalido: {}", header)),
+    }
+}
+
+/// Devuelve si el texto es un hash de objeto valido distinto del hash nulo
+pub fn es_hash(texto: &str) -> bool {
+    texto.len() == LARGO_HASH
+        && texto.chars().all(|c| c.is_ascii_hexdigit())
+        && texto.chars().any(|c| c != '0')
+}
+
+// Las entradas de un tree tienen el formato `<modo> <nombre>\0<hash en 20 bytes>`.
+// Los submodulos apuntan a commits de otro repositorio, por lo que no se siguen
+fn obtener_entradas_tree(contenido: &[u8]) -> Result<Vec<String>, String> {
+    let mut hashes = Vec::new();
+    let mut resto = contenido;
+    while !resto.is_empty() {
+        let posicion_null = resto
+            .iter()
+            .position(|&byte| byte == 0)
+            .ok_or("Entrada de tree invalida")?;
+        if resto.len() < posicion_null + 21 {
+            return Err("Entrada de tree truncada".to_string());
+        }
+        let modo_y_nombre = String::from_utf8_lossy(&resto[..posicion_null]);
+        let hash = &resto[posicion_null + 1..posicion_null + 21];
+        if !modo_y_nombre.starts_with(MODO_SUBMODULO) {
+            hashes.push(hash.iter().map(|byte| format!("{:02x}", byte)).collect());
+        }
+        resto = &resto[posicion_null + 21..];
+    }
+    Ok(hashes)
+}
+
+// Cada linea del index tiene el formato `<+/-> <merge> <modo> <hash> <ruta>`
+fn obtener_objetos_del_index(ruta_index: &Path) -> Result<Vec<String>, String> {
+    if !io::existe(ruta_index) {
+        return Ok(Vec::new());
+    }
+    Ok(io::leer_a_string(ruta_index)?
+        .lines()
+        .filter_map(|linea| linea.split_whitespace().nth(3))
+        .filter(|hash| es_hash(hash))
+        .map(|hash| hash.to_string())
+        .collect())
+}
+
+// Devuelve recursivamente todos los archivos dentro del directorio, o nada si no existe
+fn obtener_archivos(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    if !io::es_dir(dir) {
+        return Ok(Vec::new());
+    }
+    let mut archivos = Vec::new();
+    for entrada in io::leer_directorio(&dir)? {
+        let ruta = entrada
+            .map_err(|e| format!("Error leyendo directorio: {}", e))?
+            .path();
+        if io::es_dir(&ruta) {
+            archivos.extend(obtener_archivos(&ruta)?);
+        } else if ruta.extension().is_none_or(|extension| extension != "lock") {
+            archivos.push(ruta);
+        }
+    }
+    Ok(archivos)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::utils::compresion::comprimir_contenido_u8;
+
+    fn escribir_objeto(dir_gir: &Path, hash: &str, objeto: &[u8]) {
+        let ruta = dir_gir.join("objects").join(&hash[..2]).join(&hash[2..]);
+        io::escribir_bytes(ruta, comprimir_contenido_u8(objeto).unwrap()).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test01_se_obtienen_los_objetos_referenciados_por_un_commit() {
+        let commit = format!(
+            "commit 0\0tree {}\nparent {}\nauthor a <a> 0 +0000\n\nmensaje con tree {}\n",
+            "1".repeat(40),
+            "2".repeat(40),
+            "3".repeat(40)
+        );
+
+        let referenciados = obtener_objetos_referenciados(commit.as_bytes()).unwrap();
+
+        assert_eq!(referenciados, vec!["1".repeat(40), "2".repeat(40)]);
+    }
+
+    #[test]
+    #[serial]
+    fn test02_se_recorren_los_objetos_desde_las_ramas_y_el_index() {
+        let dir_gir = PathBuf::from("tmp/alcanzabilidad_test02/.gir");
+        let _ = io::rm_directorio(&dir_gir);
+        let (blob, blob_index, suelto) = ("a".repeat(40), "b".repeat(40), "c".repeat(40));
+        let tree = "d".repeat(40);
+        let commit = "e".repeat(40);
+
+        let mut contenido_tree = b"tree 0\x00100644 archivo\x00".to_vec();
+        contenido_tree.extend([0xaa; 20]);
+        escribir_objeto(&dir_gir, &blob, b"blob 0\0");
+        escribir_objeto(&dir_gir, &blob_index, b"blob 0\0");
+        escribir_objeto(&dir_gir, &suelto, b"blob 0\0");
+        escribir_objeto(&dir_gir, &tree, &contenido_tree);
+        escribir_objeto(
+            &dir_gir,
+            &commit,
+            format!("commit 0\0tree {}\n\n", tree).as_bytes(),
+        );
+        io::escribir_bytes(dir_gir.join("refs/heads/master"), &commit).unwrap();
+        io::escribir_bytes(dir_gir.join("HEAD"), "ref: refs/heads/master").unwrap();
+        io::escribir_bytes(
+            dir_gir.join("index"),
+            format!("+ 0 100644 {} otro_archivo", blob_index),
+        )
+        .unwrap();
+
+        let alcanzables = obtener_objetos_alcanzables(&dir_gir).unwrap();
+
+        assert_eq!(alcanzables, HashSet::from([blob, blob_index, tree, commit]));
+        assert!(!alcanzables.contains(&suelto));
+    }
+}
@@ -0,0 +1,173 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::tipos_de_dato::repositorio::resolver;
+
+use super::io;
+
+const EXTENSION_LOCK: &str = "lock";
+
+/// Lock exclusivo sobre un archivo al estilo git: mientras exista `<archivo>.lock`
+/// ningun otro proceso puede tomar el lock sobre el mismo archivo.
+///
+/// El contenido nuevo se escribe en el `.lock` y recien al confirmar se renombra
+/// sobre el archivo original, por lo que un lector nunca ve un archivo a medio escribir.
+/// Si el lock se descarta sin confirmar, se borra el `.lock` y el original queda intacto.
+pub struct ArchivoLock {
+    ruta_destino: PathBuf,
+    ruta_lock: PathBuf,
+    archivo: Option<File>,
+}
+
+impl ArchivoLock {
+    /// Toma el lock sobre `ruta` creando `<ruta>.lock` de forma exclusiva.
+    ///
+    /// ## Errores
+    /// - Si ya existe el `.lock` (otro proceso esta modificando el archivo)
+    /// - Si no se pudo crear el `.lock`
+    pub fn adquirir<P: AsRef<Path>>(ruta: P) -> Result<ArchivoLock, String> {
+        let ruta_destino = resolver(ruta);
+        let ruta_lock = Self::obtener_ruta_lock(&ruta_destino);
+        io::si_no_existe_directorio_de_archivo_crearlo(&ruta_lock)?;
+
+        let archivo = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&ruta_lock)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => format!(
+                    "No se pudo tomar el lock {}: otro proceso esta modificando el archivo",
+                    ruta_lock.display()
+                ),
+                _ => format!("No se pudo crear el lock {}: {}", ruta_lock.display(), e),
+            })?;
+
+        Ok(ArchivoLock {
+            ruta_destino,
+            ruta_lock,
+            archivo: Some(archivo),
+        })
+    }
+
+    /// Escribe el contenido en el `.lock` y lo sincroniza a disco.
+    pub fn escribir<C: AsRef<[u8]>>(&mut self, contenido: C) -> Result<(), String> {
+        let archivo = self
+            .archivo
+            .as_mut()
+            .ok_or_else(|| format!("El lock {} ya fue liberado", self.ruta_lock.display()))?;
+
+        archivo
+            .write_all(contenido.as_ref())
+            .and_then(|_| archivo.sync_all())
+            .map_err(|e| {
+                format!(
+                    "Error al escribir el lock {}: {}",
+                    self.ruta_lock.display(),
+                    e
+                )
+            })
+    }
+
+    /// Reemplaza el archivo original por el contenido escrito en el `.lock`. Si no se puede,
+    /// se borra el `.lock` para no dejar el archivo lockeado.
+    pub fn confirmar(mut self) -> Result<(), String> {
+        self.archivo.take();
+        fs::rename(&self.ruta_lock, &self.ruta_destino).map_err(|e| {
+            let _ = fs::remove_file(&self.ruta_lock);
+            format!(
+                "No se pudo reemplazar {} por su lock: {}",
+                self.ruta_destino.display(),
+                e
+            )
+        })
+    }
+
+    /// Devuelve el archivo protegido por el lock.
+    pub fn ruta_destino(&self) -> &Path {
+        &self.ruta_destino
+    }
+
+    fn obtener_ruta_lock(ruta: &Path) -> PathBuf {
+        let mut nombre = ruta.as_os_str().to_os_string();
+        nombre.push(".");
+        nombre.push(EXTENSION_LOCK);
+        PathBuf::from(nombre)
+    }
+}
+
+impl Drop for ArchivoLock {
+    fn drop(&mut self) {
+        // si se confirmo el `.lock` ya fue renombrado y no queda nada para limpiar
+        if self.archivo.take().is_some() {
+            let _ = fs::remove_file(&self.ruta_lock);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use serial_test::serial;
+
+    use super::ArchivoLock;
+    use crate::utils::io;
+
+    #[test]
+    #[serial]
+    fn test01_no_se_puede_tomar_dos_veces_el_mismo_lock() {
+        let ruta = PathBuf::from("tmp/archivo_lock_test01");
+        let _lock = ArchivoLock::adquirir(&ruta).unwrap();
+
+        assert!(ArchivoLock::adquirir(&ruta).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_al_confirmar_se_reemplaza_el_archivo() {
+        let ruta = PathBuf::from("tmp/archivo_lock_test02");
+        io::escribir_bytes(&ruta, "viejo").unwrap();
+
+        let mut lock = ArchivoLock::adquirir(&ruta).unwrap();
+        lock.escribir("nuevo").unwrap();
+        assert_eq!(io::leer_a_string(&ruta).unwrap(), "viejo");
+        lock.confirmar().unwrap();
+
+        assert_eq!(io::leer_a_string(&ruta).unwrap(), "nuevo");
+        assert!(!PathBuf::from("tmp/archivo_lock_test02.lock").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test03_al_descartar_el_lock_el_archivo_queda_intacto() {
+        let ruta = PathBuf::from("tmp/archivo_lock_test03");
+        io::escribir_bytes(&ruta, "viejo").unwrap();
+
+        {
+            let mut lock = ArchivoLock::adquirir(&ruta).unwrap();
+            lock.escribir("nuevo").unwrap();
+        }
+
+        assert_eq!(io::leer_a_string(&ruta).unwrap(), "viejo");
+        assert!(!PathBuf::from("tmp/archivo_lock_test03.lock").exists());
+    }
+
+    #[test]
+    #[serial]
+    fn test04_si_no_se_puede_confirmar_no_queda_el_lock() {
+        let ruta = PathBuf::from("tmp/archivo_lock_test04");
+        let _ = io::rm_directorio(&ruta);
+        // no se puede renombrar un archivo sobre un directorio con contenido
+        io::escribir_bytes(ruta.join("archivo"), "contenido").unwrap();
+
+        let mut lock = ArchivoLock::adquirir(&ruta).unwrap();
+        lock.escribir("nuevo").unwrap();
+
+        assert!(lock.confirmar().is_err());
+        assert!(!PathBuf::from("tmp/archivo_lock_test04.lock").exists());
+        assert!(ArchivoLock::adquirir(&ruta).is_ok());
+    }
+}
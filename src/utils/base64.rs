@@ -0,0 +1,83 @@
+const ALFABETO: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Codifica los bytes en base64 estandar, con `=` al final si hace falta
+pub fn codificar(bytes: &[u8]) -> String {
+    let mut codificado = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for grupo in bytes.chunks(3) {
+        let valor = grupo.iter().enumerate().fold(0u32, |valor, (i, byte)| {
+            valor | (*byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= grupo.len() {
+                let indice = (valor >> (18 - 6 * i)) & 0x3f;
+                codificado.push(ALFABETO[indice as usize] as char);
+            } else {
+                codificado.push('=');
+            }
+        }
+    }
+    codificado
+}
+
+/// Decodifica un texto en base64 estandar, con o sin `=` al final. Devuelve None si tiene
+/// caracteres que no son de base64 o su largo no puede ser el de un texto codificado
+pub fn decodificar(texto: &str) -> Option<Vec<u8>> {
+    let sin_relleno = texto.trim_end_matches('=');
+    if texto.len() - sin_relleno.len() > 2 || sin_relleno.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(sin_relleno.len() * 3 / 4);
+    let mut acumulado: u32 = 0;
+    let mut bits = 0;
+    for caracter in sin_relleno.bytes() {
+        let valor = ALFABETO.iter().position(|letra| *letra == caracter)?;
+        acumulado = (acumulado << 6) | valor as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((acumulado >> bits) as u8);
+            acumulado &= (1 << bits) - 1;
+        }
+    }
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test01_se_codifica_en_base64() {
+        assert_eq!(codificar(b""), "");
+        assert_eq!(codificar(b"h"), "aA==");
+        assert_eq!(codificar(b"ho"), "aG8=");
+        assert_eq!(codificar(b"hola\n"), "aG9sYQo=");
+    }
+
+    #[test]
+    fn test02_se_decodifica_lo_codificado() {
+        for texto in [
+            &b""[..],
+            b"h",
+            b"ho",
+            b"hola\n",
+            b"juani:secreto",
+            &[0, 255, 128, 7],
+        ] {
+            let codificado = codificar(texto);
+            assert_eq!(decodificar(&codificado).unwrap(), texto);
+            assert_eq!(
+                decodificar(codificado.trim_end_matches('=')).unwrap(),
+                texto
+            );
+        }
+    }
+
+    #[test]
+    fn test03_no_se_decodifican_textos_invalidos() {
+        assert_eq!(decodificar("aG9s*A=="), None);
+        assert_eq!(decodificar("aG9sY"), None);
+        assert_eq!(decodificar("aA==="), None);
+    }
+}
@@ -1,23 +1,81 @@
-use crate::tipos_de_dato::objetos::tree::Tree;
+use crate::tipos_de_dato::{objetos::tree::Tree, repositorio::Repositorio};
 use flate2::{read::ZlibDecoder, write::ZlibEncoder, Compression};
 use std::io::{Read, Write};
 
-use super::io;
+use super::{
+    io,
+    packs::{self, Packs},
+};
 
 /// Dado un hash y una ruta, busca el archivo de ese hash en la ruta especificada
 /// y devuelve el contenido del objeto descomprimido.
 pub fn descomprimir_objeto(hash: &str, ruta: &str) -> Result<String, String> {
-    let ruta_objeto = format!("{}{}/{}", ruta, &hash[..2], &hash[2..]);
-
-    let contenido_leido = io::leer_bytes(ruta_objeto)?;
-    let contenido_descomprimido = descomprimir_contenido_u8(&contenido_leido)?;
+    let contenido_descomprimido = leer_objeto_descomprimido(hash, ruta)?;
     let contenido_decodificado = decodificar_contenido(contenido_descomprimido)?;
     Ok(contenido_decodificado)
 }
 
-/// Descomprime el objeto indicado por el hash en la ruta .gir/objects
+/// Dado un hash y el directorio de objetos, devuelve los bytes del objeto descomprimido, con su header.
+/// Primero busca el objeto suelto y si no esta lo busca en los packfiles del directorio.
+pub fn leer_objeto_descomprimido(hash: &str, ruta: &str) -> Result<Vec<u8>, String> {
+    if let Some(objeto) = leer_objeto_suelto(hash, ruta)? {
+        return Ok(objeto);
+    }
+    if let Some(objeto) = packs::leer_objeto_de_packs(hash, ruta)? {
+        return Ok(objeto);
+    }
+    leer_objeto_de_alternativos(hash, ruta)?
+        .ok_or_else(|| format!("No se encontro el objeto {} en {}", hash, ruta))
+}
+
+/// Igual que `leer_objeto_descomprimido`, pero con los packs del directorio de objetos ya
+/// cargados. Es la que usan las operaciones que leen muchos objetos.
+pub fn leer_objeto_descomprimido_con_packs(hash: &str, packs: &Packs) -> Result<Vec<u8>, String> {
+    let ruta = packs.dir_objetos();
+    if let Some(objeto) = leer_objeto_suelto(hash, ruta)? {
+        return Ok(objeto);
+    }
+    if let Some(objeto) = packs.leer_objeto(hash)? {
+        return Ok(objeto);
+    }
+    leer_objeto_de_alternativos(hash, ruta)?
+        .ok_or_else(|| format!("No se encontro el objeto {} en {}", hash, ruta))
+}
+
+// Si `ruta` es el directorio de objetos del repositorio actual, busca el objeto en los
+// directorios de objetos alternativos del repositorio
+fn leer_objeto_de_alternativos(hash: &str, ruta: &str) -> Result<Option<Vec<u8>>, String> {
+    let repositorio = match Repositorio::entrado() {
+        Some(repositorio) if ruta == repositorio.dir_objetos() => repositorio,
+        _ => return Ok(None),
+    };
+    for dir_objetos in repositorio.objetos_alternativos() {
+        if let Some(objeto) = leer_objeto_suelto(hash, dir_objetos)? {
+            return Ok(Some(objeto));
+        }
+        if let Some(objeto) = packs::leer_objeto_de_packs(hash, dir_objetos)? {
+            return Ok(Some(objeto));
+        }
+    }
+    Ok(None)
+}
+
+// Devuelve el objeto descomprimido si esta suelto en el directorio de objetos
+fn leer_objeto_suelto(hash: &str, ruta: &str) -> Result<Option<Vec<u8>>, String> {
+    if hash.len() < 3 {
+        return Err(format!("Hash de objeto invalido: {}", hash));
+    }
+    let ruta_objeto = format!("{}{}/{}", ruta, &hash[..2], &hash[2..]);
+    if !io::existe(&ruta_objeto) {
+        return Ok(None);
+    }
+    let contenido_leido = io::leer_bytes(ruta_objeto)?;
+    descomprimir_contenido_u8(&contenido_leido).map(Some)
+}
+
+/// Descomprime el objeto indicado por el hash en el directorio de objetos del repositorio actual
 pub fn descomprimir_objeto_gir(hash: &str) -> Result<String, String> {
-    descomprimir_objeto(hash, ".gir/objects/")
+    descomprimir_objeto(hash, &Repositorio::actual().dir_objetos())
 }
 
 /// Convierte un vector de u8 a un string.
@@ -149,23 +207,14 @@ pub fn descomprimir_contenido_u8(contenido: &[u8]) -> Result<Vec<u8>, String> {
 /// Dado un hash y una ruta, busca el archivo de ese hash en la ruta especificada
 /// y devuelve el contenido del objeto comprimido, sin tener en cuenta la linea del header del objeto.
 pub fn obtener_contenido_comprimido_sin_header(hash: &str) -> Result<Vec<u8>, String> {
-    let ruta_objeto = format!(".gir/objects/{}/{}", &hash[..2], &hash[2..]);
-    let contenido_leido = io::leer_bytes(ruta_objeto)?;
-    let cont_descomprimido = descomprimir_contenido_u8(&contenido_leido).unwrap();
-    let vec: Vec<&[u8]> = cont_descomprimido.splitn(2, |&x| x == 0).collect();
-
-    let contenido = vec[1];
-    let contenido_comprimido = comprimir_contenido_u8(contenido)?;
-    Ok(contenido_comprimido)
+    obtener_contenido_comprimido_sin_header_de(hash, &Repositorio::actual().dir_objetos())
 }
 
 pub fn obtener_contenido_comprimido_sin_header_de(
     hash: &str,
     dir: &str,
 ) -> Result<Vec<u8>, String> {
-    let ruta_objeto = format!("{}{}/{}", dir, &hash[..2], &hash[2..]);
-    let contenido_leido = io::leer_bytes(ruta_objeto)?;
-    let cont_descomprimido = descomprimir_contenido_u8(&contenido_leido).unwrap();
+    let cont_descomprimido = leer_objeto_descomprimido(hash, dir)?;
     let vec: Vec<&[u8]> = cont_descomprimido.splitn(2, |&x| x == 0).collect();
 
     let contenido = vec[1];
@@ -63,10 +63,58 @@ pub fn conseguir_direccion_nombre_remoto() -> Option<String> {
     buscar_en_config_el_valor_de("remoto")
 }
 
+///extrae la ubicacion del archivo global de patrones a ignorar seteada en el archivo config.
+///Busca una entrada que sea 'excludes_file='. Si no esta, usa `$XDG_CONFIG_HOME/gir/ignore`
+///o `~/.config/gir/ignore`.
+pub fn conseguir_archivo_excludes_global() -> Option<PathBuf> {
+    if let Some(archivo) = buscar_en_config_el_valor_de("excludes_file") {
+        return match (archivo.strip_prefix("~/"), std::env::var("HOME")) {
+            (Some(resto), Ok(home)) => Some(PathBuf::from(home).join(resto)),
+            _ => Some(PathBuf::from(archivo)),
+        };
+    }
+    match std::env::var("XDG_CONFIG_HOME") {
+        Ok(config) if !config.is_empty() => Some(PathBuf::from(config).join("gir/ignore")),
+        _ => std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config/gir/ignore")),
+    }
+}
+
 pub fn conseguir_puerto_http() -> Option<String> {
     buscar_en_config_el_valor_de("puerto_http")
 }
 
+///extrae el permiso que tienen todos sobre los repositorios del servidor sin archivo de permisos.
+///Busca una entrada que sea 'permiso_por_defecto=' (`none` o `read`)
+pub fn conseguir_permiso_por_defecto() -> Option<String> {
+    buscar_en_config_el_valor_de("permiso_por_defecto")
+}
+
+///extrae los hosts no publicos a los que pueden enviar eventos los webhooks del servidor.
+///Busca una entrada que sea 'hosts_webhooks_permitidos=' (Ej: `ci.interno, 10.0.0.5`)
+pub fn conseguir_hosts_webhooks_permitidos() -> Option<String> {
+    buscar_en_config_el_valor_de("hosts_webhooks_permitidos")
+}
+
+///extrae la cantidad de threads que atienden conexiones en el servidor (`http` o `gir`) o que
+///entregan los eventos de los webhooks (`webhooks`).
+///Busca una entrada que sea 'workers_{servidor}='
+pub fn conseguir_cantidad_workers(servidor: &str) -> Option<usize> {
+    buscar_en_config_el_valor_de(&format!("workers_{servidor}"))?
+        .parse()
+        .ok()
+}
+
+///extrae cuantas conexiones (o entregas de `webhooks`) pueden esperar a un thread libre en el
+///servidor (`http` o `gir`).
+///Busca una entrada que sea 'cola_{servidor}='
+pub fn conseguir_tamanio_cola(servidor: &str) -> Option<usize> {
+    buscar_en_config_el_valor_de(&format!("cola_{servidor}"))?
+        .parse()
+        .ok()
+}
+
 ///extrae el repositorio seteada en el archivo config.
 ///Busca una entrada que sea 'repositorio='
 pub fn conseguir_direccion_nombre_repositorio() -> Option<String> {
@@ -0,0 +1,379 @@
+use std::{
+    fmt,
+    path::{Component, Path},
+};
+
+use crate::tipos_de_dato::repositorio::Repositorio;
+
+use super::{gir_config, io, ubicacion_repositorio::DIR_GIR};
+
+/// Nombre de los archivos con patrones a ignorar que puede haber en cada directorio
+pub const ARCHIVO_GIRIGNORE: &str = ".girignore";
+/// Archivo, dentro de `.gir`, con patrones a ignorar propios del repositorio, que no se versiona
+pub const ARCHIVO_EXCLUDE: &str = "info/exclude";
+
+/// Un patron de un archivo `.girignore` (o de `.gir/info/exclude` o del excludes global).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReglaIgnore {
+    /// Archivo del que se leyo la regla
+    pub origen: String,
+    /// Numero de linea de la regla dentro de su archivo, empezando en 1
+    pub linea: usize,
+    /// Patron tal como esta escrito en el archivo
+    pub patron: String,
+    /// Directorio, relativo a la raiz del repositorio, desde el que aplica la regla
+    base: Vec<String>,
+    /// Glob a comparar, sin `!`, sin la `/` inicial y sin la `/` final
+    glob: Vec<char>,
+    negada: bool,
+    solo_directorios: bool,
+    /// Si el patron tiene una `/` se compara contra la ruta desde `base`,
+    /// si no, contra el nombre del archivo en cualquier nivel
+    anclada: bool,
+}
+
+/// Reglas que aplican a una ruta, ordenadas de menor a mayor prioridad.
+/// Si varias reglas coinciden con una ruta, gana la ultima.
+pub struct ReglasIgnore {
+    reglas: Vec<ReglaIgnore>,
+}
+
+impl ReglaIgnore {
+    /// Parsea una linea de un archivo de patrones.
+    /// Devuelve None si la linea esta vacia o es un comentario.
+    pub fn from(linea: &str, origen: &str, numero_linea: usize, base: &[String]) -> Option<Self> {
+        let patron = quitar_espacios_finales(linea.trim_end_matches(['\n', '\r']));
+        if patron.is_empty() || patron.starts_with('#') {
+            return None;
+        }
+
+        let (negada, mut glob) = match patron.strip_prefix('!') {
+            Some(resto) => (true, resto),
+            None => (false, patron),
+        };
+        let solo_directorios = glob.ends_with('/') && !glob.ends_with("\\/");
+        if solo_directorios {
+            glob = glob.trim_end_matches('/');
+        }
+        let anclada = glob.contains('/');
+        glob = glob.trim_start_matches('/');
+        if glob.is_empty() {
+            return None;
+        }
+
+        Some(ReglaIgnore {
+            origen: origen.to_string(),
+            linea: numero_linea,
+            patron: patron.to_string(),
+            base: base.to_vec(),
+            glob: glob.chars().collect(),
+            negada,
+            solo_directorios,
+            anclada,
+        })
+    }
+
+    /// Devuelve si la regla ignora las rutas con las que coincide.
+    /// Las reglas negadas (`!patron`) en cambio vuelven a incluirlas.
+    pub fn ignora(&self) -> bool {
+        !self.negada
+    }
+
+    /// Devuelve si la regla coincide con la ruta, dada como sus componentes desde la raiz del repositorio
+    fn coincide(&self, componentes: &[String], es_directorio: bool) -> bool {
+        if self.solo_directorios && !es_directorio {
+            return false;
+        }
+        let relativa = match componentes.strip_prefix(self.base.as_slice()) {
+            Some(relativa) if !relativa.is_empty() => relativa,
+            _ => return false,
+        };
+
+        if self.anclada {
+            let texto: Vec<char> = relativa.join("/").chars().collect();
+            coincide_glob(&self.glob, 0, &texto, 0)
+        } else {
+            let texto: Vec<char> = relativa[relativa.len() - 1].chars().collect();
+            coincide_glob(&self.glob, 0, &texto, 0)
+        }
+    }
+}
+
+/// Formato de `check-ignore -v`: `<origen>:<linea>:<patron>`
+impl fmt::Display for ReglaIgnore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.origen, self.linea, self.patron)
+    }
+}
+
+impl ReglasIgnore {
+    /// Carga las reglas que aplican a `ruta`, en orden de prioridad:
+    /// el excludes global, `.gir/info/exclude`, el `.girignore` de la raiz y los `.girignore`
+    /// de cada directorio hasta llegar a `ruta`. Los patrones de un `.girignore` son
+    /// relativos al directorio en el que esta.
+    pub fn cargar(ruta: &Path) -> ReglasIgnore {
+        let mut reglas = Vec::new();
+
+        if let Some(excludes_global) = gir_config::conseguir_archivo_excludes_global() {
+            let origen = excludes_global.to_string_lossy().to_string();
+            agregar_reglas_de_archivo(&mut reglas, &excludes_global, &origen, &[]);
+        }
+        let exclude = Repositorio::actual().ruta_gir(ARCHIVO_EXCLUDE);
+        let origen = format!("{}/{}", DIR_GIR, ARCHIVO_EXCLUDE);
+        agregar_reglas_de_archivo(&mut reglas, &exclude, &origen, &[]);
+
+        let componentes = componentes_de(ruta);
+        for cantidad in 0..componentes.len() {
+            let base = &componentes[..cantidad];
+            let archivo = base
+                .iter()
+                .chain(std::iter::once(&ARCHIVO_GIRIGNORE.to_string()))
+                .cloned()
+                .collect::<Vec<String>>()
+                .join("/");
+            agregar_reglas_de_archivo(&mut reglas, Path::new(&archivo), &archivo, base);
+        }
+
+        ReglasIgnore { reglas }
+    }
+
+    /// Crea las reglas a partir del contenido de un archivo de patrones ubicado en la raiz
+    pub fn from_contenido(contenido: &str, origen: &str) -> ReglasIgnore {
+        let mut reglas = Vec::new();
+        agregar_reglas(&mut reglas, contenido, origen, &[]);
+        ReglasIgnore { reglas }
+    }
+
+    /// Devuelve la regla que decide si `ruta` esta ignorada, o None si ninguna coincide.
+    /// La ruta esta ignorada si la regla devuelta `ignora()`.
+    ///
+    /// Si algun directorio que contiene a `ruta` esta ignorado, la ruta tambien lo esta y se
+    /// devuelve la regla de ese directorio: como en git, no se puede volver a incluir un archivo
+    /// si se ignoro el directorio que lo contiene.
+    pub fn buscar_regla(&self, ruta: &Path, es_directorio: bool) -> Option<&ReglaIgnore> {
+        let componentes = componentes_de(ruta);
+        for cantidad in 1..componentes.len() {
+            if let Some(regla) = self.ultima_coincidencia(&componentes[..cantidad], true) {
+                if regla.ignora() {
+                    return Some(regla);
+                }
+            }
+        }
+        self.ultima_coincidencia(&componentes, es_directorio)
+    }
+
+    /// Devuelve si `ruta` esta ignorada
+    pub fn esta_ignorado(&self, ruta: &Path, es_directorio: bool) -> bool {
+        self.buscar_regla(ruta, es_directorio)
+            .is_some_and(ReglaIgnore::ignora)
+    }
+
+    fn ultima_coincidencia(
+        &self,
+        componentes: &[String],
+        es_directorio: bool,
+    ) -> Option<&ReglaIgnore> {
+        self.reglas
+            .iter()
+            .rev()
+            .find(|regla| regla.coincide(componentes, es_directorio))
+    }
+}
+
+fn agregar_reglas_de_archivo(
+    reglas: &mut Vec<ReglaIgnore>,
+    archivo: &Path,
+    origen: &str,
+    base: &[String],
+) {
+    if let Ok(contenido) = io::leer_a_string(archivo) {
+        agregar_reglas(reglas, &contenido, origen, base);
+    }
+}
+
+fn agregar_reglas(reglas: &mut Vec<ReglaIgnore>, contenido: &str, origen: &str, base: &[String]) {
+    for (numero, linea) in contenido.lines().enumerate() {
+        if let Some(regla) = ReglaIgnore::from(linea, origen, numero + 1, base) {
+            reglas.push(regla);
+        }
+    }
+}
+
+/// Devuelve los componentes de una ruta relativa a la raiz, sin `.` ni separadores
+fn componentes_de(ruta: &Path) -> Vec<String> {
+    ruta.components()
+        .filter_map(|componente| match componente {
+            Component::Normal(nombre) => Some(nombre.to_string_lossy().to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+// Los espacios al final de un patron se ignoran, salvo que esten escapados con `\`
+fn quitar_espacios_finales(patron: &str) -> &str {
+    let mut fin = patron.len();
+    while patron[..fin].ends_with(' ') && !patron[..fin - 1].ends_with('\\') {
+        fin -= 1;
+    }
+    &patron[..fin]
+}
+
+/// Devuelve si `texto` coincide con el glob `patron`, con la misma sintaxis que los patrones
+/// de `.girignore` (Ej: `release/*` coincide con `release/1.0` pero no con `release/1.0/fix`)
+pub fn coincide_patron(patron: &str, texto: &str) -> bool {
+    let glob: Vec<char> = patron.chars().collect();
+    let texto: Vec<char> = texto.chars().collect();
+    coincide_glob(&glob, 0, &texto, 0)
+}
+
+/// Compara un glob de gitignore contra un texto.
+/// `*` y `?` no pasan de un directorio a otro, `[...]` es una clase de caracteres,
+/// `**` entre barras coincide con cualquier cantidad de directorios y `\` escapa el caracter siguiente.
+fn coincide_glob(glob: &[char], i: usize, texto: &[char], j: usize) -> bool {
+    if i == glob.len() {
+        return j == texto.len();
+    }
+
+    match glob[i] {
+        '*' if glob.get(i + 1) == Some(&'*') && (i == 0 || glob[i - 1] == '/') => {
+            match glob.get(i + 2) {
+                // `**` al final coincide con todo lo que queda
+                None => true,
+                // `**/` coincide con cero o mas directorios
+                Some('/') => {
+                    coincide_glob(glob, i + 3, texto, j)
+                        || (j..texto.len())
+                            .filter(|&k| texto[k] == '/')
+                            .any(|k| coincide_glob(glob, i + 3, texto, k + 1))
+                }
+                // en cualquier otro lugar es un `*` comun
+                Some(_) => coincide_glob(glob, i + 1, texto, j),
+            }
+        }
+        '*' => {
+            let mut k = j;
+            loop {
+                if coincide_glob(glob, i + 1, texto, k) {
+                    return true;
+                }
+                if k == texto.len() || texto[k] == '/' {
+                    return false;
+                }
+                k += 1;
+            }
+        }
+        '?' => j < texto.len() && texto[j] != '/' && coincide_glob(glob, i + 1, texto, j + 1),
+        '[' => match coincide_clase(glob, i, texto.get(j).copied()) {
+            Some((coincide, siguiente)) => coincide && coincide_glob(glob, siguiente, texto, j + 1),
+            // un `[` sin cerrar se toma literal
+            None => texto.get(j) == Some(&'[') && coincide_glob(glob, i + 1, texto, j + 1),
+        },
+        '\\' if i + 1 < glob.len() => {
+            texto.get(j) == Some(&glob[i + 1]) && coincide_glob(glob, i + 2, texto, j + 1)
+        }
+        literal => texto.get(j) == Some(&literal) && coincide_glob(glob, i + 1, texto, j + 1),
+    }
+}
+
+/// Compara el caracter contra la clase que empieza en `glob[inicio]` (el `[`).
+/// Devuelve si coincide y la posicion siguiente al `]`, o None si la clase no se cierra.
+fn coincide_clase(glob: &[char], inicio: usize, caracter: Option<char>) -> Option<(bool, usize)> {
+    let mut i = inicio + 1;
+    let negada = matches!(glob.get(i), Some('!') | Some('^'));
+    if negada {
+        i += 1;
+    }
+
+    let mut coincide = false;
+    let mut primero = true;
+    loop {
+        let actual = *glob.get(i)?;
+        if actual == ']' && !primero {
+            break;
+        }
+        primero = false;
+
+        let desde = if actual == '\\' {
+            i += 1;
+            *glob.get(i)?
+        } else {
+            actual
+        };
+        let hasta = if glob.get(i + 1) == Some(&'-') && glob.get(i + 2).is_some_and(|c| *c != ']') {
+            i += 2;
+            glob[i]
+        } else {
+            desde
+        };
+        if caracter.is_some_and(|c| desde <= c && c <= hasta) {
+            coincide = true;
+        }
+        i += 1;
+    }
+
+    let coincide = match caracter {
+        Some('/') | None => false,
+        Some(_) => coincide != negada,
+    };
+    Some((coincide, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn ignora(patrones: &str, ruta: &str, es_directorio: bool) -> bool {
+        ReglasIgnore::from_contenido(patrones, ".girignore")
+            .esta_ignorado(Path::new(ruta), es_directorio)
+    }
+
+    #[test]
+    fn test01_globs() {
+        assert!(ignora("*.log", "src/debug.log", false));
+        assert!(!ignora("*.log", "src/debug.log.txt", false));
+        assert!(ignora("archivo?.txt", "archivo1.txt", false));
+        assert!(!ignora("archivo?.txt", "archivo10.txt", false));
+        assert!(ignora("archivo[0-9].txt", "archivo7.txt", false));
+        assert!(!ignora("archivo[!0-9].txt", "archivo7.txt", false));
+        assert!(ignora("src/*.rs", "src/main.rs", false));
+        assert!(!ignora("src/*.rs", "src/utils/io.rs", false));
+        assert!(ignora("src/**/*.rs", "src/utils/io.rs", false));
+        assert!(ignora("src/**/*.rs", "src/main.rs", false));
+        assert!(ignora("**/build", "a/b/build", true));
+        assert!(ignora("docs/**", "docs/a/b.md", false));
+        assert!(ignora("\\#archivo", "#archivo", false));
+    }
+
+    #[test]
+    fn test02_anclaje_y_directorios() {
+        assert!(ignora("/raiz.txt", "raiz.txt", false));
+        assert!(!ignora("/raiz.txt", "src/raiz.txt", false));
+        assert!(ignora("raiz.txt", "src/raiz.txt", false));
+        assert!(ignora("target/", "target", true));
+        assert!(!ignora("target/", "target", false));
+        assert!(ignora("target/", "src/target/debug/gir", false));
+        assert!(!ignora("# comentario\n\n", "# comentario", false));
+    }
+
+    #[test]
+    fn test03_negacion_la_ultima_regla_gana() {
+        let patrones = "*.log\n!importante.log";
+        assert!(ignora(patrones, "debug.log", false));
+        assert!(!ignora(patrones, "importante.log", false));
+
+        let reglas = ReglasIgnore::from_contenido(patrones, ".girignore");
+        let regla = reglas
+            .buscar_regla(Path::new("importante.log"), false)
+            .unwrap();
+        assert_eq!(regla.to_string(), ".girignore:2:!importante.log");
+
+        // no se puede volver a incluir un archivo de un directorio ignorado
+        assert!(ignora(
+            "logs/\n!logs/importante.log",
+            "logs/importante.log",
+            false
+        ));
+    }
+}
@@ -0,0 +1,226 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{mpsc, Arc},
+    thread,
+};
+
+use crate::tipos_de_dato::{logger::Logger, repositorio::Repositorio};
+
+use super::{io, ubicacion_repositorio::VARIABLE_GIR_DIR};
+
+/// Directorio, dentro de `.gir`, en el que se buscan los hooks
+pub const DIR_HOOKS: &str = "hooks";
+
+/// Se ejecuta antes de armar el commit. Si falla, no se commitea.
+pub const PRE_COMMIT: &str = "pre-commit";
+/// Recibe la ruta del archivo con el mensaje del commit, que puede modificar. Si falla, no se commitea.
+pub const COMMIT_MSG: &str = "commit-msg";
+/// Se ejecuta despues de crear el commit. Su resultado no cambia nada.
+pub const POST_COMMIT: &str = "post-commit";
+/// Se ejecuta antes del commit de un merge automatico. Si falla, el merge queda en curso sin commitear.
+pub const PRE_MERGE_COMMIT: &str = "pre-merge-commit";
+/// Recibe la rama sobre la que se va a rebasear. Si falla, no se hace el rebase.
+pub const PRE_REBASE: &str = "pre-rebase";
+/// Recibe el nombre y la url del remoto, y por entrada estandar las referencias a enviar
+/// (`<ref local> <sha local> <ref remota> <sha remoto>`). Si falla, no se hace el push.
+pub const PRE_PUSH: &str = "pre-push";
+/// Del lado del servidor, recibe por entrada estandar las referencias a actualizar
+/// (`<sha viejo> <sha nuevo> <ref>`). Si falla, se rechaza todo el push.
+pub const PRE_RECEIVE: &str = "pre-receive";
+/// Del lado del servidor, se ejecuta por cada referencia con `<ref> <sha viejo> <sha nuevo>`.
+/// Si falla, se rechaza el push.
+pub const UPDATE: &str = "update";
+/// Del lado del servidor, se ejecuta despues de actualizar las referencias, con la misma
+/// entrada que `pre-receive`. Su resultado no cambia nada.
+pub const POST_RECEIVE: &str = "post-receive";
+
+/// Devuelve la ruta del hook `nombre` dentro de `dir_hooks` si existe y es ejecutable.
+fn obtener_hook(dir_hooks: &Path, nombre: &str) -> Option<PathBuf> {
+    let ruta = dir_hooks.join(nombre);
+    if io::es_ejecutable(&ruta) {
+        Some(ruta)
+    } else {
+        None
+    }
+}
+
+/// Ejecuta el hook `nombre` de `.gir/hooks/` del repositorio actual con los argumentos `args`,
+/// pasandole `entrada` por entrada estandar. Si el hook no existe o no es ejecutable no hace
+/// nada, como en git.
+///
+/// El hook se ejecuta desde la raiz del repositorio y con `GIR_DIR` apuntando a su directorio `.gir`.
+/// Lo que imprime se guarda en el log. Si termina con error, devuelve un error con su salida.
+pub fn ejecutar_hook(
+    nombre: &str,
+    args: &[String],
+    entrada: Option<&str>,
+    logger: Arc<Logger>,
+) -> Result<(), String> {
+    let repositorio = Repositorio::actual();
+    let mut salida = Vec::new();
+    let exito = ejecutar_hook_en(
+        repositorio.dir_gir(),
+        repositorio.dir_trabajo(),
+        nombre,
+        args,
+        entrada,
+        logger,
+        |linea| salida.push(linea.to_string()),
+    )?;
+
+    if !exito {
+        return Err(format!(
+            "El hook {} rechazo la operacion\n{}",
+            nombre,
+            salida.join("\n")
+        ));
+    }
+    Ok(())
+}
+
+/// Ejecuta el hook `nombre` del directorio `hooks` de `dir_gir` (que en el servidor es el
+/// repositorio bare) desde `dir_ejecucion`. Cada linea que imprime el hook, tanto por salida
+/// estandar como de error, se pasa a `por_cada_linea` a medida que se imprime.
+///
+/// Devuelve si el hook termino bien. Si no existe o no es ejecutable, se toma como que termino bien.
+pub fn ejecutar_hook_en<F>(
+    dir_gir: &Path,
+    dir_ejecucion: &Path,
+    nombre: &str,
+    args: &[String],
+    entrada: Option<&str>,
+    logger: Arc<Logger>,
+    mut por_cada_linea: F,
+) -> Result<bool, String>
+where
+    F: FnMut(&str),
+{
+    let hook = match obtener_hook(&dir_gir.join(DIR_HOOKS), nombre) {
+        Some(hook) => hook,
+        None => return Ok(true),
+    };
+    logger.log(&format!("Ejecutando hook {} {:?}", nombre, args));
+
+    let mut proceso = Command::new(&hook)
+        .args(args)
+        .current_dir(dir_ejecucion)
+        .env(VARIABLE_GIR_DIR, dir_gir)
+        .stdin(if entrada.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("No se pudo ejecutar el hook {}: {}", nombre, e))?;
+
+    // la entrada y las dos salidas se manejan en threads aparte para que el hook no se trabe
+    // escribiendo mientras nadie lee, o leyendo mientras nadie escribe
+    let escritor = match (entrada, proceso.stdin.take()) {
+        (Some(entrada), Some(mut stdin)) => {
+            let entrada = entrada.to_string();
+            // si el hook termina sin leer la entrada la escritura falla, pero no es un error del hook
+            Some(thread::spawn(move || {
+                let _ = stdin.write_all(entrada.as_bytes());
+            }))
+        }
+        _ => None,
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut salidas: Vec<Box<dyn Read + Send>> = Vec::new();
+    if let Some(stdout) = proceso.stdout.take() {
+        salidas.push(Box::new(stdout));
+    }
+    if let Some(stderr) = proceso.stderr.take() {
+        salidas.push(Box::new(stderr));
+    }
+    let lectores: Vec<_> = salidas
+        .into_iter()
+        .map(|salida| {
+            let tx = tx.clone();
+            thread::spawn(move || {
+                for linea in BufReader::new(salida).lines().map_while(Result::ok) {
+                    if tx.send(linea).is_err() {
+                        break;
+                    }
+                }
+            })
+        })
+        .collect();
+    drop(tx);
+
+    for linea in rx {
+        logger.log(&format!("Hook {}: {}", nombre, linea));
+        por_cada_linea(&linea);
+    }
+    for lector in lectores.into_iter().chain(escritor) {
+        let _ = lector.join();
+    }
+
+    let estado = proceso
+        .wait()
+        .map_err(|e| format!("No se pudo esperar al hook {}: {}", nombre, e))?;
+    Ok(estado.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{path::PathBuf, sync::Arc};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::utils::{io, testing::crear_hook};
+
+    #[test]
+    #[serial]
+    fn test01_los_hooks_que_no_existen_o_no_son_ejecutables_se_ignoran() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/hooks_test01.log")).unwrap());
+        let repositorio = Repositorio::new("tmp/hooks_test01");
+        let _ = io::rm_directorio(repositorio.dir_trabajo());
+        let _activo = repositorio.entrar();
+
+        io::escribir_bytes(".gir/hooks/pre-commit", "#!/bin/sh\nexit 1\n").unwrap();
+
+        assert!(ejecutar_hook(PRE_COMMIT, &[], None, logger.clone()).is_ok());
+        assert!(ejecutar_hook(POST_COMMIT, &[], None, logger).is_ok());
+    }
+
+    #[test]
+    #[serial]
+    fn test02_el_hook_recibe_argumentos_y_entrada_y_su_error_se_devuelve() {
+        let logger = Arc::new(Logger::new(PathBuf::from("tmp/hooks_test02.log")).unwrap());
+        let repositorio = Repositorio::new("tmp/hooks_test02");
+        let _ = io::rm_directorio(repositorio.dir_trabajo());
+        let _activo = repositorio.entrar();
+
+        crear_hook(
+            PRE_PUSH,
+            "#!/bin/sh\nread ref sha resto\necho \"$1 $2 $ref\" > recibido\n[ \"$ref\" != refs/heads/prohibida ] || { echo rama protegida; exit 1; }\n",
+        );
+        let args = vec!["origin".to_string(), "url".to_string()];
+
+        ejecutar_hook(
+            PRE_PUSH,
+            &args,
+            Some("refs/heads/master a b c\n"),
+            logger.clone(),
+        )
+        .unwrap();
+        let recibido = io::leer_a_string("recibido").unwrap();
+        let error = ejecutar_hook(
+            PRE_PUSH,
+            &args,
+            Some("refs/heads/prohibida a b c\n"),
+            logger,
+        )
+        .unwrap_err();
+
+        assert_eq!(recibido.trim(), "origin url refs/heads/master");
+        assert!(error.contains("rama protegida"));
+    }
+}
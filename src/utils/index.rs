@@ -2,17 +2,21 @@ use std::{
     collections::HashSet,
     fs::{self, OpenOptions},
     io::BufRead,
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::Arc,
 };
 
 use crate::tipos_de_dato::{
     comando::Ejecutar, comandos::hash_object::HashObject, logger::Logger, objeto::Objeto,
+    repositorio::Repositorio,
 };
 
 use super::{io, path_buf::obtener_directorio_raiz};
 
-const PATH_INDEX: &str = "./.gir/index";
+/// Devuelve la ruta al archivo index del repositorio actual
+fn ruta_index() -> PathBuf {
+    Repositorio::actual().ruta_gir("index")
+}
 
 #[derive(Debug, Clone)]
 pub struct ObjetoIndex {
@@ -22,26 +26,26 @@ pub struct ObjetoIndex {
 }
 
 pub fn crear_index() {
-    if Path::new(PATH_INDEX).exists() {
+    if io::existe(ruta_index()) {
         return;
     }
-    let _ = fs::File::create(PATH_INDEX);
+    let _ = fs::File::create(ruta_index());
 }
 
 //Devuelve true si el index esta vacio y false en caso contrario.
 //Si falla se presupone que es porque no existe y por lo tanto esta vacio
 pub fn esta_vacio_el_index() -> Result<bool, String> {
-    Ok(io::esta_vacio(PATH_INDEX))
+    Ok(io::esta_vacio(ruta_index()))
 }
 
 /// Lee el archivo index y devuelve un vector de objetos index.
 /// Por cada entrie que lee crea su respectivo objeto index.
 /// Si el archivo index no existe, devuelve un vector vacio.
 pub fn leer_index(logger: Arc<Logger>) -> Result<Vec<ObjetoIndex>, String> {
-    if !PathBuf::from(PATH_INDEX).exists() {
+    if !io::existe(ruta_index()) {
         return Ok(Vec::new());
     }
-    let file = match OpenOptions::new().read(true).open(PATH_INDEX) {
+    let file = match OpenOptions::new().read(true).open(ruta_index()) {
         Ok(file) => file,
         Err(_) => return Err("No se pudo abrir el archivo index".to_string()),
     };
@@ -147,7 +151,7 @@ pub fn escribir_index(
         buffer.push_str(&line);
     }
 
-    io::escribir_bytes(PATH_INDEX, buffer)?;
+    io::escribir_bytes(ruta_index(), buffer)?;
     Ok(())
 }
 
@@ -156,7 +160,7 @@ pub fn limpiar_archivo_index() -> Result<(), String> {
     let _ = match OpenOptions::new()
         .write(true)
         .truncate(true)
-        .open("./.gir/index")
+        .open(ruta_index())
     {
         Ok(archivo) => archivo,
         Err(_) => return Err("No se pudo abrir el archivo index".to_string()),
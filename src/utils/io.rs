@@ -1,11 +1,13 @@
 use std::fmt::Debug;
 use std::fs::{self, File, ReadDir};
 use std::io::BufRead;
+use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
-use std::{env, str};
+
+use crate::tipos_de_dato::repositorio::resolver;
 
 pub(crate) fn leer_archivo(path: &mut Path) -> Result<String, String> {
-    let archivo = fs::File::open(path).map_err(|e| e.to_string())?;
+    let archivo = fs::File::open(resolver(path)).map_err(|e| e.to_string())?;
     let mut contenido = String::new();
     std::io::BufReader::new(archivo)
         .read_line(&mut contenido)
@@ -15,8 +17,8 @@ pub(crate) fn leer_archivo(path: &mut Path) -> Result<String, String> {
 
 //Devuelve true si la ubicacion esta vacia y false en caso contrario.
 //Si falla se presupone que es porque no existe y por lo tanto esta vacio
-pub fn esta_vacio(ubicacion: &str) -> bool {
-    match fs::metadata(ubicacion) {
+pub fn esta_vacio<P: AsRef<Path>>(ubicacion: P) -> bool {
+    match fs::metadata(resolver(ubicacion)) {
         Ok(metadata) => metadata.len() == 0,
         Err(_) => false,
     }
@@ -27,14 +29,14 @@ pub fn leer_directorio<P>(directorio: &P) -> Result<ReadDir, String>
 where
     P: AsRef<Path> + Debug + ?Sized,
 {
-    let metadada_dir =
-        fs::metadata(directorio).map_err(|_| format!("Error no existe el dir {:?}", directorio))?;
+    let metadada_dir = fs::metadata(resolver(directorio))
+        .map_err(|_| format!("Error no existe el dir {:?}", directorio))?;
 
     if !metadada_dir.is_dir() {
         return Err(format!("Error {:?} no es un dir", directorio));
     }
 
-    fs::read_dir(directorio).map_err(|e| format!("Error al leer {:?}: {}", directorio, e))
+    fs::read_dir(resolver(directorio)).map_err(|e| format!("Error al leer {:?}: {}", directorio, e))
 }
 
 pub fn cantidad_entradas_dir<P>(directorio: &P) -> Result<u64, String>
@@ -49,17 +51,36 @@ where
 
 ///Devuelve True si el directororio es un directorio o false en caso contrario o si no existe
 pub fn es_dir<P: AsRef<Path> + Clone + Debug>(entrada: P) -> bool {
-    match fs::metadata(entrada) {
+    match fs::metadata(resolver(entrada)) {
         Ok(metadata_contenido) => metadata_contenido.is_dir(),
         Err(_) => false,
     }
 }
 
+///Devuelve True si la entrada es un archivo o false en caso contrario o si no existe
+pub fn es_archivo<P: AsRef<Path>>(entrada: P) -> bool {
+    resolver(entrada).is_file()
+}
+
+///Devuelve True si la entrada es un archivo con permiso de ejecucion
+pub fn es_ejecutable<P: AsRef<Path>>(entrada: P) -> bool {
+    match fs::metadata(resolver(entrada)) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+///Devuelve True si existe el archivo o directorio
+pub fn existe<P: AsRef<Path>>(entrada: P) -> bool {
+    resolver(entrada).exists()
+}
+
 ///Crea un directorio
 pub fn crear_directorio<P>(directorio: P) -> Result<(), String>
 where
     P: AsRef<Path>,
 {
+    let directorio = resolver(directorio);
     let dir = fs::metadata(&directorio);
     if dir.is_ok() {
         return Ok(());
@@ -71,20 +92,16 @@ where
 }
 ///Similar a `crear_directorio` pero puede fallar si la carpeta ya existe
 pub fn crear_carpeta<P: AsRef<Path> + Clone>(carpeta: P) -> Result<(), String> {
-    match fs::create_dir_all(carpeta) {
+    match fs::create_dir_all(resolver(carpeta)) {
         Ok(_) => Ok(()),
         Err(e) => Err(format!("Error al crear la carpeta: {}", e)),
     }
 }
 
-pub fn cambiar_directorio<P: AsRef<Path> + Clone + Debug>(directorio: P) -> Result<(), String> {
-    env::set_current_dir(&directorio)
-        .map_err(|err| format!("Fallo al cambiar de directorio {:?}:{}", directorio, err))
-}
 pub fn crear_archivo<P: AsRef<Path> + Clone>(dir_directorio: P) -> Result<(), String> {
     si_no_existe_directorio_de_archivo_crearlo(&dir_directorio)?;
-    if !dir_directorio.as_ref().exists() {
-        File::create(dir_directorio.clone()).map_err(|err| format!("{}", err))?;
+    if !existe(&dir_directorio) {
+        File::create(resolver(dir_directorio)).map_err(|err| format!("{}", err))?;
     }
 
     Ok(())
@@ -94,7 +111,7 @@ pub fn leer_a_string<P>(path: P) -> Result<String, String>
 where
     P: AsRef<Path>,
 {
-    match fs::read_to_string(&path) {
+    match fs::read_to_string(resolver(&path)) {
         Ok(contenido) => Ok(contenido),
         Err(_) => Err(format!(
             "No se pudo leer el archivo {}",
@@ -109,7 +126,7 @@ where
     C: AsRef<[u8]>,
 {
     si_no_existe_directorio_de_archivo_crearlo(&dir_archivo)?;
-    match fs::write(dir_archivo, contenido) {
+    match fs::write(resolver(dir_archivo), contenido) {
         Ok(_) => Ok(()),
         Err(e) => Err(format!("Error al escribir el archivo: {}", e)),
     }
@@ -119,7 +136,7 @@ pub fn leer_bytes<P>(archivo: P) -> Result<Vec<u8>, String>
 where
     P: AsRef<Path>,
 {
-    match fs::read(&archivo) {
+    match fs::read(resolver(&archivo)) {
         Ok(contenido) => Ok(contenido),
         Err(_) => Err(format!(
             "No se pudo leer el archivo leyendo bytes {}",
@@ -147,7 +164,7 @@ pub fn rm_directorio<P>(directorio: P) -> Result<(), String>
 where
     P: AsRef<Path>,
 {
-    let metadata = fs::metadata(&directorio).map_err(|e| {
+    let metadata = fs::metadata(resolver(&directorio)).map_err(|e| {
         format!(
             "No se pudo obtener la metadata del directorio {}. {}",
             directorio.as_ref().display(),
@@ -156,7 +173,7 @@ where
     })?;
 
     if metadata.is_file() {
-        return match fs::remove_file(&directorio) {
+        return match fs::remove_file(resolver(&directorio)) {
             Ok(_) => Ok(()),
             Err(e) => Err(format!(
                 "No se pudo borrar el archivo {}. {}",
@@ -167,7 +184,7 @@ where
     }
 
     if metadata.is_dir() {
-        return match fs::remove_dir_all(&directorio) {
+        return match fs::remove_dir_all(resolver(&directorio)) {
             Ok(_) => Ok(()),
             Err(e) => Err(format!(
                 "No se pudo borrar la carpeta {}. {}",
@@ -182,6 +199,33 @@ where
     ))
 }
 
+/// Copia recursivamente el contenido de `origen` en `destino`, creando los directorios que
+/// falten. Los archivos que ya existen en `destino` no se sobrescriben.
+pub fn copiar_directorio<P, Q>(origen: P, destino: Q) -> Result<(), String>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let destino = destino.as_ref();
+    crear_directorio(destino)?;
+    for entrada in leer_directorio(origen.as_ref())? {
+        let entrada = entrada.map_err(|e| format!("Error leyendo directorio: {}", e))?;
+        let ruta_destino = destino.join(entrada.file_name());
+        if entrada.path().is_dir() {
+            copiar_directorio(entrada.path(), &ruta_destino)?;
+        } else if !existe(&ruta_destino) {
+            fs::copy(entrada.path(), resolver(&ruta_destino)).map_err(|e| {
+                format!(
+                    "No se pudo copiar el archivo {}. {}",
+                    entrada.path().display(),
+                    e
+                )
+            })?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
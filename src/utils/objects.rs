@@ -1,13 +1,28 @@
+use std::collections::HashSet;
 use std::fs;
 use std::{io, path::PathBuf};
 
-use crate::err_comunicacion::ErrorDeComunicacion;
+use crate::{err_comunicacion::ErrorDeComunicacion, tipos_de_dato::repositorio::resolver};
 
-use super::path_buf;
 use super::{io as gir_io, strings};
+use super::{packs, path_buf};
 
-///Devuelve todos los objetos dentro de objetcs (sus hash)
+///Devuelve todos los objetos dentro de objetcs (sus hash), tanto los sueltos como los
+/// que estan dentro de packfiles
 pub fn obtener_objetos_del_dir(dir: &PathBuf) -> Result<Vec<String>, String> {
+    let mut objetos = obtener_objetos_sueltos(dir)?;
+
+    let mut vistos: HashSet<String> = objetos.iter().cloned().collect();
+    for objeto in packs::obtener_objetos_de_packs(&dir.to_string_lossy())? {
+        if vistos.insert(objeto.clone()) {
+            objetos.push(objeto);
+        }
+    }
+    Ok(objetos)
+}
+
+///Devuelve los objetos sueltos dentro de objetcs (sus hash), sin los que estan en packfiles
+pub fn obtener_objetos_sueltos(dir: &PathBuf) -> Result<Vec<String>, String> {
     let dir_abierto = gir_io::leer_directorio(dir)?;
 
     let mut objetos: Vec<String> = Vec::new();
@@ -87,7 +102,7 @@ pub fn obtener_archivos_faltantes(nombres_archivos: Vec<String>, dir: &str) -> V
 
 // dado un directorio devuelve el nombre del archivo contenido (solo caso de objectos de git)
 pub fn obtener_objetos(dir: PathBuf) -> Result<String, ErrorDeComunicacion> {
-    let mut directorio = fs::read_dir(dir.clone())?;
+    let mut directorio = fs::read_dir(resolver(&dir))?;
     if let Some(archivo) = directorio.next() {
         match archivo {
             Ok(archivo) => {
@@ -106,10 +121,14 @@ pub fn obtener_objetos(dir: PathBuf) -> Result<String, ErrorDeComunicacion> {
 
 // aca depende de si esta multi_ack y esas cosas, esta es para cuando no hay multi_ack ni multi_ack_mode
 pub fn obtener_objetos_en_comun(nombres_archivos: Vec<String>, dir: &str) -> Vec<String> {
+    let packs = packs::Packs::cargar(dir).ok();
     let mut ack = Vec::new();
     for nombre in nombres_archivos {
-        let dir_archivo = format!("{}{}/{}", dir, &nombre[..2], &nombre[2..]);
-        if PathBuf::from(dir_archivo.clone()).exists() {
+        let existe = match &packs {
+            Some(packs) => packs.existe_objeto(&nombre),
+            None => packs::existe_objeto(&nombre, dir),
+        };
+        if existe {
             ack.push(strings::obtener_linea_con_largo_hex(
                 ("ACK ".to_string() + &nombre + "\n").as_str(),
             ));
@@ -0,0 +1,253 @@
+use std::{
+    fs::{self, File},
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use crate::tipos_de_dato::{
+    indice_pack::IndicePack, objetos::tree::Tree, packfile::Packfile, repositorio::resolver,
+};
+
+use super::{archivo_lock::ArchivoLock, io};
+
+const DIR_PACKS: &str = "pack";
+/// Largo del checksum con el que termina un packfile
+const LARGO_CHECKSUM: u64 = 20;
+
+/// Packfiles de un directorio de objetos con sus `.idx` ya leidos. Se carga una vez por
+/// operacion y de cada pack solo se leen los bytes de los objetos que se buscan.
+pub struct Packs {
+    dir_objetos: String,
+    packs: Vec<PackIndexado>,
+}
+
+struct PackIndexado {
+    /// Ruta al `.pack`
+    ruta: PathBuf,
+    indice: IndicePack,
+    /// Offsets de los objetos ordenados, seguidos del offset del checksum final.
+    /// Un objeto termina donde empieza el siguiente
+    limites: Vec<u64>,
+}
+
+impl Packs {
+    /// Lee los indices de todos los packfiles del directorio de objetos
+    pub fn cargar(dir_objetos: &str) -> Result<Packs, String> {
+        let mut packs = Vec::new();
+        for pack in obtener_packs(dir_objetos)? {
+            let indice = IndicePack::leer(&pack.with_extension("idx").to_string_lossy())?;
+            let ruta = pack.with_extension("pack");
+            let largo = fs::metadata(resolver(&ruta))
+                .map_err(|e| format!("No se pudo leer el pack {}: {}", ruta.display(), e))?
+                .len();
+            let mut limites: Vec<u64> = indice.entradas().iter().map(|e| e.offset).collect();
+            limites.push(largo.saturating_sub(LARGO_CHECKSUM));
+            limites.sort_unstable();
+            packs.push(PackIndexado {
+                ruta,
+                indice,
+                limites,
+            });
+        }
+        Ok(Packs {
+            dir_objetos: dir_objetos.to_string(),
+            packs,
+        })
+    }
+
+    /// Devuelve el directorio de objetos del que se cargaron los packs
+    pub fn dir_objetos(&self) -> &str {
+        &self.dir_objetos
+    }
+
+    /// Devuelve si alguno de los packs contiene al objeto
+    pub fn contiene(&self, hash: &str) -> bool {
+        self.packs
+            .iter()
+            .any(|pack| pack.indice.buscar_offset(hash).is_some())
+    }
+
+    /// Devuelve si el objeto esta en el directorio de objetos, ya sea suelto o dentro de un pack
+    pub fn existe_objeto(&self, hash: &str) -> bool {
+        hash.len() >= 3
+            && (io::existe(
+                Path::new(&self.dir_objetos)
+                    .join(&hash[..2])
+                    .join(&hash[2..]),
+            ) || self.contiene(hash))
+    }
+
+    /// Devuelve los hashes de todos los objetos de los packs
+    pub fn hashes(&self) -> Vec<String> {
+        self.packs
+            .iter()
+            .flat_map(|pack| pack.indice.entradas().iter().map(|e| e.hash.clone()))
+            .collect()
+    }
+
+    /// Busca el objeto en los packs y lo devuelve descomprimido con su header.
+    /// Devuelve None si ningun pack lo contiene.
+    pub fn leer_objeto(&self, hash: &str) -> Result<Option<Vec<u8>>, String> {
+        for pack in &self.packs {
+            if let Some(offset) = pack.indice.buscar_offset(hash) {
+                let mut archivo = File::open(resolver(&pack.ruta)).map_err(|e| {
+                    format!("No se pudo abrir el pack {}: {}", pack.ruta.display(), e)
+                })?;
+                let (tipo, contenido) = pack.leer_en_offset(&mut archivo, offset)?;
+                return Packfile::agregar_header(tipo, contenido).map(Some);
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl PackIndexado {
+    /// Lee solo los bytes del objeto que empieza en `offset` y los de sus bases, si es un delta
+    fn leer_en_offset(&self, archivo: &mut File, offset: u64) -> Result<(u8, Vec<u8>), String> {
+        let fuera_del_pack = || format!("Offset {} fuera del pack {}", offset, self.ruta.display());
+        let fin = self
+            .limites
+            .get(self.limites.partition_point(|limite| *limite <= offset))
+            .ok_or_else(fuera_del_pack)?;
+        let mut bytes = vec![0; (fin - offset) as usize];
+        archivo
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| archivo.read_exact(&mut bytes))
+            .map_err(|e| format!("No se pudo leer el pack {}: {}", self.ruta.display(), e))?;
+
+        Packfile::leer_objeto_aislado(&bytes, |distancia| {
+            let offset_base = offset
+                .checked_sub(distancia as u64)
+                .ok_or_else(fuera_del_pack)?;
+            self.leer_en_offset(archivo, offset_base)
+        })
+    }
+}
+
+/// Devuelve las rutas, sin extension, de los packfiles del directorio de objetos que tienen su `.idx`.
+/// Ej: `.gir/objects/pack/pack-<checksum>`
+pub fn obtener_packs(dir_objetos: &str) -> Result<Vec<PathBuf>, String> {
+    let dir_packs = Path::new(dir_objetos).join(DIR_PACKS);
+    if !io::existe(&dir_packs) {
+        return Ok(Vec::new());
+    }
+
+    let mut packs = Vec::new();
+    for entrada in io::leer_directorio(&dir_packs)? {
+        let ruta = entrada
+            .map_err(|e| format!("Error leyendo directorio: {}", e))?
+            .path();
+        if ruta.extension().is_some_and(|extension| extension == "idx")
+            && io::existe(ruta.with_extension("pack"))
+        {
+            packs.push(ruta.with_extension(""));
+        }
+    }
+    packs.sort();
+    Ok(packs)
+}
+
+/// Busca el objeto en los packfiles del directorio de objetos y lo devuelve descomprimido con su header.
+/// Devuelve None si ningun pack lo contiene. Para buscar varios objetos conviene cargar `Packs` una vez.
+pub fn leer_objeto_de_packs(hash: &str, dir_objetos: &str) -> Result<Option<Vec<u8>>, String> {
+    Packs::cargar(dir_objetos)?.leer_objeto(hash)
+}
+
+/// Devuelve los hashes de todos los objetos guardados en packfiles del directorio de objetos
+pub fn obtener_objetos_de_packs(dir_objetos: &str) -> Result<Vec<String>, String> {
+    Ok(Packs::cargar(dir_objetos)?.hashes())
+}
+
+/// Devuelve si el objeto esta en el directorio de objetos, ya sea suelto o dentro de un pack.
+/// Para buscar varios objetos conviene cargar `Packs` una vez.
+pub fn existe_objeto(hash: &str, dir_objetos: &str) -> bool {
+    if hash.len() < 3 {
+        return false;
+    }
+    io::existe(Path::new(dir_objetos).join(&hash[..2]).join(&hash[2..]))
+        || Packs::cargar(dir_objetos).is_ok_and(|packs| packs.contiene(hash))
+}
+
+/// Arma un packfile con deltas con los objetos dados (hash y objeto descomprimido con header)
+/// y lo escribe junto a su `.idx` en el directorio de packs. Devuelve la ruta del pack sin extension.
+///
+/// El `.idx` se escribe ultimo, de forma que el pack recien es visible cuando esta completo.
+pub fn escribir_pack(
+    dir_objetos: &str,
+    objetos: Vec<(String, Vec<u8>)>,
+) -> Result<PathBuf, String> {
+    let (packfile, entradas) = Packfile::armar_packfile_con_deltas(objetos)?;
+    let checksum = &packfile[packfile.len() - 20..];
+    let indice = IndicePack::new(entradas, checksum);
+
+    let pack = Path::new(dir_objetos)
+        .join(DIR_PACKS)
+        .join(format!("pack-{}", Tree::encode_hex(checksum)));
+
+    io::escribir_bytes(pack.with_extension("pack"), &packfile)?;
+    let mut lock_indice = ArchivoLock::adquirir(pack.with_extension("idx"))?;
+    lock_indice.escribir(indice.a_bytes()?)?;
+    lock_indice.confirmar()?;
+    Ok(pack)
+}
+
+/// Borra el packfile y su `.idx`. `pack` es la ruta sin extension.
+pub fn eliminar_pack(pack: &Path) -> Result<(), String> {
+    // primero el indice, asi nadie intenta leer del pack mientras se borra
+    io::rm_directorio(pack.with_extension("idx"))?;
+    io::rm_directorio(pack.with_extension("pack"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::utils::compresion;
+
+    #[test]
+    #[serial]
+    fn test01_los_objetos_escritos_en_un_pack_se_pueden_leer() {
+        let dir_objetos = "tmp/packs_test01/objects/";
+        let _ = io::rm_directorio("tmp/packs_test01");
+        let objeto = b"blob 5\0hola\n".to_vec();
+        let hash = "a".repeat(40);
+
+        escribir_pack(dir_objetos, vec![(hash.clone(), objeto.clone())]).unwrap();
+
+        assert!(existe_objeto(&hash, dir_objetos));
+        assert!(!existe_objeto(&"b".repeat(40), dir_objetos));
+        assert_eq!(
+            obtener_objetos_de_packs(dir_objetos).unwrap(),
+            vec![hash.clone()]
+        );
+        assert_eq!(
+            compresion::leer_objeto_descomprimido(&hash, dir_objetos).unwrap(),
+            objeto
+        );
+    }
+    #[test]
+    #[serial]
+    fn test02_se_leen_objetos_deltificados_sin_leer_el_pack_entero() {
+        let dir_objetos = "tmp/packs_test02/objects/";
+        let _ = io::rm_directorio("tmp/packs_test02");
+        let contenido = "una linea que se repite en las dos versiones\n".repeat(20);
+        let objetos: Vec<(String, Vec<u8>)> = [contenido.clone(), contenido + "una linea mas\n"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, contenido)| {
+                let objeto = format!("blob {}\0{}", contenido.len(), contenido).into_bytes();
+                (i.to_string().repeat(40), objeto)
+            })
+            .collect();
+
+        escribir_pack(dir_objetos, objetos.clone()).unwrap();
+        let packs = Packs::cargar(dir_objetos).unwrap();
+
+        for (hash, objeto) in objetos {
+            assert!(packs.existe_objeto(&hash));
+            assert_eq!(packs.leer_objeto(&hash).unwrap().unwrap(), objeto);
+        }
+        assert_eq!(packs.leer_objeto(&"2".repeat(40)).unwrap(), None);
+    }
+}
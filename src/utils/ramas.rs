@@ -1,5 +1,7 @@
 use std::path::{self, Path, PathBuf};
 
+use crate::tipos_de_dato::repositorio::Repositorio;
+
 use super::{io, path_buf};
 
 ///obtiene el nombre de la rama acutal
@@ -17,7 +19,7 @@ pub fn obtner_commit_head_rama_acutual() -> Result<String, String> {
 
 ///obtiene la ref de la rama actual
 pub fn obtener_ref_rama_actual() -> Result<PathBuf, String> {
-    let contenido_head = io::leer_a_string("./.gir/HEAD")?;
+    let contenido_head = io::leer_a_string(Repositorio::actual().ruta_gir("HEAD"))?;
     let (_, dir_rama_actual) = contenido_head
         .split_once(' ')
         .ok_or("Fallo al obtener la rama actual\n".to_string())?;
@@ -26,7 +28,7 @@ pub fn obtener_ref_rama_actual() -> Result<PathBuf, String> {
 
 pub fn obtener_gir_dir_rama_actual() -> Result<PathBuf, String> {
     let ref_rama_actual = obtener_ref_rama_actual()?;
-    let dir_rama = PathBuf::from("./.gir").join(ref_rama_actual);
+    let dir_rama = Repositorio::actual().ruta_gir(ref_rama_actual);
     Ok(dir_rama)
 }
 
@@ -42,8 +44,8 @@ pub fn obtener_hash_commit_asociado_rama(rama: &str) -> Result<String, String> {
     if !existe_la_rama(rama) {
         return Err(format!("No existe la rama {}", rama));
     }
-    let ruta = format!("./.gir/refs/heads/{}", rama);
-    let hash_commit = io::leer_a_string(path::Path::new(&ruta)).unwrap_or_else(|_| "".to_string());
+    let ruta = Repositorio::actual().ruta_gir(format!("refs/heads/{}", rama));
+    let hash_commit = io::leer_a_string(ruta).unwrap_or_else(|_| "".to_string());
     Ok(hash_commit)
 }
 
@@ -68,15 +70,15 @@ pub fn es_la_ruta_a_una_rama(dir: &Path) -> bool {
 /// # Ejemplo:
 ///
 /// recive:  ./.gir/refs/heads/master o refs/heads/master
-/// devuelve: ./.gir/refs/remotes/{remoto}/master
+/// devuelve: <dir_gir>/refs/remotes/{remoto}/master
 pub fn convertir_de_dir_rama_remota_a_dir_rama_local(
     remoto: &str,
     dir_rama_remota: &Path,
 ) -> Result<PathBuf, String> {
-    let carpeta_del_remoto = format!("./.gir/refs/remotes/{}/", remoto);
+    let carpeta_del_remoto = Repositorio::actual().ruta_gir(format!("refs/remotes/{}", remoto));
 
     let rama_remota = path_buf::obtener_nombre(dir_rama_remota)?;
-    let dir_rama_local = PathBuf::from(carpeta_del_remoto + rama_remota.as_str());
+    let dir_rama_local = carpeta_del_remoto.join(rama_remota);
 
     Ok(dir_rama_local)
 }
@@ -86,9 +88,9 @@ pub fn convertir_de_dir_rama_remota_a_dir_rama_local(
 /// ## Argumentos
 /// - rama_remota: semi path a la rama remota(Ej: origin/aaaa)
 pub fn existe_la_rama_remota(rama_remota: &str) -> bool {
-    let dir_rama_remota = PathBuf::from(format!("./.gir/refs/remotes/{}", rama_remota));
+    let dir_rama_remota = Repositorio::actual().ruta_gir(format!("refs/remotes/{}", rama_remota));
 
-    dir_rama_remota.exists()
+    io::existe(&dir_rama_remota)
 }
 
 ///Verificar si la rama existe, devuelve true. Caso contrario false
@@ -96,7 +98,7 @@ pub fn existe_la_rama_remota(rama_remota: &str) -> bool {
 /// ## Argumentos
 /// - rama: nombre de la rama(Ej: aaaa)
 pub fn existe_la_rama(rama: &str) -> bool {
-    let dir_rama = PathBuf::from(format!("./.gir/refs/heads/{}", rama));
+    let dir_rama = Repositorio::actual().ruta_gir(format!("refs/heads/{}", rama));
 
-    dir_rama.exists()
+    io::existe(&dir_rama)
 }
@@ -1,7 +1,4 @@
-use std::{
-    fs,
-    path::{Path, PathBuf},
-};
+use std::path::{Path, PathBuf};
 
 use super::{io, strings};
 
@@ -10,14 +7,14 @@ pub fn obtener_refs_con_largo_hex(
     refs_path: PathBuf,
     dir: &str,
 ) -> Result<(), String> {
-    if !refs_path.exists() {
+    if !io::existe(&refs_path) {
         return Ok(());
     }
-    let head_dir = fs::read_dir(&refs_path).map_err(|e| e.to_string())?;
+    let head_dir = io::leer_directorio(&refs_path)?;
     for archivo in head_dir {
         match archivo {
             Ok(archivo) => {
-                let mut path = archivo.path();
+                let mut path = refs_path.join(archivo.file_name());
 
                 let referencia = obtener_referencia(&mut path, dir)?;
                 refs.push(strings::obtener_linea_con_largo_hex(&referencia));
@@ -31,7 +28,7 @@ pub fn obtener_refs_con_largo_hex(
 }
 
 pub fn obtener_ref_head(path: PathBuf) -> Result<String, String> {
-    if !path.exists() {
+    if !io::existe(&path) {
         return Err("No existe HEAD".to_string());
     }
     let contenido = io::leer_archivo(&mut path.clone())?;
@@ -46,7 +43,7 @@ pub fn obtener_ref_head(path: PathBuf) -> Result<String, String> {
 
 pub fn obtener_refs(refs_path: PathBuf, dir: &str) -> Result<Vec<String>, String> {
     let mut refs: Vec<String> = Vec::new();
-    if !refs_path.exists() {
+    if !io::existe(&refs_path) {
         return Ok(refs);
         // io::Error::new(io::ErrorKind::NotFound, "No existe el repositorio");
     }
@@ -54,11 +51,11 @@ pub fn obtener_refs(refs_path: PathBuf, dir: &str) -> Result<Vec<String>, String
     if refs_path.ends_with("HEAD") {
         refs.push(obtener_ref_head(refs_path.to_path_buf())?);
     } else {
-        let head_dir = fs::read_dir(&refs_path).map_err(|e| e.to_string())?;
+        let head_dir = io::leer_directorio(&refs_path)?;
         for archivo in head_dir {
             match archivo {
                 Ok(archivo) => {
-                    let mut path = archivo.path();
+                    let mut path = refs_path.join(archivo.file_name());
                     // let mut path = archivo.path().to_string_lossy().split("./.gir/").into_iter().next().unwrap().to_string();
                     refs.push(obtener_referencia(&mut path, dir)?);
                 }
@@ -32,3 +32,35 @@ pub fn obtener_linea_con_largo_hex(line: &str) -> String {
     let largo_hex = calcular_largo_hex(line);
     format!("{}{}", largo_hex, line)
 }
+
+/// Separa una lista escrita como `a, b, c`, ignorando los elementos vacios
+pub fn separar_lista(valor: &str) -> Vec<String> {
+    valor
+        .split(',')
+        .map(|elemento| elemento.trim().to_string())
+        .filter(|elemento| !elemento.is_empty())
+        .collect()
+}
+
+/// Separa un contenido en formato pkt en sus lineas (sin el largo), hasta un flush pkt
+/// o hasta que se termine el contenido
+pub fn separar_lineas_pkt(contenido: &str) -> Result<Vec<String>, String> {
+    let mut lineas = Vec::new();
+    let mut resto = contenido;
+    while resto.len() >= 4 {
+        let largo_hex = resto
+            .get(..4)
+            .ok_or(format!("Largo de linea pkt invalido: {}", resto))?;
+        let largo = usize::from_str_radix(largo_hex, 16)
+            .map_err(|e| format!("Fallo en la conversion a entero\n{}\n", e))?;
+        if largo == 0 {
+            break;
+        }
+        let linea = resto
+            .get(4..largo)
+            .ok_or(format!("Linea pkt incompleta: {}", resto))?;
+        lineas.push(linea.to_string());
+        resto = &resto[largo..];
+    }
+    Ok(lineas)
+}
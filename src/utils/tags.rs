@@ -1,11 +1,13 @@
-use super::path_buf;
+use crate::tipos_de_dato::repositorio::Repositorio;
+
+use super::{io, path_buf};
 
 ///Devuelve un vector con todos los tags
 pub fn obtener_tags() -> Result<Vec<String>, String> {
-    let ubicacion = "./.gir/refs/tags";
+    let ubicacion = Repositorio::actual().ruta_gir("refs/tags");
     let mut tags: Vec<String> = Vec::new();
 
-    let tags_entries = std::fs::read_dir(ubicacion)
+    let tags_entries = io::leer_directorio(&ubicacion)
         .map_err(|e| format!("Error al leer el directorio de tags: {}", e))?;
 
     for tag_entry in tags_entries {
@@ -1,6 +1,6 @@
 use std::{
     io::{Read, Write},
-    path::PathBuf,
+    os::unix::fs::PermissionsExt,
     sync::Arc,
 };
 
@@ -8,9 +8,10 @@ use crate::tipos_de_dato::{
     comando::Ejecutar,
     comandos::{add::Add, branch::Branch, commit::Commit, init::Init, push::Push, remote::Remote},
     logger::Logger,
+    repositorio::Repositorio,
 };
 
-use super::io;
+use super::{hooks::DIR_HOOKS, io};
 
 pub struct MockTcpStream {
     pub lectura_data: Vec<u8>,
@@ -76,7 +77,7 @@ pub fn escribir_rama_local(rama: &str, logger: Arc<Logger>) {
 }
 
 pub fn eliminar_archivo_gir() {
-    if PathBuf::from("./.gir").exists() {
+    if io::existe("./.gir") {
         io::rm_directorio("./.gir").unwrap();
     }
 }
@@ -89,7 +90,10 @@ pub fn addear_archivos_y_comittear(args: Vec<String>, logger: Arc<Logger>) {
     commit.ejecutar().unwrap();
 }
 
-pub fn crear_repo_para_pr(logger: Arc<Logger>) {
+/// Crea en `repositorio` un repo con las ramas `master` y `rama` y las pushea al servidor
+/// de prueba, sin cambiar el directorio actual del proceso
+pub fn crear_repo_para_pr(repositorio: &Repositorio, logger: Arc<Logger>) {
+    let _activo = repositorio.entrar();
     let mut init = Init::from(vec![], logger.clone()).unwrap();
     init.ejecutar().unwrap();
 
@@ -148,3 +152,10 @@ pub fn crear_repo_para_pr(logger: Arc<Logger>) {
 
     push.ejecutar().unwrap();
 }
+
+/// Crea el hook `nombre` en el repositorio actual con el contenido dado y lo deja ejecutable
+pub fn crear_hook(nombre: &str, contenido: &str) {
+    let ruta = Repositorio::actual().ruta_gir(DIR_HOOKS).join(nombre);
+    io::escribir_bytes(&ruta, contenido).unwrap();
+    std::fs::set_permissions(&ruta, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
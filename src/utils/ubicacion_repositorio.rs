@@ -0,0 +1,295 @@
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::tipos_de_dato::repositorio::Repositorio;
+
+/// Nombre del directorio que contiene la base de datos de un repositorio
+pub const DIR_GIR: &str = ".gir";
+/// Nombre del directorio, dentro de `.gir`, que contiene los objetos del repositorio
+pub const DIR_OBJETOS: &str = "objects";
+/// Variable de entorno que indica el directorio `.gir` a usar, salteando la busqueda
+pub const VARIABLE_GIR_DIR: &str = "GIR_DIR";
+/// Opcion global que cambia el directorio desde el que se ejecuta gir, como `git -C <path>`
+const OPCION_DIRECTORIO: &str = "-C";
+
+/// Comandos que no trabajan sobre un repositorio existente
+const COMANDOS_SIN_REPOSITORIO: [&str; 4] = ["init", "clone", "version", "gui"];
+/// Comandos cuyos argumentos (salvo las opciones) son rutas de archivos
+const COMANDOS_CON_RUTAS: [&str; 5] = ["add", "rm", "hash-object", "check-ignore", "ls-files"];
+/// Opciones de cada comando cuyo valor es la ruta de un archivo, que puede estar fuera del
+/// repositorio (Ej: `commit -F <archivo>`)
+const OPCIONES_CON_RUTAS: [(&str, &str); 1] = [("commit", "-F")];
+
+/// Consume las opciones globales que estan antes del comando (`-C <path>`, que puede repetirse)
+/// y se cambia a ese directorio. Cada `-C` es relativo al anterior, como en git.
+pub fn aplicar_opciones_globales(argv: &mut Vec<String>) -> Result<(), String> {
+    while argv.first().map(String::as_str) == Some(OPCION_DIRECTORIO) {
+        if argv.len() < 2 {
+            return Err(format!(
+                "Falta el directorio de la opcion {}",
+                OPCION_DIRECTORIO
+            ));
+        }
+        let directorio = argv.remove(1);
+        argv.remove(0);
+        env::set_current_dir(&directorio)
+            .map_err(|e| format!("No se pudo cambiar al directorio {}: {}", directorio, e))?;
+    }
+    Ok(())
+}
+
+/// Devuelve el repositorio sobre el que va a trabajar el comando de `argv` y se posiciona en su raiz.
+/// El repositorio es el indicado por `GIR_DIR` o, si no esta definida, el primer `.gir` que se
+/// encuentra subiendo desde el directorio actual.
+/// Las rutas que recibe el comando se pasan a ser relativas a la raiz del repositorio, salvo
+/// los valores de `OPCIONES_CON_RUTAS`, que pasan a ser absolutos.
+///
+/// Los comandos que no necesitan un repositorio existente (Ej: `init`) trabajan sobre el directorio actual.
+pub fn entrar_al_repositorio(argv: &mut [String]) -> Result<Repositorio, String> {
+    let directorio_actual = env::current_dir()
+        .and_then(fs::canonicalize)
+        .map_err(|e| format!("No se pudo obtener el directorio actual: {}", e))?;
+    let comando = match argv.first() {
+        Some(comando) if !COMANDOS_SIN_REPOSITORIO.contains(&comando.as_str()) => comando.clone(),
+        _ => return Ok(Repositorio::new(directorio_actual)),
+    };
+
+    let repositorio = match env::var(VARIABLE_GIR_DIR) {
+        Ok(gir_dir) if !gir_dir.is_empty() => repositorio_de_gir_dir(&gir_dir, &directorio_actual)?,
+        _ => Repositorio::new(descubrir_repositorio(&directorio_actual)?),
+    };
+    let raiz = repositorio.dir_trabajo().to_path_buf();
+
+    if COMANDOS_CON_RUTAS.contains(&comando.as_str()) {
+        for arg in argv.iter_mut().skip(1) {
+            if !arg.starts_with('-') {
+                *arg = relativizar_ruta(arg, &directorio_actual, &raiz)?;
+            }
+        }
+    }
+    for (comando_con_opcion, opcion) in OPCIONES_CON_RUTAS {
+        if comando == comando_con_opcion {
+            absolutizar_valores(argv, opcion, &directorio_actual);
+        }
+    }
+
+    env::set_current_dir(&raiz)
+        .map_err(|e| format!("No se pudo entrar al repositorio {}: {}", raiz.display(), e))?;
+    Ok(repositorio)
+}
+
+/// Busca el repositorio subiendo desde `desde` hasta encontrar un directorio `.gir`.
+/// Devuelve la raiz del repositorio, es decir, el directorio que contiene a `.gir`.
+pub fn descubrir_repositorio(desde: &Path) -> Result<PathBuf, String> {
+    desde
+        .ancestors()
+        .find(|directorio| directorio.join(DIR_GIR).is_dir())
+        .map(Path::to_path_buf)
+        .ok_or_else(|| {
+            format!(
+                "No se encontro un repositorio gir en {} ni en sus directorios padre",
+                desde.display()
+            )
+        })
+}
+
+/// Devuelve el repositorio cuyo directorio gir indica `GIR_DIR`, que puede tener cualquier
+/// nombre. Su raiz es el directorio que lo contiene. Si es relativo, se toma desde
+/// `directorio_actual`, que tiene que estar canonicalizado como la raiz devuelta.
+pub fn repositorio_de_gir_dir(
+    gir_dir: &str,
+    directorio_actual: &Path,
+) -> Result<Repositorio, String> {
+    let dir_gir = fs::canonicalize(directorio_actual.join(gir_dir)).map_err(|_| {
+        format!(
+            "{}={} no es un directorio valido",
+            VARIABLE_GIR_DIR, gir_dir
+        )
+    })?;
+    if !dir_gir.is_dir() {
+        return Err(format!(
+            "{}={} no es un directorio",
+            VARIABLE_GIR_DIR, gir_dir
+        ));
+    }
+    let raiz = dir_gir
+        .parent()
+        .ok_or_else(|| format!("{} no tiene directorio padre", dir_gir.display()))?;
+    Ok(Repositorio::new(raiz).con_dir_gir(&dir_gir))
+}
+
+/// Convierte una ruta escrita por el usuario, relativa al directorio desde el que se ejecuto gir,
+/// en una ruta relativa a la raiz del repositorio.
+///
+/// # Ejemplo
+/// Desde `<raiz>/src`, `../README.md` pasa a ser `README.md` y `.` pasa a ser `src`.
+pub fn relativizar_ruta(
+    ruta: &str,
+    directorio_actual: &Path,
+    raiz: &Path,
+) -> Result<String, String> {
+    let absoluta = normalizar(&directorio_actual.join(ruta));
+    let relativa = absoluta
+        .strip_prefix(raiz)
+        .map_err(|_| format!("La ruta {} esta fuera del repositorio", ruta))?;
+
+    if relativa.as_os_str().is_empty() {
+        return Ok(".".to_string());
+    }
+    Ok(relativa.to_string_lossy().to_string())
+}
+
+// Convierte en absoluto, desde `directorio_actual`, el valor de cada aparicion de `opcion`
+fn absolutizar_valores(argv: &mut [String], opcion: &str, directorio_actual: &Path) {
+    let mut args = argv.iter_mut();
+    while let Some(arg) = args.next() {
+        if arg == opcion {
+            if let Some(valor) = args.next() {
+                *valor = directorio_actual.join(&valor).to_string_lossy().to_string();
+            }
+        }
+    }
+}
+
+// Resuelve `.` y `..` sin acceder al disco, ya que la ruta puede no existir (Ej: un archivo borrado)
+fn normalizar(ruta: &Path) -> PathBuf {
+    let mut normalizada = PathBuf::new();
+    for componente in ruta.components() {
+        match componente {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalizada.pop();
+            }
+            otro => normalizada.push(otro),
+        }
+    }
+    normalizada
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::utils::io;
+
+    #[test]
+    #[serial]
+    fn test01_se_encuentra_el_repositorio_desde_un_subdirectorio() {
+        let raiz = env::current_dir()
+            .unwrap()
+            .join("tmp/ubicacion_repositorio_test01");
+        io::crear_directorio(raiz.join(".gir")).unwrap();
+        io::crear_directorio(raiz.join("src/modulo")).unwrap();
+
+        assert_eq!(
+            descubrir_repositorio(&raiz.join("src/modulo")).unwrap(),
+            raiz
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test02_las_rutas_se_relativizan_a_la_raiz() {
+        let raiz = PathBuf::from("/repo");
+        let directorio_actual = raiz.join("src");
+
+        assert_eq!(
+            relativizar_ruta("../README.md", &directorio_actual, &raiz).unwrap(),
+            "README.md"
+        );
+        assert_eq!(
+            relativizar_ruta(".", &directorio_actual, &raiz).unwrap(),
+            "src"
+        );
+        assert_eq!(
+            relativizar_ruta("./a/../b.rs", &directorio_actual, &raiz).unwrap(),
+            "src/b.rs"
+        );
+        assert!(relativizar_ruta("../../otro", &directorio_actual, &raiz).is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test03_gir_dir_puede_apuntar_a_un_directorio_con_cualquier_nombre() {
+        let directorio_actual = fs::canonicalize(env::current_dir().unwrap()).unwrap();
+        let raiz = directorio_actual.join("tmp/ubicacion_repositorio_test03");
+        let _ = io::rm_directorio(&raiz);
+        io::crear_directorio(raiz.join("base_de_datos")).unwrap();
+        io::escribir_bytes(raiz.join("archivo"), "no es un directorio").unwrap();
+
+        let repositorio = repositorio_de_gir_dir(
+            "tmp/ubicacion_repositorio_test03/base_de_datos",
+            &directorio_actual,
+        )
+        .unwrap();
+
+        assert_eq!(repositorio.dir_trabajo(), raiz);
+        assert_eq!(repositorio.dir_gir(), raiz.join("base_de_datos"));
+        assert_eq!(
+            repositorio.ruta(".gir/HEAD"),
+            raiz.join("base_de_datos/HEAD")
+        );
+        assert!(repositorio_de_gir_dir(
+            "tmp/ubicacion_repositorio_test03/archivo",
+            &directorio_actual
+        )
+        .is_err());
+        assert!(repositorio_de_gir_dir(
+            "tmp/ubicacion_repositorio_test03/no_existe",
+            &directorio_actual
+        )
+        .is_err());
+    }
+
+    #[test]
+    #[serial]
+    fn test04_la_opcion_c_se_consume_antes_del_comando() {
+        let directorio_original = env::current_dir().unwrap();
+        io::crear_directorio("tmp/ubicacion_repositorio_test04").unwrap();
+        let mut argv = vec![
+            "-C".to_string(),
+            "tmp".to_string(),
+            "-C".to_string(),
+            "ubicacion_repositorio_test04".to_string(),
+            "status".to_string(),
+        ];
+
+        aplicar_opciones_globales(&mut argv).unwrap();
+        let directorio_nuevo = env::current_dir().unwrap();
+        env::set_current_dir(&directorio_original).unwrap();
+
+        assert_eq!(argv, vec!["status".to_string()]);
+        assert!(directorio_nuevo.ends_with("tmp/ubicacion_repositorio_test04"));
+    }
+    #[test]
+    #[serial]
+    fn test05_el_archivo_del_mensaje_del_commit_se_toma_desde_el_directorio_actual() {
+        let directorio_original = env::current_dir().unwrap();
+        let raiz = directorio_original.join("tmp/ubicacion_repositorio_test05");
+        io::crear_directorio(raiz.join(".gir")).unwrap();
+        io::crear_directorio(raiz.join("src")).unwrap();
+        env::set_current_dir(raiz.join("src")).unwrap();
+        let mut argv = vec![
+            "commit".to_string(),
+            "-m".to_string(),
+            "mensaje".to_string(),
+            "-F".to_string(),
+            "../../mensaje.txt".to_string(),
+        ];
+
+        let repositorio = entrar_al_repositorio(&mut argv);
+        env::set_current_dir(&directorio_original).unwrap();
+
+        assert_eq!(repositorio.unwrap().dir_trabajo(), raiz.as_path());
+        assert_eq!(argv[2], "mensaje");
+        assert_eq!(
+            normalizar(Path::new(&argv[4])),
+            directorio_original.join("tmp/mensaje.txt")
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::{path::PathBuf, sync::Arc};
 
 use gir::{
-    tipos_de_dato::{comando::Comando, logger::Logger},
+    tipos_de_dato::{comando::Comando, logger::Logger, repositorio::Repositorio},
     utils::{io, testing::eliminar_archivo_gir},
 };
 use serial_test::serial;
@@ -15,7 +15,7 @@ fn init_addear_committear_log() {
     eliminar_archivo_gir();
     let logger = Arc::new(Logger::new(PathBuf::from("tmp/init_addear_committear_log")).unwrap());
     let args_init = vec!["init".to_string()];
-    Comando::new(args_init, logger.clone())
+    Comando::new(args_init, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
@@ -25,7 +25,7 @@ fn init_addear_committear_log() {
         "add".to_string(),
         "tmp/init_addear_committear_log".to_string(),
     ];
-    Comando::new(args_add, logger.clone())
+    Comando::new(args_add, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
@@ -35,13 +35,13 @@ fn init_addear_committear_log() {
         "-m".to_string(),
         "mensaje".to_string(),
     ];
-    Comando::new(args_commit, logger.clone())
+    Comando::new(args_commit, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
 
     let args_log = vec!["log".to_string()];
-    let log = Comando::new(args_log, logger.clone())
+    let log = Comando::new(args_log, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
@@ -58,20 +58,20 @@ fn init_addear_status() {
     eliminar_archivo_gir();
     let logger = Arc::new(Logger::new(PathBuf::from("tmp/init_addear_status")).unwrap());
     let args_init = vec!["init".to_string()];
-    Comando::new(args_init, logger.clone())
+    Comando::new(args_init, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
 
     io::escribir_bytes("tmp/init_addear_status", "contenido").unwrap();
     let args_add = vec!["add".to_string(), "tmp/init_addear_status".to_string()];
-    Comando::new(args_add, logger.clone())
+    Comando::new(args_add, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();
 
     let args_status = vec!["status".to_string()];
-    let status = Comando::new(args_status, logger.clone())
+    let status = Comando::new(args_status, Repositorio::new("."), logger.clone())
         .unwrap()
         .ejecutar()
         .unwrap();